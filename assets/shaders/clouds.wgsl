@@ -0,0 +1,54 @@
+// Flat procedural cloud layer.
+//
+// Clouds are blocky cells of a value-noise pattern that tiles every
+// PATTERN_CELLS cells, so the CPU can drift the layer by one period per day
+// and wrap at midnight without a seam. The layer fades out toward its edge.
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct CloudSettings {
+    color: vec4<f32>,  // rgb=cloud colour, a=opacity
+    params: vec4<f32>, // x=drift along +x, y=cell size, z=fade start, w=fade end
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0)
+var<uniform> settings: CloudSettings;
+
+const PATTERN_CELLS: i32 = 64;
+const COVERAGE_THRESHOLD: f32 = 0.52;
+
+fn hash_cell(cell: vec2<i32>, period: i32) -> f32 {
+    let wrapped = vec2<u32>(((cell % period) + period) % period);
+    var h = (wrapped.x * 0x27d4eb2du) ^ ((wrapped.y + 0x165667b1u) * 0x9e3779b1u);
+    h = (h ^ (h >> 15u)) * 0x85ebca6bu;
+    h = h ^ (h >> 13u);
+    return f32(h & 0xffffu) / 65535.0;
+}
+
+// Tiling value noise with lattice points every `spacing` cells.
+fn cloud_noise(cell: vec2<i32>, spacing: i32) -> f32 {
+    let period = PATTERN_CELLS / spacing;
+    let lattice = vec2<i32>(floor(vec2<f32>(cell) / f32(spacing)));
+    let t = smoothstep(vec2(0.0), vec2(1.0), (vec2<f32>(cell - lattice * spacing) + 0.5) / f32(spacing));
+    let a = hash_cell(lattice, period);
+    let b = hash_cell(lattice + vec2(1, 0), period);
+    let c = hash_cell(lattice + vec2(0, 1), period);
+    let d = hash_cell(lattice + vec2(1, 1), period);
+    return mix(mix(a, b, t.x), mix(c, d, t.x), t.y);
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let drifted = mesh.world_position.xz + vec2(settings.params.x, 0.0);
+    let cell = vec2<i32>(floor(drifted / settings.params.y));
+    let density = 0.65 * cloud_noise(cell, 8) + 0.35 * cloud_noise(cell, 4);
+    if density < COVERAGE_THRESHOLD {
+        discard;
+    }
+
+    let distance_xz = distance(mesh.world_position.xz, view.world_position.xz);
+    let fade = 1.0 - smoothstep(settings.params.z, settings.params.w, distance_xz);
+    return vec4(settings.color.rgb, settings.color.a * fade);
+}
@@ -0,0 +1,37 @@
+// Far-field terrain beyond the resident view distance.
+//
+// Tiles carry flat per-vertex colours already shaded per face, so this only
+// applies sky light and the same distance fog and screen tint as the terrain
+// shader, keeping the seam between resident chunks and tiles invisible.
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct LodTerrainSettings {
+    sky_light_color: vec4<f32>,
+    fog_color: vec4<f32>, // rgb=fog/tint color, a=screen tint strength
+    fog_params: vec4<f32>, // x=start, y=end, z=strength
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0)
+var<uniform> settings: LodTerrainSettings;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    var base = vec3(1.0);
+#ifdef VERTEX_COLORS
+    base = mesh.color.rgb;
+#endif
+    let shaded = base * settings.sky_light_color.rgb;
+
+    let fog_start = settings.fog_params.x;
+    let fog_end = max(settings.fog_params.y, fog_start + 0.001);
+    let fog_strength = clamp(settings.fog_params.z, 0.0, 1.0);
+    let view_distance = distance(mesh.world_position.xyz, view.world_position);
+    let fog = smoothstep(fog_start, fog_end, view_distance) * fog_strength;
+    let fogged = mix(shaded, settings.fog_color.rgb, fog);
+
+    let screen_tint = clamp(settings.fog_color.a, 0.0, 1.0);
+    return vec4(mix(fogged, settings.fog_color.rgb, screen_tint), 1.0);
+}
@@ -1,5 +1,14 @@
 // Vertex-pulling shader — texture-array terrain, AO, smooth lighting.
 //
+// Non-water faces may be greedy-merged: info bits 16-19 and 20-23 hold the
+// span minus one along FACE_TANGENT_A and FACE_TANGENT_B. Texture UVs and AO
+// tile per cell from world position, and spanned faces resample smooth light
+// per fragment so every covered cell keeps its own corner light.
+//
+// Water surface vertices ripple with world position and time. Flowing tops
+// rotate the flow texture to the packed flow code and scroll it downstream;
+// water sides scroll it downward.
+//
 // Bind group 0 (per frame, global):
 //   binding 0: view_proj uniform (mat4x4<f32>)
 //   binding 1: terrain_texture (texture_2d_array<f32>)
@@ -9,16 +18,21 @@
 //   binding 6: ao_brightness uniform (vec4<f32>)
 //   binding 7: emission_factors storage (array<f32>)      // (block_type * 6 + face_dir)
 //   binding 8: terrain_visual_settings uniform
-// Bind group 1 (per chunk layer):
-//   binding 0: faces storage (array<FaceDescriptor>)
-//   binding 1: chunk_origin uniform (vec4<f32>)
-//   binding 2: light_data storage (array<u32>) // padded 18³, 4 packed light cells per u32
+// Bind group 1 (terrain arena, shared by every chunk layer):
+//   binding 0: faces storage (array<FaceDescriptor>)   // sub-allocated; vertex_index / 6 is the arena face
+//   binding 1: draws storage (array<ChunkDraw>)        // one record per chunk layer, indexed by instance_index
+//   binding 2: light_data storage (array<u32>) // padded 18³ per chunk at ChunkDraw.light_offset, 2 packed 0xSRGB light cells per u32
 
 struct FaceDescriptor {
     packed: u32,
     info: u32,
 }
 
+struct ChunkDraw {
+    origin: vec3<f32>,
+    light_offset: u32,
+}
+
 struct TerrainVisualSettings {
     sky_light_color: vec4<f32>,
     block_light_color: vec4<f32>,
@@ -37,7 +51,7 @@ struct TerrainVisualSettings {
 @group(0) @binding(8) var<uniform> terrain_visuals: TerrainVisualSettings;
 
 @group(1) @binding(0) var<storage, read> faces: array<FaceDescriptor>;
-@group(1) @binding(1) var<uniform> chunk_origin: vec4<f32>;
+@group(1) @binding(1) var<storage, read> draws: array<ChunkDraw>;
 @group(1) @binding(2) var<storage, read> light_data: array<u32>;
 
 const CORNER_OFFSETS: array<array<vec3<f32>, 4>, 6> = array(
@@ -82,6 +96,27 @@ const CORNER_HT_INDEX: array<array<u32, 4>, 6> = array(
     array(0xFFu, 0xFFu, 3u, 2u), // Back:    top corners at h11, h01
 );
 
+// True for top-surface water vertices that may ripple. Bottom vertices,
+// dry corners and corners shared with water above (9/9) stay put so faces
+// keep meeting and never dip into the block below.
+fn water_surface_vertex(face_dir: u32, qi: u32, corner_heights: u32) -> bool {
+    let index = CORNER_HT_INDEX[face_dir][qi];
+    if index == 0xFFu {
+        return false;
+    }
+    let height = (corner_heights >> (index * 4u)) & 0xFu;
+    return height > 0u && height < 9u;
+}
+
+// Vertical ripple of the water surface, never above the meshed height.
+// Depends only on world position and time, so every face sharing a corner
+// moves it by the same amount.
+fn water_wave_offset(world_xz: vec2<f32>, seconds: f32) -> f32 {
+    let primary = sin(dot(world_xz, vec2(0.91, 0.58)) + seconds * WATER_WAVE_SPEED);
+    let secondary = sin(dot(world_xz, vec2(-0.47, 1.13)) - seconds * WATER_WAVE_SPEED * 0.7);
+    return WATER_WAVE_AMPLITUDE * (primary + 0.5 * secondary) / 1.5 - WATER_WAVE_AMPLITUDE;
+}
+
 fn water_vertex_height(face_dir: u32, qi: u32, corner_heights: u32, wb_lo: u32, wb_hi: u32) -> f32 {
     let index = CORNER_HT_INDEX[face_dir][qi];
     if index == 0xFFu {
@@ -98,6 +133,10 @@ fn water_vertex_height(face_dir: u32, qi: u32, corner_heights: u32, wb_lo: u32,
 const LIGHT_FLOOR: f32 = 0.05;
 const LIGHT_FALLOFF: f32 = 0.8;
 const TEXTURE_FRAME_SECONDS: f32 = 0.08;
+const WATER_WAVE_AMPLITUDE: f32 = 0.035;
+const WATER_WAVE_SPEED: f32 = 1.4;
+// Blocks per second the flow texture travels along the current.
+const WATER_FLOW_SCROLL_SPEED: f32 = 0.6;
 
 const PADDED_DIM: u32 = 18u;
 const PADDED_AREA: u32 = PADDED_DIM * PADDED_DIM;
@@ -109,13 +148,18 @@ struct VertexOutput {
     @location(2) @interpolate(flat) block_type: u32,
     @location(3) @interpolate(flat) face_dir: u32,
     @location(4) @interpolate(flat) ao_key: u32,
-    @location(5) light: vec2<f32>,
+    @location(5) light: vec4<f32>,
     @location(6) @interpolate(flat) water_up_flow: u32,
     @location(7) @interpolate(flat) water_flow_code: u32,
+    @location(8) local_pos: vec3<f32>,
+    @location(9) @interpolate(flat) light_offset: u32,
+    @location(10) @interpolate(flat) spanned: u32,
+    @location(11) @interpolate(flat) water_geometry: u32,
 }
 
 @vertex
-fn vertex(@builtin(vertex_index) vid: u32) -> VertexOutput {
+fn vertex(@builtin(vertex_index) vid: u32, @builtin(instance_index) draw_idx: u32) -> VertexOutput {
+    let draw = draws[draw_idx];
     let face_idx = vid / 6u;
     let corner_raw = vid % 6u;
 
@@ -144,32 +188,48 @@ fn vertex(@builtin(vertex_index) vid: u32) -> VertexOutput {
         qi = TRI_TO_QUAD_A[corner_raw];
     }
 
+    var span_a = 0u;
+    var span_b = 0u;
+    if water_geometry == 0u {
+        span_a = (desc.info >> 16) & 0xFu;
+        span_b = (desc.info >> 20) & 0xFu;
+    }
+
     var offset = CORNER_OFFSETS[face_dir][qi];
     if water_geometry != 0u {
         offset.y = water_vertex_height(face_dir, qi, corner_heights, water_below_lo, water_below_hi);
     }
-    let local_pos = vec3<f32>(f32(x) + offset.x, f32(y) + offset.y, f32(z) + offset.z);
+    // Far corners of a spanned face sit on its last cell along each tangent.
+    let stretch = vec3<f32>(FACE_TANGENT_A[face_dir]) * f32(span_a)
+        + vec3<f32>(FACE_TANGENT_B[face_dir]) * f32(span_b);
+    let corner_cell = vec3<i32>(i32(x), i32(y), i32(z)) + vec3<i32>(offset * stretch);
+    var local_pos = vec3<f32>(corner_cell) + offset;
+    if water_geometry != 0u && water_surface_vertex(face_dir, qi, corner_heights) {
+        let world_xz = local_pos.xz + draw.origin.xz;
+        local_pos.y += water_wave_offset(world_xz, terrain_visuals.fog_params.w);
+    }
 
-    let light = corner_light(vec3<i32>(i32(x), i32(y), i32(z)), face_dir, offset);
+    let light = corner_light(draw.light_offset, corner_cell, face_dir, offset);
 
-    let world_pos = local_pos + chunk_origin.xyz;
+    let world_pos = local_pos + draw.origin;
     let clip_pos = view_proj * vec4(world_pos, 1.0);
+    let spanned = u32(span_a + span_b != 0u);
 
-    return VertexOutput(clip_pos, world_pos, NORMALS[face_dir], block_type, face_dir, ao_key, light, water_up_flow, water_flow_code);
+    return VertexOutput(clip_pos, world_pos, NORMALS[face_dir], block_type, face_dir, ao_key, light, water_up_flow, water_flow_code, local_pos, draw.light_offset, spanned, water_geometry);
 }
 
 fn padded_coord(value: i32) -> u32 {
     return u32(clamp(value + 1, 0, i32(PADDED_DIM) - 1));
 }
 
-fn sample_light(cell: vec3<i32>) -> vec2<f32> {
+// Light at a padded cell as (block red, block green, block blue, sky), 0..1.
+fn sample_light(light_offset: u32, cell: vec3<i32>) -> vec4<f32> {
     let ilp = vec3<u32>(padded_coord(cell.x), padded_coord(cell.y), padded_coord(cell.z));
     let light_cell_idx = ilp.x + ilp.z * PADDED_DIM + ilp.y * PADDED_AREA;
-    let light_word = light_data[light_cell_idx >> 2u];
-    let packed_light = (light_word >> ((light_cell_idx & 0x3u) * 8u)) & 0xFFu;
-    let block_light = f32(packed_light & 0x0Fu) / 15.0;
-    let sky_light = f32((packed_light >> 4) & 0x0Fu) / 15.0;
-    return vec2(block_light, sky_light);
+    let light_word = light_data[light_offset + (light_cell_idx >> 1u)];
+    let packed_light = (light_word >> ((light_cell_idx & 0x1u) * 16u)) & 0xFFFFu;
+    let levels = (vec4(packed_light) >> vec4(8u, 4u, 0u, 12u)) & vec4(0x0Fu);
+    return vec4<f32>(levels) / 15.0;
 }
 
 fn corner_axis_sign(offset: vec3<f32>, axis: vec3<i32>) -> i32 {
@@ -184,15 +244,31 @@ fn corner_tangent_offset(offset: vec3<f32>, axis: vec3<i32>) -> vec3<i32> {
     return axis * corner_axis_sign(offset, axis);
 }
 
-fn corner_light(block_cell: vec3<i32>, face_dir: u32, offset: vec3<f32>) -> vec2<f32> {
+fn corner_light(light_offset: u32, block_cell: vec3<i32>, face_dir: u32, offset: vec3<f32>) -> vec4<f32> {
     let base = block_cell + FACE_NORMALS[face_dir];
     let tangent_a = corner_tangent_offset(offset, FACE_TANGENT_A[face_dir]);
     let tangent_b = corner_tangent_offset(offset, FACE_TANGENT_B[face_dir]);
 
-    return (sample_light(base)
-        + sample_light(base + tangent_a)
-        + sample_light(base + tangent_b)
-        + sample_light(base + tangent_a + tangent_b)) * 0.25;
+    return (sample_light(light_offset, base)
+        + sample_light(light_offset, base + tangent_a)
+        + sample_light(light_offset, base + tangent_b)
+        + sample_light(light_offset, base + tangent_a + tangent_b)) * 0.25;
+}
+
+// Smooth light at a point on a face, bilinear between the corner light of the
+// cell it lies in. Matches the interpolated vertex light of one-cell faces.
+fn face_point_light(light_offset: u32, local_pos: vec3<f32>, face_dir: u32) -> vec4<f32> {
+    let normal = FACE_NORMALS[face_dir];
+    let axis_a = vec3<f32>(FACE_TANGENT_A[face_dir]);
+    let axis_b = vec3<f32>(FACE_TANGENT_B[face_dir]);
+    let cell = vec3<i32>(floor(local_pos - 0.5 * vec3<f32>(normal)));
+    let uv = fract(vec2(dot(local_pos, axis_a), dot(local_pos, axis_b)));
+
+    let l00 = corner_light(light_offset, cell, face_dir, vec3(0.0));
+    let l10 = corner_light(light_offset, cell, face_dir, axis_a);
+    let l01 = corner_light(light_offset, cell, face_dir, axis_b);
+    let l11 = corner_light(light_offset, cell, face_dir, axis_a + axis_b);
+    return mix(mix(l00, l10, uv.x), mix(l01, l11, uv.x), uv.y);
 }
 
 fn corner_ao_brightness(ao_key: u32, corner: u32) -> f32 {
@@ -209,9 +285,14 @@ fn light_level_curve(level: f32) -> f32 {
     return pow(LIGHT_FALLOFF, (1.0 - clamped) * 15.0);
 }
 
-fn combined_light_color(light: vec2<f32>) -> vec3<f32> {
-    let sky = light_level_curve(light.y) * terrain_visuals.sky_light_color.rgb;
-    let block = light_level_curve(light.x) * terrain_visuals.block_light_color.rgb;
+fn combined_light_color(light: vec4<f32>) -> vec3<f32> {
+    let sky = light_level_curve(light.w) * terrain_visuals.sky_light_color.rgb;
+    let block_curve = vec3(
+        light_level_curve(light.r),
+        light_level_curve(light.g),
+        light_level_curve(light.b),
+    );
+    let block = block_curve * terrain_visuals.block_light_color.rgb;
     return max(max(sky, block), vec3(LIGHT_FLOOR));
 }
 
@@ -266,9 +347,13 @@ fn fragment(@location(0) world_pos: vec3<f32>,
             @location(2) @interpolate(flat) block_type: u32,
             @location(3) @interpolate(flat) face_dir: u32,
             @location(4) @interpolate(flat) ao_key: u32,
-            @location(5) light: vec2<f32>,
+            @location(5) light: vec4<f32>,
             @location(6) @interpolate(flat) water_up_flow: u32,
-            @location(7) @interpolate(flat) water_flow_code: u32) -> @location(0) vec4<f32> {
+            @location(7) @interpolate(flat) water_flow_code: u32,
+            @location(8) local_pos: vec3<f32>,
+            @location(9) @interpolate(flat) light_offset: u32,
+            @location(10) @interpolate(flat) spanned: u32,
+            @location(11) @interpolate(flat) water_geometry: u32) -> @location(0) vec4<f32> {
     let n = abs(world_normal);
     let wp = world_pos;
     var face_uv: vec2<f32>;
@@ -281,8 +366,14 @@ fn fragment(@location(0) world_pos: vec3<f32>,
     }
     let geom_uv = fract(face_uv);
     var sample_face_uv = face_uv;
+    let flow_scroll = terrain_visuals.fog_params.w * WATER_FLOW_SCROLL_SPEED;
     if water_up_flow == 1u && face_dir == 3u && water_flow_code != 0u {
+        // Oriented v runs along the current; scrolling it moves the texture downstream.
         sample_face_uv = orient_water_flow_uv(face_uv, water_flow_code);
+        sample_face_uv.y -= flow_scroll;
+    } else if water_geometry != 0u && face_dir != 2u && face_dir != 3u {
+        // Water sides show the flow texture pouring down (v grows downward).
+        sample_face_uv.y -= flow_scroll;
     }
     let block_uv = fract(sample_face_uv);
 
@@ -306,7 +397,11 @@ fn fragment(@location(0) world_pos: vec3<f32>,
     let emissive = clamp(emission_factors[lookup], 0.0, 1.0);
     let face_brightness = mix(FACE_BRIGHTNESS[face_dir], 1.0, emissive);
     let ao = mix(face_ao_brightness(ao_key, face_dir, geom_uv), 1.0, emissive);
-    let light_color = max(combined_light_color(light) * ao * face_brightness, vec3(LIGHT_FLOOR * face_brightness));
+    var face_light = light;
+    if spanned != 0u {
+        face_light = face_point_light(light_offset, local_pos, face_dir);
+    }
+    let light_color = max(combined_light_color(face_light) * ao * face_brightness, vec3(LIGHT_FLOOR * face_brightness));
 
     let shaded_color = tex_color.rgb * tint.rgb * light_color;
     let fogged_color = apply_distance_fog(shaded_color, world_pos);
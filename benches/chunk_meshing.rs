@@ -14,7 +14,10 @@ use minecraft_clone::{
         WorldMetadata,
         chunk::mesh::{
             ChunkMeshBlocks, ChunkMeshLight,
-            mesher::{LayerMesh, benchmark_binary_floor, build, build_reference},
+            mesher::{
+                ChunkMeshingMode, LayerMesh, benchmark_binary_floor, build, build_reference,
+                build_with_mode,
+            },
         },
         chunk::{CHUNK_SIZE, Chunk, ChunkCell, ChunkLight, ChunkPos, ChunkPosition, LocalBlockPos},
         generation::generate_chunk,
@@ -307,6 +310,7 @@ fn bench_chunk_meshing(c: &mut Criterion) {
 
     bench_reference_mesher(c, &scenarios);
     bench_production_mesher(c, &scenarios);
+    bench_greedy_mesher(c, &scenarios);
     bench_binary_mesher_floor(c, &scenarios);
     bench_water_shape_meshing(c);
     print_mesher_output_comparison(&scenarios);
@@ -369,6 +373,22 @@ fn bench_production_mesher(c: &mut Criterion, scenarios: &[Scenario]) {
     group.finish();
 }
 
+fn bench_greedy_mesher(c: &mut Criterion, scenarios: &[Scenario]) {
+    let mut group = c.benchmark_group("chunk_mesh_greedy");
+    group.throughput(Throughput::Elements(1));
+    for scenario in scenarios {
+        let chunk_refs = scenario.chunk_refs();
+        let center = scenario.center_pos;
+        group.bench_function(BenchmarkId::from_parameter(scenario.name), |b| {
+            b.iter(|| {
+                let blocks = ChunkMeshBlocks::from_chunks(center, black_box(&chunk_refs));
+                black_box(build_with_mode(&blocks, ChunkMeshingMode::Greedy))
+            });
+        });
+    }
+    group.finish();
+}
+
 /// Absolute floor: masks + AND-NOT + iter_ones + coord recovery.
 /// Same memory access pattern as `build_binary` but skips AO, cell lookup and
 /// packed-face construction.
@@ -390,20 +410,22 @@ fn bench_binary_mesher_floor(c: &mut Criterion, scenarios: &[Scenario]) {
 
 fn print_mesher_output_comparison(scenarios: &[Scenario]) {
     println!();
-    println!("--- Reference vs production face counts ---");
+    println!("--- Reference vs production vs greedy face counts ---");
     println!(
-        "  {:<30} {:>9} {:>10}",
-        "scenario", "reference", "production",
+        "  {:<30} {:>9} {:>10} {:>8}",
+        "scenario", "reference", "production", "greedy",
     );
     for scenario in scenarios {
         let chunk_refs = scenario.chunk_refs();
         let blocks = ChunkMeshBlocks::from_chunks(scenario.center_pos, &chunk_refs);
         let reference_faces = chunk_mesh_face_count(&build_reference(&blocks));
         let production_faces = chunk_mesh_face_count(&build(&blocks));
+        let greedy_faces =
+            chunk_mesh_face_count(&build_with_mode(&blocks, ChunkMeshingMode::Greedy));
 
         println!(
-            "  {:<30} {:>9} {:>10}",
-            scenario.name, reference_faces, production_faces,
+            "  {:<30} {:>9} {:>10} {:>8}",
+            scenario.name, reference_faces, production_faces, greedy_faces,
         );
     }
     println!();
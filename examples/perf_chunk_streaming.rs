@@ -2,13 +2,19 @@
 //!
 //! The arguments are view radius, world height in chunks, lighting subchunk
 //! budget, timeout in seconds, load budget, staging budget, and activation
-//! budget, followed by the simulated frame interval in microseconds. Defaults
-//! match production except for the smaller radius, which keeps quick runs
-//! short. Pass a zero frame interval for an unpaced CPU-throughput profile.
+//! budget, followed by the simulated frame interval in microseconds and a
+//! warm-reload flag. Defaults match production except for the smaller radius,
+//! which keeps quick runs short. Pass a zero frame interval for an unpaced
+//! CPU-throughput profile.
+//!
+//! With the warm-reload flag set, the world is streamed once into an in-memory
+//! store, and the reported pass streams the same area again from that store so
+//! cached column light can skip the solver.
 //!
 //! ```text
 //! cargo run --release --example perf_chunk_streaming -- 8 5 80 120
 //! perf stat -d target/release/examples/perf_chunk_streaming 24 5 80 120 4 8 8 0
+//! cargo run --release --example perf_chunk_streaming -- 8 5 80 120 4 8 8 16667 true
 //! ```
 
 use std::time::{Duration, Instant};
@@ -23,7 +29,7 @@ use minecraft_clone::{
             Active, ColumnActivationBudget, ColumnLightBudget, ColumnLoadBudget,
             ColumnStagingBudget, DesiredColumnView, Dimension, DimensionPlugin, ViewDistance,
         },
-        storage::{ChunkRepository, NoopChunkStore},
+        storage::{ChunkRepository, InMemoryChunkStore, NoopChunkStore},
     },
 };
 
@@ -45,58 +51,40 @@ fn main() {
     let staging_budget = argument(6, DEFAULT_STAGING_BUDGET);
     let activation_budget = argument(7, DEFAULT_ACTIVATION_BUDGET);
     let frame_interval = Duration::from_micros(argument(8, DEFAULT_FRAME_INTERVAL_MICROS));
+    let warm_reload = argument(9, false);
     let metadata = WorldMetadata::default()
         .with_height_chunks(height_chunks)
         .expect("profile world height must be valid");
-    let repository = ChunkRepository::new(NoopChunkStore::new(metadata.clone()));
-    let catalog = DimensionCatalog::for_world(&metadata);
-
-    let mut app = App::new();
-    app.add_plugins(MinimalPlugins)
-        .add_plugins(StatesPlugin)
-        .add_plugins(GameStatePlugin)
-        .insert_resource(metadata)
-        .insert_resource(catalog)
-        .insert_resource(repository)
-        .insert_resource(ViewDistance::new(radius))
-        .insert_resource(ColumnLoadBudget(load_budget))
-        .insert_resource(ColumnStagingBudget(staging_budget))
-        .insert_resource(ColumnActivationBudget(activation_budget))
-        .insert_resource(ColumnLightBudget(light_budget))
-        .init_resource::<ChunkPerfCounters>()
-        .add_plugins(DimensionPlugin);
-
+    let budgets = StreamingBudgets {
+        radius,
+        light: light_budget,
+        load: load_budget,
+        staging: staging_budget,
+        activation: activation_budget,
+    };
     let timeout = Duration::from_secs(timeout_seconds);
-    let started = Instant::now();
-    let mut update_times = Vec::new();
-    let (visible_chunks, resident_chunks, loaded_chunks, published_chunks) = loop {
-        let frame_started = Instant::now();
-        app.update();
-        update_times.push(frame_started.elapsed());
 
-        let (visible_chunks, resident_chunks, loaded_chunks, published_chunks) =
-            active_dimension_counts(app.world_mut());
-        if visible_chunks > 0 && published_chunks == visible_chunks {
-            break (
-                visible_chunks,
-                resident_chunks,
-                loaded_chunks,
-                published_chunks,
-            );
-        }
-        assert!(
-            started.elapsed() < timeout,
-            "streaming profile timed out after {timeout_seconds}s: \
-             {published_chunks}/{visible_chunks} visible chunks published, \
-             {loaded_chunks}/{resident_chunks} resident chunks loaded"
+    let repository = if warm_reload {
+        let repository = ChunkRepository::new(InMemoryChunkStore::new(metadata.clone()));
+        let mut cold = streaming_app(&metadata, repository.clone(), budgets);
+        let cold_started = Instant::now();
+        stream_until_published(&mut cold, timeout, frame_interval, true);
+        println!(
+            "cold pass complete in {:.3}s, light cache saves={}",
+            cold_started.elapsed().as_secs_f64(),
+            cold.world()
+                .resource::<ChunkPerfCounters>()
+                .light_cache_saves,
         );
-        let remaining = frame_interval.saturating_sub(frame_started.elapsed());
-        if remaining.is_zero() {
-            std::thread::yield_now();
-        } else {
-            std::thread::sleep(remaining);
-        }
+        repository
+    } else {
+        ChunkRepository::new(NoopChunkStore::new(metadata.clone()))
     };
+
+    let mut app = streaming_app(&metadata, repository, budgets);
+    let started = Instant::now();
+    let ((visible_chunks, resident_chunks, loaded_chunks, published_chunks), mut update_times) =
+        stream_until_published(&mut app, timeout, frame_interval, false);
     update_times.sort_unstable();
     let perf = app.world().resource::<ChunkPerfCounters>();
     let committed_chunks = perf.light_patch_committed_columns * height_chunks;
@@ -118,7 +106,7 @@ fn main() {
     );
     println!(
         "world visible={visible_chunks}, resident={resident_chunks}, loaded={loaded_chunks}, \
-         published={published_chunks} chunks"
+         published={published_chunks} chunks, warm_reload={warm_reload}"
     );
     println!(
         "updates={} elapsed={:.3}s p50={:.3}ms p95={:.3}ms p99={:.3}ms max={:.3}ms",
@@ -139,6 +127,10 @@ fn main() {
         perf.light_patch_calculation_chunks,
         perf.light_patch_scratch_chunks,
     );
+    println!(
+        "light cache hits={} misses={} saves={}",
+        perf.light_cache_hits, perf.light_cache_misses, perf.light_cache_saves,
+    );
     println!(
         "lighting elapsed={:.3}ms max_patch={:.3}ms solve={:.3}ms ({solve_percent:.1}%) \
          prepare={:.3}ms ({prepare_percent:.1}%)",
@@ -171,6 +163,84 @@ fn main() {
     );
 }
 
+#[derive(Debug, Clone, Copy)]
+struct StreamingBudgets {
+    radius: i32,
+    light: usize,
+    load: usize,
+    staging: usize,
+    activation: usize,
+}
+
+fn streaming_app(
+    metadata: &WorldMetadata,
+    repository: ChunkRepository,
+    budgets: StreamingBudgets,
+) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(StatesPlugin)
+        .add_plugins(GameStatePlugin)
+        .insert_resource(metadata.clone())
+        .insert_resource(DimensionCatalog::for_world(metadata))
+        .insert_resource(repository)
+        .insert_resource(ViewDistance::new(budgets.radius))
+        .insert_resource(ColumnLoadBudget(budgets.load))
+        .insert_resource(ColumnStagingBudget(budgets.staging))
+        .insert_resource(ColumnActivationBudget(budgets.activation))
+        .insert_resource(ColumnLightBudget(budgets.light))
+        .init_resource::<ChunkPerfCounters>()
+        .add_plugins(DimensionPlugin);
+    app
+}
+
+/// Updates until every visible chunk is published, and optionally until every
+/// cached light write has reached the store, returning the final counts and
+/// the duration of each update.
+fn stream_until_published(
+    app: &mut App,
+    timeout: Duration,
+    frame_interval: Duration,
+    wait_for_light_cache: bool,
+) -> ((usize, usize, usize, usize), Vec<Duration>) {
+    let started = Instant::now();
+    let mut update_times = Vec::new();
+    loop {
+        let frame_started = Instant::now();
+        app.update();
+        update_times.push(frame_started.elapsed());
+
+        let counts = active_dimension_counts(app.world_mut());
+        let (visible_chunks, resident_chunks, loaded_chunks, published_chunks) = counts;
+        let cache_settled =
+            !wait_for_light_cache || pending_light_cache_saves(app.world_mut()) == 0;
+        if visible_chunks > 0 && published_chunks == visible_chunks && cache_settled {
+            return (counts, update_times);
+        }
+        assert!(
+            started.elapsed() < timeout,
+            "streaming profile timed out after {}s: \
+             {published_chunks}/{visible_chunks} visible chunks published, \
+             {loaded_chunks}/{resident_chunks} resident chunks loaded",
+            timeout.as_secs()
+        );
+        let remaining = frame_interval.saturating_sub(frame_started.elapsed());
+        if remaining.is_zero() {
+            std::thread::yield_now();
+        } else {
+            std::thread::sleep(remaining);
+        }
+    }
+}
+
+fn pending_light_cache_saves(world: &mut World) -> usize {
+    let mut query = world.query_filtered::<&Dimension, With<Active>>();
+    query
+        .iter(world)
+        .map(Dimension::pending_light_cache_saves)
+        .sum()
+}
+
 fn active_dimension_counts(world: &mut World) -> (usize, usize, usize, usize) {
     let mut query = world.query_filtered::<(&Dimension, &DesiredColumnView), With<Active>>();
     let (dimension, desired) = query
@@ -17,20 +17,26 @@ use bevy_settings::SettingsPlugin;
 
 use crate::{
     audio::{GameAudioPlugin, GameAudioSettings},
-    game_state::GameStatePlugin,
+    chat::ChatPlugin,
+    game_state::{GameState, GameStatePlugin},
     input::GameInputPlugin,
     item::DroppedItemPlugin,
     light::LightPlugin,
     memory::{MemoryTrackingPlugin, memory_profiler_enabled},
     mob::MobControllerPlugin,
-    player::{Player, PlayerPlugin, cam::MouseSettings},
+    player::{
+        Player, PlayerPlugin, cam::MouseSettings, control::KeyBindings,
+        gamepad::GamepadControlSettings,
+    },
     textures::BlockTexturePlugin,
     ui::UIPlugin,
+    weather::WeatherPlugin,
     world::{
-        WorldConfig, WorldMetadata, WorldPlugin,
+        WorldPlugin,
         chunk::{
             Chunk, ChunkColumn, ChunkContentCounts, ChunkNeedsLightRebuild, ChunkPerfCounters,
-            ChunkPos, ChunkPosition, mesh::ChunkMeshLayer,
+            ChunkPos, ChunkPosition,
+            mesh::{ChunkMeshLayer, FogSettings},
         },
         dimension::{Active, DesiredColumnView, Dimension, ViewDistance},
     },
@@ -58,10 +64,15 @@ impl Plugin for AppPlugin {
         )
         .register_type::<ViewDistance>()
         .register_type::<MouseSettings>()
+        .register_type::<KeyBindings>()
+        .register_type::<GamepadControlSettings>()
         .register_type::<GameAudioSettings>()
+        .register_type::<FogSettings>()
         .add_plugins(SettingsPlugin::new("io.github.matt.minecraft_clone"))
         .add_plugins(EguiPlugin::default())
         .insert_resource(ClearColor(Srgba::hex("74b3ff").unwrap().into()))
+        // The world is picked on the title screen before anything is generated.
+        .insert_state(GameState::MainMenu)
         .add_plugins(GameStatePlugin)
         .add_plugins(GameAudioPlugin)
         .add_plugins(LightPlugin)
@@ -69,19 +80,10 @@ impl Plugin for AppPlugin {
         .add_plugins(PlayerPlugin)
         .add_plugins(GameInputPlugin)
         .add_plugins(DroppedItemPlugin)
+        .add_plugins(ChatPlugin)
         .add_plugins(BlockTexturePlugin)
-        .insert_resource({
-            let metadata = WorldMetadata::default();
-            #[cfg(feature = "turso-store")]
-            {
-                WorldConfig::development_turso(metadata)
-            }
-            #[cfg(not(feature = "turso-store"))]
-            {
-                WorldConfig::development_sqlite(metadata)
-            }
-        })
         .add_plugins(WorldPlugin)
+        .add_plugins(WeatherPlugin)
         .add_plugins(UIPlugin)
         .insert_resource(Time::<Fixed>::from_hz(FIXED_TICK_RATE_HZ))
         .insert_resource(Time::<Virtual>::from_max_delta(Duration::from_secs_f64(
@@ -529,6 +531,7 @@ fn log_frame_perf(
             chunk_perf.mesh_apply_max_elapsed.as_secs_f64() * 1_000.0
         ),
         light_rebuild_targets_5s = chunk_perf.light_rebuild_targets,
+        light_incremental_updates_5s = chunk_perf.light_incremental_updates,
         light_patch_submitted_5s = chunk_perf.light_patch_runs,
         light_patch_accepted_5s = chunk_perf.light_patch_accepted_results,
         light_patch_calculation_chunks_5s = chunk_perf.light_patch_calculation_chunks,
@@ -601,6 +604,9 @@ fn log_frame_perf(
         ),
         light_patch_stale_results_5s = chunk_perf.light_patch_stale_results,
         light_patch_cancelled_5s = chunk_perf.light_patch_cancelled,
+        light_cache_hits_5s = chunk_perf.light_cache_hits,
+        light_cache_misses_5s = chunk_perf.light_cache_misses,
+        light_cache_saves_5s = chunk_perf.light_cache_saves,
         light_uploads_5s = chunk_perf.light_uploads,
         mesh_layers,
         mesh_faces,
@@ -0,0 +1,534 @@
+//! Ambience loops: spatial voices on nearby flowing water, and cave and wind
+//! beds that follow the sky light and altitude at the listener.
+
+use bevy::{
+    audio::{
+        AudioPlayer, AudioSink, AudioSinkPlayback, PlaybackSettings, SpatialAudioSink,
+        SpatialListener, Volume,
+    },
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::world::{
+    chunk::{Chunk, ChunkContentCounts, ChunkLight, ChunkPos, WorldBlockPos},
+    dimension::{Active, Dimension},
+};
+
+use super::{GameAudioSettings, SoundBank};
+
+/// Flowing water further than this many blocks from the listener is culled.
+const WATER_AUDIBLE_DISTANCE: f32 = 16.0;
+/// Water loops fade out over this last stretch before the cull distance.
+const WATER_FADE_DISTANCE: f32 = 4.0;
+/// Only the nearest clusters play, however much water flows nearby.
+const MAX_WATER_VOICES: usize = 4;
+/// Edge length, in blocks, of the grid that flowing cells are clustered on.
+const WATER_CLUSTER_SIZE: i32 = 4;
+/// Flowing cells at which a cluster plays at full level.
+const WATER_CLUSTER_FULL_CELLS: usize = 12;
+/// Seconds between rescans of the chunks around the listener.
+const WATER_SCAN_INTERVAL: f32 = 0.5;
+
+/// Cave ambience is at full level at or below this sky light.
+const CAVE_DARK_SKY_LIGHT: u8 = 2;
+/// Cave ambience is silent at or above this sky light.
+const CAVE_OPEN_SKY_LIGHT: u8 = 8;
+const CAVE_AMBIENCE_LEVEL: f32 = 0.5;
+/// Wind starts above this altitude and reaches full level at
+/// `WIND_FULL_ALTITUDE`.
+const WIND_CALM_ALTITUDE: f32 = 36.0;
+const WIND_FULL_ALTITUDE: f32 = 64.0;
+const WIND_AMBIENCE_LEVEL: f32 = 0.6;
+/// Listener loops move toward their target level at this rate per second, so
+/// walking in and out of a cave mouth swells rather than cuts.
+const LISTENER_AMBIENCE_FADE_RATE: f32 = 1.5;
+
+/// A looping emitter placed on one cluster of flowing water.
+#[derive(Component, Debug)]
+pub(super) struct WaterAmbience {
+    cluster: IVec3,
+}
+
+/// Flowing water found by the last scan around the listener.
+#[derive(Resource, Default)]
+pub(super) struct WaterAmbienceScan {
+    until_next: f32,
+    clusters: Vec<WaterCluster>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WaterCluster {
+    key: IVec3,
+    center: Vec3,
+    cells: usize,
+}
+
+/// Ambience loops that surround the listener rather than coming from a point.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ListenerAmbience {
+    Cave,
+    Wind,
+}
+
+impl ListenerAmbience {
+    const ALL: [Self; 2] = [Self::Cave, Self::Wind];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Cave => "Cave ambience",
+            Self::Wind => "Wind ambience",
+        }
+    }
+
+    const fn base_level(self) -> f32 {
+        match self {
+            Self::Cave => CAVE_AMBIENCE_LEVEL,
+            Self::Wind => WIND_AMBIENCE_LEVEL,
+        }
+    }
+
+    fn sound(self, sounds: &SoundBank) -> Handle<AudioSource> {
+        match self {
+            Self::Cave => sounds.cave_loop.clone(),
+            Self::Wind => sounds.wind_loop.clone(),
+        }
+    }
+}
+
+/// Smoothed levels of the listener loops, each in `0.0..=1.0`.
+#[derive(Resource, Debug, Default)]
+pub(super) struct ListenerAmbienceLevels {
+    pub(super) cave: f32,
+    pub(super) wind: f32,
+}
+
+impl ListenerAmbienceLevels {
+    fn get_mut(&mut self, ambience: ListenerAmbience) -> &mut f32 {
+        match ambience {
+            ListenerAmbience::Cave => &mut self.cave,
+            ListenerAmbience::Wind => &mut self.wind,
+        }
+    }
+}
+
+/// Rescans flowing water around the listener on an interval and keeps one
+/// spatial loop on each of the nearest audible clusters.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn update_water_ambience(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<GameAudioSettings>,
+    sounds: Res<SoundBank>,
+    mut scan: ResMut<WaterAmbienceScan>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+    chunks: Query<(&Chunk, &ChunkContentCounts)>,
+    mut emitters: Query<(
+        Entity,
+        &WaterAmbience,
+        &mut Transform,
+        Option<&mut SpatialAudioSink>,
+    )>,
+) {
+    let gain = settings.ambience_gain();
+    let (Some(listener), Some(dimension)) = (listener, dimension) else {
+        for (entity, ..) in &emitters {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    let listener = listener.translation();
+
+    scan.until_next -= time.delta_secs();
+    if scan.until_next <= 0.0 {
+        scan.until_next = WATER_SCAN_INTERVAL;
+        scan.clusters = flowing_water_clusters(flowing_water_near(listener, &dimension, &chunks));
+    }
+
+    let mut targets = if gain > 0.0 {
+        audible_water_clusters(&scan.clusters, listener)
+            .into_iter()
+            .map(|cluster| (cluster.key, cluster))
+            .collect::<HashMap<_, _>>()
+    } else {
+        HashMap::new()
+    };
+
+    for (entity, emitter, mut transform, sink) in &mut emitters {
+        let Some(cluster) = targets.remove(&emitter.cluster) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        transform.translation = cluster.center;
+        if let Some(mut sink) = sink {
+            sink.set_volume(Volume::Linear(
+                gain * water_cluster_level(&cluster, listener),
+            ));
+        }
+    }
+
+    for cluster in targets.into_values() {
+        let volume = gain * water_cluster_level(&cluster, listener);
+        commands.spawn((
+            Name::new("Water ambience"),
+            WaterAmbience {
+                cluster: cluster.key,
+            },
+            AudioPlayer::new(sounds.water_loop.clone()),
+            PlaybackSettings::LOOP
+                .with_volume(Volume::Linear(volume))
+                .with_spatial(true),
+            Transform::from_translation(cluster.center),
+        ));
+    }
+}
+
+/// Flowing water cells in the published chunks within audible range.
+fn flowing_water_near(
+    listener: Vec3,
+    dimension: &Dimension,
+    chunks: &Query<(&Chunk, &ChunkContentCounts)>,
+) -> Vec<WorldBlockPos> {
+    let min = ChunkPos::containing_translation(listener - Vec3::splat(WATER_AUDIBLE_DISTANCE));
+    let max = ChunkPos::containing_translation(listener + Vec3::splat(WATER_AUDIBLE_DISTANCE));
+    let (min, max) = (min.as_ivec3(), max.as_ivec3());
+
+    let mut cells = Vec::new();
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
+            for z in min.z..=max.z {
+                let position = ChunkPos::new(x, y, z);
+                let Some((chunk, counts)) = dimension
+                    .published_chunk_entity(position)
+                    .and_then(|entity| chunks.get(entity).ok())
+                else {
+                    continue;
+                };
+                if counts.fluids == 0 {
+                    continue;
+                }
+                cells.extend(
+                    chunk
+                        .iter()
+                        .filter(|(cell, _)| cell.as_fluid().is_some_and(|fluid| !fluid.is_source()))
+                        .map(|(_, local)| position.block(local).world()),
+                );
+            }
+        }
+    }
+    cells
+}
+
+/// Groups flowing cells on a coarse grid, one cluster per occupied grid cell,
+/// centred on the middle of its cells.
+fn flowing_water_clusters(cells: impl IntoIterator<Item = WorldBlockPos>) -> Vec<WaterCluster> {
+    let mut sums = HashMap::<IVec3, (Vec3, usize)>::new();
+    for cell in cells {
+        let position = cell.as_ivec3();
+        let (sum, count) = sums
+            .entry(position.div_euclid(IVec3::splat(WATER_CLUSTER_SIZE)))
+            .or_default();
+        *sum += position.as_vec3() + Vec3::splat(0.5);
+        *count += 1;
+    }
+    sums.into_iter()
+        .map(|(key, (sum, cells))| WaterCluster {
+            key,
+            center: sum / cells as f32,
+            cells,
+        })
+        .collect()
+}
+
+/// The nearest clusters within audible range, at most one per voice.
+fn audible_water_clusters(clusters: &[WaterCluster], listener: Vec3) -> Vec<WaterCluster> {
+    let mut audible = clusters
+        .iter()
+        .copied()
+        .filter(|cluster| cluster.center.distance(listener) <= WATER_AUDIBLE_DISTANCE)
+        .collect::<Vec<_>>();
+    audible.sort_by(|a, b| {
+        a.center
+            .distance_squared(listener)
+            .total_cmp(&b.center.distance_squared(listener))
+            .then_with(|| a.key.to_array().cmp(&b.key.to_array()))
+    });
+    audible.truncate(MAX_WATER_VOICES);
+    audible
+}
+
+fn water_cluster_level(cluster: &WaterCluster, listener: Vec3) -> f32 {
+    let size = (cluster.cells as f32 / WATER_CLUSTER_FULL_CELLS as f32).min(1.0);
+    let edge = (WATER_AUDIBLE_DISTANCE - cluster.center.distance(listener)) / WATER_FADE_DISTANCE;
+    size * edge.clamp(0.0, 1.0)
+}
+
+/// Fades the cave and wind loops toward levels taken from the sky light and
+/// altitude at the listener's cell.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn update_listener_ambience(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<GameAudioSettings>,
+    sounds: Res<SoundBank>,
+    mut levels: ResMut<ListenerAmbienceLevels>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+    lights: Query<&ChunkLight>,
+    mut loops: Query<(Entity, &ListenerAmbience, Option<&mut AudioSink>)>,
+) {
+    let targets = match (listener, dimension) {
+        (Some(listener), Some(dimension)) => {
+            let position = listener.translation();
+            let address = WorldBlockPos::from_translation(position).split();
+            // Open sky is assumed outside loaded chunks, such as above the
+            // world's top.
+            let sky_light = dimension
+                .published_chunk_entity(address.chunk())
+                .and_then(|entity| lights.get(entity).ok())
+                .map_or(15, |light| light.sky_light(address.local()));
+            [cave_level(sky_light), wind_level(position.y, sky_light)]
+        }
+        _ => [0.0; 2],
+    };
+
+    let step = LISTENER_AMBIENCE_FADE_RATE * time.delta_secs();
+    let gain = settings.ambience_gain();
+    for (ambience, target) in ListenerAmbience::ALL.into_iter().zip(targets) {
+        let level = levels.get_mut(ambience);
+        *level += (target - *level).clamp(-step, step);
+        let volume = gain * ambience.base_level() * *level;
+
+        let existing = loops
+            .iter_mut()
+            .find(|(_, playing, _)| **playing == ambience);
+        match (existing, volume > 0.0) {
+            (None, true) => {
+                commands.spawn((
+                    Name::new(ambience.name()),
+                    ambience,
+                    AudioPlayer::new(ambience.sound(&sounds)),
+                    PlaybackSettings::LOOP.with_volume(Volume::Linear(volume)),
+                ));
+            }
+            (Some((entity, ..)), false) => commands.entity(entity).despawn(),
+            (Some((_, _, Some(mut sink))), true) => sink.set_volume(Volume::Linear(volume)),
+            (Some((_, _, None)), true) | (None, false) => {}
+        }
+    }
+}
+
+fn cave_level(sky_light: u8) -> f32 {
+    let open = f32::from(CAVE_OPEN_SKY_LIGHT);
+    let dark = f32::from(CAVE_DARK_SKY_LIGHT);
+    ((open - f32::from(sky_light)) / (open - dark)).clamp(0.0, 1.0)
+}
+
+/// Wind rises with altitude but only where the sky is open.
+fn wind_level(altitude: f32, sky_light: u8) -> f32 {
+    let height = (altitude - WIND_CALM_ALTITUDE) / (WIND_FULL_ALTITUDE - WIND_CALM_ALTITUDE);
+    height.clamp(0.0, 1.0) * f32::from(sky_light) / 15.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{chunk::ChunkCell, generation::WorldHeight};
+
+    fn cluster(key: IVec3, center: Vec3, cells: usize) -> WaterCluster {
+        WaterCluster { key, center, cells }
+    }
+
+    #[test]
+    fn flowing_cells_cluster_on_a_coarse_grid() {
+        let cells = [
+            WorldBlockPos::new(0, 0, 0),
+            WorldBlockPos::new(3, 0, 1),
+            WorldBlockPos::new(-1, 0, 0),
+        ];
+
+        let mut clusters = flowing_water_clusters(cells);
+        clusters.sort_by_key(|cluster| cluster.key.x);
+
+        assert_eq!(
+            clusters,
+            [
+                cluster(IVec3::new(-1, 0, 0), Vec3::new(-0.5, 0.5, 0.5), 1),
+                cluster(IVec3::ZERO, Vec3::new(2.0, 0.5, 1.0), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_the_nearest_clusters_in_range_get_voices() {
+        let clusters = (0..8)
+            .map(|i| cluster(IVec3::new(i, 0, 0), Vec3::new(i as f32 * 3.0, 0.0, 0.0), 4))
+            .collect::<Vec<_>>();
+
+        let audible = audible_water_clusters(&clusters, Vec3::new(9.0, 0.0, 0.0));
+        assert_eq!(
+            audible
+                .iter()
+                .map(|cluster| cluster.key.x)
+                .collect::<Vec<_>>(),
+            [3, 2, 4, 1],
+            "equidistant clusters break ties by key"
+        );
+
+        let far = audible_water_clusters(&clusters, Vec3::new(100.0, 0.0, 0.0));
+        assert!(far.is_empty());
+    }
+
+    #[test]
+    fn water_loops_scale_with_cluster_size_and_fade_before_the_cull_distance() {
+        let listener = Vec3::ZERO;
+        let small = cluster(IVec3::ZERO, Vec3::X * 2.0, WATER_CLUSTER_FULL_CELLS / 2);
+        let large = cluster(IVec3::ZERO, Vec3::X * 2.0, WATER_CLUSTER_FULL_CELLS * 2);
+        let edge = cluster(
+            IVec3::ZERO,
+            Vec3::X * (WATER_AUDIBLE_DISTANCE - WATER_FADE_DISTANCE / 2.0),
+            WATER_CLUSTER_FULL_CELLS,
+        );
+
+        assert_eq!(water_cluster_level(&small, listener), 0.5);
+        assert_eq!(water_cluster_level(&large, listener), 1.0);
+        assert_eq!(water_cluster_level(&edge, listener), 0.5);
+    }
+
+    #[test]
+    fn caves_follow_sky_light_and_wind_needs_altitude_and_open_sky() {
+        assert_eq!(cave_level(0), 1.0);
+        assert_eq!(cave_level(CAVE_DARK_SKY_LIGHT), 1.0);
+        assert!(cave_level(5) > 0.0 && cave_level(5) < 1.0);
+        assert_eq!(cave_level(CAVE_OPEN_SKY_LIGHT), 0.0);
+        assert_eq!(cave_level(15), 0.0);
+
+        assert_eq!(wind_level(WIND_CALM_ALTITUDE, 15), 0.0);
+        assert_eq!(wind_level(WIND_FULL_ALTITUDE + 10.0, 15), 1.0);
+        assert_eq!(wind_level(WIND_FULL_ALTITUDE, 0), 0.0);
+    }
+
+    fn ambience_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(GameAudioSettings::default())
+            .insert_resource(super::super::tests::sound_bank(0))
+            .init_resource::<WaterAmbienceScan>()
+            .init_resource::<ListenerAmbienceLevels>()
+            .add_systems(Update, (update_water_ambience, update_listener_ambience));
+        app
+    }
+
+    fn spawn_listener(app: &mut App, position: Vec3) -> Entity {
+        app.world_mut()
+            .spawn((
+                SpatialListener::default(),
+                GlobalTransform::from_translation(position),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn water_voices_follow_the_listener_and_are_capped() {
+        let mut app = ambience_app();
+        let mut chunk = Chunk::default();
+        // Six short streams, each in its own cluster: four in a row along X
+        // and two further back along Z.
+        let streams = [(0, 0), (4, 0), (8, 0), (12, 0), (0, 8), (4, 8)];
+        for (x, z) in streams {
+            for dz in 0..4 {
+                chunk.set_cell_xyz(x + 1, 2, z + dz, ChunkCell::water_flow(3));
+            }
+        }
+        chunk.set_cell_xyz(1, 6, 0, ChunkCell::water_source());
+        let counts = chunk.compute_content_counts();
+        let position = ChunkPos::new(0, 0, 0);
+        let entity = app.world_mut().spawn((chunk, counts)).id();
+        let mut dimension = Dimension::new_for_test(Entity::PLACEHOLDER, WorldHeight::default());
+        dimension.register_published_chunk(position, entity);
+        app.world_mut().spawn((dimension, Active));
+        let listener = spawn_listener(&mut app, Vec3::new(0.0, 3.0, 2.0));
+
+        app.update();
+        app.update();
+
+        let mut emitters = app
+            .world_mut()
+            .query::<(&WaterAmbience, &Transform, &PlaybackSettings)>();
+        let mut playing = emitters
+            .iter(app.world())
+            .map(|(emitter, transform, playback)| {
+                assert!(playback.spatial);
+                assert_eq!(playback.mode, bevy::audio::PlaybackMode::Loop);
+                (emitter.cluster, transform.translation)
+            })
+            .collect::<Vec<_>>();
+        playing.sort_by_key(|(cluster, _)| (cluster.z, cluster.x));
+        assert_eq!(
+            playing
+                .iter()
+                .map(|(cluster, _)| *cluster)
+                .collect::<Vec<_>>(),
+            [
+                IVec3::new(0, 0, 0),
+                IVec3::new(1, 0, 0),
+                IVec3::new(2, 0, 0),
+                IVec3::new(0, 0, 2),
+            ],
+            "the furthest clusters get no voice"
+        );
+        assert_eq!(playing[0].1, Vec3::new(1.5, 2.5, 2.0));
+
+        app.world_mut()
+            .entity_mut(listener)
+            .insert(GlobalTransform::from_translation(Vec3::new(
+                200.0, 3.0, 2.0,
+            )));
+        app.update();
+        app.update();
+        assert_eq!(emitters.iter(app.world()).count(), 0);
+    }
+
+    #[test]
+    fn cave_loop_fades_in_underground_and_out_again_in_the_open() {
+        let mut app = ambience_app();
+        // A fresh light volume is dark throughout.
+        let dark = ChunkLight::default();
+        let position = ChunkPos::new(0, 0, 0);
+        let entity = app.world_mut().spawn(dark).id();
+        let mut dimension = Dimension::new_for_test(Entity::PLACEHOLDER, WorldHeight::default());
+        dimension.register_published_chunk(position, entity);
+        app.world_mut().spawn((dimension, Active));
+        let listener = spawn_listener(&mut app, Vec3::new(8.5, 8.5, 8.5));
+        let mut loops = app
+            .world_mut()
+            .query::<(&ListenerAmbience, &PlaybackSettings)>();
+
+        app.update();
+        app.update();
+        let playing = loops
+            .iter(app.world())
+            .map(|(ambience, playback)| {
+                assert!(!playback.spatial);
+                *ambience
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(playing, [ListenerAmbience::Cave]);
+
+        app.world_mut()
+            .entity_mut(listener)
+            .insert(GlobalTransform::from_translation(Vec3::new(
+                8.5, 200.0, 8.5,
+            )));
+        app.world_mut()
+            .resource_mut::<ListenerAmbienceLevels>()
+            .cave = 0.0;
+        app.update();
+        let playing = loops
+            .iter(app.world())
+            .map(|(ambience, _)| *ambience)
+            .collect::<Vec<_>>();
+        assert_eq!(playing, [ListenerAmbience::Wind]);
+    }
+}
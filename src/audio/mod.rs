@@ -0,0 +1,1061 @@
+use bevy::{
+    audio::{
+        AudioPlayer, AudioSink, AudioSinkPlayback, AudioSource, PlaybackSettings, SpatialListener,
+        Volume,
+    },
+    platform::collections::HashMap,
+    prelude::*,
+};
+use bevy_settings::{ReflectSettingsGroup, SettingsGroup};
+
+use crate::{
+    block::BlockSoundGroup,
+    game_state::GameState,
+    item::ItemPickedUp,
+    mob::controller::{Footstep, FootstepKind},
+    player::{
+        Player,
+        cam::MouseCam,
+        interaction::{BlockEditCommitted, BlockEditKind},
+    },
+    weather::Weather,
+    world::{
+        chunk::{Chunk, ChunkCell, WorldBlockPos},
+        dimension::{Active, Dimension},
+    },
+};
+
+mod ambient;
+mod music;
+mod occlusion;
+
+pub use music::{MUSIC_PLAYLIST, MusicSituation, MusicTrack};
+use occlusion::{ReverbEcho, SoundEnvironment};
+
+pub const ITEM_PICKUP_SOUND_PATHS: &[&str] = &["audio/item/pickup.ogg"];
+pub const RAIN_LOOP_SOUND_PATH: &str = "audio/ambient/rain.ogg";
+pub const WATER_FLOW_LOOP_SOUND_PATH: &str = "audio/ambient/water_flow.ogg";
+pub const CAVE_LOOP_SOUND_PATH: &str = "audio/ambient/cave.ogg";
+pub const WIND_LOOP_SOUND_PATH: &str = "audio/ambient/wind.ogg";
+
+const SPATIAL_LISTENER_EAR_GAP: f32 = 0.2;
+/// How far below a character's feet to look for the block it stands on.
+const FOOTING_PROBE_DEPTH: f32 = 0.05;
+/// Slower landings, such as stepping down a ledge, make no sound.
+const AUDIBLE_LANDING_SPEED: f32 = 4.0;
+/// Landings from roughly three blocks or higher play the fall sound instead
+/// of a step.
+const HARD_LANDING_SPEED: f32 = 13.0;
+/// Rain loop level in plain rain; thunderstorms raise it to full volume.
+const RAIN_AMBIENCE_LEVEL: f32 = 0.6;
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameAudioSettings>()
+            .init_resource::<SoundBank>()
+            .init_resource::<VariantCursor>()
+            .init_resource::<music::MusicDirector>()
+            .add_message::<BlockEditCommitted>()
+            .add_message::<Footstep>()
+            .add_message::<ItemPickedUp>()
+            .add_message::<PlaySound>()
+            .add_systems(
+                Update,
+                (
+                    attach_spatial_listener,
+                    update_rain_ambience,
+                    ambient::update_water_ambience,
+                    ambient::update_listener_ambience,
+                    occlusion::release_reverb_echoes,
+                    music::direct_music
+                        .after(ambient::update_listener_ambience)
+                        .run_if(not(in_state(GameState::Paused))),
+                ),
+            )
+            .add_systems(OnEnter(GameState::Paused), music::pause_music)
+            .add_systems(OnExit(GameState::Paused), music::resume_music)
+            .add_systems(
+                Update,
+                (
+                    request_block_edit_sounds,
+                    request_footstep_sounds,
+                    request_item_pickup_sounds,
+                    play_requested_sounds,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// User-facing audio levels. These are registered with `bevy-settings` by the
+/// application before its settings plugin is installed.
+///
+/// Each field is a linear gain in the inclusive range `0.0..=1.0`. Values are
+/// sanitized at playback as a final guard against hand-edited settings files.
+#[derive(Resource, SettingsGroup, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, SettingsGroup, Default)]
+pub struct GameAudioSettings {
+    pub master_volume: f32,
+    pub sound_effects_volume: f32,
+    pub ambience_volume: f32,
+    pub music_volume: f32,
+}
+
+impl Default for GameAudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            sound_effects_volume: 0.8,
+            ambience_volume: 0.7,
+            music_volume: 0.5,
+        }
+    }
+}
+
+impl GameAudioSettings {
+    fn sound_effect_gain(self) -> f32 {
+        sanitized_gain(self.master_volume) * sanitized_gain(self.sound_effects_volume)
+    }
+
+    fn ambience_gain(self) -> f32 {
+        sanitized_gain(self.master_volume) * sanitized_gain(self.ambience_volume)
+    }
+
+    fn music_gain(self) -> f32 {
+        sanitized_gain(self.master_volume) * sanitized_gain(self.music_volume)
+    }
+}
+
+fn sanitized_gain(value: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// What a block was doing when it made a sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockSoundAction {
+    Break,
+    Place,
+    Step,
+    Fall,
+}
+
+impl BlockSoundAction {
+    pub const COUNT: usize = 4;
+    pub const ALL: [Self; Self::COUNT] = [Self::Break, Self::Place, Self::Step, Self::Fall];
+}
+
+/// Asset variants for one block sound group and action.
+///
+/// Falls reuse the step variants at a lower pitch, and ice shatters like
+/// glass when broken.
+pub const fn block_sound_paths(
+    group: BlockSoundGroup,
+    action: BlockSoundAction,
+) -> &'static [&'static str] {
+    use BlockSoundAction::{Break, Fall, Place, Step};
+
+    match (group, action) {
+        (BlockSoundGroup::Stone, Break) => &["audio/block/rock_break.ogg"],
+        (BlockSoundGroup::Stone, Place) => &["audio/block/small_rock_impact.ogg"],
+        (BlockSoundGroup::Stone, Step | Fall) => &[
+            "audio/block/stone/step1.ogg",
+            "audio/block/stone/step2.ogg",
+            "audio/block/stone/step3.ogg",
+        ],
+        (BlockSoundGroup::Wood, Break | Place) => {
+            &["audio/block/wood/dig1.ogg", "audio/block/wood/dig2.ogg"]
+        }
+        (BlockSoundGroup::Wood, Step | Fall) => &[
+            "audio/block/wood/step1.ogg",
+            "audio/block/wood/step2.ogg",
+            "audio/block/wood/step3.ogg",
+        ],
+        (BlockSoundGroup::Grass, Break | Place) => {
+            &["audio/block/grass/dig1.ogg", "audio/block/grass/dig2.ogg"]
+        }
+        (BlockSoundGroup::Grass, Step | Fall) => &[
+            "audio/block/grass/step1.ogg",
+            "audio/block/grass/step2.ogg",
+            "audio/block/grass/step3.ogg",
+        ],
+        (BlockSoundGroup::Sand, Break | Place) => {
+            &["audio/block/sand/dig1.ogg", "audio/block/sand/dig2.ogg"]
+        }
+        (BlockSoundGroup::Sand, Step | Fall) => &[
+            "audio/block/sand/step1.ogg",
+            "audio/block/sand/step2.ogg",
+            "audio/block/sand/step3.ogg",
+        ],
+        (BlockSoundGroup::Glass | BlockSoundGroup::Ice, Break) => &[
+            "audio/block/glass/break1.ogg",
+            "audio/block/glass/break2.ogg",
+        ],
+        (BlockSoundGroup::Glass, Place) => {
+            &["audio/block/glass/dig1.ogg", "audio/block/glass/dig2.ogg"]
+        }
+        (BlockSoundGroup::Glass, Step | Fall) => &[
+            "audio/block/glass/step1.ogg",
+            "audio/block/glass/step2.ogg",
+            "audio/block/glass/step3.ogg",
+        ],
+        (BlockSoundGroup::Ice, Place) => &["audio/block/ice/dig1.ogg", "audio/block/ice/dig2.ogg"],
+        (BlockSoundGroup::Ice, Step | Fall) => &[
+            "audio/block/ice/step1.ogg",
+            "audio/block/ice/step2.ogg",
+            "audio/block/ice/step3.ogg",
+        ],
+    }
+}
+
+/// A semantic sound identifier. Gameplay code asks for a cue rather than
+/// depending on an asset path or Bevy audio component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundCue {
+    Block {
+        group: BlockSoundGroup,
+        action: BlockSoundAction,
+    },
+    ItemPickup,
+}
+
+impl SoundCue {
+    pub const fn block(group: BlockSoundGroup, action: BlockSoundAction) -> Self {
+        Self::Block { group, action }
+    }
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        BlockSoundGroup::ALL
+            .into_iter()
+            .flat_map(|group| {
+                BlockSoundAction::ALL
+                    .into_iter()
+                    .map(move |action| Self::block(group, action))
+            })
+            .chain([Self::ItemPickup])
+    }
+
+    pub const fn sound_paths(self) -> &'static [&'static str] {
+        match self {
+            Self::Block { group, action } => block_sound_paths(group, action),
+            Self::ItemPickup => ITEM_PICKUP_SOUND_PATHS,
+        }
+    }
+
+    const fn playback_speed(self) -> f32 {
+        match self {
+            // The CC0 source effects are longer, lower-pitched recordings than
+            // the short, bright impacts expected for voxel interactions.
+            Self::Block {
+                group: BlockSoundGroup::Stone,
+                action: BlockSoundAction::Break | BlockSoundAction::Place,
+            } => 1.35,
+            // A landing is a heavier, deeper footstep.
+            Self::Block {
+                action: BlockSoundAction::Fall,
+                ..
+            } => 0.75,
+            Self::Block { .. } => 1.0,
+            // Minecraft applies pitch at playback; the raw pop asset sounds
+            // roughly an octave too low when played at Bevy's default 1.0x.
+            Self::ItemPickup => 2.0,
+        }
+    }
+
+    /// Level relative to the sound-effect gain, keeping frequent footsteps
+    /// under deliberate edits.
+    const fn volume(self) -> f32 {
+        match self {
+            Self::Block {
+                action: BlockSoundAction::Step,
+                ..
+            } => 0.35,
+            Self::Block {
+                action: BlockSoundAction::Fall,
+                ..
+            } => 0.8,
+            Self::Block { .. } | Self::ItemPickup => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoundEmitter {
+    Listener,
+    World(Vec3),
+}
+
+/// Reusable request boundary for one-shot sounds. Future UI, mobs, and ambient
+/// systems can publish these without knowing how sounds are loaded or played.
+#[derive(Message, Debug, Clone, Copy, PartialEq)]
+pub struct PlaySound {
+    pub cue: SoundCue,
+    pub emitter: SoundEmitter,
+}
+
+impl PlaySound {
+    pub const fn at(cue: SoundCue, position: Vec3) -> Self {
+        Self {
+            cue,
+            emitter: SoundEmitter::World(position),
+        }
+    }
+
+    pub const fn at_listener(cue: SoundCue) -> Self {
+        Self {
+            cue,
+            emitter: SoundEmitter::Listener,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SoundBank {
+    cues: HashMap<SoundCue, Vec<Handle<AudioSource>>>,
+    rain_loop: Handle<AudioSource>,
+    water_loop: Handle<AudioSource>,
+    cave_loop: Handle<AudioSource>,
+    wind_loop: Handle<AudioSource>,
+    /// One handle per [`MUSIC_PLAYLIST`] entry.
+    music: Vec<Handle<AudioSource>>,
+}
+
+impl FromWorld for SoundBank {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self {
+            cues: SoundCue::all()
+                .map(|cue| (cue, load_sound_variants(asset_server, cue.sound_paths())))
+                .collect(),
+            rain_loop: asset_server.load(RAIN_LOOP_SOUND_PATH),
+            water_loop: asset_server.load(WATER_FLOW_LOOP_SOUND_PATH),
+            cave_loop: asset_server.load(CAVE_LOOP_SOUND_PATH),
+            wind_loop: asset_server.load(WIND_LOOP_SOUND_PATH),
+            music: MUSIC_PLAYLIST
+                .iter()
+                .map(|track| asset_server.load(track.path))
+                .collect(),
+        }
+    }
+}
+
+impl SoundBank {
+    fn variants(&self, cue: SoundCue) -> &[Handle<AudioSource>] {
+        self.cues.get(&cue).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn load_sound_variants(
+    asset_server: &AssetServer,
+    paths: &'static [&'static str],
+) -> Vec<Handle<AudioSource>> {
+    paths.iter().map(|&path| asset_server.load(path)).collect()
+}
+
+#[derive(Resource, Default)]
+struct VariantCursor(HashMap<SoundCue, usize>);
+
+impl VariantCursor {
+    fn next(&mut self, cue: SoundCue, variant_count: usize) -> Option<usize> {
+        if variant_count == 0 {
+            return None;
+        }
+
+        let cursor = self.0.entry(cue).or_default();
+        let selected = *cursor % variant_count;
+        *cursor = cursor.wrapping_add(1);
+        Some(selected)
+    }
+}
+
+fn attach_spatial_listener(
+    mut commands: Commands,
+    listeners: Query<(), With<SpatialListener>>,
+    cameras: Query<Entity, (With<MouseCam>, Without<SpatialListener>)>,
+) {
+    if !listeners.is_empty() {
+        return;
+    }
+    let Some(camera) = cameras.iter().next() else {
+        return;
+    };
+
+    commands
+        .entity(camera)
+        .insert(SpatialListener::new(SPATIAL_LISTENER_EAR_GAP));
+}
+
+/// Marks the looping rain sound. Rain surrounds the player, so the loop plays
+/// at the listener rather than from a world position.
+#[derive(Component)]
+struct RainAmbience;
+
+fn rain_ambience_volume(weather: &Weather, settings: GameAudioSettings) -> f32 {
+    let storm = RAIN_AMBIENCE_LEVEL + (1.0 - RAIN_AMBIENCE_LEVEL) * weather.thunder;
+    settings.ambience_gain() * weather.precipitation * storm
+}
+
+/// Starts the rain loop when precipitation begins, follows its fade through
+/// the sink volume, and stops it once the sky clears.
+fn update_rain_ambience(
+    mut commands: Commands,
+    weather: Option<Res<Weather>>,
+    settings: Res<GameAudioSettings>,
+    sounds: Res<SoundBank>,
+    mut loops: Query<(Entity, Option<&mut AudioSink>), With<RainAmbience>>,
+) {
+    let volume = weather.map_or(0.0, |weather| rain_ambience_volume(&weather, *settings));
+    match (loops.iter_mut().next(), volume > 0.0) {
+        (None, true) => {
+            commands.spawn((
+                Name::new("Rain ambience"),
+                RainAmbience,
+                AudioPlayer::new(sounds.rain_loop.clone()),
+                PlaybackSettings::LOOP.with_volume(Volume::Linear(volume)),
+            ));
+        }
+        (Some((entity, _)), false) => commands.entity(entity).despawn(),
+        (Some((_, Some(mut sink))), true) => sink.set_volume(Volume::Linear(volume)),
+        (Some((_, None)), true) | (None, false) => {}
+    }
+}
+
+fn request_block_edit_sounds(
+    mut edits: MessageReader<BlockEditCommitted>,
+    mut sounds: MessageWriter<PlaySound>,
+) {
+    for edit in edits.read() {
+        let Some(cue) = cue_for_block_edit(edit) else {
+            continue;
+        };
+        let position = edit.position.world().as_ivec3().as_vec3() + Vec3::splat(0.5);
+        sounds.write(PlaySound::at(cue, position));
+    }
+}
+
+/// Plays step and landing sounds for the block beneath each footstep.
+fn request_footstep_sounds(
+    mut footsteps: MessageReader<Footstep>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+    chunks: Query<&Chunk>,
+    mut sounds: MessageWriter<PlaySound>,
+) {
+    let Some(dimension) = dimension else {
+        footsteps.clear();
+        return;
+    };
+
+    for footstep in footsteps.read() {
+        let Some(action) = footstep_action(footstep.kind) else {
+            continue;
+        };
+        let footing =
+            WorldBlockPos::from_translation(footstep.feet - Vec3::Y * FOOTING_PROBE_DEPTH).split();
+        let Some(cell) = dimension
+            .published_chunk_entity(footing.chunk())
+            .and_then(|entity| chunks.get(entity).ok())
+            .map(|chunk| chunk.cell(footing.local()))
+        else {
+            continue;
+        };
+        if let Some(cue) = block_cue(cell, action) {
+            sounds.write(PlaySound::at(cue, footstep.feet));
+        }
+    }
+}
+
+fn footstep_action(kind: FootstepKind) -> Option<BlockSoundAction> {
+    match kind {
+        FootstepKind::Step => Some(BlockSoundAction::Step),
+        FootstepKind::Landing { impact_speed } if impact_speed >= HARD_LANDING_SPEED => {
+            Some(BlockSoundAction::Fall)
+        }
+        FootstepKind::Landing { impact_speed } if impact_speed >= AUDIBLE_LANDING_SPEED => {
+            Some(BlockSoundAction::Step)
+        }
+        FootstepKind::Landing { .. } => None,
+    }
+}
+
+fn request_item_pickup_sounds(
+    mut pickups: MessageReader<ItemPickedUp>,
+    players: Query<&Transform, With<Player>>,
+    mut sounds: MessageWriter<PlaySound>,
+) {
+    for pickup in pickups.read() {
+        let Ok(transform) = players.get(pickup.player) else {
+            continue;
+        };
+        sounds.write(PlaySound::at(SoundCue::ItemPickup, transform.translation));
+    }
+}
+
+fn cue_for_block_edit(edit: &BlockEditCommitted) -> Option<SoundCue> {
+    match edit.kind {
+        BlockEditKind::Break => block_cue(edit.delta.old, BlockSoundAction::Break),
+        BlockEditKind::Place => block_cue(edit.delta.new, BlockSoundAction::Place),
+    }
+}
+
+/// The cue for `action` on a block cell; fluids and air have no block sounds.
+fn block_cue(cell: ChunkCell, action: BlockSoundAction) -> Option<SoundCue> {
+    let group = cell.as_block()?.sound_group()?;
+    Some(SoundCue::block(group, action))
+}
+
+/// Spawns one voice per request. World sounds are muffled by the terrain
+/// between them and the listener, and enclosed spaces add a delayed
+/// reflection.
+#[allow(clippy::too_many_arguments)]
+fn play_requested_sounds(
+    mut commands: Commands,
+    mut requests: MessageReader<PlaySound>,
+    sounds: Res<SoundBank>,
+    settings: Res<GameAudioSettings>,
+    mut variants: ResMut<VariantCursor>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+    chunks: Query<&Chunk>,
+) {
+    let gain = settings.sound_effect_gain();
+    if gain == 0.0 {
+        requests.clear();
+        return;
+    }
+    let cell_at = |position: WorldBlockPos| {
+        let address = position.split();
+        dimension
+            .as_ref()?
+            .published_chunk_entity(address.chunk())
+            .and_then(|entity| chunks.get(entity).ok())
+            .map(|chunk| chunk.cell(address.local()))
+    };
+
+    for request in requests.read().copied() {
+        let available = sounds.variants(request.cue);
+        let Some(index) = variants.next(request.cue, available.len()) else {
+            warn!(?request.cue, "sound cue has no configured asset variants");
+            continue;
+        };
+
+        let volume = gain * request.cue.volume();
+        let playback = PlaybackSettings::DESPAWN.with_speed(request.cue.playback_speed());
+        let SoundEmitter::World(position) = request.emitter else {
+            commands.spawn((
+                AudioPlayer::new(available[index].clone()),
+                playback.with_volume(Volume::Linear(volume)),
+            ));
+            continue;
+        };
+
+        let environment = match (&listener, &dimension) {
+            (Some(listener), Some(_)) => {
+                SoundEnvironment::between(position, listener.translation(), cell_at)
+            }
+            _ => SoundEnvironment::OPEN,
+        };
+        let playback = playback.with_spatial(true);
+        commands.spawn((
+            AudioPlayer::new(available[index].clone()),
+            playback.with_volume(Volume::Linear(volume * environment.direct_gain())),
+            Transform::from_translation(position),
+        ));
+        if let Some(reverb) = environment.reverb_gain() {
+            commands.spawn((
+                AudioPlayer::new(available[index].clone()),
+                PlaybackSettings {
+                    paused: true,
+                    ..playback.with_volume(Volume::Linear(volume * reverb))
+                },
+                Transform::from_translation(position),
+                ReverbEcho {
+                    delay: Timer::from_seconds(occlusion::REVERB_DELAY_SECS, TimerMode::Once),
+                },
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bevy::audio::Decodable;
+
+    use crate::{
+        item::{Item, ItemStack},
+        player::interaction::{BlockEditCommitted, BlockEditKind},
+        world::{
+            chunk::{CellDelta, ChunkBlockPos, ChunkCell, ChunkPos, WorldBlockPos},
+            generation::WorldHeight,
+        },
+    };
+
+    use super::*;
+
+    pub(super) fn sound_bank(variants: usize) -> SoundBank {
+        SoundBank {
+            cues: SoundCue::all()
+                .map(|cue| (cue, vec![Handle::default(); variants]))
+                .collect(),
+            rain_loop: Handle::default(),
+            water_loop: Handle::default(),
+            cave_loop: Handle::default(),
+            wind_loop: Handle::default(),
+            music: vec![Handle::default(); MUSIC_PLAYLIST.len()],
+        }
+    }
+
+    fn edit(kind: BlockEditKind, old: ChunkCell, new: ChunkCell) -> BlockEditCommitted {
+        BlockEditCommitted {
+            kind,
+            position: ChunkBlockPos::default(),
+            delta: CellDelta { old, new },
+        }
+    }
+
+    #[test]
+    fn block_edit_cues_follow_committed_material_changes() {
+        assert_eq!(
+            cue_for_block_edit(&edit(
+                BlockEditKind::Break,
+                Item::Stone.into(),
+                ChunkCell::EMPTY,
+            )),
+            Some(SoundCue::block(
+                BlockSoundGroup::Stone,
+                BlockSoundAction::Break
+            ))
+        );
+        assert_eq!(
+            cue_for_block_edit(&edit(
+                BlockEditKind::Place,
+                ChunkCell::EMPTY,
+                Item::Dirt.into(),
+            )),
+            Some(SoundCue::block(
+                BlockSoundGroup::Grass,
+                BlockSoundAction::Place
+            ))
+        );
+        assert_eq!(
+            cue_for_block_edit(&edit(
+                BlockEditKind::Break,
+                Item::Glass.into(),
+                ChunkCell::EMPTY,
+            )),
+            Some(SoundCue::block(
+                BlockSoundGroup::Glass,
+                BlockSoundAction::Break
+            ))
+        );
+        assert_eq!(
+            cue_for_block_edit(&edit(
+                BlockEditKind::Place,
+                ChunkCell::EMPTY,
+                ChunkCell::water_source(),
+            )),
+            None,
+            "fluid placement needs its own material-appropriate cue"
+        );
+    }
+
+    #[test]
+    fn sound_effect_gain_sanitizes_persisted_settings() {
+        assert_eq!(GameAudioSettings::default().sound_effect_gain(), 0.8);
+        assert_eq!(
+            GameAudioSettings {
+                master_volume: 2.0,
+                sound_effects_volume: 0.5,
+                ..default()
+            }
+            .sound_effect_gain(),
+            0.5
+        );
+        assert_eq!(
+            GameAudioSettings {
+                master_volume: f32::NAN,
+                sound_effects_volume: 1.0,
+                ..default()
+            }
+            .sound_effect_gain(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn configured_sound_assets_are_bundled_and_decodable() {
+        let asset_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        for path in SoundCue::all()
+            .flat_map(SoundCue::sound_paths)
+            .chain([
+                &RAIN_LOOP_SOUND_PATH,
+                &WATER_FLOW_LOOP_SOUND_PATH,
+                &CAVE_LOOP_SOUND_PATH,
+                &WIND_LOOP_SOUND_PATH,
+            ])
+            .chain(MUSIC_PLAYLIST.iter().map(|track| &track.path))
+        {
+            let bytes = std::fs::read(asset_root.join(path))
+                .unwrap_or_else(|error| panic!("could not read audio asset {path}: {error}"));
+            let source = AudioSource {
+                bytes: bytes.into(),
+            };
+            assert!(
+                source.decoder().next().is_some(),
+                "audio asset did not decode any samples: {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn every_block_sound_group_has_variants_for_every_action() {
+        assert_eq!(
+            SoundCue::all().count(),
+            BlockSoundGroup::COUNT * BlockSoundAction::COUNT + 1
+        );
+        for cue in SoundCue::all() {
+            assert!(!cue.sound_paths().is_empty(), "{cue:?} has no variants");
+        }
+        assert_ne!(
+            block_sound_paths(BlockSoundGroup::Glass, BlockSoundAction::Break),
+            block_sound_paths(BlockSoundGroup::Stone, BlockSoundAction::Break)
+        );
+    }
+
+    #[test]
+    fn variant_selection_cycles_without_an_rng_dependency() {
+        let stone_break = SoundCue::block(BlockSoundGroup::Stone, BlockSoundAction::Break);
+        let sand_step = SoundCue::block(BlockSoundGroup::Sand, BlockSoundAction::Step);
+        let mut cursor = VariantCursor::default();
+        assert_eq!(cursor.next(stone_break, 3), Some(0));
+        assert_eq!(cursor.next(stone_break, 3), Some(1));
+        assert_eq!(cursor.next(sand_step, 3), Some(0));
+        assert_eq!(cursor.next(stone_break, 3), Some(2));
+        assert_eq!(cursor.next(stone_break, 3), Some(0));
+        assert_eq!(cursor.next(sand_step, 0), None);
+        assert_eq!(cursor.next(SoundCue::ItemPickup, 1), Some(0));
+    }
+
+    #[test]
+    fn sound_cues_apply_intentional_playback_pitch() {
+        use BlockSoundAction::{Break, Fall, Place, Step};
+
+        assert_eq!(
+            SoundCue::block(BlockSoundGroup::Stone, Break).playback_speed(),
+            1.35
+        );
+        assert_eq!(
+            SoundCue::block(BlockSoundGroup::Stone, Place).playback_speed(),
+            1.35
+        );
+        assert_eq!(
+            SoundCue::block(BlockSoundGroup::Wood, Place).playback_speed(),
+            1.0
+        );
+        assert_eq!(
+            SoundCue::block(BlockSoundGroup::Stone, Step).playback_speed(),
+            1.0
+        );
+        assert_eq!(
+            SoundCue::block(BlockSoundGroup::Stone, Fall).playback_speed(),
+            0.75
+        );
+        assert_eq!(SoundCue::ItemPickup.playback_speed(), 2.0);
+        assert!(
+            SoundCue::block(BlockSoundGroup::Grass, Step).volume()
+                < SoundCue::block(BlockSoundGroup::Grass, Break).volume()
+        );
+    }
+
+    #[test]
+    fn landings_step_or_fall_by_impact_speed() {
+        let landing = |impact_speed| footstep_action(FootstepKind::Landing { impact_speed });
+        assert_eq!(
+            footstep_action(FootstepKind::Step),
+            Some(BlockSoundAction::Step)
+        );
+        assert_eq!(landing(1.6), None);
+        assert_eq!(landing(8.0), Some(BlockSoundAction::Step));
+        assert_eq!(landing(HARD_LANDING_SPEED), Some(BlockSoundAction::Fall));
+    }
+
+    #[test]
+    fn footsteps_sound_like_the_block_beneath_the_feet() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<Footstep>()
+            .add_message::<PlaySound>()
+            .add_systems(Update, request_footstep_sounds);
+
+        let mut chunk = Chunk::default();
+        chunk.set_cell_xyz(2, 4, 3, Item::Sand.into());
+        chunk.set_cell_xyz(5, 4, 3, Item::OakLog.into());
+        let chunk = app.world_mut().spawn(chunk).id();
+        let mut dimension = Dimension::new_for_test(Entity::PLACEHOLDER, WorldHeight::default());
+        dimension.register_published_chunk(ChunkPos::new(0, 0, 0), chunk);
+        app.world_mut().spawn((dimension, Active));
+
+        let player = app.world_mut().spawn_empty().id();
+        let footsteps = [
+            (Vec3::new(2.5, 5.015, 3.5), FootstepKind::Step),
+            (
+                Vec3::new(5.5, 5.015, 3.5),
+                FootstepKind::Landing {
+                    impact_speed: HARD_LANDING_SPEED + 1.0,
+                },
+            ),
+            (Vec3::new(8.5, 5.015, 3.5), FootstepKind::Step),
+        ];
+        for (feet, kind) in footsteps {
+            app.world_mut().write_message(Footstep {
+                entity: player,
+                feet,
+                kind,
+            });
+        }
+
+        app.update();
+
+        let sounds = app
+            .world()
+            .resource::<Messages<PlaySound>>()
+            .iter_current_update_messages()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sounds,
+            vec![
+                PlaySound::at(
+                    SoundCue::block(BlockSoundGroup::Sand, BlockSoundAction::Step),
+                    Vec3::new(2.5, 5.015, 3.5),
+                ),
+                PlaySound::at(
+                    SoundCue::block(BlockSoundGroup::Wood, BlockSoundAction::Fall),
+                    Vec3::new(5.5, 5.015, 3.5),
+                ),
+            ],
+            "footsteps over air stay silent"
+        );
+    }
+
+    #[test]
+    fn walled_off_sounds_are_muffled_and_enclosed_sounds_echo() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<PlaySound>()
+            .insert_resource(sound_bank(1))
+            .insert_resource(GameAudioSettings::default())
+            .init_resource::<VariantCursor>()
+            .add_systems(Update, play_requested_sounds);
+
+        // A sealed stone box split by a stone wall at x = 8.
+        let mut chunk = Chunk::default();
+        for x in 2..=14 {
+            for y in 2..=10 {
+                for z in 2..=10 {
+                    let shell = x == 2 || x == 14 || y == 2 || y == 10 || z == 2 || z == 10;
+                    if shell || x == 8 {
+                        chunk.set_cell_xyz(x, y, z, Item::Stone.into());
+                    }
+                }
+            }
+        }
+        let chunk = app.world_mut().spawn(chunk).id();
+        let mut dimension = Dimension::new_for_test(Entity::PLACEHOLDER, WorldHeight::default());
+        dimension.register_published_chunk(ChunkPos::new(0, 0, 0), chunk);
+        app.world_mut().spawn((dimension, Active));
+        app.world_mut().spawn((
+            SpatialListener::default(),
+            GlobalTransform::from_translation(Vec3::new(11.5, 6.5, 6.5)),
+        ));
+
+        let cue = SoundCue::block(BlockSoundGroup::Wood, BlockSoundAction::Place);
+        app.world_mut()
+            .write_message(PlaySound::at(cue, Vec3::new(5.5, 6.5, 6.5)));
+        app.update();
+
+        let mut voices = app
+            .world_mut()
+            .query::<(&PlaybackSettings, Has<ReverbEcho>)>();
+        let voices = voices
+            .iter(app.world())
+            .map(|(playback, echo)| (playback.volume, playback.paused, echo))
+            .collect::<Vec<_>>();
+        let gain = GameAudioSettings::default().sound_effect_gain();
+        let direct = voices
+            .iter()
+            .find(|(_, _, echo)| !echo)
+            .expect("the direct voice plays");
+        assert!(!direct.1);
+        assert!(
+            direct.0.to_linear() < gain * 0.2,
+            "the wall muffles the sound"
+        );
+        let echo = voices
+            .iter()
+            .find(|(_, _, echo)| *echo)
+            .expect("the enclosed room adds a reflection");
+        assert!(echo.1, "the reflection waits for its delay");
+        assert_eq!(voices.len(), 2);
+    }
+
+    #[test]
+    fn only_one_spatial_listener_is_attached() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems(Update, attach_spatial_listener);
+        app.world_mut().spawn(MouseCam);
+        app.world_mut().spawn(MouseCam);
+
+        app.update();
+
+        let listener_count = app
+            .world_mut()
+            .query_filtered::<Entity, With<SpatialListener>>()
+            .iter(app.world())
+            .count();
+        assert_eq!(listener_count, 1);
+    }
+
+    #[test]
+    fn committed_edits_schedule_one_spatial_sound_each_without_replaying() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<BlockEditCommitted>()
+            .add_message::<PlaySound>()
+            .insert_resource(sound_bank(1))
+            .insert_resource(GameAudioSettings::default())
+            .init_resource::<VariantCursor>()
+            .add_systems(
+                Update,
+                (request_block_edit_sounds, play_requested_sounds).chain(),
+            );
+
+        let edits = [
+            BlockEditCommitted {
+                kind: BlockEditKind::Break,
+                position: WorldBlockPos::new(-2, 3, 4).split(),
+                delta: CellDelta {
+                    old: Item::Stone.into(),
+                    new: ChunkCell::EMPTY,
+                },
+            },
+            BlockEditCommitted {
+                kind: BlockEditKind::Place,
+                position: WorldBlockPos::new(8, -1, 6).split(),
+                delta: CellDelta {
+                    old: ChunkCell::EMPTY,
+                    new: Item::Dirt.into(),
+                },
+            },
+        ];
+        for edit in edits {
+            app.world_mut().write_message(edit);
+        }
+
+        app.update();
+
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(&Transform, &PlaybackSettings), With<AudioPlayer<AudioSource>>>();
+        let emitters = query
+            .iter(app.world())
+            .map(|(transform, playback)| {
+                assert!(playback.spatial);
+                (transform.translation, playback.speed)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(emitters.len(), 2);
+        assert!(emitters.contains(&(Vec3::new(-1.5, 3.5, 4.5), 1.35)));
+        assert!(emitters.contains(&(Vec3::new(8.5, -0.5, 6.5), 1.0)));
+
+        app.update();
+        assert_eq!(query.iter(app.world()).count(), 2);
+    }
+
+    #[test]
+    fn rain_ambience_follows_precipitation_and_storms() {
+        let settings = GameAudioSettings::default();
+        let mut weather = Weather::default();
+        assert_eq!(rain_ambience_volume(&weather, settings), 0.0);
+
+        weather.precipitation = 1.0;
+        let rain = rain_ambience_volume(&weather, settings);
+        weather.thunder = 1.0;
+        let storm = rain_ambience_volume(&weather, settings);
+        assert!(rain > 0.0 && storm > rain);
+        assert_eq!(storm, settings.ambience_gain());
+    }
+
+    #[test]
+    fn rain_loop_starts_with_precipitation_and_stops_when_clear() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(sound_bank(0))
+            .insert_resource(GameAudioSettings::default())
+            .init_resource::<Weather>()
+            .add_systems(Update, update_rain_ambience);
+        let mut loops = app
+            .world_mut()
+            .query_filtered::<&PlaybackSettings, With<RainAmbience>>();
+
+        app.update();
+        assert_eq!(loops.iter(app.world()).count(), 0);
+
+        app.world_mut().resource_mut::<Weather>().precipitation = 0.5;
+        app.update();
+        app.update();
+        let playback = loops.single(app.world()).unwrap();
+        assert_eq!(playback.mode, bevy::audio::PlaybackMode::Loop);
+        assert!(!playback.spatial);
+
+        app.world_mut().resource_mut::<Weather>().precipitation = 0.0;
+        app.update();
+        assert_eq!(loops.iter(app.world()).count(), 0);
+    }
+
+    #[test]
+    fn item_pickup_schedules_sound_at_the_player() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<ItemPickedUp>()
+            .add_message::<PlaySound>()
+            .add_systems(Update, request_item_pickup_sounds);
+
+        let player = app
+            .world_mut()
+            .spawn((Player::default(), Transform::from_xyz(3.0, 4.0, 5.0)))
+            .id();
+        let item = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(ItemPickedUp {
+            player,
+            item,
+            stack: ItemStack {
+                item: Item::Dirt,
+                count: 1,
+            },
+        });
+
+        app.update();
+
+        let sounds = app
+            .world()
+            .resource::<Messages<PlaySound>>()
+            .iter_current_update_messages()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sounds,
+            vec![PlaySound::at(
+                SoundCue::ItemPickup,
+                Vec3::new(3.0, 4.0, 5.0),
+            )]
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use bevy::{
+    audio::{AudioPlayer, AudioSink, AudioSinkPlayback, PlaybackSettings, Volume},
+    prelude::*,
+};
+
+use crate::{
+    light::DayNightCycle,
+    player::{GameMode, Player},
+    world::generation::SplitMix64,
+};
+
+use super::{GameAudioSettings, SoundBank, ambient::ListenerAmbienceLevels};
+
+/// Every music track and the situation it is written for.
+pub const MUSIC_PLAYLIST: &[MusicTrack] = &[
+    MusicTrack {
+        path: "audio/music/day.ogg",
+        situation: MusicSituation::Day,
+    },
+    MusicTrack {
+        path: "audio/music/night.ogg",
+        situation: MusicSituation::Night,
+    },
+    MusicTrack {
+        path: "audio/music/underground.ogg",
+        situation: MusicSituation::Underground,
+    },
+    MusicTrack {
+        path: "audio/music/creative.ogg",
+        situation: MusicSituation::Creative,
+    },
+];
+
+/// Silence before the first track of a session, in seconds.
+const FIRST_SILENCE_SECONDS: (f32, f32) = (8.0, 30.0);
+/// Silence between tracks, in seconds. Music is an occasional accent rather
+/// than a constant bed.
+const SILENCE_SECONDS: (f32, f32) = (60.0, 180.0);
+/// Seconds for a track to fade in, or out when the situation changes under it.
+const MUSIC_FADE_SECONDS: f32 = 4.0;
+/// Daylight at or above which day tracks are chosen.
+const DAY_MUSIC_DAYLIGHT: f32 = 0.5;
+/// Cave ambience level at or above which the player counts as underground.
+const UNDERGROUND_CAVE_LEVEL: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MusicTrack {
+    pub path: &'static str,
+    pub situation: MusicSituation,
+}
+
+/// When a track may play. Underground music replaces every surface track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicSituation {
+    Day,
+    Night,
+    Underground,
+    /// On the surface in creative or spectator mode, at any time of day.
+    Creative,
+}
+
+impl MusicSituation {
+    const fn fits(self, context: MusicContext) -> bool {
+        match self {
+            Self::Day => !context.underground && context.daytime,
+            Self::Night => !context.underground && !context.daytime,
+            Self::Underground => context.underground,
+            Self::Creative => !context.underground && context.creative,
+        }
+    }
+}
+
+/// What the player is doing, as far as music is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MusicContext {
+    daytime: bool,
+    underground: bool,
+    creative: bool,
+}
+
+/// Picks tracks for the current situation and spaces them with silence.
+#[derive(Resource, Debug)]
+pub(super) struct MusicDirector {
+    phase: MusicPhase,
+    last_track: Option<usize>,
+    /// Draws track choices and silence lengths.
+    rng: SplitMix64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MusicPhase {
+    Silence { remaining: f32 },
+    Playing,
+}
+
+impl Default for MusicDirector {
+    fn default() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::seeded(seed)
+    }
+}
+
+impl MusicDirector {
+    fn seeded(seed: u64) -> Self {
+        let mut director = Self {
+            phase: MusicPhase::Playing,
+            last_track: None,
+            rng: SplitMix64::new(seed),
+        };
+        director.phase = director.roll_silence(FIRST_SILENCE_SECONDS);
+        director
+    }
+
+    fn roll_silence(&mut self, (min, max): (f32, f32)) -> MusicPhase {
+        let unit = self.rng.next_unit();
+        MusicPhase::Silence {
+            remaining: min + (max - min) * unit,
+        }
+    }
+
+    /// A random track for `context`, avoiding the one that just played when
+    /// there is a choice.
+    fn choose_track(&mut self, context: MusicContext) -> Option<usize> {
+        let fitting = MUSIC_PLAYLIST
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| track.situation.fits(context))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let fresh = fitting
+            .iter()
+            .copied()
+            .filter(|&index| Some(index) != self.last_track)
+            .collect::<Vec<_>>();
+        let candidates = if fresh.is_empty() { fitting } else { fresh };
+        if candidates.is_empty() {
+            return None;
+        }
+        let track = candidates[(self.rng.next_u64() % candidates.len() as u64) as usize];
+        self.last_track = Some(track);
+        Some(track)
+    }
+}
+
+/// One playing music track. `level` is its fade position in `0.0..=1.0`.
+#[derive(Component, Debug)]
+pub(super) struct MusicVoice {
+    track: usize,
+    level: f32,
+    fading_out: bool,
+}
+
+/// Fades tracks in and out, cross-fades when the situation changes under a
+/// track, and starts the next track once the silence after the last one ends.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn direct_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<GameAudioSettings>,
+    sounds: Res<SoundBank>,
+    mut director: ResMut<MusicDirector>,
+    cycle: Option<Res<DayNightCycle>>,
+    ambience: Res<ListenerAmbienceLevels>,
+    player: Option<Single<&Player>>,
+    mut voices: Query<(Entity, &mut MusicVoice, Option<&mut AudioSink>)>,
+) {
+    let context = MusicContext {
+        daytime: cycle.is_none_or(|cycle| cycle.lighting().daylight >= DAY_MUSIC_DAYLIGHT),
+        underground: ambience.cave >= UNDERGROUND_CAVE_LEVEL,
+        creative: player.is_some_and(|player| {
+            matches!(player.gamemode, GameMode::Creative | GameMode::Spectator)
+        }),
+    };
+    let gain = settings.music_gain();
+    let step = time.delta_secs() / MUSIC_FADE_SECONDS;
+
+    let mut playing = false;
+    let mut interrupted = false;
+    for (entity, mut voice, sink) in &mut voices {
+        if !voice.fading_out && !MUSIC_PLAYLIST[voice.track].situation.fits(context) {
+            voice.fading_out = true;
+            interrupted = true;
+        }
+        if voice.fading_out {
+            voice.level -= step;
+            if voice.level <= 0.0 {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        } else {
+            voice.level = (voice.level + step).min(1.0);
+            playing = true;
+        }
+        if let Some(mut sink) = sink {
+            sink.set_volume(Volume::Linear(gain * voice.level));
+        }
+    }
+
+    let start = match director.phase {
+        MusicPhase::Playing if playing => false,
+        // The situation changed under the track: cross-fade straight into one
+        // that fits.
+        MusicPhase::Playing if interrupted => true,
+        // The track finished on its own.
+        MusicPhase::Playing => {
+            director.phase = director.roll_silence(SILENCE_SECONDS);
+            false
+        }
+        MusicPhase::Silence { remaining } => {
+            let remaining = remaining - time.delta_secs();
+            director.phase = MusicPhase::Silence { remaining };
+            remaining <= 0.0
+        }
+    };
+    if !start {
+        return;
+    }
+
+    let Some(track) = director.choose_track(context) else {
+        director.phase = director.roll_silence(SILENCE_SECONDS);
+        return;
+    };
+    director.phase = MusicPhase::Playing;
+    commands.spawn((
+        Name::new("Music"),
+        MusicVoice {
+            track,
+            level: 0.0,
+            fading_out: false,
+        },
+        AudioPlayer::new(sounds.music[track].clone()),
+        PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.0)),
+    ));
+}
+
+pub(super) fn pause_music(voices: Query<&AudioSink, With<MusicVoice>>) {
+    for sink in &voices {
+        sink.pause();
+    }
+}
+
+pub(super) fn resume_music(voices: Query<&AudioSink, With<MusicVoice>>) {
+    for sink in &voices {
+        sink.play();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: MusicContext = MusicContext {
+        daytime: true,
+        underground: false,
+        creative: false,
+    };
+
+    fn track(situation: MusicSituation) -> usize {
+        MUSIC_PLAYLIST
+            .iter()
+            .position(|track| track.situation == situation)
+            .expect("every situation has a track")
+    }
+
+    #[test]
+    fn tracks_fit_the_time_of_day_depth_and_game_mode() {
+        let night = MusicContext {
+            daytime: false,
+            ..DAY
+        };
+        let underground = MusicContext {
+            underground: true,
+            creative: true,
+            ..DAY
+        };
+        let creative = MusicContext {
+            creative: true,
+            ..night
+        };
+
+        assert!(MusicSituation::Day.fits(DAY));
+        assert!(!MusicSituation::Night.fits(DAY));
+        assert!(!MusicSituation::Creative.fits(DAY));
+        assert!(MusicSituation::Night.fits(night));
+        assert!(MusicSituation::Creative.fits(creative));
+        assert!(MusicSituation::Night.fits(creative));
+        for situation in [
+            MusicSituation::Day,
+            MusicSituation::Night,
+            MusicSituation::Creative,
+        ] {
+            assert!(
+                !situation.fits(underground),
+                "{situation:?} plays underground"
+            );
+        }
+        assert!(MusicSituation::Underground.fits(underground));
+    }
+
+    #[test]
+    fn silences_are_seeded_and_within_their_range() {
+        let mut first = MusicDirector::seeded(3);
+        let mut second = MusicDirector::seeded(3);
+        assert_eq!(first.phase, second.phase);
+        for _ in 0..32 {
+            let MusicPhase::Silence { remaining } = first.roll_silence(SILENCE_SECONDS) else {
+                unreachable!();
+            };
+            assert!((SILENCE_SECONDS.0..=SILENCE_SECONDS.1).contains(&remaining));
+            assert_eq!(
+                MusicPhase::Silence { remaining },
+                second.roll_silence(SILENCE_SECONDS)
+            );
+        }
+    }
+
+    #[test]
+    fn the_last_track_is_not_repeated_when_another_fits() {
+        let creative_day = MusicContext {
+            creative: true,
+            ..DAY
+        };
+        let mut director = MusicDirector::seeded(11);
+        let mut previous = director.choose_track(creative_day).unwrap();
+        for _ in 0..16 {
+            let next = director.choose_track(creative_day).unwrap();
+            assert_ne!(next, previous);
+            assert!(MUSIC_PLAYLIST[next].situation.fits(creative_day));
+            previous = next;
+        }
+
+        // With only one fitting track, it may repeat.
+        assert_eq!(director.choose_track(DAY), Some(track(MusicSituation::Day)));
+        assert_eq!(director.choose_track(DAY), Some(track(MusicSituation::Day)));
+    }
+
+    fn music_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(GameAudioSettings::default())
+            .insert_resource(super::super::tests::sound_bank(0))
+            .insert_resource(MusicDirector {
+                phase: MusicPhase::Silence { remaining: 0.0 },
+                last_track: None,
+                rng: SplitMix64::new(5),
+            })
+            .insert_resource(DayNightCycle::default())
+            .init_resource::<ListenerAmbienceLevels>()
+            .add_systems(Update, direct_music);
+        app
+    }
+
+    fn voices(app: &mut App) -> Vec<(usize, bool)> {
+        let mut query = app.world_mut().query::<&MusicVoice>();
+        let mut voices = query
+            .iter(app.world())
+            .map(|voice| (voice.track, voice.fading_out))
+            .collect::<Vec<_>>();
+        voices.sort();
+        voices
+    }
+
+    #[test]
+    fn a_track_starts_after_the_silence_and_cross_fades_when_the_player_goes_underground() {
+        let mut app = music_app();
+
+        app.update();
+        assert_eq!(voices(&mut app), [(track(MusicSituation::Day), false)]);
+        let mut playback = app
+            .world_mut()
+            .query_filtered::<&PlaybackSettings, With<MusicVoice>>();
+        let playback = playback.single(app.world()).unwrap();
+        assert_eq!(playback.mode, bevy::audio::PlaybackMode::Despawn);
+        assert!(!playback.spatial);
+
+        let mut levels = app.world_mut().query::<&mut MusicVoice>();
+        for mut voice in levels.iter_mut(app.world_mut()) {
+            voice.level = 1.0;
+        }
+        app.world_mut()
+            .resource_mut::<ListenerAmbienceLevels>()
+            .cave = 1.0;
+        app.update();
+        let mut expected = vec![
+            (track(MusicSituation::Day), true),
+            (track(MusicSituation::Underground), false),
+        ];
+        expected.sort();
+        assert_eq!(
+            voices(&mut app),
+            expected,
+            "both tracks play during the fade"
+        );
+        assert_eq!(
+            app.world().resource::<MusicDirector>().phase,
+            MusicPhase::Playing
+        );
+    }
+
+    #[test]
+    fn a_finished_track_is_followed_by_silence() {
+        let mut app = music_app();
+        app.update();
+        let voice = app
+            .world_mut()
+            .query_filtered::<Entity, With<MusicVoice>>()
+            .single(app.world())
+            .unwrap();
+
+        app.world_mut().despawn(voice);
+        app.update();
+
+        assert!(voices(&mut app).is_empty());
+        let MusicPhase::Silence { remaining } = app.world().resource::<MusicDirector>().phase
+        else {
+            panic!("a finished track must be followed by silence");
+        };
+        assert!(remaining >= SILENCE_SECONDS.0);
+    }
+}
@@ -0,0 +1,387 @@
+use bevy::{
+    audio::{AudioSinkPlayback, SpatialAudioSink},
+    prelude::*,
+};
+
+use crate::{
+    item::Item,
+    world::chunk::{ChunkCell, WorldBlockPos},
+};
+
+/// How much a fully occluded sound is attenuated. Some sound always carries
+/// through walls, so buried sources stay faintly audible.
+const OCCLUDED_ATTENUATION: f32 = 0.85;
+/// Share of sound blocked by each glass or ice cell on the path.
+const GLASS_OCCLUSION: f32 = 0.4;
+/// Share of sound blocked by each foliage cell on the path.
+const FOLIAGE_OCCLUSION: f32 = 0.15;
+/// Emitters further away than this are not marched; they are quiet enough
+/// that occlusion would not be heard.
+const OCCLUSION_MAX_DISTANCE: f32 = 48.0;
+/// Length of the probes that look for walls and ceilings around a point.
+const ENCLOSURE_PROBE_DISTANCE: f32 = 8.0;
+/// Level of the reflected copy of a sound in a fully enclosed space.
+const REVERB_SEND_LEVEL: f32 = 0.35;
+/// Reflections quieter than this are not worth a voice.
+const MIN_REVERB_SEND: f32 = 0.05;
+/// Delay before the reflected copy of a sound starts playing.
+pub(super) const REVERB_DELAY_SECS: f32 = 0.08;
+
+/// How terrain shapes a sound between its emitter and the listener.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SoundEnvironment {
+    /// Share of the direct path blocked by cells in `0.0..=1.0`.
+    pub(super) occlusion: f32,
+    /// Level of the delayed reflection in `0.0..=1.0`.
+    pub(super) reverb_send: f32,
+}
+
+impl SoundEnvironment {
+    pub(super) const OPEN: Self = Self {
+        occlusion: 0.0,
+        reverb_send: 0.0,
+    };
+
+    /// Marches the cells between `emitter` and `listener` and probes how
+    /// enclosed both ends are. `cell_at` returns `None` for unloaded cells,
+    /// which are treated as open air.
+    pub(super) fn between(
+        emitter: Vec3,
+        listener: Vec3,
+        cell_at: impl Fn(WorldBlockPos) -> Option<ChunkCell>,
+    ) -> Self {
+        if emitter.distance(listener) > OCCLUSION_MAX_DISTANCE {
+            return Self::OPEN;
+        }
+        let enclosure =
+            (enclosure_around(emitter, &cell_at) + enclosure_around(listener, &cell_at)) / 2.0;
+        Self {
+            occlusion: occlusion_between(emitter, listener, &cell_at),
+            reverb_send: enclosure * REVERB_SEND_LEVEL,
+        }
+    }
+
+    /// Gain applied to the direct sound.
+    pub(super) fn direct_gain(self) -> f32 {
+        1.0 - self.occlusion * OCCLUDED_ATTENUATION
+    }
+
+    /// Gain of the delayed reflection, if it is loud enough to play.
+    pub(super) fn reverb_gain(self) -> Option<f32> {
+        (self.reverb_send >= MIN_REVERB_SEND).then_some(self.reverb_send)
+    }
+}
+
+/// Share of sound a cell blocks. Fluids and air let sound through.
+fn cell_occlusion(cell: ChunkCell) -> f32 {
+    match cell.as_block() {
+        None => 0.0,
+        Some(Item::Glass | Item::Ice) => GLASS_OCCLUSION,
+        Some(Item::OakLeaves) => FOLIAGE_OCCLUSION,
+        Some(_) => 1.0,
+    }
+}
+
+/// Share of the straight path between two points blocked by the cells it
+/// crosses. The cells holding the endpoints are skipped so a sound made by
+/// a block, or heard from inside one, is not muffled by that block.
+pub(super) fn occlusion_between(
+    from: Vec3,
+    to: Vec3,
+    cell_at: impl Fn(WorldBlockPos) -> Option<ChunkCell>,
+) -> f32 {
+    let end = to.floor().as_ivec3();
+    let mut transmission = 1.0;
+    for cell in VoxelMarch::new(from, to).filter(|&cell| cell != end) {
+        let occlusion = cell_at(WorldBlockPos::from_ivec3(cell)).map_or(0.0, cell_occlusion);
+        transmission *= 1.0 - occlusion;
+        if transmission <= 0.0 {
+            break;
+        }
+    }
+    1.0 - transmission
+}
+
+/// Fraction of probes from `at` that hit an opaque cell within
+/// `ENCLOSURE_PROBE_DISTANCE`: near 1.0 in a sealed cave, lower in the open.
+pub(super) fn enclosure_around(
+    at: Vec3,
+    cell_at: impl Fn(WorldBlockPos) -> Option<ChunkCell>,
+) -> f32 {
+    let directions = enclosure_probe_directions();
+    let hits = directions
+        .iter()
+        .filter(|&&direction| {
+            VoxelMarch::new(at, at + direction * ENCLOSURE_PROBE_DISTANCE).any(|cell| {
+                cell_at(WorldBlockPos::from_ivec3(cell))
+                    .is_some_and(|cell| cell_occlusion(cell) >= 1.0)
+            })
+        })
+        .count();
+    hits as f32 / directions.len() as f32
+}
+
+/// The six face directions and eight corner diagonals.
+fn enclosure_probe_directions() -> Vec<Vec3> {
+    let faces = [
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Y,
+        Vec3::NEG_Y,
+        Vec3::Z,
+        Vec3::NEG_Z,
+    ];
+    let corners = [-1.0, 1.0].into_iter().flat_map(|x| {
+        [-1.0, 1.0]
+            .into_iter()
+            .flat_map(move |y| [-1.0, 1.0].map(|z| Vec3::new(x, y, z).normalize()))
+    });
+    faces.into_iter().chain(corners).collect()
+}
+
+/// Cells crossed by a segment, in order, excluding the cell it starts in.
+/// This is the Amanatides-Woo grid traversal parameterized over the segment,
+/// so it stops at the segment's end.
+struct VoxelMarch {
+    cell: IVec3,
+    step: IVec3,
+    t_max: Vec3,
+    t_delta: Vec3,
+}
+
+impl VoxelMarch {
+    fn new(from: Vec3, to: Vec3) -> Self {
+        let cell = from.floor().as_ivec3();
+        let direction = to - from;
+        let mut step = IVec3::ZERO;
+        let mut t_max = Vec3::INFINITY;
+        let mut t_delta = Vec3::INFINITY;
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                continue;
+            }
+            let boundary = if direction[axis] > 0.0 {
+                step[axis] = 1;
+                cell[axis] as f32 + 1.0
+            } else {
+                step[axis] = -1;
+                cell[axis] as f32
+            };
+            t_max[axis] = (boundary - from[axis]) / direction[axis];
+            t_delta[axis] = 1.0 / direction[axis].abs();
+        }
+        Self {
+            cell,
+            step,
+            t_max,
+            t_delta,
+        }
+    }
+}
+
+impl Iterator for VoxelMarch {
+    type Item = IVec3;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let axis = if self.t_max.x <= self.t_max.y && self.t_max.x <= self.t_max.z {
+            0
+        } else if self.t_max.y <= self.t_max.z {
+            1
+        } else {
+            2
+        };
+        if self.t_max[axis] > 1.0 {
+            return None;
+        }
+        self.cell[axis] += self.step[axis];
+        self.t_max[axis] += self.t_delta[axis];
+        Some(self.cell)
+    }
+}
+
+/// A paused reflected copy of a sound, started once its delay has passed.
+#[derive(Component, Debug)]
+pub(super) struct ReverbEcho {
+    pub(super) delay: Timer,
+}
+
+pub(super) fn release_reverb_echoes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut echoes: Query<(Entity, &mut ReverbEcho, Option<&SpatialAudioSink>)>,
+) {
+    for (entity, mut echo, sink) in &mut echoes {
+        if !echo.delay.tick(time.delta()).is_finished() {
+            continue;
+        }
+        // The sink appears once the source has loaded; wait for it.
+        let Some(sink) = sink else {
+            continue;
+        };
+        sink.play();
+        commands.entity(entity).remove::<ReverbEcho>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::platform::collections::HashMap;
+
+    use super::*;
+    use crate::world::chunk::{Chunk, ChunkPos};
+
+    /// Hand-built terrain: `blocks` are placed into whichever chunks they
+    /// fall in; every other loaded cell is air.
+    fn terrain(blocks: impl IntoIterator<Item = (IVec3, Item)>) -> HashMap<ChunkPos, Chunk> {
+        let mut chunks = HashMap::<ChunkPos, Chunk>::new();
+        for offset in [
+            IVec3::ZERO,
+            IVec3::NEG_X,
+            IVec3::NEG_Y,
+            IVec3::new(-1, -1, 0),
+        ] {
+            for z in -1..=0 {
+                chunks.insert(ChunkPos::from_ivec3(offset.with_z(z)), Chunk::default());
+            }
+        }
+        for (position, item) in blocks {
+            let address = WorldBlockPos::from_ivec3(position).split();
+            let chunk = chunks
+                .get_mut(&address.chunk())
+                .expect("test block must be inside the hand-built chunks");
+            let local = address.local();
+            chunk.set_cell_xyz(local.x(), local.y(), local.z(), item.into());
+        }
+        chunks
+    }
+
+    fn lookup(chunks: &HashMap<ChunkPos, Chunk>) -> impl Fn(WorldBlockPos) -> Option<ChunkCell> {
+        move |position| {
+            let address = position.split();
+            chunks
+                .get(&address.chunk())
+                .map(|chunk| chunk.cell(address.local()))
+        }
+    }
+
+    fn wall(x: i32, item: Item) -> impl Iterator<Item = (IVec3, Item)> {
+        (-4..4).flat_map(move |y| (-4..4).map(move |z| (IVec3::new(x, y, z), item)))
+    }
+
+    #[test]
+    fn march_visits_each_crossed_cell_once_in_order() {
+        let cells = VoxelMarch::new(Vec3::new(0.5, 0.5, 0.5), Vec3::new(-2.5, 0.5, 0.5))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cells,
+            [
+                IVec3::new(-1, 0, 0),
+                IVec3::new(-2, 0, 0),
+                IVec3::new(-3, 0, 0)
+            ]
+        );
+
+        let diagonal =
+            VoxelMarch::new(Vec3::new(0.5, 0.2, 0.5), Vec3::new(2.5, 1.2, 0.5)).collect::<Vec<_>>();
+        assert_eq!(diagonal.last(), Some(&IVec3::new(2, 1, 0)));
+        for pair in diagonal.windows(2) {
+            let step = (pair[1] - pair[0]).abs();
+            assert_eq!(step.x + step.y + step.z, 1, "cells must be face neighbours");
+        }
+
+        assert_eq!(
+            VoxelMarch::new(Vec3::splat(0.5), Vec3::splat(0.7)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn clear_air_does_not_occlude() {
+        let chunks = terrain([]);
+        let occlusion = occlusion_between(
+            Vec3::new(-6.5, 1.5, 0.5),
+            Vec3::new(6.5, 1.5, -3.5),
+            lookup(&chunks),
+        );
+        assert_eq!(occlusion, 0.0);
+    }
+
+    #[test]
+    fn opaque_walls_block_and_glass_and_ice_partially_block() {
+        let from = Vec3::new(-6.5, 0.5, 0.5);
+        let to = Vec3::new(6.5, 0.5, 0.5);
+
+        let stone = terrain(wall(0, Item::Stone));
+        assert_eq!(occlusion_between(from, to, lookup(&stone)), 1.0);
+
+        let glass = terrain(wall(0, Item::Glass));
+        assert!((occlusion_between(from, to, lookup(&glass)) - GLASS_OCCLUSION).abs() < 1e-6);
+
+        let glass_and_ice = terrain(wall(-3, Item::Glass).chain(wall(3, Item::Ice)));
+        let open = (1.0 - GLASS_OCCLUSION) * (1.0 - GLASS_OCCLUSION);
+        assert!((occlusion_between(from, to, lookup(&glass_and_ice)) - (1.0 - open)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn the_cells_holding_the_endpoints_do_not_occlude() {
+        let chunks = terrain([
+            (IVec3::new(-3, 0, 0), Item::Stone),
+            (IVec3::new(3, 0, 0), Item::Stone),
+        ]);
+        let occlusion = occlusion_between(
+            Vec3::new(-2.5, 0.5, 0.5),
+            Vec3::new(3.5, 0.5, 0.5),
+            lookup(&chunks),
+        );
+        assert_eq!(
+            occlusion, 0.0,
+            "a placed block must not muffle its own sound"
+        );
+    }
+
+    #[test]
+    fn unloaded_cells_count_as_open_air() {
+        let occlusion =
+            occlusion_between(Vec3::new(-6.5, 0.5, 0.5), Vec3::new(6.5, 0.5, 0.5), |_| {
+                None
+            });
+        assert_eq!(occlusion, 0.0);
+    }
+
+    #[test]
+    fn sealed_rooms_are_fully_enclosed_and_open_ground_is_not() {
+        let shell = (-3..=3).flat_map(|x| {
+            (-3..=3).flat_map(move |y| {
+                (-3..=3).filter_map(move |z| {
+                    let position = IVec3::new(x, y, z);
+                    (position.abs().max_element() == 3).then_some((position, Item::Stone))
+                })
+            })
+        });
+        let room = terrain(shell);
+        assert_eq!(enclosure_around(Vec3::splat(0.5), lookup(&room)), 1.0);
+
+        let floor = terrain(
+            (-12..12).flat_map(|x| (-12..12).map(move |z| (IVec3::new(x, -2, z), Item::Grass))),
+        );
+        let open = enclosure_around(Vec3::splat(0.5), lookup(&floor));
+        assert_eq!(open, 5.0 / 14.0, "only the downward probes hit the ground");
+    }
+
+    #[test]
+    fn buried_sounds_stay_faintly_audible_and_reflections_need_walls() {
+        let room = SoundEnvironment {
+            occlusion: 1.0,
+            reverb_send: REVERB_SEND_LEVEL,
+        };
+        assert!(room.direct_gain() > 0.0 && room.direct_gain() < 0.2);
+        assert_eq!(room.reverb_gain(), Some(REVERB_SEND_LEVEL));
+
+        assert_eq!(SoundEnvironment::OPEN.direct_gain(), 1.0);
+        assert_eq!(SoundEnvironment::OPEN.reverb_gain(), None);
+
+        let far =
+            SoundEnvironment::between(Vec3::ZERO, Vec3::X * 100.0, |_| Some(Item::Stone.into()));
+        assert_eq!(far, SoundEnvironment::OPEN);
+    }
+}
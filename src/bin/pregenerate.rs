@@ -0,0 +1,140 @@
+//! Headless pregeneration of a region of a persisted world.
+//!
+//! Every column within the radius is generated, lit and saved with its light
+//! cache, so exploring the region later streams stored columns instead of
+//! generating them. Columns already stored with light are skipped, which makes
+//! an interrupted run resumable by running the same command again.
+//!
+//! ```text
+//! cargo run --release --bin pregenerate -- <world> <dimension> <radius> [center-x center-z] [--seed <seed>] [--height-chunks <chunks>]
+//! ```
+//!
+//! An existing world keeps its own `world_metadata`; `--seed` and
+//! `--height-chunks` choose the metadata of a new world and must match an
+//! existing one. Directories and paths ending in `.region` are region-file
+//! worlds; other paths are SQLite worlds unless they select Turso.
+
+use std::{io::Write, path::PathBuf, process::ExitCode};
+
+use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+use minecraft_clone::world::{
+    DimensionId, WorldMetadata,
+    chunk::ChunkColumn,
+    dimension::{PregenerationProgress, PregenerationRequest, pregenerate_columns},
+    storage::WorldStoreKind,
+};
+
+const USAGE: &str = "usage: pregenerate <world> <dimension> <radius> [center-x center-z] \
+[--seed <seed>] [--height-chunks <chunks>]";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("pregenerate: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut seed = None;
+    let mut height_chunks = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = Some(parse::<u64>(option_value(args.next(), arg)?, "seed")?),
+            "--height-chunks" => {
+                height_chunks = Some(parse::<usize>(
+                    option_value(args.next(), arg)?,
+                    "height-chunks",
+                )?);
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    let (world, dimension, radius, center) = match positional.as_slice() {
+        [world, dimension, radius] => (world, dimension, radius, ChunkColumn::new(0, 0)),
+        [world, dimension, radius, x, z] => (
+            world,
+            dimension,
+            radius,
+            ChunkColumn::new(parse(x, "center-x")?, parse(z, "center-z")?),
+        ),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let world = PathBuf::from(world);
+    let request = PregenerationRequest {
+        dimension: DimensionId::new(parse(dimension, "dimension")?),
+        center,
+        radius: parse(radius, "radius")?,
+    };
+
+    let store = WorldStoreKind::from_path(&world);
+    let mut metadata = if world.exists() {
+        store
+            .read_metadata(&world)
+            .map_err(|error| format!("cannot read {}: {error}", world.display()))?
+    } else {
+        WorldMetadata::default()
+    };
+    if let Some(seed) = seed {
+        metadata.seed = seed;
+    }
+    if let Some(chunks) = height_chunks {
+        metadata = metadata
+            .with_height_chunks(chunks)
+            .map_err(|error| error.to_string())?;
+    }
+    let repository = store
+        .open(&world, &metadata)
+        .map_err(|error| format!("cannot open {}: {error}", world.display()))?;
+
+    AsyncComputeTaskPool::get_or_init(TaskPool::new);
+    let summary = pregenerate_columns(&repository, request, print_progress)
+        .map_err(|error| format!("pregeneration stopped: {error}"))?;
+    eprintln!();
+    println!(
+        "pregenerated {} columns ({} chunks) and skipped {} already stored in {:.1}s",
+        summary.completed_columns,
+        summary.generated_chunks,
+        summary.skipped_columns,
+        summary.elapsed.as_secs_f64(),
+    );
+    Ok(())
+}
+
+fn print_progress(progress: &PregenerationProgress) {
+    let elapsed = progress.elapsed.as_secs_f64();
+    let rate = if elapsed > 0.0 {
+        progress.completed_columns as f64 / elapsed
+    } else {
+        0.0
+    };
+    eprint!(
+        "\r{}/{} columns ({} skipped), {:.1} columns/s",
+        progress.finished_columns(),
+        progress.total_columns,
+        progress.skipped_columns,
+        rate,
+    );
+    let _ = std::io::stderr().flush();
+}
+
+fn option_value<'a>(value: Option<&'a String>, option: &str) -> Result<&'a str, String> {
+    value
+        .map(String::as_str)
+        .ok_or_else(|| format!("{option} needs a value\n{USAGE}"))
+}
+
+fn parse<T>(value: &str, name: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|error| format!("invalid {name} {value:?}: {error}"))
+}
@@ -0,0 +1,220 @@
+//! Offline inspection and maintenance of a persisted world.
+//!
+//! The first argument is the world file. SQLite worlds are opened by default,
+//! directories and paths ending in `.region` open as region-file worlds, and
+//! with the `turso-store` feature, files ending in `.turso` open as Turso
+//! worlds. The world's own `world_metadata` selects the seed and height, so no
+//! other configuration is needed.
+//!
+//! ```text
+//! cargo run --bin worldtool -- <world> info
+//! cargo run --bin worldtool -- <world> list [dimension]
+//! cargo run --bin worldtool -- <world> dump <dimension> <x> <y> <z> [slice-y]
+//! cargo run --bin worldtool -- <world> verify [dimension]
+//! cargo run --bin worldtool -- <world> prune <dimension> <radius> [center-x center-z] [--apply]
+//! ```
+//!
+//! `prune` only reports the columns it would delete unless `--apply` is given.
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use minecraft_clone::world::{
+    ChunkAddress, ColumnAddress, DimensionId,
+    chunk::{CHUNK_SIZE, ChunkColumn, ChunkPos},
+    inspect::{ascii_slice, block_histogram, columns_outside_radius, verify_column},
+    storage::{ChunkRepository, WorldStoreKind, open_world_store},
+};
+
+const USAGE: &str = "usage: worldtool <world> <info | list [dimension] | \
+dump <dimension> <x> <y> <z> [slice-y] | verify [dimension] | \
+prune <dimension> <radius> [center-x center-z] [--apply]>";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let [world, command, rest @ ..] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let world = PathBuf::from(world);
+
+    let result = match command.as_str() {
+        "info" => info(&world),
+        "list" => open_world(&world).and_then(|repository| list(&repository, rest)),
+        "dump" => open_world(&world).and_then(|repository| dump(&repository, rest)),
+        "verify" => open_world(&world).and_then(|repository| verify(&repository, rest)),
+        "prune" => open_world(&world).and_then(|repository| prune(&repository, rest)),
+        _ => Err(format!("unknown command {command:?}\n{USAGE}")),
+    };
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("worldtool: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn info(world: &Path) -> Result<ExitCode, String> {
+    for (key, value) in WorldStoreKind::from_path(world)
+        .read_metadata_entries(world)
+        .map_err(|error| error.to_string())?
+    {
+        println!("{key} = {value}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn list(repository: &ChunkRepository, args: &[String]) -> Result<ExitCode, String> {
+    for dimension in selected_dimensions(repository, args.first())? {
+        let columns = repository
+            .list_columns(dimension)
+            .map_err(|error| error.to_string())?;
+        println!("dimension {dimension}: {} stored columns", columns.len());
+        for column in columns {
+            println!("  {} {}", column.x(), column.z());
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn dump(repository: &ChunkRepository, args: &[String]) -> Result<ExitCode, String> {
+    let [dimension, x, y, z, slice @ ..] = args else {
+        return Err(USAGE.to_owned());
+    };
+    let address = ChunkAddress::new(
+        DimensionId::new(parse(dimension, "dimension")?),
+        ChunkPos::new(parse(x, "x")?, parse(y, "y")?, parse(z, "z")?),
+    );
+    let slice_y = slice
+        .first()
+        .map(|value| parse::<usize>(value, "slice-y"))
+        .transpose()?;
+    if slice_y.is_some_and(|y| y >= CHUNK_SIZE) {
+        return Err(format!("slice-y must be below {CHUNK_SIZE}"));
+    }
+
+    let Some((chunk, heightmap)) = repository
+        .load_chunk(address)
+        .map_err(|error| error.to_string())?
+    else {
+        println!("chunk {address:?} is not stored");
+        return Ok(ExitCode::FAILURE);
+    };
+    println!("chunk {address:?}");
+    for (label, count) in block_histogram(&chunk) {
+        println!("  {label:<16} {count:>5}");
+    }
+    let heights = heightmap.heights.as_flattened();
+    println!(
+        "column heightmap min={} max={}",
+        heights.iter().min().copied().unwrap_or_default(),
+        heights.iter().max().copied().unwrap_or_default(),
+    );
+    if let Some(y) = slice_y {
+        println!("slice y={y} (x across, z down)");
+        print!("{}", ascii_slice(&chunk, y));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn verify(repository: &ChunkRepository, args: &[String]) -> Result<ExitCode, String> {
+    let mut checked = 0;
+    let mut failed = 0;
+    for dimension in selected_dimensions(repository, args.first())? {
+        let columns = repository
+            .list_columns(dimension)
+            .map_err(|error| error.to_string())?;
+        for column in columns {
+            let verification = verify_column(repository, ColumnAddress::new(dimension, column));
+            checked += 1;
+            if !verification.is_ok() {
+                failed += 1;
+                for issue in &verification.issues {
+                    println!(
+                        "dimension {dimension} column {} {}: {issue}",
+                        column.x(),
+                        column.z()
+                    );
+                }
+            }
+        }
+    }
+    println!("verified {checked} columns, {failed} with problems");
+    Ok(if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn prune(repository: &ChunkRepository, args: &[String]) -> Result<ExitCode, String> {
+    let apply = args.iter().any(|arg| arg == "--apply");
+    let args = args
+        .iter()
+        .filter(|arg| *arg != "--apply")
+        .collect::<Vec<_>>();
+    let (dimension, radius, center) = match args.as_slice() {
+        [dimension, radius] => (dimension, radius, ChunkColumn::new(0, 0)),
+        [dimension, radius, x, z] => (
+            dimension,
+            radius,
+            ChunkColumn::new(parse(x, "center-x")?, parse(z, "center-z")?),
+        ),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let dimension = DimensionId::new(parse(dimension, "dimension")?);
+    let radius = parse::<i32>(radius, "radius")?;
+
+    let columns = repository
+        .list_columns(dimension)
+        .map_err(|error| error.to_string())?;
+    let outside = columns_outside_radius(&columns, center, radius);
+    for column in &outside {
+        if apply {
+            repository
+                .delete_column(ColumnAddress::new(dimension, *column))
+                .map_err(|error| error.to_string())?;
+        }
+        println!("  {} {}", column.x(), column.z());
+    }
+    println!(
+        "{} {} of {} columns outside radius {radius} around {} {}",
+        if apply { "deleted" } else { "would delete" },
+        outside.len(),
+        columns.len(),
+        center.x(),
+        center.z(),
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn selected_dimensions(
+    repository: &ChunkRepository,
+    selected: Option<&String>,
+) -> Result<Vec<DimensionId>, String> {
+    match selected {
+        Some(value) => Ok(vec![DimensionId::new(parse(value, "dimension")?)]),
+        None => Ok(repository
+            .catalog()
+            .iter()
+            .map(|definition| definition.id())
+            .collect()),
+    }
+}
+
+fn parse<T>(value: &str, name: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|error| format!("invalid {name} {value:?}: {error}"))
+}
+
+fn open_world(world: &Path) -> Result<ChunkRepository, String> {
+    open_world_store(world).map_err(|error| format!("cannot open {}: {error}", world.display()))
+}
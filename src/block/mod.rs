@@ -5,8 +5,8 @@ use crate::item::Item;
 
 pub use properties::{
     BLOCK_FLAG_CUTOUT, BLOCK_FLAG_EMITS_INTERNAL_FACES, BLOCK_FLAG_FULL_CUBE, BLOCK_FLAG_RENDERED,
-    BLOCK_FLAG_TRANSLUCENT, BlockMaterialLayer, BlockRenderLayer, BlockRenderProfile,
-    FaceOcclusion,
+    BLOCK_FLAG_TRANSLUCENT, BlockLightRgb, BlockMaterialLayer, BlockRenderLayer,
+    BlockRenderProfile, BlockSoundGroup, FaceOcclusion,
 };
 pub use visual::{
     BlockTextureAnimation, BlockTextureLayer, BlockTextureMap, BlockVisualTable,
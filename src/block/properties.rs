@@ -43,6 +43,30 @@ impl BlockMaterialLayer {
     }
 }
 
+/// The family of sounds a block makes when broken, placed, walked on or
+/// landed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockSoundGroup {
+    Stone,
+    Wood,
+    Grass,
+    Sand,
+    Glass,
+    Ice,
+}
+
+impl BlockSoundGroup {
+    pub const COUNT: usize = 6;
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::Stone,
+        Self::Wood,
+        Self::Grass,
+        Self::Sand,
+        Self::Glass,
+        Self::Ice,
+    ];
+}
+
 impl BlockRenderProfile {
     pub const fn material_layer(self) -> BlockMaterialLayer {
         match self.layer {
@@ -53,6 +77,77 @@ impl BlockRenderProfile {
     }
 }
 
+/// Block light carried as independent red, green and blue levels, each `0..=15`.
+///
+/// Channels propagate separately, so light from differently coloured emitters
+/// mixes by taking the brightest level per channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BlockLightRgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl BlockLightRgb {
+    pub const MAX_LEVEL: u8 = 15;
+    pub const NONE: Self = Self::new(0, 0, 0);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            r: r & Self::MAX_LEVEL,
+            g: g & Self::MAX_LEVEL,
+            b: b & Self::MAX_LEVEL,
+        }
+    }
+
+    /// Uncoloured light at the same level on every channel.
+    pub const fn white(level: u8) -> Self {
+        Self::new(level, level, level)
+    }
+
+    /// The brightest channel, used wherever block light is a single level.
+    pub const fn level(self) -> u8 {
+        let rg = if self.r > self.g { self.r } else { self.g };
+        if rg > self.b { rg } else { self.b }
+    }
+
+    pub const fn is_dark(self) -> bool {
+        self.r == 0 && self.g == 0 && self.b == 0
+    }
+
+    /// Every channel dimmed by `amount`, stopping at zero.
+    pub const fn attenuated(self, amount: u8) -> Self {
+        Self {
+            r: self.r.saturating_sub(amount),
+            g: self.g.saturating_sub(amount),
+            b: self.b.saturating_sub(amount),
+        }
+    }
+
+    /// The per-channel maximum of two lights.
+    pub const fn brightest(self, other: Self) -> Self {
+        Self {
+            r: if self.r > other.r { self.r } else { other.r },
+            g: if self.g > other.g { self.g } else { other.g },
+            b: if self.b > other.b { self.b } else { other.b },
+        }
+    }
+
+    /// Whether any channel of `self` is brighter than the same channel of `other`.
+    pub const fn exceeds(self, other: Self) -> bool {
+        self.r > other.r || self.g > other.g || self.b > other.b
+    }
+
+    /// Channels packed as `0xRGB`.
+    pub const fn packed(self) -> u16 {
+        ((self.r as u16) << 8) | ((self.g as u16) << 4) | self.b as u16
+    }
+
+    pub const fn from_packed(packed: u16) -> Self {
+        Self::new((packed >> 8) as u8, (packed >> 4) as u8, packed as u8)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,4 +169,19 @@ mod tests {
             assert_eq!(profile.material_layer(), expected);
         }
     }
+
+    #[test]
+    fn block_light_channels_mix_attenuate_and_pack_independently() {
+        let warm = BlockLightRgb::new(15, 9, 2);
+        let cool = BlockLightRgb::new(3, 7, 12);
+
+        assert_eq!(warm.brightest(cool), BlockLightRgb::new(15, 9, 12));
+        assert_eq!(warm.attenuated(3), BlockLightRgb::new(12, 6, 0));
+        assert_eq!(cool.level(), 12);
+        assert!(warm.exceeds(cool) && cool.exceeds(warm));
+        assert!(!warm.attenuated(1).exceeds(warm));
+        assert_eq!(warm.packed(), 0xF92);
+        assert_eq!(BlockLightRgb::from_packed(warm.packed()), warm);
+        assert!(BlockLightRgb::white(4).attenuated(4).is_dark());
+    }
 }
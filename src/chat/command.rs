@@ -0,0 +1,570 @@
+//! Slash-command syntax: the registry of known commands and parsing a line
+//! into a [`ChatCommand`].
+
+use std::{fmt, str::SplitWhitespace};
+
+use bevy::prelude::*;
+
+use crate::{item::Item, light::TICKS_PER_DAY, player::GameMode, world::DimensionId};
+
+/// `/fill` and `/clone` refuse boxes larger than this many blocks.
+pub const MAX_FILL_BLOCKS: u64 = 32_768;
+/// `/give` accepts counts up to one hotbar stack.
+pub const MAX_GIVE_COUNT: u32 = crate::ui::HOTBAR_STACK_SIZE;
+
+/// The kind of value a command argument takes, used for tab completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentKind {
+    /// A fixed word, such as `set` in `/time set`.
+    Literal(&'static str),
+    GameMode,
+    Time,
+    /// One axis of a position: 0 for x, 1 for y and 2 for z.
+    Coordinate(usize),
+    Item,
+    Block,
+    /// How `/fill` treats the blocks already in its box.
+    FillMode,
+    Count,
+    Dimension,
+}
+
+/// One entry of the command registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub summary: &'static str,
+    pub arguments: &'static [ArgumentKind],
+}
+
+const COORDINATES: [ArgumentKind; 3] = [
+    ArgumentKind::Coordinate(0),
+    ArgumentKind::Coordinate(1),
+    ArgumentKind::Coordinate(2),
+];
+
+/// Every command the chat understands, in the order `/help` lists them.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "help",
+        usage: "/help",
+        summary: "List the available commands",
+        arguments: &[],
+    },
+    CommandSpec {
+        name: "gamemode",
+        usage: "/gamemode <creative|spectator>",
+        summary: "Change your game mode",
+        arguments: &[ArgumentKind::GameMode],
+    },
+    CommandSpec {
+        name: "time",
+        usage: "/time set <day|noon|night|midnight|ticks>",
+        summary: "Set the time of day",
+        arguments: &[ArgumentKind::Literal("set"), ArgumentKind::Time],
+    },
+    CommandSpec {
+        name: "tp",
+        usage: "/tp <x> <y> <z>",
+        summary: "Teleport, with ~ for positions relative to you",
+        arguments: &COORDINATES,
+    },
+    CommandSpec {
+        name: "give",
+        usage: "/give <item> [count]",
+        summary: "Put an item in your hotbar",
+        arguments: &[ArgumentKind::Item, ArgumentKind::Count],
+    },
+    CommandSpec {
+        name: "setblock",
+        usage: "/setblock <x> <y> <z> <block>",
+        summary: "Replace one block",
+        arguments: &[
+            COORDINATES[0],
+            COORDINATES[1],
+            COORDINATES[2],
+            ArgumentKind::Block,
+        ],
+    },
+    CommandSpec {
+        name: "fill",
+        usage: "/fill <x1> <y1> <z1> <x2> <y2> <z2> <block> [hollow|replace <block>]",
+        summary: "Fill a box of blocks, or only its shell or one kind of block",
+        arguments: &[
+            COORDINATES[0],
+            COORDINATES[1],
+            COORDINATES[2],
+            COORDINATES[0],
+            COORDINATES[1],
+            COORDINATES[2],
+            ArgumentKind::Block,
+            ArgumentKind::FillMode,
+            ArgumentKind::Block,
+        ],
+    },
+    CommandSpec {
+        name: "clone",
+        usage: "/clone <x1> <y1> <z1> <x2> <y2> <z2> <x> <y> <z>",
+        summary: "Copy a box of blocks to a new lowest corner",
+        arguments: &[
+            COORDINATES[0],
+            COORDINATES[1],
+            COORDINATES[2],
+            COORDINATES[0],
+            COORDINATES[1],
+            COORDINATES[2],
+            COORDINATES[0],
+            COORDINATES[1],
+            COORDINATES[2],
+        ],
+    },
+    CommandSpec {
+        name: "dimension",
+        usage: "/dimension <id>",
+        summary: "Travel to another dimension",
+        arguments: &[ArgumentKind::Dimension],
+    },
+    CommandSpec {
+        name: "seed",
+        usage: "/seed",
+        summary: "Show the world seed",
+        arguments: &[],
+    },
+];
+
+pub fn find_command(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+/// One axis of a command position: absolute, or `~`-relative to the player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordinate {
+    Absolute(f32),
+    Relative(f32),
+}
+
+impl Coordinate {
+    pub fn resolve(self, origin: f32) -> f32 {
+        match self {
+            Self::Absolute(value) => value,
+            Self::Relative(offset) => origin + offset,
+        }
+    }
+}
+
+/// A position argument such as `10 ~ ~-2`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates(pub [Coordinate; 3]);
+
+impl Coordinates {
+    pub fn resolve(self, origin: Vec3) -> Vec3 {
+        let [x, y, z] = self.0;
+        vec3(
+            x.resolve(origin.x),
+            y.resolve(origin.y),
+            z.resolve(origin.z),
+        )
+    }
+
+    /// The block containing the resolved position; relative axes count from
+    /// the block containing `origin`.
+    pub fn resolve_block(self, origin: Vec3) -> IVec3 {
+        self.resolve(origin.floor()).floor().as_ivec3()
+    }
+}
+
+/// What a block argument puts in the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockArgument {
+    Air,
+    Block(Item),
+}
+
+/// How `/fill` treats the blocks already in its box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillMode {
+    Fill,
+    /// Only the shell gets the block; the inside becomes air.
+    Hollow,
+    /// Only blocks of this kind are replaced.
+    Replace(BlockArgument),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatCommand {
+    Help,
+    GameMode(GameMode),
+    SetTime {
+        ticks: u32,
+    },
+    Teleport(Coordinates),
+    Give {
+        item: Item,
+        count: u32,
+    },
+    SetBlock {
+        position: Coordinates,
+        block: BlockArgument,
+    },
+    Fill {
+        from: Coordinates,
+        to: Coordinates,
+        block: BlockArgument,
+        mode: FillMode,
+    },
+    Clone {
+        from: Coordinates,
+        to: Coordinates,
+        destination: Coordinates,
+    },
+    Dimension(DimensionId),
+    Seed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    UnknownCommand(String),
+    Usage(&'static str),
+    InvalidArgument {
+        argument: String,
+        expected: &'static str,
+    },
+    Unsupported(String),
+    Unavailable(&'static str),
+    NotLoaded,
+    OutsideWorld,
+    TooManyBlocks(u64),
+    UnknownDimension(DimensionId),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand(name) => {
+                write!(f, "Unknown command /{name}. Type /help for a list")
+            }
+            Self::Usage(usage) => write!(f, "Usage: {usage}"),
+            Self::InvalidArgument { argument, expected } => {
+                write!(f, "Expected {expected}, got {argument:?}")
+            }
+            Self::Unsupported(what) => write!(f, "{what} is not available yet"),
+            Self::Unavailable(what) => write!(f, "{what} is not available right now"),
+            Self::NotLoaded => write!(f, "That area is not loaded"),
+            Self::OutsideWorld => write!(f, "That area is outside the world"),
+            Self::TooManyBlocks(count) => write!(
+                f,
+                "Too many blocks in the area ({count}, at most {MAX_FILL_BLOCKS})"
+            ),
+            Self::UnknownDimension(id) => write!(f, "There is no dimension {id}"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Parses a command line, with or without its leading `/`.
+pub fn parse_command(line: &str) -> Result<ChatCommand, CommandError> {
+    let line = line.strip_prefix('/').unwrap_or(line);
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let spec = find_command(name).ok_or_else(|| CommandError::UnknownCommand(name.to_owned()))?;
+    let mut arguments = Arguments { spec, words };
+
+    let command = match spec.name {
+        "help" => ChatCommand::Help,
+        "gamemode" => ChatCommand::GameMode(parse_game_mode(arguments.next()?)?),
+        "time" => {
+            arguments.literal("set")?;
+            ChatCommand::SetTime {
+                ticks: parse_time(arguments.next()?)?,
+            }
+        }
+        "tp" => ChatCommand::Teleport(arguments.coordinates()?),
+        "give" => ChatCommand::Give {
+            item: parse_item(arguments.next()?)?,
+            count: arguments.optional().map_or(Ok(1), parse_count)?,
+        },
+        "setblock" => ChatCommand::SetBlock {
+            position: arguments.coordinates()?,
+            block: parse_block(arguments.next()?)?,
+        },
+        "fill" => ChatCommand::Fill {
+            from: arguments.coordinates()?,
+            to: arguments.coordinates()?,
+            block: parse_block(arguments.next()?)?,
+            mode: arguments.fill_mode()?,
+        },
+        "clone" => ChatCommand::Clone {
+            from: arguments.coordinates()?,
+            to: arguments.coordinates()?,
+            destination: arguments.coordinates()?,
+        },
+        "dimension" => ChatCommand::Dimension(parse_dimension(arguments.next()?)?),
+        "seed" => ChatCommand::Seed,
+        name => unreachable!("registered command /{name} has no parser"),
+    };
+    arguments.finish()?;
+    Ok(command)
+}
+
+/// The remaining words of a command line. Missing or extra words report the
+/// command's usage.
+struct Arguments<'a> {
+    spec: &'static CommandSpec,
+    words: SplitWhitespace<'a>,
+}
+
+impl<'a> Arguments<'a> {
+    fn next(&mut self) -> Result<&'a str, CommandError> {
+        self.words
+            .next()
+            .ok_or(CommandError::Usage(self.spec.usage))
+    }
+
+    fn optional(&mut self) -> Option<&'a str> {
+        self.words.next()
+    }
+
+    fn literal(&mut self, expected: &'static str) -> Result<(), CommandError> {
+        if self.next()? == expected {
+            Ok(())
+        } else {
+            Err(CommandError::Usage(self.spec.usage))
+        }
+    }
+
+    fn coordinates(&mut self) -> Result<Coordinates, CommandError> {
+        Ok(Coordinates([
+            parse_coordinate(self.next()?)?,
+            parse_coordinate(self.next()?)?,
+            parse_coordinate(self.next()?)?,
+        ]))
+    }
+
+    fn fill_mode(&mut self) -> Result<FillMode, CommandError> {
+        match self.optional() {
+            None => Ok(FillMode::Fill),
+            Some("hollow") => Ok(FillMode::Hollow),
+            Some("replace") => Ok(FillMode::Replace(parse_block(self.next()?)?)),
+            Some(_) => Err(CommandError::Usage(self.spec.usage)),
+        }
+    }
+
+    fn finish(mut self) -> Result<(), CommandError> {
+        match self.words.next() {
+            Some(_) => Err(CommandError::Usage(self.spec.usage)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid(argument: &str, expected: &'static str) -> CommandError {
+    CommandError::InvalidArgument {
+        argument: argument.to_owned(),
+        expected,
+    }
+}
+
+pub(super) const GAME_MODE_NAMES: [&str; 4] = ["survival", "creative", "adventure", "spectator"];
+
+fn parse_game_mode(text: &str) -> Result<GameMode, CommandError> {
+    match text {
+        "survival" => Ok(GameMode::Survival),
+        "creative" => Ok(GameMode::Creative),
+        "adventure" => Ok(GameMode::Adventure),
+        "spectator" => Ok(GameMode::Spectator),
+        _ => Err(invalid(text, "a game mode")),
+    }
+}
+
+pub(super) const FILL_MODE_NAMES: [&str; 2] = ["hollow", "replace"];
+
+/// Named times, in ticks after sunrise.
+pub(super) const NAMED_TIMES: [(&str, u32); 4] = [
+    ("day", 1_000),
+    ("noon", 6_000),
+    ("night", 13_000),
+    ("midnight", 18_000),
+];
+
+fn parse_time(text: &str) -> Result<u32, CommandError> {
+    if let Some(&(_, ticks)) = NAMED_TIMES.iter().find(|(name, _)| *name == text) {
+        return Ok(ticks);
+    }
+    text.parse::<u32>()
+        .ok()
+        .filter(|&ticks| (ticks as f32) < TICKS_PER_DAY)
+        .ok_or_else(|| invalid(text, "a time of day from 0 to 23999"))
+}
+
+/// Coordinates stay inside the `i32` range of world block positions.
+const COORDINATE_LIMIT: f32 = 2_147_483_648.0;
+
+fn parse_coordinate(text: &str) -> Result<Coordinate, CommandError> {
+    let parse = |number: &str| {
+        number
+            .parse::<f32>()
+            .ok()
+            .filter(|value| (-COORDINATE_LIMIT..COORDINATE_LIMIT).contains(value))
+            .ok_or_else(|| invalid(text, "a coordinate inside the world"))
+    };
+    match text.strip_prefix('~') {
+        Some("") => Ok(Coordinate::Relative(0.0)),
+        Some(offset) => parse(offset).map(Coordinate::Relative),
+        None => parse(text).map(Coordinate::Absolute),
+    }
+}
+
+fn parse_item(text: &str) -> Result<Item, CommandError> {
+    Item::from_name(text).ok_or_else(|| invalid(text, "an item"))
+}
+
+fn parse_block(text: &str) -> Result<BlockArgument, CommandError> {
+    if text == "air" {
+        return Ok(BlockArgument::Air);
+    }
+    Item::from_name(text)
+        .filter(|item| item.is_block())
+        .map(BlockArgument::Block)
+        .ok_or_else(|| invalid(text, "a block"))
+}
+
+fn parse_count(text: &str) -> Result<u32, CommandError> {
+    text.parse::<u32>()
+        .ok()
+        .filter(|count| (1..=MAX_GIVE_COUNT).contains(count))
+        .ok_or_else(|| invalid(text, "a count from 1 to 64"))
+}
+
+fn parse_dimension(text: &str) -> Result<DimensionId, CommandError> {
+    text.parse::<u32>()
+        .map(DimensionId::new)
+        .map_err(|_| invalid(text, "a dimension id"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_registered_command_parses() {
+        for line in [
+            "/help",
+            "/gamemode spectator",
+            "/time set noon",
+            "/tp 1 2 3",
+            "/give stone",
+            "/setblock 0 0 0 air",
+            "/fill 0 0 0 1 1 1 glass",
+            "/fill 0 0 0 1 1 1 glass hollow",
+            "/fill 0 0 0 1 1 1 air replace stone",
+            "/clone 0 0 0 1 1 1 ~ ~ ~",
+            "/dimension 2",
+            "/seed",
+        ] {
+            assert!(parse_command(line).is_ok(), "{line}");
+        }
+        for spec in COMMANDS {
+            assert!(spec.usage.starts_with(&format!("/{}", spec.name)));
+        }
+    }
+
+    #[test]
+    fn arguments_parse_into_typed_values() {
+        assert_eq!(
+            parse_command("/give oak_log 16"),
+            Ok(ChatCommand::Give {
+                item: Item::OakLog,
+                count: 16
+            })
+        );
+        assert_eq!(
+            parse_command("time set 12345"),
+            Ok(ChatCommand::SetTime { ticks: 12_345 })
+        );
+        assert_eq!(
+            parse_command("/tp ~ 64 ~-2.5"),
+            Ok(ChatCommand::Teleport(Coordinates([
+                Coordinate::Relative(0.0),
+                Coordinate::Absolute(64.0),
+                Coordinate::Relative(-2.5),
+            ])))
+        );
+        assert_eq!(
+            parse_command("/fill 0 0 0 1 1 1 air replace stone"),
+            Ok(ChatCommand::Fill {
+                from: Coordinates([Coordinate::Absolute(0.0); 3]),
+                to: Coordinates([Coordinate::Absolute(1.0); 3]),
+                block: BlockArgument::Air,
+                mode: FillMode::Replace(BlockArgument::Block(Item::Stone)),
+            })
+        );
+        assert_eq!(
+            parse_command("/fill 0 0 0 1 1 1 air solid"),
+            Err(CommandError::Usage(find_command("fill").unwrap().usage))
+        );
+        assert_eq!(
+            parse_command("/dimension 1"),
+            Ok(ChatCommand::Dimension(DimensionId::GRASS_FLOOR))
+        );
+    }
+
+    #[test]
+    fn bad_lines_explain_what_is_wrong() {
+        assert_eq!(
+            parse_command("/fly"),
+            Err(CommandError::UnknownCommand("fly".to_owned()))
+        );
+        assert_eq!(
+            parse_command("/tp 1 2"),
+            Err(CommandError::Usage("/tp <x> <y> <z>"))
+        );
+        assert_eq!(
+            parse_command("/seed now"),
+            Err(CommandError::Usage("/seed"))
+        );
+        assert_eq!(
+            parse_command("/time add 5"),
+            Err(CommandError::Usage(
+                "/time set <day|noon|night|midnight|ticks>"
+            ))
+        );
+        assert!(matches!(
+            parse_command("/setblock 0 0 0 diamond"),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+        assert!(matches!(
+            parse_command("/give stone 65"),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+        assert!(matches!(
+            parse_command("/tp ~x 0 0"),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+        assert!(matches!(
+            parse_command("/fill 0 0 0 3e9 0 0 stone"),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+        assert!(matches!(
+            parse_command("/tp ~ inf 0"),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+        assert!(matches!(
+            parse_command("/time set 24000"),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn positions_resolve_relative_to_the_origin() {
+        let position = Coordinates([
+            Coordinate::Relative(1.0),
+            Coordinate::Absolute(5.5),
+            Coordinate::Relative(-1.0),
+        ]);
+        let origin = vec3(10.25, 40.0, -3.5);
+        assert_eq!(position.resolve(origin), vec3(11.25, 5.5, -4.5));
+        assert_eq!(position.resolve_block(origin), ivec3(11, 5, -5));
+    }
+}
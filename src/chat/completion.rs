@@ -0,0 +1,134 @@
+//! Tab completion for the last word of a command line.
+
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use super::command::{
+    ArgumentKind, COMMANDS, FILL_MODE_NAMES, GAME_MODE_NAMES, MAX_GIVE_COUNT, NAMED_TIMES,
+    find_command,
+};
+use crate::{item::Item, world::DimensionId};
+
+/// World state completion may suggest from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionContext {
+    /// The block the player is looking at, offered for coordinates.
+    pub target_block: Option<IVec3>,
+    pub dimensions: Vec<DimensionId>,
+}
+
+/// Every way to complete the last word of `line`, each as the whole line.
+/// Plain chat text has no completions.
+pub fn complete(line: &str, context: &CompletionContext) -> Vec<String> {
+    let Some(command) = line.strip_prefix('/') else {
+        return Vec::new();
+    };
+    let (head, partial) = match command.rfind(char::is_whitespace) {
+        Some(split) => command.split_at(split + 1),
+        None => ("", command),
+    };
+    let words = head.split_whitespace().collect::<Vec<_>>();
+
+    let candidates = match words.split_first() {
+        None => COMMANDS.iter().map(|spec| spec.name.to_owned()).collect(),
+        Some((name, arguments)) => find_command(name)
+            .and_then(|spec| spec.arguments.get(arguments.len()))
+            .map_or_else(Vec::new, |&kind| argument_candidates(kind, context)),
+    };
+
+    let mut completions = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(partial) && candidate != partial)
+        .map(|candidate| format!("/{head}{candidate}"))
+        .collect::<Vec<_>>();
+    completions.sort();
+    completions.dedup();
+    completions
+}
+
+fn argument_candidates(kind: ArgumentKind, context: &CompletionContext) -> Vec<String> {
+    let names = |names: &[&str]| names.iter().map(|name| (*name).to_owned()).collect();
+    match kind {
+        ArgumentKind::Literal(word) => vec![word.to_owned()],
+        ArgumentKind::GameMode => names(&GAME_MODE_NAMES),
+        ArgumentKind::Time => NAMED_TIMES
+            .iter()
+            .map(|(name, _)| (*name).to_owned())
+            .collect(),
+        ArgumentKind::Coordinate(axis) => context
+            .target_block
+            .map(|block| block[axis].to_string())
+            .into_iter()
+            .chain(["~".to_owned()])
+            .collect(),
+        ArgumentKind::Item => Item::iter().map(Item::name).collect(),
+        ArgumentKind::Block => ["air".to_owned()]
+            .into_iter()
+            .chain(Item::BLOCKS.into_iter().map(Item::name))
+            .collect(),
+        ArgumentKind::FillMode => names(&FILL_MODE_NAMES),
+        ArgumentKind::Count => vec!["1".to_owned(), MAX_GIVE_COUNT.to_string()],
+        ArgumentKind::Dimension => context.dimensions.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> CompletionContext {
+        CompletionContext {
+            target_block: Some(ivec3(12, 40, -7)),
+            dimensions: vec![DimensionId::OVERWORLD, DimensionId::GRASS_FLOOR],
+        }
+    }
+
+    #[test]
+    fn completes_command_names() {
+        assert_eq!(complete("/s", &context()), ["/seed", "/setblock"]);
+        assert_eq!(complete("/", &context()).len(), COMMANDS.len());
+        assert!(complete("hello", &context()).is_empty());
+    }
+
+    #[test]
+    fn completes_item_and_block_names() {
+        assert_eq!(
+            complete("/give oak", &context()),
+            ["/give oak_leaves", "/give oak_log"]
+        );
+        assert_eq!(
+            complete("/setblock 1 2 3 a", &context()),
+            ["/setblock 1 2 3 air"]
+        );
+        assert!(complete("/give stone 64 ", &context()).is_empty());
+    }
+
+    #[test]
+    fn completes_coordinates_from_the_targeted_block() {
+        assert_eq!(complete("/tp ", &context()), ["/tp 12", "/tp ~"]);
+        assert_eq!(complete("/tp 12 ", &context()), ["/tp 12 40", "/tp 12 ~"]);
+        assert_eq!(
+            complete("/fill 0 0 0 1 1 ", &context()),
+            ["/fill 0 0 0 1 1 -7", "/fill 0 0 0 1 1 ~"]
+        );
+        let nothing_targeted = CompletionContext::default();
+        assert_eq!(complete("/tp ", &nothing_targeted), ["/tp ~"]);
+    }
+
+    #[test]
+    fn completes_fixed_words_and_dimensions() {
+        assert_eq!(complete("/time ", &context()), ["/time set"]);
+        assert_eq!(
+            complete("/time set n", &context()),
+            ["/time set night", "/time set noon"]
+        );
+        assert_eq!(
+            complete("/gamemode sp", &context()),
+            ["/gamemode spectator"]
+        );
+        assert_eq!(
+            complete("/dimension ", &context()),
+            ["/dimension 0", "/dimension 1"]
+        );
+    }
+}
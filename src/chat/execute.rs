@@ -0,0 +1,457 @@
+//! Running parsed commands against the world.
+
+use avian3d::prelude::Position;
+use bevy::{ecs::system::SystemState, prelude::*};
+
+use super::command::{
+    BlockArgument, COMMANDS, ChatCommand, CommandError, FillMode, MAX_FILL_BLOCKS,
+};
+use crate::{
+    light::DayNightCycle,
+    mob::controller::Velocity,
+    player::{GameMode, PlayableGameMode, Player, PlayerDimension, control::apply_game_mode},
+    ui::Hotbar,
+    world::{
+        DimensionCatalog, WorldMetadata,
+        chunk::ChunkCell,
+        dimension::{
+            Active, BlockRegion, Dimension, DimensionSwitchRequest, RegionEdit, RegionEditChunks,
+            RegionEditError, apply_region_edit,
+        },
+    },
+};
+
+/// Runs `command` and returns the feedback shown in the chat.
+pub fn execute_command(world: &mut World, command: ChatCommand) -> Result<String, CommandError> {
+    match command {
+        ChatCommand::Help => Ok(COMMANDS
+            .iter()
+            .map(|spec| format!("{} - {}", spec.usage, spec.summary))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        ChatCommand::GameMode(mode) => set_game_mode(world, mode),
+        ChatCommand::SetTime { ticks } => {
+            let mut cycle = world
+                .get_resource_mut::<DayNightCycle>()
+                .ok_or(CommandError::Unavailable("The time of day"))?;
+            cycle.time_of_day_ticks = ticks as f32;
+            Ok(format!("Set the time to {ticks}"))
+        }
+        ChatCommand::Teleport(coordinates) => {
+            let player = local_player(world)?;
+            let mut entity = world.entity_mut(player);
+            let origin = entity
+                .get::<Position>()
+                .ok_or(CommandError::Unavailable("The player"))?
+                .0;
+            let destination = coordinates.resolve(origin);
+            entity.insert(Position(destination));
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                transform.translation = destination;
+            }
+            if let Some(mut velocity) = entity.get_mut::<Velocity>() {
+                **velocity = Vec3::ZERO;
+            }
+            Ok(format!(
+                "Teleported to {:.1}, {:.1}, {:.1}",
+                destination.x, destination.y, destination.z
+            ))
+        }
+        ChatCommand::Give { item, count } => {
+            let added = world
+                .get_resource_mut::<Hotbar>()
+                .ok_or(CommandError::Unavailable("The hotbar"))?
+                .give(item, count);
+            if added < count {
+                Ok(format!("Gave {added} {}, the stack is full", item.name()))
+            } else {
+                Ok(format!("Gave {count} {}", item.name()))
+            }
+        }
+        ChatCommand::SetBlock { position, block } => {
+            let block_position = position.resolve_block(player_position(world)?);
+            let changed = edit_region(
+                world,
+                RegionEdit::Fill {
+                    region: BlockRegion::new(block_position, block_position),
+                    cell: block_cell(block),
+                },
+            )?;
+            Ok(match changed {
+                0 => "The block did not change".to_owned(),
+                _ => format!(
+                    "Changed the block at {}, {}, {}",
+                    block_position.x, block_position.y, block_position.z
+                ),
+            })
+        }
+        ChatCommand::Fill {
+            from,
+            to,
+            block,
+            mode,
+        } => {
+            let origin = player_position(world)?;
+            let region = BlockRegion::new(from.resolve_block(origin), to.resolve_block(origin));
+            let cell = block_cell(block);
+            let edit = match mode {
+                FillMode::Fill => RegionEdit::Fill { region, cell },
+                FillMode::Hollow => RegionEdit::Hollow { region, cell },
+                FillMode::Replace(replaced) => RegionEdit::Replace {
+                    region,
+                    from: block_cell(replaced),
+                    to: cell,
+                },
+            };
+            let changed = edit_region(world, edit)?;
+            Ok(format!("Filled {changed} blocks"))
+        }
+        ChatCommand::Clone {
+            from,
+            to,
+            destination,
+        } => {
+            let origin = player_position(world)?;
+            let source = BlockRegion::new(from.resolve_block(origin), to.resolve_block(origin));
+            let offset = IVec3::try_from(
+                destination.resolve_block(origin).as_i64vec3() - source.min().as_i64vec3(),
+            )
+            .map_err(|_| CommandError::OutsideWorld)?;
+            let changed = edit_region(world, RegionEdit::Clone { source, offset })?;
+            Ok(format!("Cloned {changed} blocks"))
+        }
+        ChatCommand::Dimension(target) => {
+            let catalog = world
+                .get_resource::<DimensionCatalog>()
+                .ok_or(CommandError::Unavailable("The world"))?;
+            if catalog.get(target).is_none() {
+                return Err(CommandError::UnknownDimension(target));
+            }
+            let player = local_player(world)?;
+            if world
+                .get::<PlayerDimension>(player)
+                .is_some_and(|dimension| dimension.id() == target)
+            {
+                return Ok(format!("Already in dimension {target}"));
+            }
+            world
+                .write_message(DimensionSwitchRequest { target })
+                .ok_or(CommandError::Unavailable("Dimension travel"))?;
+            Ok(format!("Travelling to dimension {target}"))
+        }
+        ChatCommand::Seed => {
+            let metadata = world
+                .get_resource::<WorldMetadata>()
+                .ok_or(CommandError::Unavailable("The world"))?;
+            Ok(format!("Seed: {}", metadata.seed))
+        }
+    }
+}
+
+fn local_player(world: &mut World) -> Result<Entity, CommandError> {
+    world
+        .query_filtered::<Entity, With<Player>>()
+        .single(world)
+        .map_err(|_| CommandError::Unavailable("The player"))
+}
+
+fn player_position(world: &mut World) -> Result<Vec3, CommandError> {
+    let player = local_player(world)?;
+    world
+        .get::<Position>(player)
+        .map(|position| position.0)
+        .ok_or(CommandError::Unavailable("The player"))
+}
+
+fn set_game_mode(world: &mut World, mode: GameMode) -> Result<String, CommandError> {
+    let playable = PlayableGameMode::try_from(mode)
+        .map_err(|mode| CommandError::Unsupported(format!("{mode:?} mode")))?;
+    let player = local_player(world)?;
+    let mut entity = world.entity_mut(player);
+    if let Some(mut velocity) = entity.get_mut::<Velocity>() {
+        **velocity = Vec3::ZERO;
+    }
+    let Some(mut state) = entity.get_mut::<Player>() else {
+        return Err(CommandError::Unavailable("The player"));
+    };
+    if state.gamemode == mode {
+        return Ok(format!("Already in {mode:?} mode"));
+    }
+    state.gamemode = mode;
+    apply_game_mode(&mut world.commands().entity(player), playable);
+    world.flush();
+    Ok(format!("Set game mode to {mode:?}"))
+}
+
+fn block_cell(block: BlockArgument) -> ChunkCell {
+    match block {
+        BlockArgument::Air => ChunkCell::EMPTY,
+        BlockArgument::Block(item) => ChunkCell::block(item),
+    }
+}
+
+/// Applies `edit` to the active dimension and returns how many blocks
+/// changed.
+fn edit_region(world: &mut World, edit: RegionEdit) -> Result<usize, CommandError> {
+    let volume = edit.target().ok_or(CommandError::OutsideWorld)?.volume();
+    if volume > MAX_FILL_BLOCKS {
+        return Err(CommandError::TooManyBlocks(volume));
+    }
+
+    let mut state: SystemState<(
+        Commands,
+        Query<&mut Dimension, With<Active>>,
+        RegionEditChunks,
+    )> = SystemState::new(world);
+    let (mut commands, mut dimensions, mut chunks) = state.get_mut(world);
+    let mut dimension = dimensions
+        .single_mut()
+        .map_err(|_| CommandError::Unavailable("The world"))?;
+    let report =
+        apply_region_edit(&mut commands, &mut dimension, &mut chunks, edit).map_err(|error| {
+            match error {
+                RegionEditError::NotLoaded(_) => CommandError::NotLoaded,
+                RegionEditError::OutOfRange => CommandError::OutsideWorld,
+            }
+        })?;
+    state.apply(world);
+    Ok(report.changed_cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chat::command::parse_command,
+        item::Item,
+        world::{
+            DimensionId,
+            chunk::{
+                Chunk, ChunkBlockPos, ChunkContentCounts, ChunkPos, ChunkPosition, WorldBlockPos,
+            },
+        },
+    };
+
+    fn run(world: &mut World, line: &str) -> Result<String, CommandError> {
+        execute_command(world, parse_command(line)?)
+    }
+
+    fn player_world() -> (World, Entity) {
+        let mut world = World::new();
+        let player = world
+            .spawn((
+                Player::default(),
+                PlayerDimension::new(DimensionId::OVERWORLD),
+                Position(vec3(0.5, 20.0, 0.5)),
+                Transform::from_xyz(0.5, 20.0, 0.5),
+                Velocity(Vec3::X),
+            ))
+            .id();
+        (world, player)
+    }
+
+    /// A world with one active dimension and an empty chunk at the origin.
+    fn block_world() -> (World, Entity) {
+        let (mut world, _) = player_world();
+        let metadata = WorldMetadata::with_seed(3).with_height_chunks(1).unwrap();
+        let catalog = DimensionCatalog::for_world(&metadata);
+        let root = Dimension::spawn_in_world(&mut world, &catalog, DimensionId::OVERWORLD);
+        world.entity_mut(root).insert(Active);
+        let chunk = world
+            .spawn((
+                ChunkPosition::from(ChunkPos::ZERO),
+                Chunk::default(),
+                ChunkContentCounts::default(),
+            ))
+            .id();
+        world
+            .get_mut::<Dimension>(root)
+            .unwrap()
+            .register_published_chunk(ChunkPos::ZERO, chunk);
+        (world, chunk)
+    }
+
+    fn cell_at(world: &World, chunk: Entity, x: i32, y: i32, z: i32) -> ChunkCell {
+        let position = ChunkBlockPos::from_world(WorldBlockPos::new(x, y, z));
+        world.get::<Chunk>(chunk).unwrap().cell(position.local())
+    }
+
+    #[test]
+    fn teleport_resolves_relative_coordinates() {
+        let (mut world, player) = player_world();
+
+        run(&mut world, "/tp ~2 64 ~").unwrap();
+
+        let player = world.entity(player);
+        assert_eq!(player.get::<Position>().unwrap().0, vec3(2.5, 64.0, 0.5));
+        assert_eq!(
+            player.get::<Transform>().unwrap().translation,
+            vec3(2.5, 64.0, 0.5)
+        );
+        assert_eq!(player.get::<Velocity>().unwrap().0, Vec3::ZERO);
+    }
+
+    #[test]
+    fn time_give_and_seed_touch_their_resources() {
+        let (mut world, _) = player_world();
+        world.init_resource::<DayNightCycle>();
+        world.insert_resource(Hotbar {
+            slots: [None; crate::ui::HOTBAR_SLOTS],
+            counts: [0; crate::ui::HOTBAR_SLOTS],
+            selected: 4,
+        });
+        world.insert_resource(WorldMetadata::with_seed(99));
+
+        run(&mut world, "/time set midnight").unwrap();
+        assert_eq!(
+            world.resource::<DayNightCycle>().time_of_day_ticks,
+            18_000.0
+        );
+
+        assert_eq!(
+            run(&mut world, "/give glass 3"),
+            Ok("Gave 3 glass".to_owned())
+        );
+        run(&mut world, "/give ice").unwrap();
+        assert_eq!(
+            run(&mut world, "/give glass 64"),
+            Ok("Gave 61 glass, the stack is full".to_owned())
+        );
+        let hotbar = world.resource::<Hotbar>();
+        assert_eq!(hotbar.slots[0], Some(Item::Glass));
+        assert_eq!(hotbar.slots[1], Some(Item::Ice));
+        assert_eq!(hotbar.counts[..2], [64, 1]);
+        assert_eq!(hotbar.selected, 0);
+
+        assert_eq!(run(&mut world, "/seed"), Ok("Seed: 99".to_owned()));
+    }
+
+    #[test]
+    fn game_mode_switches_between_creative_and_spectator() {
+        let (mut world, player) = player_world();
+
+        run(&mut world, "/gamemode spectator").unwrap();
+        assert_eq!(
+            world.get::<Player>(player).unwrap().gamemode,
+            GameMode::Spectator
+        );
+        assert!(
+            world
+                .get::<crate::mob::controller::Flying>(player)
+                .is_some()
+        );
+
+        run(&mut world, "/gamemode creative").unwrap();
+        assert!(
+            world
+                .get::<crate::mob::controller::Flying>(player)
+                .is_none()
+        );
+        assert!(matches!(
+            run(&mut world, "/gamemode survival"),
+            Err(CommandError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn setblock_and_fill_edit_loaded_chunks() {
+        let (mut world, chunk) = block_world();
+
+        run(&mut world, "/setblock 1 2 3 stone").unwrap();
+        assert_eq!(
+            cell_at(&world, chunk, 1, 2, 3),
+            ChunkCell::block(Item::Stone)
+        );
+
+        assert_eq!(
+            run(&mut world, "/fill 0 0 0 2 2 2 glass"),
+            Ok("Filled 27 blocks".to_owned())
+        );
+        assert_eq!(
+            cell_at(&world, chunk, 2, 2, 2),
+            ChunkCell::block(Item::Glass)
+        );
+        assert_eq!(
+            run(&mut world, "/fill 0 0 0 2 2 2 glass"),
+            Ok("Filled 0 blocks".to_owned())
+        );
+
+        run(&mut world, "/setblock 1 1 1 air").unwrap();
+        assert_eq!(cell_at(&world, chunk, 1, 1, 1), ChunkCell::EMPTY);
+    }
+
+    #[test]
+    fn fill_modes_and_clone_report_changed_blocks() {
+        let (mut world, chunk) = block_world();
+
+        assert_eq!(
+            run(&mut world, "/fill 0 0 0 2 2 2 glass hollow"),
+            Ok("Filled 26 blocks".to_owned())
+        );
+        assert_eq!(cell_at(&world, chunk, 1, 1, 1), ChunkCell::EMPTY);
+        assert_eq!(
+            run(&mut world, "/fill 0 0 0 2 0 2 stone replace glass"),
+            Ok("Filled 9 blocks".to_owned())
+        );
+        assert_eq!(
+            run(&mut world, "/clone 0 0 0 2 0 2 4 0 0"),
+            Ok("Cloned 9 blocks".to_owned())
+        );
+        assert_eq!(
+            cell_at(&world, chunk, 6, 0, 2),
+            ChunkCell::block(Item::Stone)
+        );
+    }
+
+    #[test]
+    fn edits_outside_the_loaded_area_or_too_large_are_refused() {
+        let (mut world, chunk) = block_world();
+
+        assert_eq!(
+            run(&mut world, "/fill 0 0 0 20 0 0 stone"),
+            Err(CommandError::NotLoaded)
+        );
+        assert_eq!(cell_at(&world, chunk, 0, 0, 0), ChunkCell::EMPTY);
+        assert_eq!(
+            run(&mut world, "/fill 0 0 0 100 100 100 stone"),
+            Err(CommandError::TooManyBlocks(101 * 101 * 101))
+        );
+        assert_eq!(
+            run(&mut world, "/fill -2147483648 0 0 2147483000 0 0 stone"),
+            Err(CommandError::TooManyBlocks(4_294_966_657))
+        );
+        assert_eq!(
+            run(&mut world, "/clone 0 0 0 200 0 0 2147483520 0 0"),
+            Err(CommandError::OutsideWorld)
+        );
+    }
+
+    #[test]
+    fn dimension_requests_a_switch_to_a_known_dimension() {
+        let (mut world, _) = player_world();
+        world.insert_resource(DimensionCatalog::for_world(&WorldMetadata::with_seed(1)));
+        world.init_resource::<Messages<DimensionSwitchRequest>>();
+
+        run(&mut world, "/dimension 2").unwrap();
+        let requests = world
+            .resource_mut::<Messages<DimensionSwitchRequest>>()
+            .drain()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            [DimensionSwitchRequest {
+                target: DimensionId::CENTER_GLASS_PLATFORM
+            }]
+        );
+
+        assert!(
+            run(&mut world, "/dimension 0")
+                .unwrap()
+                .starts_with("Already")
+        );
+        assert_eq!(
+            run(&mut world, "/dimension 9"),
+            Err(CommandError::UnknownDimension(DimensionId::new(9)))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Chat messages and slash commands typed into the chat.
+
+pub mod command;
+pub mod completion;
+pub mod execute;
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{game_state::GameState, world::session::WorldSessionSystems};
+
+pub use command::{ChatCommand, CommandError, parse_command};
+pub use completion::{CompletionContext, complete};
+pub use execute::execute_command;
+
+/// The chat keeps at most this many lines.
+const MAX_CHAT_LINES: usize = 100;
+
+pub struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatLog>().add_systems(
+            OnEnter(GameState::MainMenu),
+            clear_chat_log.in_set(WorldSessionSystems::Close),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatLineKind {
+    Message,
+    Feedback,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatLine {
+    pub kind: ChatLineKind,
+    pub text: String,
+    /// Real time the line was added, used to fade it out.
+    pub added_at: Duration,
+}
+
+/// Lines shown in the chat, oldest first.
+#[derive(Resource, Debug, Default)]
+pub struct ChatLog {
+    lines: VecDeque<ChatLine>,
+}
+
+impl ChatLog {
+    pub fn push(&mut self, kind: ChatLineKind, text: impl Into<String>, added_at: Duration) {
+        if self.lines.len() == MAX_CHAT_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(ChatLine {
+            kind,
+            text: text.into(),
+            added_at,
+        });
+    }
+
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &ChatLine> + ExactSizeIterator {
+        self.lines.iter()
+    }
+}
+
+/// Handles a line entered in the chat. Lines starting with `/` run as
+/// commands and report back; anything else is said by the local player.
+pub fn submit_chat_line(world: &mut World, line: &str) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    let now = world
+        .get_resource::<Time<Real>>()
+        .map_or(Duration::ZERO, Time::elapsed);
+
+    let Some(command) = line.strip_prefix('/') else {
+        world.resource_mut::<ChatLog>().push(
+            ChatLineKind::Message,
+            format!("<Player> {line}"),
+            now,
+        );
+        return;
+    };
+
+    let result = parse_command(command).and_then(|command| execute_command(world, command));
+    let mut log = world.resource_mut::<ChatLog>();
+    match result {
+        Ok(feedback) => {
+            for text in feedback.lines() {
+                log.push(ChatLineKind::Feedback, text, now);
+            }
+        }
+        Err(error) => log.push(ChatLineKind::Error, error.to_string(), now),
+    }
+}
+
+fn clear_chat_log(mut log: ResMut<ChatLog>) {
+    log.lines.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_lines_are_said_and_commands_report_back() {
+        let mut world = World::new();
+        world.init_resource::<ChatLog>();
+
+        submit_chat_line(&mut world, "  hello there ");
+        submit_chat_line(&mut world, "/seed");
+        submit_chat_line(&mut world, "/");
+        submit_chat_line(&mut world, "   ");
+
+        let log = world.resource::<ChatLog>();
+        let lines = log
+            .lines()
+            .map(|line| (line.kind, line.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                (ChatLineKind::Message, "<Player> hello there"),
+                (ChatLineKind::Error, "The world is not available right now"),
+                (
+                    ChatLineKind::Error,
+                    "Unknown command /. Type /help for a list"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_log_keeps_only_recent_lines() {
+        let mut log = ChatLog::default();
+        for line in 0..MAX_CHAT_LINES + 5 {
+            log.push(ChatLineKind::Message, line.to_string(), Duration::ZERO);
+        }
+        assert_eq!(log.lines().len(), MAX_CHAT_LINES);
+        assert_eq!(log.lines().next().unwrap().text, "5");
+    }
+}
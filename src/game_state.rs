@@ -7,12 +7,17 @@ pub struct GameStatePlugin;
 impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
+            .add_sub_state::<PauseScreen>()
+            .add_sub_state::<TitleScreen>()
+            .add_sub_state::<ChatState>()
             .add_systems(PreUpdate, request_pause_state.after(InputSystems))
             .add_systems(
                 OnEnter(GameState::Paused),
                 (pause_virtual_time, reset_gameplay_inputs),
             )
             .add_systems(OnExit(GameState::Paused), resume_virtual_time)
+            // Keys typed into the chat must not keep moving the player.
+            .add_systems(OnExit(ChatState::Open), reset_gameplay_inputs)
             // Also discard the click/key press used to leave the menu. Without
             // this, holding the resume click can immediately break a block.
             .add_systems(
@@ -36,14 +41,46 @@ pub enum GameState {
     Paused,
 }
 
+/// Which screen of the pause menu is open while the game is paused.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Hash, SubStates)]
+#[source(GameState = GameState::Paused)]
+pub enum PauseScreen {
+    #[default]
+    Main,
+    Options,
+    Controls,
+}
+
+/// Which screen of the title menu is open.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Hash, SubStates)]
+#[source(GameState = GameState::MainMenu)]
+pub enum TitleScreen {
+    #[default]
+    Worlds,
+    CreateWorld,
+    RenameWorld,
+}
+
+/// Whether the chat input is open. The world keeps running while players type.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Hash, SubStates)]
+#[source(GameState = GameState::Playing)]
+pub enum ChatState {
+    #[default]
+    Closed,
+    Open,
+}
+
 #[derive(Debug, SystemSet, Hash, PartialEq, Eq, Clone, Copy)]
 pub struct Playing;
 
 fn request_pause_state(
     keys: Option<Res<ButtonInput<KeyCode>>>,
     game_state: Res<State<GameState>>,
+    pause_screen: Option<Res<State<PauseScreen>>>,
+    chat: Option<Res<State<ChatState>>>,
     primary_windows: Query<&Window, With<PrimaryWindow>>,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_pause_screen: ResMut<NextState<PauseScreen>>,
     mut previous_focus: Local<Option<bool>>,
 ) {
     let window_focused = unambiguous_primary_window_is_focused(primary_windows.iter());
@@ -61,6 +98,17 @@ fn request_pause_state(
         return;
     }
 
+    // The chat closes itself on Escape.
+    if chat.is_some_and(|chat| *chat.get() == ChatState::Open) {
+        return;
+    }
+
+    // Escape backs out of a pause sub-screen before it resumes the game.
+    if pause_screen.is_some_and(|screen| *screen.get() != PauseScreen::Main) {
+        next_pause_screen.set(PauseScreen::Main);
+        return;
+    }
+
     match game_state.get() {
         GameState::Playing => next_game_state.set(GameState::Paused),
         GameState::Paused if window_focused => next_game_state.set(GameState::Playing),
@@ -187,6 +235,40 @@ mod tests {
         assert!(!app.world().resource::<Time<Virtual>>().is_paused());
     }
 
+    #[test]
+    fn escape_leaves_a_pause_sub_screen_before_resuming() {
+        let mut app = playing_app();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Escape);
+        app.update();
+        app.world_mut()
+            .resource_mut::<NextState<PauseScreen>>()
+            .set(PauseScreen::Controls);
+        app.update();
+        assert_eq!(
+            *app.world().resource::<State<PauseScreen>>().get(),
+            PauseScreen::Controls
+        );
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Escape);
+        app.update();
+
+        assert_eq!(
+            *app.world().resource::<State<GameState>>().get(),
+            GameState::Paused
+        );
+        assert_eq!(
+            *app.world().resource::<State<PauseScreen>>().get(),
+            PauseScreen::Main
+        );
+    }
+
     #[test]
     fn focus_loss_pauses_and_does_not_auto_resume() {
         let mut app = playing_app();
@@ -4,8 +4,8 @@ use strum::{Display, EnumIter, EnumString};
 use crate::{
     block::{
         BLOCK_FLAG_CUTOUT, BLOCK_FLAG_EMITS_INTERNAL_FACES, BLOCK_FLAG_FULL_CUBE,
-        BLOCK_FLAG_RENDERED, BLOCK_FLAG_TRANSLUCENT, BlockMaterialLayer, BlockRenderLayer,
-        BlockRenderProfile, FaceOcclusion,
+        BLOCK_FLAG_RENDERED, BLOCK_FLAG_TRANSLUCENT, BlockLightRgb, BlockMaterialLayer,
+        BlockRenderLayer, BlockRenderProfile, BlockSoundGroup, FaceOcclusion,
     },
     quad::Direction,
 };
@@ -95,9 +95,14 @@ impl Item {
     }
 
     pub const fn light_emission(self) -> u8 {
+        self.light_color().level()
+    }
+
+    /// The coloured block light this item emits when placed.
+    pub const fn light_color(self) -> BlockLightRgb {
         match self {
-            Self::Glowstone => 15,
-            _ => 0,
+            Self::Glowstone => BlockLightRgb::new(15, 12, 8),
+            _ => BlockLightRgb::NONE,
         }
     }
 
@@ -148,6 +153,21 @@ impl Item {
         }
     }
 
+    /// Sounds played when this block is broken, placed or stepped on.
+    pub const fn sound_group(self) -> Option<BlockSoundGroup> {
+        if !self.is_block() {
+            return None;
+        }
+        Some(match self {
+            Self::Stone => BlockSoundGroup::Stone,
+            Self::OakLog => BlockSoundGroup::Wood,
+            Self::Grass | Self::Dirt | Self::OakLeaves => BlockSoundGroup::Grass,
+            Self::Sand => BlockSoundGroup::Sand,
+            Self::Glass | Self::Glowstone => BlockSoundGroup::Glass,
+            Self::Ice => BlockSoundGroup::Ice,
+        })
+    }
+
     pub const fn is_solid(self) -> bool {
         self.is_block()
     }
@@ -242,6 +262,16 @@ mod tests {
         assert_eq!(Item::Ice.block_storage_id(), Some(8));
     }
 
+    #[test]
+    fn every_block_belongs_to_a_sound_group() {
+        for item in Item::BLOCKS {
+            assert!(item.sound_group().is_some(), "{item} has no sound group");
+        }
+        assert_eq!(Item::Glass.sound_group(), Some(BlockSoundGroup::Glass));
+        assert_eq!(Item::OakLeaves.sound_group(), Some(BlockSoundGroup::Grass));
+        assert_eq!(Item::Ice.sound_group(), Some(BlockSoundGroup::Ice));
+    }
+
     #[test]
     fn grass_and_logs_define_face_specific_textures() {
         assert_ne!(
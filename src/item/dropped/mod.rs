@@ -7,10 +7,11 @@ use bevy::{mesh::MeshTag, prelude::*};
 
 use crate::{
     block::{BlockTextureMap, render_id_for_block},
+    game_state::GameState,
     input::GameActionSystems,
     player::{PLAYER_HEIGHT, Player, cam::gameplay_input_active, spawn::EYELINE},
     textures::BlockTextures,
-    world::{ITEM_COLLISION_LAYERS, PICKUP_SENSOR_COLLISION_LAYERS},
+    world::{ITEM_COLLISION_LAYERS, PICKUP_SENSOR_COLLISION_LAYERS, session::WorldSessionSystems},
 };
 
 use super::ItemStack;
@@ -56,6 +57,10 @@ impl Plugin for DroppedItemPlugin {
             .add_systems(
                 FixedPostUpdate,
                 pick_up_eligible_items.after(PhysicsSystems::Last),
+            )
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                despawn_dropped_items.in_set(WorldSessionSystems::Close),
             );
     }
 }
@@ -102,6 +107,13 @@ fn on_drop_item(
     }
 }
 
+/// Dropped items are not saved, so they end with the world they fell in.
+fn despawn_dropped_items(mut commands: Commands, items: Query<Entity, With<DroppedItem>>) {
+    for item in &items {
+        commands.entity(item).despawn();
+    }
+}
+
 fn on_player_spawn(add: On<Add, Player>, mut commands: Commands) {
     let sensor = commands.spawn(PlayerPickupSensor).id();
     commands.entity(add.entity).add_child(sensor);
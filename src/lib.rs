@@ -3,6 +3,7 @@
 pub mod app;
 pub mod audio;
 pub mod block;
+pub mod chat;
 pub mod game_state;
 pub mod input;
 pub mod item;
@@ -14,6 +15,7 @@ pub mod quad;
 pub mod textures;
 pub mod ui;
 pub mod util;
+pub mod weather;
 pub mod world;
 
 pub use app::{AppPlugin, run};
@@ -1,3 +1,5 @@
+mod sky;
+
 use std::f32::consts::TAU;
 
 use bevy::{
@@ -28,13 +30,15 @@ impl Plugin for LightPlugin {
                 brightness: DAY_AMBIENT_BRIGHTNESS,
                 affects_lightmapped_meshes: true,
             })
+            .add_plugins(sky::SkyPlugin)
             .add_systems(Startup, spawn_sky_lighting)
             .add_systems(Update, update_day_night_cycle);
     }
 }
 
 /// Mutable world time. The defaults start at noon and reproduce Minecraft's
-/// 20-minute day length. This is registered for the F5 inspector.
+/// 20-minute day length. This is registered for the F5 inspector; sun, moon,
+/// clouds and stars all follow `time_of_day_ticks`.
 #[derive(Resource, Reflect, Debug, Clone, Copy)]
 #[reflect(Resource)]
 pub struct DayNightCycle {
@@ -0,0 +1,334 @@
+//! Cloud layer and star field.
+//!
+//! Both are derived from [`DayNightCycle::time_of_day_ticks`] rather than
+//! elapsed time: clouds drift by a whole pattern period per day and stars turn
+//! with the celestial plane, so scrubbing the clock in the inspector moves them
+//! consistently with the sun.
+
+use std::f32::consts::TAU;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::ShaderRef,
+};
+
+use crate::world::generation::SplitMix64;
+
+use super::{DayNightCycle, DayNightLighting, SKY_RADIUS, TICKS_PER_DAY, smoothstep};
+
+const CLOUD_SHADER_PATH: &str = "shaders/clouds.wgsl";
+const CLOUD_HEIGHT: f32 = 192.0;
+/// Blocks per side of one cloud cell.
+const CLOUD_CELL_BLOCKS: f32 = 12.0;
+/// Cells before the cloud pattern repeats; must match `PATTERN_CELLS` in the
+/// cloud shader.
+const CLOUD_PATTERN_CELLS: f32 = 64.0;
+const CLOUD_PERIOD_BLOCKS: f32 = CLOUD_CELL_BLOCKS * CLOUD_PATTERN_CELLS;
+const CLOUD_LAYER_RADIUS: f32 = 512.0;
+const CLOUD_FADE_START: f32 = 320.0;
+const CLOUD_DAY_OPACITY: f32 = 0.8;
+const CLOUD_NIGHT_OPACITY: f32 = 0.35;
+
+/// Vanilla's star seed and count.
+const STAR_SEED: u64 = 10_842;
+const STAR_COUNT: usize = 1_500;
+const STAR_RADIUS: f32 = SKY_RADIUS - 50.0;
+const STAR_MIN_SIZE: f32 = 0.9;
+const STAR_MAX_SIZE: f32 = 2.4;
+
+pub(super) struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<CloudMaterial>::default())
+            .add_systems(Startup, spawn_sky_layers)
+            .add_systems(
+                Update,
+                update_sky_layers.after(super::update_day_night_cycle),
+            );
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct CloudMaterial {
+    #[uniform(0)]
+    settings: CloudSettings,
+}
+
+impl Material for CloudMaterial {
+    fn fragment_shader() -> ShaderRef {
+        CLOUD_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
+
+#[derive(ShaderType, Debug, Clone, Copy, PartialEq)]
+struct CloudSettings {
+    /// Cloud colour with opacity in alpha.
+    color: Vec4,
+    /// Drift along +x, cell size, fade start and fade end, all in blocks.
+    params: Vec4,
+}
+
+impl CloudSettings {
+    fn new(cycle: &DayNightCycle, lighting: &DayNightLighting) -> Self {
+        let night_color = vec3(0.10, 0.11, 0.16);
+        let color = night_color.lerp(Vec3::ONE, lighting.daylight) * lighting.sky_light_color;
+        let opacity =
+            CLOUD_NIGHT_OPACITY + (CLOUD_DAY_OPACITY - CLOUD_NIGHT_OPACITY) * lighting.daylight;
+        Self {
+            color: color.extend(opacity),
+            params: vec4(
+                cloud_drift_blocks(cycle.time_of_day_ticks),
+                CLOUD_CELL_BLOCKS,
+                CLOUD_FADE_START,
+                CLOUD_LAYER_RADIUS,
+            ),
+        }
+    }
+}
+
+#[derive(Component)]
+struct CloudLayer(Handle<CloudMaterial>);
+
+#[derive(Component)]
+struct StarField(Handle<StandardMaterial>);
+
+/// Cloud offset along +x. One day moves the layer by exactly one pattern
+/// period, so the wrap at midnight is seamless.
+fn cloud_drift_blocks(time_of_day_ticks: f32) -> f32 {
+    time_of_day_ticks.rem_euclid(TICKS_PER_DAY) / TICKS_PER_DAY * CLOUD_PERIOD_BLOCKS
+}
+
+/// Star opacity; stars only come out once the sky is mostly dark.
+fn star_brightness(daylight: f32) -> f32 {
+    let night = 1.0 - smoothstep(0.05, 0.6, daylight);
+    night * night
+}
+
+/// Rotation of the celestial plane, matching [`DayNightCycle::sun_direction`].
+fn celestial_rotation(time_of_day_ticks: f32) -> Quat {
+    Quat::from_rotation_z(time_of_day_ticks.rem_euclid(TICKS_PER_DAY) / TICKS_PER_DAY * TAU)
+}
+
+struct Star {
+    direction: Vec3,
+    size: f32,
+    brightness: f32,
+}
+
+/// Seeded, uniformly spread stars on the unit sphere.
+fn star_field(seed: u64) -> Vec<Star> {
+    let mut rng = SplitMix64::new(seed);
+    (0..STAR_COUNT)
+        .map(|_| {
+            let y = rng.next_unit() * 2.0 - 1.0;
+            let azimuth = rng.next_unit() * TAU;
+            let ring = (1.0 - y * y).sqrt();
+            Star {
+                direction: vec3(ring * azimuth.cos(), y, ring * azimuth.sin()),
+                size: STAR_MIN_SIZE + (STAR_MAX_SIZE - STAR_MIN_SIZE) * rng.next_unit(),
+                brightness: 0.5 + 0.5 * rng.next_unit(),
+            }
+        })
+        .collect()
+}
+
+fn star_field_mesh(stars: &[Star]) -> Mesh {
+    let mut positions = Vec::with_capacity(stars.len() * 4);
+    let mut colours = Vec::with_capacity(stars.len() * 4);
+    let mut indices = Vec::with_capacity(stars.len() * 6);
+    for star in stars {
+        let centre = star.direction * STAR_RADIUS;
+        let right = star.direction.any_orthonormal_vector() * star.size;
+        let up = star.direction.cross(right);
+        let base = positions.len() as u32;
+        for corner in [-right - up, right - up, right + up, -right + up] {
+            positions.push((centre + corner).to_array());
+            colours.push([star.brightness, star.brightness, star.brightness, 1.0]);
+        }
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colours)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// A square facing both up and down, so clouds show from either side.
+fn cloud_layer_mesh() -> Mesh {
+    let r = CLOUD_LAYER_RADIUS;
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[-r, 0.0, -r], [-r, 0.0, r], [r, 0.0, r], [r, 0.0, -r]],
+    )
+    .with_inserted_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3, 0, 2, 1, 0, 3, 2]))
+}
+
+fn spawn_sky_layers(
+    mut commands: Commands,
+    cycle: Res<DayNightCycle>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cloud_materials: ResMut<Assets<CloudMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let cloud_material = cloud_materials.add(CloudMaterial {
+        settings: CloudSettings::new(&cycle, &cycle.lighting()),
+    });
+    commands.spawn((
+        Name::new("Cloud layer"),
+        CloudLayer(cloud_material.clone()),
+        Mesh3d(meshes.add(cloud_layer_mesh())),
+        MeshMaterial3d(cloud_material),
+        Transform::from_xyz(0.0, CLOUD_HEIGHT, 0.0),
+    ));
+
+    let star_material = materials.add(StandardMaterial {
+        base_color: Color::WHITE.with_alpha(star_brightness(cycle.lighting().daylight)),
+        unlit: true,
+        fog_enabled: false,
+        alpha_mode: AlphaMode::Add,
+        cull_mode: None,
+        ..default()
+    });
+    commands.spawn((
+        Name::new("Star field"),
+        StarField(star_material.clone()),
+        Mesh3d(meshes.add(star_field_mesh(&star_field(STAR_SEED)))),
+        MeshMaterial3d(star_material),
+        Transform::from_rotation(celestial_rotation(cycle.time_of_day_ticks)),
+    ));
+}
+
+fn update_sky_layers(
+    cycle: Res<DayNightCycle>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    mut clouds: Query<(&CloudLayer, &mut Transform), Without<StarField>>,
+    mut stars: Query<(&StarField, &mut Transform), Without<CloudLayer>>,
+    mut cloud_materials: ResMut<Assets<CloudMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(camera_position) = cameras.iter().next().map(GlobalTransform::translation) else {
+        return;
+    };
+    let lighting = cycle.lighting();
+
+    let settings = CloudSettings::new(&cycle, &lighting);
+    for (layer, mut transform) in &mut clouds {
+        transform.translation = vec3(camera_position.x, CLOUD_HEIGHT, camera_position.z);
+        if cloud_materials
+            .get(&layer.0)
+            .is_some_and(|material| material.settings != settings)
+            && let Some(material) = cloud_materials.get_mut(&layer.0)
+        {
+            material.settings = settings;
+        }
+    }
+
+    let star_alpha = star_brightness(lighting.daylight);
+    for (field, mut transform) in &mut stars {
+        *transform = Transform::from_translation(camera_position)
+            .with_rotation(celestial_rotation(cycle.time_of_day_ticks));
+        if materials
+            .get(&field.0)
+            .is_some_and(|material| material.base_color.alpha() != star_alpha)
+            && let Some(material) = materials.get_mut(&field.0)
+        {
+            material.base_color.set_alpha(star_alpha);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cloud_drift_wraps_seamlessly_at_midnight() {
+        assert_eq!(cloud_drift_blocks(0.0), 0.0);
+        let end_of_day = cloud_drift_blocks(TICKS_PER_DAY - 0.01);
+        assert!((end_of_day - CLOUD_PERIOD_BLOCKS).abs() < 0.01);
+        assert_eq!(
+            cloud_drift_blocks(6_000.0),
+            cloud_drift_blocks(6_000.0 + TICKS_PER_DAY)
+        );
+    }
+
+    #[test]
+    fn stars_turn_with_the_sun() {
+        for ticks in [0.0, 3_000.0, 13_500.0, 21_000.0] {
+            let cycle = DayNightCycle {
+                time_of_day_ticks: ticks,
+                ..default()
+            };
+            let sun = celestial_rotation(ticks) * Vec3::X;
+            assert!(sun.abs_diff_eq(cycle.sun_direction(), 0.0001));
+        }
+    }
+
+    #[test]
+    fn stars_show_only_at_night() {
+        let noon = DayNightLighting::from_sun_elevation(1.0);
+        let midnight = DayNightLighting::from_sun_elevation(-1.0);
+        assert_eq!(star_brightness(noon.daylight), 0.0);
+        assert!(star_brightness(midnight.daylight) > 0.99);
+    }
+
+    #[test]
+    fn star_field_is_seeded_and_on_the_sphere() {
+        let stars = star_field(STAR_SEED);
+        let again = star_field(STAR_SEED);
+        let other = star_field(STAR_SEED + 1);
+
+        assert_eq!(stars.len(), STAR_COUNT);
+        assert!(
+            stars
+                .iter()
+                .all(|star| (star.direction.length() - 1.0).abs() < 1e-4)
+        );
+        assert!(
+            stars
+                .iter()
+                .zip(&again)
+                .all(|(a, b)| a.direction == b.direction && a.size == b.size)
+        );
+        assert!(
+            stars
+                .iter()
+                .zip(&other)
+                .any(|(a, b)| a.direction != b.direction)
+        );
+        let upper = stars.iter().filter(|star| star.direction.y > 0.0).count();
+        assert!(
+            (600..900).contains(&upper),
+            "{upper} stars above the horizon"
+        );
+    }
+
+    #[test]
+    fn clouds_dim_and_thin_out_at_night() {
+        let noon = DayNightCycle::default();
+        let midnight = DayNightCycle {
+            time_of_day_ticks: 18_000.0,
+            ..default()
+        };
+        let day = CloudSettings::new(&noon, &noon.lighting());
+        let night = CloudSettings::new(&midnight, &midnight.lighting());
+
+        assert!(night.color.w < day.color.w);
+        assert!(night.color.truncate().length() < day.color.truncate().length() * 0.5);
+    }
+}
@@ -1,9 +1,15 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
 
-use super::controller::{CharacterController, Grounded, Velocity};
+use super::controller::{
+    CharacterController, Footstep, FootstepKind, Grounded, StrideDistance, Velocity,
+};
 use crate::world::WORLD_LAYER;
 
+/// Ground distance between footsteps, matching the cadence of a walking
+/// player taking one step per stride.
+pub(crate) const FOOTSTEP_STRIDE: f32 = 1.7;
+
 #[derive(Component, Clone)]
 pub struct CollideAndSlideConfig {
     /// Maximum amount of bounces before we early exit.
@@ -41,10 +47,11 @@ pub fn move_character_controllers(
             With<CharacterController>,
         >,
         SpatialQuery,
-        Query<(&mut Velocity, &mut Position), With<CharacterController>>,
+        Query<(&mut Velocity, &mut Position, &mut StrideDistance), With<CharacterController>>,
     )>,
     children_q: Query<&Children>,
     time: Res<Time<Fixed>>,
+    mut footsteps: MessageWriter<Footstep>,
 ) {
     // SpatialQuery reads Position internally, so stage results before mutating Position.
     let inputs = params
@@ -92,10 +99,11 @@ pub fn move_character_controllers(
                 .into_iter()
                 .flat_map(|children| children.iter())
                 .chain(std::iter::once(entity));
-            let (new_position, is_grounded) = if let Some(collider) = collider {
+            let fall_speed = (-velocity.y).max(0.0);
+            let (new_position, is_grounded) = if let Some(collider) = &collider {
                 collide_and_slide(
                     &spatial_query,
-                    &collider,
+                    collider,
                     position,
                     rotation,
                     &mut velocity,
@@ -108,21 +116,55 @@ pub fn move_character_controllers(
                 (position + velocity * time.delta_secs(), false)
             };
 
-            outputs.push((entity, velocity, new_position, is_grounded, was_grounded));
+            let feet = collider.map_or(new_position, |collider| {
+                new_position.with_y(collider.aabb(new_position, rotation).min.y)
+            });
+
+            outputs.push((
+                entity,
+                velocity,
+                new_position,
+                is_grounded,
+                was_grounded,
+                feet,
+                fall_speed,
+            ));
         }
     }
 
     let mut query = params.p2();
-    for (entity, new_velocity, new_position, is_grounded, was_grounded) in outputs {
-        let Ok((mut velocity, mut position)) = query.get_mut(entity) else {
+    for (entity, new_velocity, new_position, is_grounded, was_grounded, feet, fall_speed) in outputs
+    {
+        let Ok((mut velocity, mut position, mut stride)) = query.get_mut(entity) else {
             continue;
         };
 
+        let walked = (new_position - position.0).xz().length();
         velocity.0 = new_velocity;
         position.0 = new_position;
 
+        if is_grounded && was_grounded {
+            stride.0 += walked;
+            if stride.0 >= FOOTSTEP_STRIDE {
+                stride.0 %= FOOTSTEP_STRIDE;
+                footsteps.write(Footstep {
+                    entity,
+                    feet,
+                    kind: FootstepKind::Step,
+                });
+            }
+        }
+
         if is_grounded && !was_grounded {
             commands.entity(entity).insert(Grounded);
+            stride.0 = 0.0;
+            footsteps.write(Footstep {
+                entity,
+                feet,
+                kind: FootstepKind::Landing {
+                    impact_speed: fall_speed,
+                },
+            });
         }
 
         if !is_grounded && was_grounded {
@@ -1,6 +1,11 @@
 use crate::{
     mob::collide_and_slide::CollideAndSlideConfig,
-    player::{Player, cam::MouseCam, control::KeyBindings},
+    player::{
+        Player,
+        cam::MouseCam,
+        control::KeyBindings,
+        gamepad::{GamepadControlSettings, player_movement_intent},
+    },
 };
 
 use avian3d::prelude::*;
@@ -14,6 +19,7 @@ use bevy::prelude::*;
     MovementAcceleration = MovementAcceleration(39.2),
     AirMovementAcceleration = AirMovementAcceleration(8.0),
     CollideAndSlideConfig,
+    StrideDistance,
     SleepingDisabled
 )]
 pub struct CharacterController;
@@ -36,6 +42,31 @@ pub struct AirMovementAcceleration(pub f32);
 #[component(storage = "SparseSet")]
 pub struct Grounded;
 
+/// Horizontal distance walked on the ground since the last footstep.
+#[derive(Component, Default)]
+pub struct StrideDistance(pub f32);
+
+/// A foot contact published by
+/// [`move_character_controllers`](super::collide_and_slide::move_character_controllers).
+///
+/// Consumers look up the block under `feet` to decide what it sounds like.
+#[derive(Message, Debug, Clone, Copy, PartialEq)]
+pub struct Footstep {
+    pub entity: Entity,
+    /// Bottom centre of the character's collider after the move.
+    pub feet: Vec3,
+    pub kind: FootstepKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FootstepKind {
+    /// The character walked another stride along the ground.
+    Step,
+    /// The character touched down while falling at `impact_speed` blocks per
+    /// second.
+    Landing { impact_speed: f32 },
+}
+
 #[derive(Component)]
 pub struct FlyController;
 
@@ -45,6 +76,8 @@ pub struct Flying;
 pub fn apply_player_movement_input(
     keys: Res<ButtonInput<KeyCode>>,
     key_bindings: Res<KeyBindings>,
+    gamepads: Query<&Gamepad>,
+    gamepad_settings: Res<GamepadControlSettings>,
     player_q: Single<
         (
             &MovementAcceleration,
@@ -67,7 +100,8 @@ pub fn apply_player_movement_input(
         is_grounded,
         flying,
     ) = player_q.into_inner();
-    let movement_intent = key_bindings.movement_intent(&keys);
+    let movement_intent =
+        player_movement_intent(&keys, &key_bindings, &gamepads, &gamepad_settings);
 
     let look_transform = camera.into_inner();
     let look_forward = horizontal_direction(*look_transform.forward());
@@ -151,10 +185,13 @@ pub fn apply_jump_impulse(
 pub fn apply_flight_vertical_input(
     keys: Res<ButtonInput<KeyCode>>,
     key_bindings: Res<KeyBindings>,
+    gamepads: Query<&Gamepad>,
+    gamepad_settings: Res<GamepadControlSettings>,
     mut player_q: Query<(&MovementAcceleration, &mut Velocity), (With<Flying>, With<Player>)>,
     time: Res<Time<Fixed>>,
 ) {
-    let movement_intent = key_bindings.movement_intent(&keys);
+    let movement_intent =
+        player_movement_intent(&keys, &key_bindings, &gamepads, &gamepad_settings);
     let vertical_direction = Vec3::Y * movement_intent.local_move_axis.y;
 
     for (movement_acceleration, mut linear_velocity) in player_q.iter_mut() {
@@ -7,7 +7,7 @@ use avian3d::prelude::PhysicsSystems;
 use bevy::prelude::*;
 use collide_and_slide::move_character_controllers;
 use controller::{
-    Flying, Grounded, Velocity, apply_flight_vertical_input, apply_player_movement_input,
+    Flying, Footstep, Grounded, Velocity, apply_flight_vertical_input, apply_player_movement_input,
 };
 
 /// Core physics plugin with Minecraft-like movement physics.
@@ -16,7 +16,7 @@ pub struct MobPhysicsPlugin;
 
 impl Plugin for MobPhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_message::<Footstep>().add_systems(
             FixedUpdate,
             apply_horizontal_drag
                 .in_set(MobPhysicsSystems::HorizontalDrag)
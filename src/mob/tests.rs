@@ -9,9 +9,10 @@ use bevy::time::TimeUpdateStrategy;
 use std::time::Duration;
 
 use super::MobPhysicsPlugin;
+use super::collide_and_slide::FOOTSTEP_STRIDE;
 use super::controller::{
-    CharacterController, Grounded, Velocity, apply_jump_impulse, horizontal_velocity_delta,
-    world_move_direction,
+    CharacterController, Footstep, FootstepKind, Grounded, Velocity, apply_jump_impulse,
+    horizontal_velocity_delta, world_move_direction,
 };
 use crate::player::control::{KeyBindings, PlayerMovementIntent};
 
@@ -600,6 +601,45 @@ fn grounded_on_flat_floor() {
         expected
     );
 }
+
+#[derive(Resource, Default)]
+struct RecordedFootsteps(Vec<Footstep>);
+
+fn record_footsteps(
+    mut footsteps: MessageReader<Footstep>,
+    mut recorded: ResMut<RecordedFootsteps>,
+) {
+    recorded.0.extend(footsteps.read().copied());
+}
+
+#[test]
+fn landing_and_each_walked_stride_publish_footsteps_at_the_feet() {
+    let mut t = MovementTest::new(Vec3::new(0.0, 2.0, 0.0));
+    t.spawn_static(Vec3::new(0.0, 0.0, 0.0), Vec3::new(40.0, 1.0, 20.0));
+    t.app
+        .init_resource::<RecordedFootsteps>()
+        .add_systems(Last, record_footsteps);
+    t.warmup_query_pipeline();
+    t.set_velocity(Vec3::ZERO);
+    t.tick_n(30);
+
+    let landed = std::mem::take(&mut t.app.world_mut().resource_mut::<RecordedFootsteps>().0);
+    assert_eq!(landed.len(), 1, "one landing, no strides while standing");
+    assert_eq!(landed[0].entity, t.player);
+    assert!(matches!(
+        landed[0].kind,
+        FootstepKind::Landing { impact_speed } if impact_speed > 0.0
+    ));
+    assert!((landed[0].feet.y - floor_top_y()).abs() < EPSILON);
+
+    t.tick_n_with_horizontal_velocity(40, Vec3::new(4.0, 0.0, 0.0));
+
+    let steps = &t.app.world().resource::<RecordedFootsteps>().0;
+    assert!(steps.iter().all(|step| step.kind == FootstepKind::Step));
+    assert_eq!(steps.len(), (t.pos().x / FOOTSTEP_STRIDE) as usize);
+    assert!(steps.len() >= 2, "walked {:.2} blocks", t.pos().x);
+}
+
 #[test]
 fn walk_into_wall_slides_along() {
     let mut t = MovementTest::new(Vec3::new(0.0, 2.0, 0.0));
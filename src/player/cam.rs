@@ -5,6 +5,7 @@ use bevy::prelude::*;
 use bevy::window::{CursorGrabMode, CursorOptions, PrimaryWindow};
 use bevy_settings::{ReflectSettingsGroup, SettingsGroup};
 
+use super::gamepad::GamepadControlSettings;
 use crate::game_state::GameState;
 
 pub struct PlayerCamPlugin;
@@ -23,9 +24,11 @@ impl Plugin for PlayerCamPlugin {
             .add_systems(OnEnter(MouseState::Free), apply_free_cursor)
             .add_systems(OnEnter(GameState::Playing), enter_gameplay_cursor)
             .add_systems(OnExit(GameState::Playing), leave_gameplay_cursor)
-            .add_systems(PreUpdate, release_unfocused_cursor.after(InputSystems));
+            .add_systems(PreUpdate, release_unfocused_cursor.after(InputSystems))
+            .add_systems(Update, apply_field_of_view);
 
-        app.init_resource::<MouseSettings>();
+        app.init_resource::<MouseSettings>()
+            .init_resource::<GamepadControlSettings>();
     }
 }
 
@@ -50,6 +53,21 @@ impl Default for MouseSettings {
     }
 }
 
+impl MouseSettings {
+    pub const FOV_RANGE: std::ops::RangeInclusive<f32> = 30.0..=110.0;
+
+    /// Vertical field of view in radians, kept in `FOV_RANGE` degrees.
+    pub fn fov_radians(self) -> f32 {
+        let fov = if self.fov.is_finite() {
+            self.fov
+        } else {
+            Self::default().fov
+        };
+        fov.clamp(*Self::FOV_RANGE.start(), *Self::FOV_RANGE.end())
+            .to_radians()
+    }
+}
+
 #[derive(Component)]
 #[require(Transform = Transform::default().looking_to(Vec3::X, Vec3::Y))]
 #[require(Projection = Projection::Perspective(PerspectiveProjection::default()))]
@@ -191,12 +209,34 @@ fn release_unfocused_cursor(
     next_mouse_state.set(MouseState::Free);
 }
 
+/// Keeps the player camera's field of view in step with `MouseSettings`, so
+/// changes from the options screen apply immediately.
+fn apply_field_of_view(
+    settings: Res<MouseSettings>,
+    mut cameras: Query<&mut Projection, With<MouseCam>>,
+) {
+    let fov = settings.fov_radians();
+    for mut projection in &mut cameras {
+        let Projection::Perspective(perspective) = &*projection else {
+            continue;
+        };
+        if perspective.fov != fov
+            && let Projection::Perspective(perspective) = &mut *projection
+        {
+            perspective.fov = fov;
+        }
+    }
+}
+
 const EPSILON: f32 = 0.01;
 
 fn player_look(
     settings: Res<MouseSettings>,
+    gamepad_settings: Res<GamepadControlSettings>,
+    time: Res<Time>,
     primary_windows: Query<&Window, With<PrimaryWindow>>,
     mouse_motion: Res<AccumulatedMouseMotion>,
+    gamepads: Query<&Gamepad>,
     mut mouse_cam: Single<&mut Transform, With<MouseCam>>,
 ) {
     let Ok(window) = primary_windows.single() else {
@@ -206,6 +246,11 @@ fn player_look(
     let window_scale = window.height().min(window.width());
     pitch -= (settings.sensitivity * mouse_motion.delta.y * window_scale).to_radians();
     yaw -= (settings.sensitivity * mouse_motion.delta.x * window_scale).to_radians();
+    for gamepad in &gamepads {
+        let look = gamepad_settings.look_delta(gamepad, time.delta_secs());
+        yaw -= look.x;
+        pitch += look.y;
+    }
 
     pitch = pitch.clamp(-PI / 2.0 + EPSILON, PI / 2.0 - EPSILON);
 
@@ -275,6 +320,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn field_of_view_follows_settings_within_range() {
+        let mut app = App::new();
+        app.init_resource::<MouseSettings>()
+            .add_systems(Update, apply_field_of_view);
+        let camera = app.world_mut().spawn(MouseCam).id();
+
+        app.world_mut().resource_mut::<MouseSettings>().fov = 70.0;
+        app.update();
+        let Projection::Perspective(perspective) = app.world().get::<Projection>(camera).unwrap()
+        else {
+            panic!("player camera should use a perspective projection");
+        };
+        assert!((perspective.fov - 70f32.to_radians()).abs() < 1e-6);
+
+        app.world_mut().resource_mut::<MouseSettings>().fov = 500.0;
+        app.update();
+        let Projection::Perspective(perspective) = app.world().get::<Projection>(camera).unwrap()
+        else {
+            panic!("player camera should use a perspective projection");
+        };
+        assert!((perspective.fov - 110f32.to_radians()).abs() < 1e-6);
+    }
+
     #[test]
     fn multiple_primary_windows_are_forcibly_released() {
         let mut app = App::new();
@@ -1,17 +1,18 @@
 use crate::{
     input::DoubleTap,
     mob::controller::{FlyController, Flying, Velocity},
+    ui::HOTBAR_SLOTS,
     world::dimension::{Active, Dimension, ViewDistance},
 };
 
 use super::{
-    GameMode, Player, PlayerDimension,
+    GameMode, PlayableGameMode, Player, PlayerDimension,
     cam::{MouseCam, gameplay_input_active},
     spawn::make_player_collider,
 };
 use avian3d::prelude::*;
 use bevy::prelude::*;
-use bevy_settings::SaveSettingsDeferred;
+use bevy_settings::{ReflectSettingsGroup, SaveSettingsDeferred, SettingsGroup};
 
 pub struct ControlPlayerPlugin;
 
@@ -33,7 +34,10 @@ impl Plugin for ControlPlayerPlugin {
     }
 }
 
-#[derive(Resource)]
+/// Player controls. These are registered with `bevy-settings` by the
+/// application and can be rebound from the pause menu's controls screen.
+#[derive(Resource, SettingsGroup, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Resource, SettingsGroup, Default)]
 pub struct KeyBindings {
     pub move_forward: KeyCode,
     pub move_backward: KeyCode,
@@ -49,6 +53,138 @@ pub struct KeyBindings {
     pub debug_reset_character: KeyCode,
     pub view_distance_decrease: KeyCode,
     pub view_distance_increase: KeyCode,
+    pub open_chat: KeyCode,
+    pub open_command: KeyCode,
+    pub break_block: MouseButton,
+    pub place_block: MouseButton,
+    pub pick_block: MouseButton,
+    pub hotbar_slots: [KeyCode; HOTBAR_SLOTS],
+}
+
+/// Something a binding triggers, in the order the controls screen lists them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindingAction {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sprint,
+    MoveAscend,
+    MoveDescend,
+    BreakBlock,
+    PlaceBlock,
+    PickBlock,
+    DropItem,
+    HotbarSlot(usize),
+    ChangeGamemode,
+    SwitchDimension,
+    DebugResetCharacter,
+    ViewDistanceDecrease,
+    ViewDistanceIncrease,
+    OpenChat,
+    OpenCommand,
+}
+
+/// A key or mouse button bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+/// Actions that may share a binding because they never apply at once: jump
+/// on the ground and ascend while flying.
+const SHAREABLE_BINDINGS: &[(BindingAction, BindingAction)] =
+    &[(BindingAction::Jump, BindingAction::MoveAscend)];
+
+impl BindingAction {
+    pub fn all() -> impl Iterator<Item = Self> {
+        use BindingAction::*;
+
+        [
+            MoveForward,
+            MoveBackward,
+            MoveLeft,
+            MoveRight,
+            Jump,
+            Sprint,
+            MoveAscend,
+            MoveDescend,
+            BreakBlock,
+            PlaceBlock,
+            PickBlock,
+            DropItem,
+        ]
+        .into_iter()
+        .chain((0..HOTBAR_SLOTS).map(HotbarSlot))
+        .chain([
+            ChangeGamemode,
+            SwitchDimension,
+            DebugResetCharacter,
+            ViewDistanceDecrease,
+            ViewDistanceIncrease,
+            OpenChat,
+            OpenCommand,
+        ])
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::MoveForward => "Walk Forwards".to_owned(),
+            Self::MoveBackward => "Walk Backwards".to_owned(),
+            Self::MoveLeft => "Strafe Left".to_owned(),
+            Self::MoveRight => "Strafe Right".to_owned(),
+            Self::Jump => "Jump".to_owned(),
+            Self::Sprint => "Sprint".to_owned(),
+            Self::MoveAscend => "Fly Up".to_owned(),
+            Self::MoveDescend => "Fly Down".to_owned(),
+            Self::BreakBlock => "Break Block".to_owned(),
+            Self::PlaceBlock => "Place Block".to_owned(),
+            Self::PickBlock => "Pick Block".to_owned(),
+            Self::DropItem => "Drop Item".to_owned(),
+            Self::HotbarSlot(slot) => format!("Hotbar Slot {}", slot + 1),
+            Self::ChangeGamemode => "Change Game Mode".to_owned(),
+            Self::SwitchDimension => "Switch Dimension".to_owned(),
+            Self::DebugResetCharacter => "Reset Character".to_owned(),
+            Self::ViewDistanceDecrease => "View Distance -".to_owned(),
+            Self::ViewDistanceIncrease => "View Distance +".to_owned(),
+            Self::OpenChat => "Open Chat".to_owned(),
+            Self::OpenCommand => "Open Command".to_owned(),
+        }
+    }
+
+    /// Block interactions are bound to mouse buttons; everything else to keys.
+    pub const fn uses_mouse(self) -> bool {
+        matches!(self, Self::BreakBlock | Self::PlaceBlock | Self::PickBlock)
+    }
+}
+
+impl InputBinding {
+    pub fn label(self) -> String {
+        match self {
+            Self::Key(key) => key_label(key),
+            Self::Mouse(MouseButton::Left) => "Left Button".to_owned(),
+            Self::Mouse(MouseButton::Right) => "Right Button".to_owned(),
+            Self::Mouse(MouseButton::Middle) => "Middle Button".to_owned(),
+            Self::Mouse(MouseButton::Back) => "Back Button".to_owned(),
+            Self::Mouse(MouseButton::Forward) => "Forward Button".to_owned(),
+            Self::Mouse(MouseButton::Other(button)) => format!("Button {button}"),
+        }
+    }
+}
+
+/// Short display name for a key, such as `W` for `KeyW` or `4` for `Digit4`.
+fn key_label(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    for prefix in ["Key", "Digit"] {
+        if let Some(short) = name.strip_prefix(prefix)
+            && short.len() == 1
+        {
+            return short.to_owned();
+        }
+    }
+    name
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -63,9 +199,128 @@ impl PlayerMovementIntent {
     pub fn wants_forward_sprint(self) -> bool {
         self.sprint && self.local_move_axis.z > 0.0
     }
+
+    /// Merges input from two devices, keeping each axis within one unit.
+    pub fn combine(self, other: Self) -> Self {
+        Self {
+            local_move_axis: (self.local_move_axis + other.local_move_axis)
+                .clamp(Vec3::NEG_ONE, Vec3::ONE),
+            jump: self.jump || other.jump,
+            sprint: self.sprint || other.sprint,
+        }
+    }
 }
 
 impl KeyBindings {
+    pub fn binding(&self, action: BindingAction) -> InputBinding {
+        use BindingAction::*;
+
+        match action {
+            BreakBlock => InputBinding::Mouse(self.break_block),
+            PlaceBlock => InputBinding::Mouse(self.place_block),
+            PickBlock => InputBinding::Mouse(self.pick_block),
+            _ => InputBinding::Key(self.key(action)),
+        }
+    }
+
+    /// Rebinds `action`. Returns `false`, leaving the bindings unchanged, when
+    /// the binding is for the wrong device, or is `Escape`, which always
+    /// opens the pause menu.
+    pub fn set_binding(&mut self, action: BindingAction, binding: InputBinding) -> bool {
+        match (action, binding) {
+            (_, InputBinding::Key(KeyCode::Escape)) => false,
+            (BindingAction::BreakBlock, InputBinding::Mouse(button)) => {
+                self.break_block = button;
+                true
+            }
+            (BindingAction::PlaceBlock, InputBinding::Mouse(button)) => {
+                self.place_block = button;
+                true
+            }
+            (BindingAction::PickBlock, InputBinding::Mouse(button)) => {
+                self.pick_block = button;
+                true
+            }
+            (action, InputBinding::Key(key)) if !action.uses_mouse() => {
+                *self.key_mut(action) = key;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Every action whose binding is also used by another action, except for
+    /// pairs in `SHAREABLE_BINDINGS`.
+    pub fn conflicts(&self) -> Vec<BindingAction> {
+        let actions = BindingAction::all().collect::<Vec<_>>();
+        actions
+            .iter()
+            .copied()
+            .filter(|&action| {
+                actions.iter().any(|&other| {
+                    other != action
+                        && self.binding(other) == self.binding(action)
+                        && !SHAREABLE_BINDINGS.contains(&(action, other))
+                        && !SHAREABLE_BINDINGS.contains(&(other, action))
+                })
+            })
+            .collect()
+    }
+
+    fn key(&self, action: BindingAction) -> KeyCode {
+        use BindingAction::*;
+
+        match action {
+            MoveForward => self.move_forward,
+            MoveBackward => self.move_backward,
+            MoveLeft => self.move_left,
+            MoveRight => self.move_right,
+            Jump => self.jump,
+            Sprint => self.sprint,
+            MoveAscend => self.move_ascend,
+            MoveDescend => self.move_descend,
+            DropItem => self.drop_item,
+            HotbarSlot(slot) => self.hotbar_slots[slot],
+            ChangeGamemode => self.change_gamemode,
+            SwitchDimension => self.switch_dimension,
+            DebugResetCharacter => self.debug_reset_character,
+            ViewDistanceDecrease => self.view_distance_decrease,
+            ViewDistanceIncrease => self.view_distance_increase,
+            OpenChat => self.open_chat,
+            OpenCommand => self.open_command,
+            BreakBlock | PlaceBlock | PickBlock => {
+                unreachable!("{action:?} is bound to a mouse button")
+            }
+        }
+    }
+
+    fn key_mut(&mut self, action: BindingAction) -> &mut KeyCode {
+        use BindingAction::*;
+
+        match action {
+            MoveForward => &mut self.move_forward,
+            MoveBackward => &mut self.move_backward,
+            MoveLeft => &mut self.move_left,
+            MoveRight => &mut self.move_right,
+            Jump => &mut self.jump,
+            Sprint => &mut self.sprint,
+            MoveAscend => &mut self.move_ascend,
+            MoveDescend => &mut self.move_descend,
+            DropItem => &mut self.drop_item,
+            HotbarSlot(slot) => &mut self.hotbar_slots[slot],
+            ChangeGamemode => &mut self.change_gamemode,
+            SwitchDimension => &mut self.switch_dimension,
+            DebugResetCharacter => &mut self.debug_reset_character,
+            ViewDistanceDecrease => &mut self.view_distance_decrease,
+            ViewDistanceIncrease => &mut self.view_distance_increase,
+            OpenChat => &mut self.open_chat,
+            OpenCommand => &mut self.open_command,
+            BreakBlock | PlaceBlock | PickBlock => {
+                unreachable!("{action:?} is bound to a mouse button")
+            }
+        }
+    }
+
     pub fn movement_intent(&self, keys: &ButtonInput<KeyCode>) -> PlayerMovementIntent {
         let mut local_move_axis = Vec3::ZERO;
 
@@ -113,6 +368,22 @@ impl Default for KeyBindings {
             debug_reset_character: KeyCode::KeyR,
             view_distance_decrease: KeyCode::BracketLeft,
             view_distance_increase: KeyCode::BracketRight,
+            open_chat: KeyCode::KeyT,
+            open_command: KeyCode::Slash,
+            break_block: MouseButton::Left,
+            place_block: MouseButton::Right,
+            pick_block: MouseButton::Middle,
+            hotbar_slots: [
+                KeyCode::Digit1,
+                KeyCode::Digit2,
+                KeyCode::Digit3,
+                KeyCode::Digit4,
+                KeyCode::Digit5,
+                KeyCode::Digit6,
+                KeyCode::Digit7,
+                KeyCode::Digit8,
+                KeyCode::Digit9,
+            ],
         }
     }
 }
@@ -127,22 +398,30 @@ fn change_gamemode(
     let player_entity = &mut commands.get_entity(player_entity).unwrap();
 
     if keys.just_pressed(key_bindings.change_gamemode) {
-        match player.gamemode {
+        let next = match player.gamemode {
             GameMode::Survival => todo!(),
-            GameMode::Creative => {
-                player_entity.remove::<Collider>();
-                player_entity.insert(Flying);
-                **velocity = Vec3::ZERO;
-                player.gamemode = GameMode::Spectator;
-            }
+            GameMode::Creative => PlayableGameMode::Spectator,
             GameMode::Adventure => todo!(),
-            GameMode::Spectator => {
-                player_entity.insert(make_player_collider());
-                player_entity.remove::<Flying>();
-                **velocity = Vec3::ZERO;
-                player.gamemode = GameMode::Creative;
-            }
+            GameMode::Spectator => PlayableGameMode::Creative,
         };
+        apply_game_mode(player_entity, next);
+        **velocity = Vec3::ZERO;
+        player.gamemode = next.into();
+    }
+}
+
+/// Gives the player the physics of `mode`: spectators fly through terrain,
+/// creative players collide with it.
+pub(crate) fn apply_game_mode(player: &mut EntityCommands, mode: PlayableGameMode) {
+    match mode {
+        PlayableGameMode::Creative => {
+            player.insert(make_player_collider());
+            player.remove::<Flying>();
+        }
+        PlayableGameMode::Spectator => {
+            player.remove::<Collider>();
+            player.insert(Flying);
+        }
     }
 }
 
@@ -151,9 +430,10 @@ fn toggle_fly(
     keys: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
     flyer: Single<(Entity, Has<Flying>), With<FlyController>>,
+    key_bindings: Res<KeyBindings>,
     mut double_tap: Local<DoubleTap>,
 ) {
-    if !double_tap.just_double_tapped(KeyCode::Space, &keys, &time) {
+    if !double_tap.just_double_tapped(key_bindings.jump, &keys, &time) {
         return;
     }
     let (entity, flying) = *flyer;
@@ -211,3 +491,85 @@ fn adjust_view_distance(
         commands.queue(SaveSettingsDeferred::default());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_only_share_shareable_keys() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.jump, bindings.move_ascend);
+        assert!(bindings.conflicts().is_empty());
+    }
+
+    #[test]
+    fn every_action_reads_back_its_own_binding() {
+        let actions = BindingAction::all().collect::<Vec<_>>();
+        let mut bindings = KeyBindings::default();
+        let keys = [
+            KeyCode::F1,
+            KeyCode::F2,
+            KeyCode::F3,
+            KeyCode::F4,
+            KeyCode::F5,
+        ];
+        let buttons = [
+            MouseButton::Back,
+            MouseButton::Forward,
+            MouseButton::Other(7),
+        ];
+        let (mut next_key, mut next_button) = (keys.iter().cycle(), buttons.iter());
+
+        for &action in &actions {
+            let binding = if action.uses_mouse() {
+                InputBinding::Mouse(*next_button.next().unwrap())
+            } else {
+                InputBinding::Key(*next_key.next().unwrap())
+            };
+            assert!(bindings.set_binding(action, binding));
+            assert_eq!(bindings.binding(action), binding, "{action:?}");
+        }
+        assert_eq!(actions.len(), 19 + HOTBAR_SLOTS);
+    }
+
+    #[test]
+    fn shared_binding_marks_both_actions_as_conflicting() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings.set_binding(BindingAction::DropItem, InputBinding::Key(KeyCode::KeyW)));
+        assert!(bindings.set_binding(
+            BindingAction::PickBlock,
+            InputBinding::Mouse(MouseButton::Left)
+        ));
+
+        assert_eq!(
+            bindings.conflicts(),
+            [
+                BindingAction::MoveForward,
+                BindingAction::BreakBlock,
+                BindingAction::PickBlock,
+                BindingAction::DropItem,
+            ]
+        );
+    }
+
+    #[test]
+    fn set_binding_rejects_escape_and_the_wrong_device() {
+        let mut bindings = KeyBindings::default();
+        assert!(!bindings.set_binding(BindingAction::Jump, InputBinding::Key(KeyCode::Escape)));
+        assert!(!bindings.set_binding(BindingAction::Jump, InputBinding::Mouse(MouseButton::Left)));
+        assert!(!bindings.set_binding(BindingAction::BreakBlock, InputBinding::Key(KeyCode::KeyB)));
+        assert_eq!(bindings, KeyBindings::default());
+    }
+
+    #[test]
+    fn binding_labels_are_short() {
+        assert_eq!(InputBinding::Key(KeyCode::KeyW).label(), "W");
+        assert_eq!(InputBinding::Key(KeyCode::Digit4).label(), "4");
+        assert_eq!(InputBinding::Key(KeyCode::ShiftLeft).label(), "ShiftLeft");
+        assert_eq!(
+            InputBinding::Mouse(MouseButton::Right).label(),
+            "Right Button"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use bevy::prelude::*;
+use bevy_settings::{ReflectSettingsGroup, SettingsGroup};
+
+use super::control::{KeyBindings, PlayerMovementIntent};
+
+/// Hold to jump, or to ascend while flying.
+pub const GAMEPAD_JUMP: GamepadButton = GamepadButton::South;
+/// Hold to descend while flying.
+pub const GAMEPAD_DESCEND: GamepadButton = GamepadButton::East;
+pub const GAMEPAD_SPRINT: GamepadButton = GamepadButton::LeftThumb;
+pub const GAMEPAD_BREAK_BLOCK: GamepadButton = GamepadButton::RightTrigger2;
+pub const GAMEPAD_PLACE_BLOCK: GamepadButton = GamepadButton::LeftTrigger2;
+pub const GAMEPAD_HOTBAR_NEXT: GamepadButton = GamepadButton::RightTrigger;
+pub const GAMEPAD_HOTBAR_PREVIOUS: GamepadButton = GamepadButton::LeftTrigger;
+
+/// Stick tuning for gamepads. Button mapping is fixed by the `GAMEPAD_*`
+/// constants.
+#[derive(Resource, SettingsGroup, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, SettingsGroup, Default)]
+pub struct GamepadControlSettings {
+    /// Share of stick travel, from the centre, that is ignored.
+    pub stick_deadzone: f32,
+    /// Camera turn rate at full right-stick deflection, in degrees per second.
+    pub look_sensitivity: f32,
+    pub invert_look_y: bool,
+}
+
+impl Default for GamepadControlSettings {
+    fn default() -> Self {
+        Self {
+            stick_deadzone: 0.15,
+            look_sensitivity: 180.0,
+            invert_look_y: false,
+        }
+    }
+}
+
+impl GamepadControlSettings {
+    /// Removes the deadzone from a stick position and rescales the remaining
+    /// travel so output still starts at zero and reaches full deflection.
+    pub fn stick(&self, stick: Vec2) -> Vec2 {
+        // Settings files can be edited by hand; keep some usable travel.
+        let deadzone = if self.stick_deadzone.is_finite() {
+            self.stick_deadzone.clamp(0.0, 0.9)
+        } else {
+            Self::default().stick_deadzone
+        };
+        let length = stick.length();
+        if !length.is_finite() || length <= deadzone {
+            return Vec2::ZERO;
+        }
+
+        stick / length * ((length - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+
+    /// Yaw and pitch to turn by this frame, in radians. Positive yaw turns
+    /// right and positive pitch looks up.
+    pub fn look_delta(&self, gamepad: &Gamepad, delta_secs: f32) -> Vec2 {
+        let mut stick = self.stick(gamepad.right_stick());
+        if self.invert_look_y {
+            stick.y = -stick.y;
+        }
+        let sensitivity = if self.look_sensitivity.is_finite() {
+            self.look_sensitivity.max(0.0)
+        } else {
+            Self::default().look_sensitivity
+        };
+
+        stick * sensitivity.to_radians() * delta_secs
+    }
+
+    pub fn movement_intent(&self, gamepad: &Gamepad) -> PlayerMovementIntent {
+        let stick = self.stick(gamepad.left_stick());
+        let mut local_move_axis = Vec3::new(stick.x, 0.0, stick.y);
+        if gamepad.pressed(GAMEPAD_JUMP) {
+            local_move_axis.y += 1.0;
+        }
+        if gamepad.pressed(GAMEPAD_DESCEND) {
+            local_move_axis.y -= 1.0;
+        }
+
+        PlayerMovementIntent {
+            local_move_axis,
+            jump: gamepad.pressed(GAMEPAD_JUMP),
+            sprint: gamepad.pressed(GAMEPAD_SPRINT),
+        }
+    }
+}
+
+/// Keyboard movement combined with every connected gamepad.
+pub fn player_movement_intent<'a>(
+    keys: &ButtonInput<KeyCode>,
+    key_bindings: &KeyBindings,
+    gamepads: impl IntoIterator<Item = &'a Gamepad>,
+    settings: &GamepadControlSettings,
+) -> PlayerMovementIntent {
+    gamepads
+        .into_iter()
+        .fold(key_bindings.movement_intent(keys), |intent, gamepad| {
+            intent.combine(settings.movement_intent(gamepad))
+        })
+}
+
+/// Hotbar slots to move by this frame: `1` for the next slot, `-1` for the
+/// previous one.
+pub fn hotbar_step(gamepad: &Gamepad) -> isize {
+    isize::from(gamepad.just_pressed(GAMEPAD_HOTBAR_NEXT))
+        - isize::from(gamepad.just_pressed(GAMEPAD_HOTBAR_PREVIOUS))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bevy::input::{
+        InputPlugin,
+        gamepad::{
+            GamepadConnection, GamepadConnectionEvent, RawGamepadAxisChangedEvent,
+            RawGamepadButtonChangedEvent, RawGamepadEvent,
+        },
+    };
+
+    fn gamepad_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        let gamepad = connect_gamepad(&mut app);
+        (app, gamepad)
+    }
+
+    /// Connects a synthetic gamepad through Bevy's input processing. The app
+    /// needs `InputPlugin`.
+    pub(crate) fn connect_gamepad(app: &mut App) -> Entity {
+        let gamepad = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected {
+                name: "Test Pad".to_owned(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+        app.update();
+        assert!(app.world().get::<Gamepad>(gamepad).is_some());
+        gamepad
+    }
+
+    fn move_axis(app: &mut App, gamepad: Entity, axis: GamepadAxis, value: f32) {
+        app.world_mut()
+            .write_message(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(
+                gamepad, axis, value,
+            )));
+    }
+
+    pub(crate) fn set_button(app: &mut App, gamepad: Entity, button: GamepadButton, value: f32) {
+        app.world_mut()
+            .write_message(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+                gamepad, button, value,
+            )));
+    }
+
+    fn gamepad(app: &App, gamepad: Entity) -> &Gamepad {
+        app.world().get::<Gamepad>(gamepad).unwrap()
+    }
+
+    #[test]
+    fn left_stick_moves_outside_the_deadzone() {
+        let (mut app, pad) = gamepad_app();
+        let settings = GamepadControlSettings::default();
+
+        move_axis(&mut app, pad, GamepadAxis::LeftStickY, 0.1);
+        app.update();
+        assert_eq!(
+            settings.movement_intent(gamepad(&app, pad)).local_move_axis,
+            Vec3::ZERO
+        );
+
+        move_axis(&mut app, pad, GamepadAxis::LeftStickX, -1.0);
+        move_axis(&mut app, pad, GamepadAxis::LeftStickY, 0.0);
+        app.update();
+        let intent = settings.movement_intent(gamepad(&app, pad));
+        assert!(intent.local_move_axis.abs_diff_eq(Vec3::NEG_X, 1e-5));
+        assert!(!intent.jump);
+    }
+
+    #[test]
+    fn face_buttons_jump_sprint_and_descend() {
+        let (mut app, pad) = gamepad_app();
+        let settings = GamepadControlSettings::default();
+
+        move_axis(&mut app, pad, GamepadAxis::LeftStickY, 1.0);
+        set_button(&mut app, pad, GAMEPAD_JUMP, 1.0);
+        set_button(&mut app, pad, GAMEPAD_SPRINT, 1.0);
+        app.update();
+        let intent = settings.movement_intent(gamepad(&app, pad));
+        assert!(intent.jump);
+        assert!(intent.wants_forward_sprint());
+        assert_eq!(intent.local_move_axis.y, 1.0);
+
+        set_button(&mut app, pad, GAMEPAD_JUMP, 0.0);
+        set_button(&mut app, pad, GAMEPAD_DESCEND, 1.0);
+        app.update();
+        let intent = settings.movement_intent(gamepad(&app, pad));
+        assert!(!intent.jump);
+        assert_eq!(intent.local_move_axis.y, -1.0);
+    }
+
+    #[test]
+    fn keyboard_and_gamepad_movement_combine() {
+        let (mut app, pad) = gamepad_app();
+        let settings = GamepadControlSettings::default();
+        let bindings = KeyBindings::default();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(bindings.move_forward);
+
+        move_axis(&mut app, pad, GamepadAxis::LeftStickY, 1.0);
+        move_axis(&mut app, pad, GamepadAxis::LeftStickX, 1.0);
+        app.update();
+        let intent = player_movement_intent(&keys, &bindings, [gamepad(&app, pad)], &settings);
+
+        // Both inputs push forward, but the axis stays within one unit.
+        assert_eq!(intent.local_move_axis.z, 1.0);
+        assert!(intent.local_move_axis.x > 0.0);
+    }
+
+    #[test]
+    fn right_stick_turns_at_the_look_sensitivity() {
+        let (mut app, pad) = gamepad_app();
+        let mut settings = GamepadControlSettings::default();
+
+        move_axis(&mut app, pad, GamepadAxis::RightStickX, 1.0);
+        move_axis(&mut app, pad, GamepadAxis::RightStickY, -1.0);
+        app.update();
+        let look = settings.look_delta(gamepad(&app, pad), 0.5);
+        let expected = settings.stick(vec2(1.0, -1.0)) * 90f32.to_radians();
+        assert!(look.abs_diff_eq(expected, 1e-5));
+        assert!(look.x > 0.0 && look.y < 0.0);
+
+        settings.invert_look_y = true;
+        assert!(settings.look_delta(gamepad(&app, pad), 0.5).y > 0.0);
+    }
+
+    #[test]
+    fn bumpers_step_the_hotbar_once_per_press() {
+        let (mut app, pad) = gamepad_app();
+
+        set_button(&mut app, pad, GAMEPAD_HOTBAR_NEXT, 1.0);
+        app.update();
+        assert_eq!(hotbar_step(gamepad(&app, pad)), 1);
+
+        app.update();
+        assert_eq!(hotbar_step(gamepad(&app, pad)), 0);
+
+        set_button(&mut app, pad, GAMEPAD_HOTBAR_NEXT, 0.0);
+        set_button(&mut app, pad, GAMEPAD_HOTBAR_PREVIOUS, 1.0);
+        app.update();
+        assert_eq!(hotbar_step(gamepad(&app, pad)), -1);
+    }
+
+    #[test]
+    fn deadzone_rescales_and_survives_bad_settings() {
+        let settings = GamepadControlSettings {
+            stick_deadzone: 0.2,
+            ..default()
+        };
+        assert_eq!(settings.stick(vec2(0.2, 0.0)), Vec2::ZERO);
+        assert!(
+            settings
+                .stick(vec2(0.6, 0.0))
+                .abs_diff_eq(vec2(0.5, 0.0), 1e-6)
+        );
+        assert_eq!(settings.stick(vec2(0.0, -1.0)), vec2(0.0, -1.0));
+
+        let broken = GamepadControlSettings {
+            stick_deadzone: f32::NAN,
+            look_sensitivity: f32::INFINITY,
+            invert_look_y: false,
+        };
+        assert!(broken.stick(vec2(1.0, 0.0)).is_finite());
+        assert_eq!(broken.stick(vec2(0.1, 0.0)), Vec2::ZERO);
+    }
+}
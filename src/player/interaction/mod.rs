@@ -17,7 +17,11 @@ use crate::{
     },
 };
 
-use super::cam::{MouseCam, MouseState, PlayerCameraSystems, gameplay_input_active};
+use super::{
+    cam::{MouseCam, MouseState, PlayerCameraSystems, gameplay_input_active},
+    control::KeyBindings,
+    gamepad::{GAMEPAD_BREAK_BLOCK, GAMEPAD_PLACE_BLOCK},
+};
 
 pub struct BlockInteractionPlugin;
 
@@ -185,6 +189,8 @@ fn draw_block_target_gizmos(gizmos: &mut Gizmos, target: BlockTarget) {
 
 fn emit_block_interaction_requests(
     mouse_buttons: Res<ButtonInput<MouseButton>>,
+    bindings: Res<KeyBindings>,
+    gamepads: Query<&Gamepad>,
     current_target: Res<CurrentBlockTarget>,
     mut requests: MessageWriter<BlockInteractionRequest>,
 ) {
@@ -192,12 +198,22 @@ fn emit_block_interaction_requests(
         return;
     };
 
-    for (button, kind) in [
-        (MouseButton::Middle, BlockInteractionKind::Pick),
-        (MouseButton::Left, BlockInteractionKind::Break),
-        (MouseButton::Right, BlockInteractionKind::Place),
+    for (button, gamepad_button, kind) in [
+        (bindings.pick_block, None, BlockInteractionKind::Pick),
+        (
+            bindings.break_block,
+            Some(GAMEPAD_BREAK_BLOCK),
+            BlockInteractionKind::Break,
+        ),
+        (
+            bindings.place_block,
+            Some(GAMEPAD_PLACE_BLOCK),
+            BlockInteractionKind::Place,
+        ),
     ] {
-        if mouse_buttons.pressed(button) {
+        let gamepad_pressed = gamepad_button
+            .is_some_and(|gamepad_button| gamepads.iter().any(|pad| pad.pressed(gamepad_button)));
+        if mouse_buttons.pressed(button) || gamepad_pressed {
             requests.write(BlockInteractionRequest { kind, target });
         }
     }
@@ -80,6 +80,7 @@ fn app_with_request_emitter() -> App {
             1.0 / 60.0,
         )))
         .insert_resource(ButtonInput::<MouseButton>::default())
+        .init_resource::<KeyBindings>()
         .insert_resource(CurrentBlockTarget(Some(target())))
         .init_resource::<InteractionCounts>()
         .add_message::<BlockInteractionRequest>()
@@ -117,6 +118,26 @@ fn held_buttons_emit_one_request_per_action_per_fixed_tick() {
     assert_eq!(counts.place, 20);
 }
 
+#[test]
+fn held_gamepad_triggers_break_and_place() {
+    use crate::player::gamepad::tests::{connect_gamepad, set_button};
+
+    let mut app = app_with_request_emitter();
+    app.add_plugins(bevy::input::InputPlugin);
+    let gamepad = connect_gamepad(&mut app);
+    set_button(&mut app, gamepad, GAMEPAD_BREAK_BLOCK, 1.0);
+    set_button(&mut app, gamepad, GAMEPAD_PLACE_BLOCK, 1.0);
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let counts = app.world().resource::<InteractionCounts>();
+    assert_eq!(counts.pick, 0);
+    assert!(counts.break_block > 0);
+    assert_eq!(counts.break_block, counts.place);
+}
+
 #[test]
 fn interaction_request_uses_action_specific_block_position() {
     let target = target();
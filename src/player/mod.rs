@@ -1,5 +1,6 @@
 pub mod cam;
 pub mod control;
+pub mod gamepad;
 pub mod inspector;
 pub mod interaction;
 mod persistence;
@@ -78,7 +79,7 @@ impl PlayerDimension {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameMode {
     Survival,
     #[default]
@@ -86,3 +87,32 @@ pub enum GameMode {
     Adventure,
     Spectator,
 }
+
+/// The game modes a player can be switched into so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayableGameMode {
+    Creative,
+    Spectator,
+}
+
+impl From<PlayableGameMode> for GameMode {
+    fn from(mode: PlayableGameMode) -> Self {
+        match mode {
+            PlayableGameMode::Creative => Self::Creative,
+            PlayableGameMode::Spectator => Self::Spectator,
+        }
+    }
+}
+
+impl TryFrom<GameMode> for PlayableGameMode {
+    /// The mode that cannot be played yet.
+    type Error = GameMode;
+
+    fn try_from(mode: GameMode) -> Result<Self, GameMode> {
+        match mode {
+            GameMode::Creative => Ok(Self::Creative),
+            GameMode::Spectator => Ok(Self::Spectator),
+            GameMode::Survival | GameMode::Adventure => Err(mode),
+        }
+    }
+}
@@ -3,7 +3,13 @@ use std::collections::{HashMap, HashSet};
 use avian3d::prelude::Position;
 use bevy::{app::AppExit, prelude::*, time::Real, window::ExitSystems};
 
-use crate::world::storage::{ChunkRepository, StoredPlayer, StoredPlayerPosition};
+use crate::{
+    game_state::GameState,
+    world::{
+        session::WorldSessionSystems,
+        storage::{ChunkRepository, StoredPlayer, StoredPlayerPosition},
+    },
+};
 
 use super::{Player, PlayerDimension, PlayerId};
 
@@ -20,7 +26,11 @@ pub(super) struct PlayerPersistenceDisabled;
 impl Plugin for PlayerPersistencePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerSaveState>()
-            .add_systems(Last, save_player_positions.after(ExitSystems));
+            .add_systems(Last, save_player_positions.after(ExitSystems))
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                save_players_on_close.in_set(WorldSessionSystems::Save),
+            );
     }
 }
 
@@ -41,9 +51,16 @@ impl Default for PlayerSaveState {
     }
 }
 
+type SavedPlayers<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Player, &'static PlayerDimension, &'static Position),
+    Without<PlayerPersistenceDisabled>,
+>;
+
 fn save_player_positions(
     repository: Option<Res<ChunkRepository>>,
-    players: Query<(&Player, &PlayerDimension, &Position), Without<PlayerPersistenceDisabled>>,
+    players: SavedPlayers,
     real_time: Res<Time<Real>>,
     mut exits: MessageReader<AppExit>,
     mut state: ResMut<PlayerSaveState>,
@@ -54,9 +71,31 @@ fn save_player_positions(
         return;
     };
 
-    for (player, dimension, position) in &players {
+    write_player_positions(&repository, &players, &mut state, autosave_due || exiting);
+}
+
+/// Saves every player as the world closes, then forgets what was saved so the
+/// next world starts with first-spawn saves again.
+fn save_players_on_close(
+    repository: Option<Res<ChunkRepository>>,
+    players: SavedPlayers,
+    mut state: ResMut<PlayerSaveState>,
+) {
+    if let Some(repository) = repository {
+        write_player_positions(&repository, &players, &mut state, true);
+    }
+    *state = PlayerSaveState::default();
+}
+
+fn write_player_positions(
+    repository: &ChunkRepository,
+    players: &SavedPlayers,
+    state: &mut PlayerSaveState,
+    save_all: bool,
+) {
+    for (player, dimension, position) in players {
         let first_attempt = state.attempted.insert(player.id);
-        if !first_attempt && !autosave_due && !exiting {
+        if !first_attempt && !save_all {
             continue;
         }
 
@@ -157,6 +196,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn returning_to_the_title_saves_the_latest_position() {
+        let mut app = persistence_app();
+        app.add_plugins(bevy::state::app::StatesPlugin)
+            .init_state::<GameState>();
+        let player = app
+            .world_mut()
+            .spawn((
+                Player::default(),
+                PlayerDimension::new(DimensionId::OVERWORLD),
+                Position::new(Vec3::new(1.0, 2.0, 3.0)),
+            ))
+            .id();
+        app.update();
+
+        app.world_mut().get_mut::<Position>(player).unwrap().0 = Vec3::new(8.5, 20.0, -9.25);
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::MainMenu);
+        app.update();
+
+        let repository = app.world().resource::<ChunkRepository>();
+        let saved = repository.load_player(PlayerId::LOCAL).unwrap().unwrap();
+        assert_eq!(saved.position().translation(), Vec3::new(8.5, 20.0, -9.25));
+    }
+
     #[test]
     fn window_exit_systems_run_before_the_final_player_flush() {
         let mut app = persistence_app();
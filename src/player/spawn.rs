@@ -1,5 +1,3 @@
-use std::f32::consts::PI;
-
 use super::{
     PLAYER_HEIGHT, PLAYER_LENGTH, PLAYER_WIDTH, Player, PlayerDimension,
     cam::{MouseCam, MouseSettings},
@@ -14,6 +12,7 @@ use crate::{
     world::{
         ACTOR_COLLISION_LAYERS,
         dimension::{Active, DesiredColumnView, Dimension},
+        session::WorldSessionSystems,
         storage::ChunkRepository,
     },
 };
@@ -22,7 +21,11 @@ pub struct SpawnPlayerPlugin;
 
 impl Plugin for SpawnPlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::GenWorld), spawn_player);
+        app.add_systems(OnExit(GameState::GenWorld), spawn_player)
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                despawn_players.in_set(WorldSessionSystems::Close),
+            );
     }
 }
 
@@ -121,7 +124,7 @@ fn spawn_player(
                 .looking_to(Vec3::X, Vec3::Y)
                 .with_translation(Vec3::Y * (PLAYER_HEIGHT / 2.0 - EYELINE)),
             Projection::Perspective(PerspectiveProjection {
-                fov: MouseSettings::default().fov / 180.0 * PI,
+                fov: MouseSettings::default().fov_radians(),
                 ..default()
             }),
             IsDefaultUiCamera,
@@ -132,6 +135,12 @@ fn spawn_player(
     }
 }
 
+fn despawn_players(mut commands: Commands, players: Query<Entity, With<Player>>) {
+    for player in &players {
+        commands.entity(player).despawn();
+    }
+}
+
 pub fn make_player_collider() -> Collider {
     Collider::cuboid(PLAYER_LENGTH, PLAYER_HEIGHT, PLAYER_WIDTH)
 }
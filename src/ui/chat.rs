@@ -0,0 +1,422 @@
+use std::time::Duration;
+
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
+
+use crate::{
+    chat::{ChatLineKind, ChatLog, CompletionContext, complete, submit_chat_line},
+    game_state::{ChatState, GameState},
+    player::{
+        cam::{MouseState, gameplay_input_active},
+        control::KeyBindings,
+        interaction::CurrentBlockTarget,
+    },
+    world::DimensionCatalog,
+};
+
+const VISIBLE_LINES: usize = 10;
+/// Closed chat shows a line this long before it has faded out.
+const LINE_LIFETIME: Duration = Duration::from_secs(10);
+const LINE_FADE: Duration = Duration::from_secs(1);
+const MAX_DRAFT_CHARS: usize = 256;
+const MAX_HISTORY: usize = 50;
+const CHAT_FONT_SIZE: f32 = 16.0;
+const LINE_BACKGROUND_ALPHA: f32 = 0.45;
+const FEEDBACK_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const ERROR_COLOR: Color = Color::srgb(1.0, 0.33, 0.33);
+
+pub struct ChatOverlayPlugin;
+
+impl Plugin for ChatOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatDraft>()
+            .add_systems(Startup, spawn_chat_overlay)
+            .add_systems(OnEnter(ChatState::Open), release_mouse_for_chat)
+            .add_systems(
+                OnExit(ChatState::Open),
+                (clear_chat_draft, grab_mouse_after_chat),
+            )
+            .add_systems(
+                Update,
+                (
+                    open_chat
+                        .run_if(in_state(ChatState::Closed))
+                        .run_if(gameplay_input_active),
+                    type_into_chat.run_if(in_state(GameState::Playing)),
+                    refresh_chat_overlay,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The line being typed, with the history and completions it can cycle
+/// through.
+#[derive(Resource, Default, Debug)]
+struct ChatDraft {
+    text: String,
+    /// Sent lines, oldest first.
+    history: Vec<String>,
+    recalled: Option<usize>,
+    completions: Vec<String>,
+    completion: Option<usize>,
+}
+
+impl ChatDraft {
+    fn type_text(&mut self, text: &str) {
+        self.completion = None;
+        for character in text.chars().filter(|character| !character.is_control()) {
+            if self.text.chars().count() >= MAX_DRAFT_CHARS {
+                break;
+            }
+            self.text.push(character);
+        }
+    }
+
+    fn erase(&mut self) {
+        self.completion = None;
+        self.text.pop();
+    }
+
+    /// Replaces the draft with the next completion of what was typed.
+    fn complete(&mut self, context: &CompletionContext) {
+        let next = match self.completion {
+            Some(index) => (index + 1) % self.completions.len(),
+            None => {
+                self.completions = complete(&self.text, context);
+                if self.completions.is_empty() {
+                    return;
+                }
+                0
+            }
+        };
+        self.completion = Some(next);
+        self.text.clone_from(&self.completions[next]);
+    }
+
+    /// Steps through sent lines, towards older ones when `older` is set.
+    /// Stepping past the newest line clears the draft.
+    fn recall(&mut self, older: bool) {
+        let Some(newest) = self.history.len().checked_sub(1) else {
+            return;
+        };
+        let index = match (self.recalled, older) {
+            (None, true) => newest,
+            (Some(index), true) => index.saturating_sub(1),
+            (Some(index), false) if index < newest => index + 1,
+            (Some(_), false) => {
+                self.recalled = None;
+                self.text.clear();
+                return;
+            }
+            (None, false) => return,
+        };
+        self.recalled = Some(index);
+        self.completion = None;
+        self.text.clone_from(&self.history[index]);
+    }
+
+    /// Takes the draft to send it, remembering it for `recall`.
+    fn take_line(&mut self) -> String {
+        let line = std::mem::take(&mut self.text);
+        if !line.trim().is_empty() && self.history.last() != Some(&line) {
+            if self.history.len() == MAX_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(line.clone());
+        }
+        self.recalled = None;
+        self.completion = None;
+        line
+    }
+}
+
+/// One of the recent-line rows, counted up from the newest line.
+#[derive(Component, Clone, Copy)]
+struct ChatLineSlot(usize);
+
+#[derive(Component)]
+struct ChatInput;
+
+fn chat_text_font() -> TextFont {
+    TextFont {
+        font_size: FontSize::Px(CHAT_FONT_SIZE),
+        ..default()
+    }
+}
+
+fn spawn_chat_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Chat"),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(4.0),
+                bottom: Val::Px(4.0),
+                width: Val::Vw(45.0),
+                max_width: Val::Px(640.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            Pickable::IGNORE,
+            GlobalZIndex(500),
+        ))
+        .with_children(|chat| {
+            chat.spawn(Node {
+                flex_direction: FlexDirection::ColumnReverse,
+                ..default()
+            })
+            .with_children(|lines| {
+                for slot in 0..VISIBLE_LINES {
+                    lines.spawn((
+                        ChatLineSlot(slot),
+                        Text::new(""),
+                        chat_text_font(),
+                        TextColor(Color::WHITE),
+                        TextShadow {
+                            offset: Vec2::splat(1.0),
+                            color: Color::BLACK,
+                        },
+                        BackgroundColor(Color::NONE),
+                        Node {
+                            padding: UiRect::horizontal(Val::Px(4.0)),
+                            ..default()
+                        },
+                        Visibility::Hidden,
+                    ));
+                }
+            });
+
+            chat.spawn((
+                ChatInput,
+                Text::new(""),
+                chat_text_font(),
+                TextColor(Color::WHITE),
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, LINE_BACKGROUND_ALPHA)),
+                Node {
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                Visibility::Hidden,
+            ));
+        });
+}
+
+fn open_chat(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut draft: ResMut<ChatDraft>,
+    mut next_chat: ResMut<NextState<ChatState>>,
+) {
+    let prefix = if keys.just_pressed(bindings.open_command) {
+        "/"
+    } else if keys.just_pressed(bindings.open_chat) {
+        ""
+    } else {
+        return;
+    };
+    draft.text = prefix.to_owned();
+    next_chat.set(ChatState::Open);
+}
+
+fn release_mouse_for_chat(mut next_mouse_state: ResMut<NextState<MouseState>>) {
+    next_mouse_state.set(MouseState::Free);
+}
+
+fn grab_mouse_after_chat(
+    game_state: Res<State<GameState>>,
+    mut next_mouse_state: ResMut<NextState<MouseState>>,
+) {
+    if *game_state.get() == GameState::Playing {
+        next_mouse_state.set(MouseState::Grabbed);
+    }
+}
+
+fn clear_chat_draft(mut draft: ResMut<ChatDraft>) {
+    draft.take_line();
+}
+
+/// Keyboard messages are read every frame so the key that opened the chat
+/// is not also typed into it.
+fn type_into_chat(
+    mut commands: Commands,
+    mut keys: MessageReader<KeyboardInput>,
+    chat: Res<State<ChatState>>,
+    mut next_chat: ResMut<NextState<ChatState>>,
+    mut draft: ResMut<ChatDraft>,
+    target: Option<Res<CurrentBlockTarget>>,
+    catalog: Option<Res<DimensionCatalog>>,
+) {
+    if *chat.get() != ChatState::Open {
+        keys.clear();
+        return;
+    }
+
+    for key in keys.read() {
+        if !key.state.is_pressed() {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Enter => {
+                let line = draft.take_line();
+                commands.queue(move |world: &mut World| submit_chat_line(world, &line));
+                next_chat.set(ChatState::Closed);
+                break;
+            }
+            Key::Escape => {
+                next_chat.set(ChatState::Closed);
+                break;
+            }
+            Key::Tab => {
+                let context = CompletionContext {
+                    target_block: target
+                        .as_ref()
+                        .and_then(|target| target.0)
+                        .map(|target| target.hit_block.world().as_ivec3()),
+                    dimensions: catalog.as_ref().map_or_else(Vec::new, |catalog| {
+                        catalog.iter().map(|definition| definition.id()).collect()
+                    }),
+                };
+                draft.complete(&context);
+            }
+            Key::ArrowUp => draft.recall(true),
+            Key::ArrowDown => draft.recall(false),
+            Key::Backspace => draft.erase(),
+            Key::Space => draft.type_text(" "),
+            Key::Character(text) => draft.type_text(text),
+            _ => {}
+        }
+    }
+}
+
+type ChatLineSlots<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ChatLineSlot,
+        &'static mut Text,
+        &'static mut TextColor,
+        &'static mut BackgroundColor,
+        &'static mut Visibility,
+    ),
+    Without<ChatInput>,
+>;
+
+/// Shows recent lines, fading them out while the chat is closed, and the
+/// draft while it is open.
+fn refresh_chat_overlay(
+    time: Res<Time<Real>>,
+    log: Res<ChatLog>,
+    draft: Res<ChatDraft>,
+    chat: Option<Res<State<ChatState>>>,
+    mut slots: ChatLineSlots,
+    input: Single<(&mut Text, &mut Visibility), With<ChatInput>>,
+) {
+    let open = chat.is_some_and(|chat| *chat.get() == ChatState::Open);
+    let now = time.elapsed();
+
+    for (slot, mut text, mut color, mut background, mut visibility) in &mut slots {
+        let line = log.lines().rev().nth(slot.0);
+        let opacity = match line {
+            Some(_) if open => 1.0,
+            Some(line) => line_opacity(now.saturating_sub(line.added_at)),
+            None => 0.0,
+        };
+        let shown = if opacity > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(shown);
+        let Some(line) = line.filter(|_| opacity > 0.0) else {
+            continue;
+        };
+
+        if text.0 != line.text {
+            text.0.clone_from(&line.text);
+        }
+        let line_color = match line.kind {
+            ChatLineKind::Message => Color::WHITE,
+            ChatLineKind::Feedback => FEEDBACK_COLOR,
+            ChatLineKind::Error => ERROR_COLOR,
+        };
+        color.set_if_neq(TextColor(line_color.with_alpha(opacity)));
+        background.set_if_neq(BackgroundColor(
+            Color::BLACK.with_alpha(LINE_BACKGROUND_ALPHA * opacity),
+        ));
+    }
+
+    let (mut input_text, mut input_visibility) = input.into_inner();
+    input_visibility.set_if_neq(if open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if open {
+        let shown = format!("{}_", draft.text);
+        if input_text.0 != shown {
+            input_text.0 = shown;
+        }
+    }
+}
+
+/// Closed-chat opacity of a line `age` old: solid, then fading over the
+/// last second of its lifetime.
+fn line_opacity(age: Duration) -> f32 {
+    let remaining = LINE_LIFETIME.saturating_sub(age);
+    (remaining.as_secs_f32() / LINE_FADE.as_secs_f32()).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_cycles_through_completions() {
+        let mut draft = ChatDraft::default();
+        draft.type_text("/give oak");
+        let context = CompletionContext::default();
+
+        draft.complete(&context);
+        assert_eq!(draft.text, "/give oak_leaves");
+        draft.complete(&context);
+        assert_eq!(draft.text, "/give oak_log");
+        draft.complete(&context);
+        assert_eq!(draft.text, "/give oak_leaves");
+
+        draft.type_text(" 5");
+        draft.complete(&context);
+        assert_eq!(draft.text, "/give oak_leaves 5");
+    }
+
+    #[test]
+    fn up_and_down_recall_sent_lines() {
+        let mut draft = ChatDraft::default();
+        for line in ["/seed", "hello", "hello", " "] {
+            draft.type_text(line);
+            draft.take_line();
+        }
+        assert_eq!(draft.history, ["/seed", "hello"]);
+
+        draft.recall(true);
+        assert_eq!(draft.text, "hello");
+        draft.recall(true);
+        draft.recall(true);
+        assert_eq!(draft.text, "/seed");
+        draft.recall(false);
+        assert_eq!(draft.text, "hello");
+        draft.recall(false);
+        assert_eq!(draft.text, "");
+    }
+
+    #[test]
+    fn closed_chat_lines_fade_out() {
+        assert_eq!(line_opacity(Duration::ZERO), 1.0);
+        assert_eq!(line_opacity(Duration::from_millis(9_500)), 0.5);
+        assert_eq!(line_opacity(LINE_LIFETIME), 0.0);
+    }
+}
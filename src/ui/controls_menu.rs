@@ -0,0 +1,379 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+use bevy_settings::SaveSettingsDeferred;
+
+use super::pause_menu::{MenuButtonSize, PauseMenuTextures, spawn_menu_button};
+use crate::{
+    game_state::PauseScreen,
+    player::control::{BindingAction, InputBinding, KeyBindings},
+};
+
+const CONFLICT_COLOR: Color = Color::srgb(1.0, 0.33, 0.33);
+
+pub struct ControlsMenuPlugin;
+
+impl Plugin for ControlsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(PauseScreen::Controls),
+            (reset_capture, spawn_controls_menu),
+        )
+        .add_systems(OnExit(PauseScreen::Controls), reset_capture)
+        .add_systems(
+            Update,
+            (
+                handle_controls_input,
+                save_key_bindings.run_if(resource_changed::<KeyBindings>),
+                refresh_binding_labels,
+            )
+                .chain()
+                .run_if(in_state(PauseScreen::Controls)),
+        );
+    }
+}
+
+/// The action waiting for its next key or mouse press, if any.
+#[derive(Resource, Default, Debug, PartialEq, Eq)]
+struct ControlsCapture(Option<BindingAction>);
+
+#[derive(Component, Debug, Clone, Copy)]
+enum ControlsMenuAction {
+    Rebind(BindingAction),
+    ResetDefaults,
+    Done,
+}
+
+/// Text showing the current binding of an action.
+#[derive(Component, Clone, Copy)]
+struct BindingLabel(BindingAction);
+
+fn reset_capture(mut commands: Commands) {
+    commands.insert_resource(ControlsCapture::default());
+}
+
+fn spawn_controls_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<KeyBindings>,
+) {
+    let textures = PauseMenuTextures::load(&asset_server);
+
+    commands
+        .spawn((
+            Name::new("Controls Menu"),
+            DespawnOnExit(PauseScreen::Controls),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::ZERO,
+                top: Val::ZERO,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.62)),
+            FocusPolicy::Block,
+            GlobalZIndex(1_000),
+        ))
+        .with_children(|overlay| {
+            overlay
+                .spawn(Node {
+                    width: Val::Vw(90.0),
+                    max_width: Val::Px(900.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|menu| {
+                    menu.spawn((
+                        Text::new("Controls"),
+                        TextFont {
+                            font_size: FontSize::Px(38.0),
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        TextShadow {
+                            offset: Vec2::splat(3.0),
+                            color: Color::BLACK,
+                        },
+                    ));
+
+                    menu.spawn(Node {
+                        width: Val::Percent(100.0),
+                        display: Display::Grid,
+                        grid_template_columns: RepeatedGridTrack::flex(2, 1.0),
+                        column_gap: Val::Px(32.0),
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    })
+                    .with_children(|grid| {
+                        for action in BindingAction::all() {
+                            spawn_binding_row(grid, action, &bindings, &textures);
+                        }
+                    });
+
+                    menu.spawn(Node {
+                        width: Val::Percent(100.0),
+                        column_gap: Val::Px(16.0),
+                        margin: UiRect {
+                            top: Val::Px(12.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|footer| {
+                        spawn_menu_button(
+                            footer,
+                            "Reset to Defaults",
+                            Some(ControlsMenuAction::ResetDefaults),
+                            controls_button(Val::Percent(50.0)),
+                            (),
+                            &textures,
+                        );
+                        spawn_menu_button(
+                            footer,
+                            "Done",
+                            Some(ControlsMenuAction::Done),
+                            controls_button(Val::Percent(50.0)),
+                            (),
+                            &textures,
+                        );
+                    });
+                });
+        });
+}
+
+fn spawn_binding_row(
+    parent: &mut ChildSpawnerCommands,
+    action: BindingAction,
+    bindings: &KeyBindings,
+    textures: &PauseMenuTextures,
+) {
+    parent
+        .spawn(Node {
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceBetween,
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                Text::new(action.label()),
+                TextFont {
+                    font_size: FontSize::Px(18.0),
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextShadow {
+                    offset: Vec2::splat(2.0),
+                    color: Color::BLACK,
+                },
+            ));
+            spawn_menu_button(
+                row,
+                bindings.binding(action).label(),
+                Some(ControlsMenuAction::Rebind(action)),
+                controls_button(Val::Px(170.0)),
+                BindingLabel(action),
+                textures,
+            );
+        });
+}
+
+const fn controls_button(width: Val) -> MenuButtonSize {
+    MenuButtonSize {
+        width,
+        height: 36.0,
+        font_size: 18.0,
+    }
+}
+
+type ControlsMenuButtons<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static ControlsMenuAction),
+    (Changed<Interaction>, With<Button>),
+>;
+
+/// While a rebind is pending, the next key or mouse press becomes the binding
+/// and button clicks are ignored, so the click that started the capture, or one
+/// that lands on another button, is not treated as a menu action.
+fn handle_controls_input(
+    buttons: ControlsMenuButtons,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut capture: ResMut<ControlsCapture>,
+    mut bindings: ResMut<KeyBindings>,
+    mut next_pause_screen: ResMut<NextState<PauseScreen>>,
+) {
+    if let Some(action) = capture.0 {
+        let pressed = if action.uses_mouse() {
+            mouse
+                .get_just_pressed()
+                .next()
+                .copied()
+                .map(InputBinding::Mouse)
+        } else {
+            keys.get_just_pressed()
+                .find(|&&key| key != KeyCode::Escape)
+                .copied()
+                .map(InputBinding::Key)
+        };
+        if let Some(binding) = pressed {
+            // Only write through `ResMut` on a real change, so an unchanged
+            // rebind does not trigger a settings save.
+            if bindings.binding(action) != binding {
+                bindings.set_binding(action, binding);
+            }
+            capture.0 = None;
+        }
+        return;
+    }
+
+    for (interaction, action) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *action {
+            ControlsMenuAction::Rebind(action) => capture.0 = Some(action),
+            ControlsMenuAction::ResetDefaults => {
+                if *bindings != KeyBindings::default() {
+                    *bindings = KeyBindings::default();
+                }
+            }
+            ControlsMenuAction::Done => next_pause_screen.set(PauseScreen::Main),
+        }
+    }
+}
+
+fn save_key_bindings(mut commands: Commands) {
+    commands.queue(SaveSettingsDeferred::default());
+}
+
+/// Shows each action's binding, marks the one being captured, and colours
+/// bindings shared by more than one action.
+fn refresh_binding_labels(
+    bindings: Res<KeyBindings>,
+    capture: Res<ControlsCapture>,
+    mut labels: Query<(&BindingLabel, &mut Text, &mut TextColor)>,
+) {
+    if !bindings.is_changed() && !capture.is_changed() {
+        return;
+    }
+
+    let conflicts = bindings.conflicts();
+    for (&BindingLabel(action), mut text, mut color) in &mut labels {
+        text.0 = if capture.0 == Some(action) {
+            format!("> {} <", bindings.binding(action).label())
+        } else {
+            bindings.binding(action).label()
+        };
+        color.0 = if conflicts.contains(&action) {
+            CONFLICT_COLOR
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::GameState;
+    use bevy::state::app::StatesPlugin;
+
+    fn controls_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<GameState>()
+            .add_sub_state::<PauseScreen>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ControlsCapture>()
+            .insert_resource(ButtonInput::<KeyCode>::default())
+            .insert_resource(ButtonInput::<MouseButton>::default())
+            .add_systems(Update, handle_controls_input);
+        app
+    }
+
+    fn press_button(app: &mut App, action: ControlsMenuAction) {
+        app.world_mut()
+            .spawn((Button, Interaction::Pressed, action));
+        app.update();
+    }
+
+    #[test]
+    fn capture_ignores_the_starting_click_and_binds_the_next_press() {
+        let mut app = controls_app();
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        press_button(
+            &mut app,
+            ControlsMenuAction::Rebind(BindingAction::PlaceBlock),
+        );
+        assert_eq!(
+            app.world().resource::<ControlsCapture>().0,
+            Some(BindingAction::PlaceBlock)
+        );
+        assert_eq!(
+            app.world().resource::<KeyBindings>().place_block,
+            MouseButton::Right
+        );
+
+        let mut mouse = app.world_mut().resource_mut::<ButtonInput<MouseButton>>();
+        mouse.clear();
+        mouse.press(MouseButton::Back);
+        app.update();
+
+        assert_eq!(app.world().resource::<ControlsCapture>().0, None);
+        assert_eq!(
+            app.world().resource::<KeyBindings>().place_block,
+            MouseButton::Back
+        );
+    }
+
+    #[test]
+    fn key_capture_ignores_mouse_and_escape() {
+        let mut app = controls_app();
+        app.world_mut().resource_mut::<ControlsCapture>().0 = Some(BindingAction::Jump);
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Escape);
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ControlsCapture>().0,
+            Some(BindingAction::Jump)
+        );
+        assert_eq!(app.world().resource::<KeyBindings>().jump, KeyCode::Space);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyJ);
+        app.update();
+
+        assert_eq!(app.world().resource::<ControlsCapture>().0, None);
+        assert_eq!(app.world().resource::<KeyBindings>().jump, KeyCode::KeyJ);
+    }
+
+    #[test]
+    fn reset_to_defaults_restores_every_binding() {
+        let mut app = controls_app();
+        {
+            let mut bindings = app.world_mut().resource_mut::<KeyBindings>();
+            bindings.move_forward = KeyCode::KeyI;
+            bindings.break_block = MouseButton::Forward;
+            bindings.hotbar_slots[0] = KeyCode::KeyQ;
+        }
+        press_button(&mut app, ControlsMenuAction::ResetDefaults);
+
+        assert_eq!(
+            *app.world().resource::<KeyBindings>(),
+            KeyBindings::default()
+        );
+    }
+}
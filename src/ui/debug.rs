@@ -5,6 +5,7 @@ use bevy::{
 };
 
 use crate::{
+    block::BlockLightRgb,
     input::ModifierCombo,
     memory::GameMemorySnapshot,
     player::Player,
@@ -129,9 +130,9 @@ fn update_debug_text(
                 chunk_lights.get(entity).ok()
             })
         })
-        .unwrap_or(0xF0);
-    let current_sky = current_light >> 4;
-    let current_block = current_light & 0x0F;
+        .unwrap_or(0xF000);
+    let current_sky = current_light >> 12;
+    let current_block = BlockLightRgb::from_packed(current_light);
 
     let facing = facing_dir(cam_transform);
     let (loaded, published) = active_dimension.as_ref().map_or((0, 0), |dimension| {
@@ -151,7 +152,7 @@ fn update_debug_text(
          \n\
          XYZ: {x:.3} / {y:.3} / {z:.3}\n\
          Block: {bx} {by} {bz}\n\
-         Light: {ft_sky} sky  {ft_block} block\n\
+         Light: {ft_sky} sky  {ft_block} block ({ft_r} {ft_g} {ft_b})\n\
          Chunk: {cx} {cy} {cz}\n\
          \n\
          Facing: {facing}\n\
@@ -167,7 +168,10 @@ fn update_debug_text(
         cy = chunk.y,
         cz = chunk.z,
         ft_sky = current_sky,
-        ft_block = current_block,
+        ft_block = current_block.level(),
+        ft_r = current_block.r,
+        ft_g = current_block.g,
+        ft_b = current_block.b,
         facing = facing,
         loaded = loaded,
         vd = view_distance.chunks(),
@@ -257,9 +261,9 @@ fn manage_light_labels(
                 .and_then(|dimension| {
                     packed_light_at(dimension, address, |entity| chunk_lights.get(entity).ok())
                 })
-                .unwrap_or(0xF0);
-            let sky = light >> 4;
-            let block = light & 0x0F;
+                .unwrap_or(0xF000);
+            let sky = light >> 12;
+            let block = BlockLightRgb::from_packed(light).level();
             needed.insert(world, format!("B{block}\nS{sky}"));
         }
     }
@@ -377,7 +381,7 @@ fn packed_light_at<'a>(
     dimension: &Dimension,
     address: ChunkBlockPos,
     light_for_entity: impl FnOnce(Entity) -> Option<&'a ChunkLight>,
-) -> Option<u8> {
+) -> Option<u16> {
     let entity = dimension.published_chunk_entity(address.chunk())?;
     light_for_entity(entity).map(|light| light.packed_light(address.local()))
 }
@@ -409,6 +413,6 @@ mod tests {
             _ => None,
         });
 
-        assert_eq!(packed, Some(3));
+        assert_eq!(packed, Some(0x0333));
     }
 }
@@ -13,7 +13,11 @@ use crate::quad::Direction;
 use crate::{
     game_state::GameState,
     item::Item,
-    player::cam::{MouseState, gameplay_input_is_active},
+    player::{
+        cam::{MouseState, gameplay_input_is_active},
+        control::KeyBindings,
+        gamepad,
+    },
 };
 
 pub struct HotbarPlugin;
@@ -38,10 +42,14 @@ impl Plugin for HotbarPlugin {
 }
 
 pub const HOTBAR_SLOTS: usize = 9;
+/// Items one hotbar slot holds at most.
+pub const HOTBAR_STACK_SIZE: u32 = 64;
 
 #[derive(Resource)]
 pub struct Hotbar {
     pub slots: [Option<Item>; HOTBAR_SLOTS],
+    /// Items held by each slot. Creative placement never uses them up.
+    pub counts: [u32; HOTBAR_SLOTS],
     pub selected: usize,
 }
 
@@ -59,6 +67,7 @@ impl Default for Hotbar {
                 Some(Item::Grass),
                 Some(Item::Ice),
             ],
+            counts: [HOTBAR_STACK_SIZE; HOTBAR_SLOTS],
             selected: 0,
         }
     }
@@ -69,8 +78,33 @@ impl Hotbar {
         self.slots[self.selected]
     }
 
+    /// Replaces the selected slot with a full stack of `item`.
     pub fn set_selected_item(&mut self, item: Item) {
         self.slots[self.selected] = Some(item);
+        self.counts[self.selected] = HOTBAR_STACK_SIZE;
+    }
+
+    /// Adds `count` of `item` and selects it, using the slot that holds it,
+    /// the first empty slot, or else the selected slot. Returns how many fit
+    /// in that slot's stack.
+    pub fn give(&mut self, item: Item, count: u32) -> u32 {
+        if let Some(slot) = self
+            .slots
+            .iter()
+            .position(|slot| *slot == Some(item))
+            .or_else(|| self.slots.iter().position(Option::is_none))
+        {
+            self.selected = slot;
+        }
+        let held = if self.slots[self.selected] == Some(item) {
+            self.counts[self.selected]
+        } else {
+            0
+        };
+        let added = count.min(HOTBAR_STACK_SIZE.saturating_sub(held));
+        self.slots[self.selected] = Some(item);
+        self.counts[self.selected] = held + added;
+        added
     }
 }
 
@@ -85,6 +119,9 @@ struct HotbarSlot(usize);
 #[derive(Component)]
 struct HotbarSelection;
 
+#[derive(Component)]
+struct HotbarSlotCount(usize);
+
 fn setup_gui_textures(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
@@ -192,6 +229,24 @@ fn spawn_hotbar_ui(commands: &mut Commands, bg_handle: &Handle<Image>, sel_handl
                                         height: Val::Px(48.0),
                                         ..default()
                                     },
+                                ))
+                                .with_child((
+                                    Text::default(),
+                                    TextFont {
+                                        font_size: FontSize::Px(18.0),
+                                        ..default()
+                                    },
+                                    TextShadow {
+                                        offset: Vec2::splat(2.0),
+                                        color: Color::BLACK,
+                                    },
+                                    Node {
+                                        position_type: PositionType::Absolute,
+                                        right: Val::Px(6.0),
+                                        bottom: Val::Px(6.0),
+                                        ..default()
+                                    },
+                                    HotbarSlotCount(i),
                                 ));
                             }
                         });
@@ -369,10 +424,13 @@ fn warp_face(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_hotbar_input(
     mut hotbar: ResMut<Hotbar>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
     mut mouse_wheel: MessageReader<MouseWheel>,
+    gamepads: Query<&Gamepad>,
     game_state: Res<State<GameState>>,
     mouse_state: Res<State<MouseState>>,
     primary_windows: Query<(&Window, &CursorOptions), With<PrimaryWindow>>,
@@ -396,19 +454,7 @@ fn handle_hotbar_input(
         return;
     }
 
-    for i in 0..HOTBAR_SLOTS {
-        let key = match i {
-            0 => KeyCode::Digit1,
-            1 => KeyCode::Digit2,
-            2 => KeyCode::Digit3,
-            3 => KeyCode::Digit4,
-            4 => KeyCode::Digit5,
-            5 => KeyCode::Digit6,
-            6 => KeyCode::Digit7,
-            7 => KeyCode::Digit8,
-            8 => KeyCode::Digit9,
-            _ => unreachable!(),
-        };
+    for (i, &key) in bindings.hotbar_slots.iter().enumerate() {
         if keyboard.just_pressed(key) {
             hotbar.selected = i;
         }
@@ -421,6 +467,12 @@ fn handle_hotbar_input(
             hotbar.selected = (hotbar.selected + 1) % HOTBAR_SLOTS;
         }
     }
+
+    for gamepad in &gamepads {
+        let step = gamepad::hotbar_step(gamepad);
+        hotbar.selected =
+            (hotbar.selected as isize + step).rem_euclid(HOTBAR_SLOTS as isize) as usize;
+    }
 }
 
 fn update_hotbar_ui(
@@ -429,6 +481,7 @@ fn update_hotbar_ui(
     mut selection: Query<&mut Node, With<HotbarSelection>>,
     slot_children: Query<(&HotbarSlot, &Children)>,
     mut images: Query<&mut ImageNode>,
+    mut counts: Query<(&HotbarSlotCount, &mut Text)>,
 ) {
     if let Ok(mut node) = selection.single_mut() {
         node.left = Val::Px(hotbar.selected as f32 * 60.0 - 3.0);
@@ -444,4 +497,16 @@ fn update_hotbar_ui(
             }
         }
     }
+
+    for (slot, mut text) in &mut counts {
+        let count = hotbar.counts[slot.0];
+        let label = if hotbar.slots[slot.0].is_some() && count > 1 {
+            count.to_string()
+        } else {
+            String::new()
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
 }
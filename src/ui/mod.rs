@@ -1,17 +1,25 @@
+mod chat;
+mod controls_menu;
 mod crosshair;
 mod debug;
 mod hotbar;
+mod options_menu;
 mod pause_menu;
+mod title_menu;
 
 use bevy::prelude::*;
 #[cfg(debug_assertions)]
 use bevy_dev_tools::diagnostics_overlay::{DiagnosticsOverlay, DiagnosticsOverlayPlugin};
+use chat::ChatOverlayPlugin;
+use controls_menu::ControlsMenuPlugin;
 use crosshair::CrosshairPlugin;
 use debug::DebugPlugin;
 use hotbar::HotbarPlugin;
+use options_menu::OptionsMenuPlugin;
 use pause_menu::PauseMenuPlugin;
+use title_menu::TitleMenuPlugin;
 
-pub use hotbar::Hotbar;
+pub use hotbar::{HOTBAR_SLOTS, HOTBAR_STACK_SIZE, Hotbar};
 
 pub struct UIPlugin;
 
@@ -20,7 +28,11 @@ impl Plugin for UIPlugin {
         app.add_plugins(CrosshairPlugin);
         app.add_plugins(DebugPlugin);
         app.add_plugins(HotbarPlugin);
+        app.add_plugins(ChatOverlayPlugin);
         app.add_plugins(PauseMenuPlugin);
+        app.add_plugins(OptionsMenuPlugin);
+        app.add_plugins(ControlsMenuPlugin);
+        app.add_plugins(TitleMenuPlugin);
         #[cfg(debug_assertions)]
         app.add_plugins(DiagnosticsOverlayPlugin)
             .add_systems(Startup, spawn_diagnostics_overlay);
@@ -0,0 +1,591 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition, widget::NodeImageMode},
+};
+use bevy_settings::SaveSettingsDeferred;
+
+use super::pause_menu::{MenuButtonSize, PauseMenuTextures, load_ui_texture, spawn_menu_button};
+use crate::{
+    audio::GameAudioSettings,
+    game_state::PauseScreen,
+    player::cam::MouseSettings,
+    world::{chunk::mesh::FogSettings, dimension::ViewDistance},
+};
+
+const VIEW_DISTANCE_RANGE: (i32, i32) = (2, 32);
+/// Largest mouse sensitivity the slider reaches, as a multiple of the default.
+const MAX_SENSITIVITY_SCALE: f32 = 2.0;
+const SLIDER_HANDLE_WIDTH: f32 = 16.0;
+const OPTIONS_BUTTON: MenuButtonSize = MenuButtonSize {
+    width: Val::Percent(100.0),
+    height: 40.0,
+    font_size: 20.0,
+};
+
+pub struct OptionsMenuPlugin;
+
+impl Plugin for OptionsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseScreen::Options), spawn_options_menu)
+            .add_systems(
+                Update,
+                (
+                    (drag_option_sliders, handle_options_buttons),
+                    save_options,
+                    refresh_option_widgets,
+                )
+                    .chain()
+                    .run_if(in_state(PauseScreen::Options)),
+            );
+    }
+}
+
+/// A setting adjusted by dragging across a slider.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum OptionSlider {
+    FieldOfView,
+    ViewDistance,
+    MouseSensitivity,
+    FogStrength,
+    MasterVolume,
+    SoundEffectsVolume,
+    AmbienceVolume,
+    MusicVolume,
+}
+
+/// A setting switched on and off by clicking.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum OptionToggle {
+    Fog,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+enum OptionsMenuAction {
+    Toggle(OptionToggle),
+    Done,
+}
+
+/// Text showing an option's name and current value.
+#[derive(Component, Clone, Copy)]
+enum OptionLabel {
+    Slider(OptionSlider),
+    Toggle(OptionToggle),
+}
+
+#[derive(Component, Clone, Copy)]
+struct SliderHandle(OptionSlider);
+
+/// The settings groups the options screen edits. Writers compare before
+/// assigning, so dragging a slider within one step does not trigger a save.
+#[derive(SystemParam)]
+struct OptionTargets<'w> {
+    audio: ResMut<'w, GameAudioSettings>,
+    mouse: ResMut<'w, MouseSettings>,
+    view_distance: ResMut<'w, ViewDistance>,
+    fog: ResMut<'w, FogSettings>,
+}
+
+impl OptionSlider {
+    const ALL: [Self; 8] = [
+        Self::FieldOfView,
+        Self::ViewDistance,
+        Self::MouseSensitivity,
+        Self::FogStrength,
+        Self::MasterVolume,
+        Self::SoundEffectsVolume,
+        Self::AmbienceVolume,
+        Self::MusicVolume,
+    ];
+
+    /// Current value as a slider position from `0.0` to `1.0`.
+    fn fraction(self, targets: &OptionTargets) -> f32 {
+        let fraction = match self {
+            Self::FieldOfView => {
+                let (min, max) = fov_range();
+                (targets.mouse.fov_radians().to_degrees() - min) / (max - min)
+            }
+            Self::ViewDistance => {
+                let (min, max) = VIEW_DISTANCE_RANGE;
+                (targets.view_distance.chunks() - min) as f32 / (max - min) as f32
+            }
+            Self::MouseSensitivity => {
+                targets.mouse.sensitivity / (default_sensitivity() * MAX_SENSITIVITY_SCALE)
+            }
+            Self::FogStrength => targets.fog.strength,
+            Self::MasterVolume => targets.audio.master_volume,
+            Self::SoundEffectsVolume => targets.audio.sound_effects_volume,
+            Self::AmbienceVolume => targets.audio.ambience_volume,
+            Self::MusicVolume => targets.audio.music_volume,
+        };
+        if fraction.is_finite() {
+            fraction.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn set_fraction(self, targets: &mut OptionTargets, fraction: f32) {
+        let fraction = fraction.clamp(0.0, 1.0);
+        match self {
+            Self::FieldOfView => {
+                let (min, max) = fov_range();
+                set_if_changed(
+                    &mut targets.mouse,
+                    |mouse| &mut mouse.fov,
+                    (min + fraction * (max - min)).round(),
+                );
+            }
+            Self::ViewDistance => {
+                let (min, max) = VIEW_DISTANCE_RANGE;
+                let chunks = min + (fraction * (max - min) as f32).round() as i32;
+                if targets.view_distance.chunks() != chunks {
+                    *targets.view_distance = ViewDistance::new(chunks);
+                }
+            }
+            Self::MouseSensitivity => set_if_changed(
+                &mut targets.mouse,
+                |mouse| &mut mouse.sensitivity,
+                default_sensitivity() * MAX_SENSITIVITY_SCALE * percent(fraction),
+            ),
+            Self::FogStrength => {
+                set_if_changed(&mut targets.fog, |fog| &mut fog.strength, percent(fraction))
+            }
+            Self::MasterVolume => set_if_changed(
+                &mut targets.audio,
+                |audio| &mut audio.master_volume,
+                percent(fraction),
+            ),
+            Self::SoundEffectsVolume => set_if_changed(
+                &mut targets.audio,
+                |audio| &mut audio.sound_effects_volume,
+                percent(fraction),
+            ),
+            Self::AmbienceVolume => set_if_changed(
+                &mut targets.audio,
+                |audio| &mut audio.ambience_volume,
+                percent(fraction),
+            ),
+            Self::MusicVolume => set_if_changed(
+                &mut targets.audio,
+                |audio| &mut audio.music_volume,
+                percent(fraction),
+            ),
+        }
+    }
+
+    fn label(self, targets: &OptionTargets) -> String {
+        let percent = (self.fraction(targets) * 100.0).round();
+        match self {
+            Self::FieldOfView => {
+                format!("FOV: {}", targets.mouse.fov_radians().to_degrees().round())
+            }
+            Self::ViewDistance => {
+                format!("Render Distance: {} chunks", targets.view_distance.chunks())
+            }
+            Self::MouseSensitivity => format!("Sensitivity: {}%", percent * MAX_SENSITIVITY_SCALE),
+            Self::FogStrength => format!("Fog Strength: {percent}%"),
+            Self::MasterVolume => volume_label("Master Volume", percent),
+            Self::SoundEffectsVolume => volume_label("Blocks & Actions", percent),
+            Self::AmbienceVolume => volume_label("Ambient/Environment", percent),
+            Self::MusicVolume => volume_label("Music", percent),
+        }
+    }
+}
+
+impl OptionToggle {
+    fn toggle(self, targets: &mut OptionTargets) {
+        match self {
+            Self::Fog => targets.fog.enabled = !targets.fog.enabled,
+        }
+    }
+
+    fn label(self, targets: &OptionTargets) -> String {
+        match self {
+            Self::Fog => format!("Fog: {}", if targets.fog.enabled { "ON" } else { "OFF" }),
+        }
+    }
+}
+
+fn fov_range() -> (f32, f32) {
+    (
+        *MouseSettings::FOV_RANGE.start(),
+        *MouseSettings::FOV_RANGE.end(),
+    )
+}
+
+fn default_sensitivity() -> f32 {
+    MouseSettings::default().sensitivity
+}
+
+/// Rounds a slider position to whole percent steps.
+fn percent(fraction: f32) -> f32 {
+    (fraction * 100.0).round() / 100.0
+}
+
+fn volume_label(name: &str, percent: f32) -> String {
+    if percent == 0.0 {
+        format!("{name}: OFF")
+    } else {
+        format!("{name}: {percent}%")
+    }
+}
+
+fn set_if_changed<T: Resource>(
+    resource: &mut ResMut<T>,
+    field: impl Fn(&mut T) -> &mut f32,
+    value: f32,
+) {
+    if *field(resource.bypass_change_detection()) != value {
+        *field(resource) = value;
+    }
+}
+
+fn spawn_options_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let textures = PauseMenuTextures::load(&asset_server);
+    let slider = load_ui_texture(&asset_server, "textures/gui/sprites/widget/slider.png");
+    let handle = load_ui_texture(
+        &asset_server,
+        "textures/gui/sprites/widget/slider_handle.png",
+    );
+
+    commands
+        .spawn((
+            Name::new("Options Menu"),
+            DespawnOnExit(PauseScreen::Options),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::ZERO,
+                top: Val::ZERO,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.62)),
+            FocusPolicy::Block,
+            GlobalZIndex(1_000),
+        ))
+        .with_children(|overlay| {
+            overlay
+                .spawn(Node {
+                    width: Val::Vw(90.0),
+                    max_width: Val::Px(820.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|menu| {
+                    menu.spawn((
+                        Text::new("Options"),
+                        TextFont {
+                            font_size: FontSize::Px(38.0),
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        TextShadow {
+                            offset: Vec2::splat(3.0),
+                            color: Color::BLACK,
+                        },
+                    ));
+
+                    menu.spawn(Node {
+                        width: Val::Percent(100.0),
+                        display: Display::Grid,
+                        grid_template_columns: RepeatedGridTrack::flex(2, 1.0),
+                        column_gap: Val::Px(16.0),
+                        row_gap: Val::Px(8.0),
+                        ..default()
+                    })
+                    .with_children(|grid| {
+                        for option in OptionSlider::ALL {
+                            spawn_slider(grid, option, &slider, &handle);
+                        }
+                        // Toggle text is filled in by `refresh_option_widgets`.
+                        spawn_menu_button(
+                            grid,
+                            "",
+                            Some(OptionsMenuAction::Toggle(OptionToggle::Fog)),
+                            OPTIONS_BUTTON,
+                            OptionLabel::Toggle(OptionToggle::Fog),
+                            &textures,
+                        );
+                    });
+
+                    menu.spawn(Node {
+                        width: Val::Percent(50.0),
+                        margin: UiRect {
+                            top: Val::Px(12.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|footer| {
+                        spawn_menu_button(
+                            footer,
+                            "Done",
+                            Some(OptionsMenuAction::Done),
+                            OPTIONS_BUTTON,
+                            (),
+                            &textures,
+                        );
+                    });
+                });
+        });
+}
+
+fn spawn_slider(
+    parent: &mut ChildSpawnerCommands,
+    option: OptionSlider,
+    slider: &Handle<Image>,
+    handle: &Handle<Image>,
+) {
+    parent
+        .spawn((
+            Name::new(format!("{option:?} Slider")),
+            Button,
+            option,
+            RelativeCursorPosition::default(),
+            ImageNode::new(slider.clone()).with_mode(NodeImageMode::Stretch),
+            Node {
+                height: Val::Px(40.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|slider| {
+            slider.spawn((
+                SliderHandle(option),
+                ImageNode::new(handle.clone()).with_mode(NodeImageMode::Stretch),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::ZERO,
+                    width: Val::Px(SLIDER_HANDLE_WIDTH),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ));
+            slider.spawn((
+                OptionLabel::Slider(option),
+                Text::default(),
+                option_text_font(),
+                TextColor(Color::WHITE),
+                TextShadow {
+                    offset: Vec2::splat(2.0),
+                    color: Color::BLACK,
+                },
+                Pickable::IGNORE,
+            ));
+        });
+}
+
+fn option_text_font() -> TextFont {
+    TextFont {
+        font_size: FontSize::Px(20.0),
+        ..default()
+    }
+}
+
+/// Sets a slider from the cursor for as long as it is held, including while
+/// the cursor is dragged past either end.
+fn drag_option_sliders(
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &OptionSlider)>,
+    mut targets: OptionTargets,
+) {
+    for (interaction, cursor, &option) in &sliders {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // The normalized position is relative to the node's centre.
+        if let Some(position) = cursor.normalized {
+            option.set_fraction(&mut targets, position.x + 0.5);
+        }
+    }
+}
+
+type OptionsMenuButtons<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static OptionsMenuAction),
+    (Changed<Interaction>, With<Button>),
+>;
+
+fn handle_options_buttons(
+    buttons: OptionsMenuButtons,
+    mut targets: OptionTargets,
+    mut next_pause_screen: ResMut<NextState<PauseScreen>>,
+) {
+    for (interaction, action) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *action {
+            OptionsMenuAction::Toggle(toggle) => toggle.toggle(&mut targets),
+            OptionsMenuAction::Done => next_pause_screen.set(PauseScreen::Main),
+        }
+    }
+}
+
+fn save_options(
+    mut commands: Commands,
+    audio: Res<GameAudioSettings>,
+    mouse: Res<MouseSettings>,
+    view_distance: Res<ViewDistance>,
+    fog: Res<FogSettings>,
+) {
+    if audio.is_changed() || mouse.is_changed() || view_distance.is_changed() || fog.is_changed() {
+        commands.queue(SaveSettingsDeferred::default());
+    }
+}
+
+/// Shows the current values, which can also change from key bindings while
+/// the screen is open.
+fn refresh_option_widgets(
+    targets: OptionTargets,
+    mut labels: Query<(&OptionLabel, &mut Text)>,
+    mut handles: Query<(&SliderHandle, &mut Node)>,
+) {
+    for (&label, mut text) in &mut labels {
+        let value = match label {
+            OptionLabel::Slider(option) => option.label(&targets),
+            OptionLabel::Toggle(toggle) => toggle.label(&targets),
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+
+    for (&SliderHandle(option), mut node) in &mut handles {
+        let fraction = option.fraction(&targets);
+        let left = Val::Percent(fraction * 100.0);
+        let margin = Val::Px(-fraction * SLIDER_HANDLE_WIDTH);
+        if node.left != left || node.margin.left != margin {
+            node.left = left;
+            node.margin.left = margin;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+    use std::path::Path;
+
+    fn options_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<crate::game_state::GameState>()
+            .add_sub_state::<PauseScreen>()
+            .init_resource::<GameAudioSettings>()
+            .init_resource::<MouseSettings>()
+            .init_resource::<ViewDistance>()
+            .init_resource::<FogSettings>()
+            .add_systems(
+                Update,
+                (
+                    drag_option_sliders,
+                    handle_options_buttons,
+                    refresh_option_widgets,
+                )
+                    .chain(),
+            );
+        app
+    }
+
+    fn drag(app: &mut App, option: OptionSlider, x: f32) {
+        let mut cursor = RelativeCursorPosition::default();
+        cursor.normalized = Some(vec2(x, 0.0));
+        let slider = app
+            .world_mut()
+            .spawn((Interaction::Pressed, cursor, option))
+            .id();
+        app.update();
+        app.world_mut().despawn(slider);
+    }
+
+    #[test]
+    fn dragging_sliders_updates_settings_live() {
+        let mut app = options_app();
+
+        drag(&mut app, OptionSlider::MusicVolume, 0.0);
+        drag(&mut app, OptionSlider::FieldOfView, 0.5);
+        drag(&mut app, OptionSlider::ViewDistance, -0.5);
+        drag(&mut app, OptionSlider::MouseSensitivity, 0.0);
+
+        let world = app.world();
+        assert_eq!(world.resource::<GameAudioSettings>().music_volume, 0.5);
+        assert_eq!(world.resource::<MouseSettings>().fov, 110.0);
+        assert_eq!(
+            world.resource::<MouseSettings>().sensitivity,
+            MouseSettings::default().sensitivity
+        );
+        assert_eq!(world.resource::<ViewDistance>().chunks(), 2);
+    }
+
+    #[test]
+    fn dragging_past_the_end_clamps() {
+        let mut app = options_app();
+
+        drag(&mut app, OptionSlider::MasterVolume, 3.0);
+        drag(&mut app, OptionSlider::FogStrength, -3.0);
+
+        assert_eq!(
+            app.world().resource::<GameAudioSettings>().master_volume,
+            1.0
+        );
+        assert_eq!(app.world().resource::<FogSettings>().strength, 0.0);
+    }
+
+    #[test]
+    fn fog_toggle_flips_and_labels_follow() {
+        let mut app = options_app();
+        let label = app
+            .world_mut()
+            .spawn((OptionLabel::Toggle(OptionToggle::Fog), Text::default()))
+            .id();
+        app.world_mut().spawn((
+            Button,
+            Interaction::Pressed,
+            OptionsMenuAction::Toggle(OptionToggle::Fog),
+        ));
+        app.update();
+
+        assert!(!app.world().resource::<FogSettings>().enabled);
+        assert_eq!(app.world().get::<Text>(label).unwrap().0, "Fog: OFF");
+    }
+
+    #[test]
+    fn unchanged_slider_position_does_not_mark_settings_changed() {
+        let mut app = options_app();
+        drag(&mut app, OptionSlider::SoundEffectsVolume, 0.3);
+        let last_changed = app
+            .world()
+            .resource_ref::<GameAudioSettings>()
+            .last_changed();
+
+        drag(&mut app, OptionSlider::SoundEffectsVolume, 0.3);
+
+        assert_eq!(
+            app.world()
+                .resource_ref::<GameAudioSettings>()
+                .last_changed(),
+            last_changed
+        );
+    }
+
+    #[test]
+    fn slider_texture_assets_are_present() {
+        for path in [
+            "assets/textures/gui/sprites/widget/slider.png",
+            "assets/textures/gui/sprites/widget/slider_handle.png",
+        ] {
+            assert!(Path::new(path).is_file(), "missing options asset: {path}");
+        }
+    }
+}
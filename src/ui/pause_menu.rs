@@ -4,17 +4,21 @@ use bevy::{
     ui::{FocusPolicy, widget::NodeImageMode},
 };
 
-use crate::game_state::GameState;
+use crate::game_state::{GameState, PauseScreen};
 
 pub struct PauseMenuPlugin;
 
 impl Plugin for PauseMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
+        app.add_systems(OnEnter(PauseScreen::Main), spawn_pause_menu)
             .add_systems(
                 Update,
-                (handle_pause_menu_action, update_button_visuals)
-                    .run_if(in_state(GameState::Paused)),
+                handle_pause_menu_action.run_if(in_state(GameState::Paused)),
+            )
+            .add_systems(
+                Update,
+                update_button_visuals
+                    .run_if(in_state(GameState::Paused).or(in_state(GameState::MainMenu))),
             );
     }
 }
@@ -22,25 +26,28 @@ impl Plugin for PauseMenuPlugin {
 #[derive(Component)]
 struct PauseMenuRoot;
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy)]
 enum PauseMenuAction {
     Resume,
+    Options,
+    Controls,
+    QuitToTitle,
 }
 
 #[derive(Component)]
-struct PauseButtonVisual {
-    normal: Handle<Image>,
-    highlighted: Handle<Image>,
+pub(super) struct PauseButtonVisual {
+    pub(super) normal: Handle<Image>,
+    pub(super) highlighted: Handle<Image>,
 }
 
 #[derive(Clone)]
-struct PauseMenuTextures {
-    button: Handle<Image>,
-    button_highlighted: Handle<Image>,
-    button_disabled: Handle<Image>,
+pub(super) struct PauseMenuTextures {
+    pub(super) button: Handle<Image>,
+    pub(super) button_highlighted: Handle<Image>,
+    pub(super) button_disabled: Handle<Image>,
 }
 
-fn load_ui_texture(asset_server: &AssetServer, path: &'static str) -> Handle<Image> {
+pub(super) fn load_ui_texture(asset_server: &AssetServer, path: &'static str) -> Handle<Image> {
     asset_server
         .load_builder()
         .with_settings(|settings: &mut ImageLoaderSettings| {
@@ -49,24 +56,30 @@ fn load_ui_texture(asset_server: &AssetServer, path: &'static str) -> Handle<Ima
         .load(path)
 }
 
+impl PauseMenuTextures {
+    pub(super) fn load(asset_server: &AssetServer) -> Self {
+        Self {
+            button: load_ui_texture(asset_server, "textures/gui/sprites/widget/button.png"),
+            button_highlighted: load_ui_texture(
+                asset_server,
+                "textures/gui/sprites/widget/button_highlighted.png",
+            ),
+            button_disabled: load_ui_texture(
+                asset_server,
+                "textures/gui/sprites/widget/button_disabled.png",
+            ),
+        }
+    }
+}
+
 fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let textures = PauseMenuTextures {
-        button: load_ui_texture(&asset_server, "textures/gui/sprites/widget/button.png"),
-        button_highlighted: load_ui_texture(
-            &asset_server,
-            "textures/gui/sprites/widget/button_highlighted.png",
-        ),
-        button_disabled: load_ui_texture(
-            &asset_server,
-            "textures/gui/sprites/widget/button_disabled.png",
-        ),
-    };
+    let textures = PauseMenuTextures::load(&asset_server);
 
     commands
         .spawn((
             Name::new("Pause Menu"),
             PauseMenuRoot,
-            DespawnOnExit(GameState::Paused),
+            DespawnOnExit(PauseScreen::Main),
             Node {
                 position_type: PositionType::Absolute,
                 left: Val::ZERO,
@@ -116,10 +129,34 @@ fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                         menu,
                         "Back to Game",
                         Some(PauseMenuAction::Resume),
+                        MenuButtonSize::WIDE,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        menu,
+                        "Options...",
+                        Some(PauseMenuAction::Options),
+                        MenuButtonSize::WIDE,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        menu,
+                        "Controls...",
+                        Some(PauseMenuAction::Controls),
+                        MenuButtonSize::WIDE,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        menu,
+                        "Save and Quit to Title",
+                        Some(PauseMenuAction::QuitToTitle),
+                        MenuButtonSize::WIDE,
+                        (),
                         &textures,
                     );
-                    spawn_menu_button(menu, "Options...  (coming soon)", None, &textures);
-                    spawn_menu_button(menu, "Save and Quit  (coming soon)", None, &textures);
 
                     menu.spawn((
                         Text::new("Press Esc to return to the game"),
@@ -144,12 +181,39 @@ fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         });
 }
 
-fn spawn_menu_button(
+/// The box and label size of a menu button.
+#[derive(Clone, Copy)]
+pub(super) struct MenuButtonSize {
+    pub(super) width: Val,
+    pub(super) height: f32,
+    pub(super) font_size: f32,
+}
+
+impl MenuButtonSize {
+    /// The pause menu's full-width buttons.
+    const WIDE: Self = Self {
+        width: Val::Percent(100.0),
+        height: 60.0,
+        font_size: 28.0,
+    };
+}
+
+/// Spawns a menu button that triggers `action`, or a greyed-out one without
+/// an action. `label_marker` goes on the text entity so a menu can rewrite
+/// the label later.
+pub(super) fn spawn_menu_button<A: Component + std::fmt::Debug>(
     parent: &mut ChildSpawnerCommands,
-    label: &'static str,
-    action: Option<PauseMenuAction>,
+    label: impl Into<String>,
+    action: Option<A>,
+    size: MenuButtonSize,
+    label_marker: impl Bundle,
     textures: &PauseMenuTextures,
 ) {
+    let label = label.into();
+    let name = match &action {
+        Some(action) if label.is_empty() => format!("{action:?} Button"),
+        _ => label.clone(),
+    };
     let enabled = action.is_some();
     let image = if enabled {
         textures.button.clone()
@@ -157,11 +221,11 @@ fn spawn_menu_button(
         textures.button_disabled.clone()
     };
     let mut button = parent.spawn((
-        Name::new(label),
+        Name::new(name),
         ImageNode::new(image).with_mode(NodeImageMode::Stretch),
         Node {
-            width: Val::Percent(100.0),
-            height: Val::Px(60.0),
+            width: size.width,
+            height: Val::Px(size.height),
             align_items: AlignItems::Center,
             justify_content: JustifyContent::Center,
             ..default()
@@ -182,7 +246,7 @@ fn spawn_menu_button(
     button.with_child((
         Text::new(label),
         TextFont {
-            font_size: FontSize::Px(28.0),
+            font_size: FontSize::Px(size.font_size),
             ..default()
         },
         TextColor(if enabled {
@@ -195,6 +259,7 @@ fn spawn_menu_button(
             color: Color::BLACK,
         },
         Pickable::IGNORE,
+        label_marker,
     ));
 }
 
@@ -208,6 +273,7 @@ type PauseMenuActionButtons<'w, 's> = Query<
 fn handle_pause_menu_action(
     buttons: PauseMenuActionButtons,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_pause_screen: ResMut<NextState<PauseScreen>>,
 ) {
     for (interaction, action) in &buttons {
         if *interaction != Interaction::Pressed {
@@ -216,6 +282,10 @@ fn handle_pause_menu_action(
 
         match action {
             PauseMenuAction::Resume => next_game_state.set(GameState::Playing),
+            PauseMenuAction::Options => next_pause_screen.set(PauseScreen::Options),
+            PauseMenuAction::Controls => next_pause_screen.set(PauseScreen::Controls),
+            // Saving and teardown run as the title screen is entered.
+            PauseMenuAction::QuitToTitle => next_game_state.set(GameState::MainMenu),
         }
     }
 }
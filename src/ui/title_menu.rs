@@ -0,0 +1,977 @@
+use bevy::{
+    app::AppExit,
+    ecs::system::SystemParam,
+    input::{
+        keyboard::{Key, KeyboardInput},
+        mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    },
+    prelude::*,
+    ui::{FocusPolicy, widget::NodeImageMode},
+};
+
+use super::pause_menu::{MenuButtonSize, PauseButtonVisual, PauseMenuTextures, spawn_menu_button};
+use crate::{
+    game_state::{GameState, TitleScreen},
+    world::{
+        GeneratorProfile, WorldConfig, WorldHeight,
+        generation::MAX_WORLD_HEIGHT_CHUNKS,
+        saves::{
+            MAX_WORLD_NAME_CHARS, NewWorld, SavesDirectory, WorldSave, create_world, delete_world,
+            list_worlds, rename_world,
+        },
+        session::open_world,
+    },
+};
+
+const TITLE_BACKGROUND: Color = Color::srgb(0.13, 0.11, 0.09);
+const DETAIL_COLOR: Color = Color::srgb(0.63, 0.63, 0.63);
+const ERROR_COLOR: Color = Color::srgb(1.0, 0.33, 0.33);
+const MAX_SEED_CHARS: usize = 32;
+const MAX_HEIGHT_CHARS: usize = 3;
+const SCROLL_LINE_PIXELS: f32 = 40.0;
+const TITLE_BUTTON: MenuButtonSize = MenuButtonSize {
+    width: Val::Percent(100.0),
+    height: 40.0,
+    font_size: 18.0,
+};
+
+pub struct TitleMenuPlugin;
+
+impl Plugin for TitleMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavesDirectory>()
+            .init_resource::<TitleWorlds>()
+            .init_resource::<WorldForm>()
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                (spawn_title_camera, reload_worlds),
+            )
+            .add_systems(OnEnter(TitleScreen::Worlds), spawn_worlds_screen)
+            .add_systems(OnEnter(TitleScreen::CreateWorld), spawn_create_world_screen)
+            .add_systems(OnEnter(TitleScreen::RenameWorld), spawn_rename_world_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_title_buttons,
+                    type_into_world_form.run_if(
+                        in_state(TitleScreen::CreateWorld).or(in_state(TitleScreen::RenameWorld)),
+                    ),
+                    (
+                        rebuild_world_list.run_if(resource_changed::<TitleWorlds>),
+                        scroll_world_list,
+                    )
+                        .run_if(in_state(TitleScreen::Worlds)),
+                    refresh_title_labels,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
+            );
+    }
+}
+
+/// The worlds found in the saves directory and the one picked in the list.
+#[derive(Resource, Default, Debug)]
+struct TitleWorlds {
+    worlds: Vec<WorldSave>,
+    selected: Option<usize>,
+    /// Set by the first press of Delete; the second press removes the world.
+    confirm_delete: bool,
+    status: Option<String>,
+}
+
+impl TitleWorlds {
+    fn selected(&self) -> Option<&WorldSave> {
+        self.worlds.get(self.selected?)
+    }
+
+    /// Re-reads the saves directory, keeping the selection on the same save.
+    fn reload(&mut self, directory: &SavesDirectory) {
+        let selected = self.selected().map(|world| world.config.storage.clone());
+        self.worlds = list_worlds(&directory.0);
+        self.selected = selected.and_then(|storage| {
+            self.worlds
+                .iter()
+                .position(|world| world.config.storage == storage)
+        });
+        self.confirm_delete = false;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FormField {
+    Name,
+    Seed,
+    Height,
+}
+
+/// Text typed into the create-world and rename-world screens.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+struct WorldForm {
+    name: String,
+    seed: String,
+    height: String,
+    generator: GeneratorProfile,
+    focus: FormField,
+    error: Option<String>,
+}
+
+impl Default for WorldForm {
+    fn default() -> Self {
+        Self {
+            name: "New World".to_owned(),
+            seed: String::new(),
+            height: WorldHeight::DEFAULT.chunks().to_string(),
+            generator: GeneratorProfile::OverworldV1,
+            focus: FormField::Name,
+            error: None,
+        }
+    }
+}
+
+impl WorldForm {
+    fn text(&self, field: FormField) -> &str {
+        match field {
+            FormField::Name => &self.name,
+            FormField::Seed => &self.seed,
+            FormField::Height => &self.height,
+        }
+    }
+
+    fn type_text(&mut self, text: &str) {
+        let (value, max_chars) = match self.focus {
+            FormField::Name => (&mut self.name, MAX_WORLD_NAME_CHARS),
+            FormField::Seed => (&mut self.seed, MAX_SEED_CHARS),
+            FormField::Height => (&mut self.height, MAX_HEIGHT_CHARS),
+        };
+        for character in text.chars().filter(|character| !character.is_control()) {
+            if value.chars().count() >= max_chars {
+                break;
+            }
+            value.push(character);
+        }
+    }
+
+    fn erase(&mut self) {
+        match self.focus {
+            FormField::Name => self.name.pop(),
+            FormField::Seed => self.seed.pop(),
+            FormField::Height => self.height.pop(),
+        };
+    }
+
+    /// Moves focus to the next field shown on `screen`.
+    fn focus_next(&mut self, screen: TitleScreen) {
+        self.focus = match (screen, self.focus) {
+            (TitleScreen::CreateWorld, FormField::Name) => FormField::Seed,
+            (TitleScreen::CreateWorld, FormField::Seed) => FormField::Height,
+            _ => FormField::Name,
+        };
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+enum TitleAction {
+    SelectWorld(usize),
+    Play,
+    NewWorld,
+    Rename,
+    Delete,
+    Quit,
+    Focus(FormField),
+    CycleGenerator,
+    Confirm,
+    Cancel,
+}
+
+/// Text kept up to date by `refresh_title_labels`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+enum TitleLabel {
+    Field(FormField),
+    Generator,
+    FormError,
+    Status,
+    Delete,
+}
+
+/// Container holding one button per saved world.
+#[derive(Component)]
+struct WorldList;
+
+fn spawn_title_camera(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Title Camera"),
+        Camera2d,
+        DespawnOnExit(GameState::MainMenu),
+    ));
+}
+
+fn reload_worlds(
+    mut worlds: ResMut<TitleWorlds>,
+    directory: Res<SavesDirectory>,
+    mut form: ResMut<WorldForm>,
+) {
+    *worlds = TitleWorlds::default();
+    worlds.reload(&directory);
+    *form = WorldForm::default();
+}
+
+fn title_overlay(name: &'static str, screen: TitleScreen) -> impl Bundle {
+    (
+        Name::new(name),
+        DespawnOnExit(screen),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::ZERO,
+            top: Val::ZERO,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        // Opaque, so the in-game HUD left underneath stays hidden.
+        BackgroundColor(TITLE_BACKGROUND),
+        FocusPolicy::Block,
+        GlobalZIndex(1_000),
+    )
+}
+
+fn menu_column(width: f32) -> Node {
+    Node {
+        width: Val::Vw(90.0),
+        max_width: Val::Px(width),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        row_gap: Val::Px(12.0),
+        ..default()
+    }
+}
+
+fn spawn_heading(parent: &mut ChildSpawnerCommands, text: &str) {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font_size: FontSize::Px(38.0),
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextShadow {
+            offset: Vec2::splat(3.0),
+            color: Color::BLACK,
+        },
+    ));
+}
+
+fn spawn_caption<'a>(
+    parent: &'a mut ChildSpawnerCommands,
+    text: &str,
+    color: Color,
+) -> EntityCommands<'a> {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font_size: FontSize::Px(16.0),
+            ..default()
+        },
+        TextColor(color),
+        TextShadow {
+            offset: Vec2::splat(2.0),
+            color: Color::BLACK,
+        },
+        Pickable::IGNORE,
+    ))
+}
+
+fn button_row() -> Node {
+    Node {
+        width: Val::Percent(100.0),
+        column_gap: Val::Px(12.0),
+        ..default()
+    }
+}
+
+fn spawn_worlds_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    worlds: Res<TitleWorlds>,
+) {
+    let textures = PauseMenuTextures::load(&asset_server);
+
+    commands
+        .spawn(title_overlay("Title Screen", TitleScreen::Worlds))
+        .with_children(|overlay| {
+            overlay.spawn(menu_column(720.0)).with_children(|menu| {
+                spawn_heading(menu, "Select World");
+
+                menu.spawn((
+                    WorldList,
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Vh(45.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.45)),
+                ))
+                .with_children(|list| spawn_world_rows(list, &worlds, &textures));
+
+                spawn_caption(menu, "", ERROR_COLOR).insert(TitleLabel::Status);
+
+                menu.spawn(button_row()).with_children(|row| {
+                    spawn_menu_button(
+                        row,
+                        "Play Selected World",
+                        Some(TitleAction::Play),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        row,
+                        "Create New World",
+                        Some(TitleAction::NewWorld),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                });
+                menu.spawn(button_row()).with_children(|row| {
+                    spawn_menu_button(
+                        row,
+                        "Rename",
+                        Some(TitleAction::Rename),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        row,
+                        "Delete",
+                        Some(TitleAction::Delete),
+                        TITLE_BUTTON,
+                        TitleLabel::Delete,
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        row,
+                        "Quit Game",
+                        Some(TitleAction::Quit),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                });
+            });
+        });
+}
+
+fn spawn_world_rows(
+    list: &mut ChildSpawnerCommands,
+    worlds: &TitleWorlds,
+    textures: &PauseMenuTextures,
+) {
+    if worlds.worlds.is_empty() {
+        spawn_caption(
+            list,
+            "No worlds yet. Create one to start playing.",
+            DETAIL_COLOR,
+        );
+        return;
+    }
+
+    for (index, world) in worlds.worlds.iter().enumerate() {
+        // The selected world keeps the highlighted texture while not hovered.
+        let normal = if worlds.selected == Some(index) {
+            textures.button_highlighted.clone()
+        } else {
+            textures.button.clone()
+        };
+        let metadata = world.metadata();
+        let details = format!(
+            "Seed {} | {} chunks tall | {}",
+            metadata.seed,
+            metadata.height_chunks(),
+            generator_label(world.spawn_generator),
+        );
+
+        list.spawn((
+            Name::new(format!("World {}", world.name)),
+            Button,
+            TitleAction::SelectWorld(index),
+            PauseButtonVisual {
+                normal: normal.clone(),
+                highlighted: textures.button_highlighted.clone(),
+            },
+            ImageNode::new(normal).with_mode(NodeImageMode::Stretch),
+            Node {
+                width: Val::Percent(100.0),
+                min_height: Val::Px(56.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                padding: UiRect::horizontal(Val::Px(12.0)),
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn((
+                Text::new(world.name.clone()),
+                TextFont {
+                    font_size: FontSize::Px(18.0),
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextShadow {
+                    offset: Vec2::splat(2.0),
+                    color: Color::BLACK,
+                },
+                Pickable::IGNORE,
+            ));
+            spawn_caption(row, &details, DETAIL_COLOR);
+        });
+    }
+}
+
+fn spawn_create_world_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let textures = PauseMenuTextures::load(&asset_server);
+
+    commands
+        .spawn(title_overlay("Create World", TitleScreen::CreateWorld))
+        .with_children(|overlay| {
+            overlay.spawn(menu_column(520.0)).with_children(|menu| {
+                spawn_heading(menu, "Create New World");
+                spawn_field(menu, "World Name", FormField::Name);
+                spawn_field(
+                    menu,
+                    "Seed (leave blank for a random seed)",
+                    FormField::Seed,
+                );
+                spawn_field(
+                    menu,
+                    &format!("World Height in Chunks (1 to {MAX_WORLD_HEIGHT_CHUNKS})"),
+                    FormField::Height,
+                );
+                spawn_menu_button(
+                    menu,
+                    "",
+                    Some(TitleAction::CycleGenerator),
+                    TITLE_BUTTON,
+                    TitleLabel::Generator,
+                    &textures,
+                );
+
+                spawn_caption(menu, "", ERROR_COLOR).insert(TitleLabel::FormError);
+
+                menu.spawn(button_row()).with_children(|row| {
+                    spawn_menu_button(
+                        row,
+                        "Create World",
+                        Some(TitleAction::Confirm),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        row,
+                        "Cancel",
+                        Some(TitleAction::Cancel),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                });
+            });
+        });
+}
+
+fn spawn_rename_world_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let textures = PauseMenuTextures::load(&asset_server);
+
+    commands
+        .spawn(title_overlay("Rename World", TitleScreen::RenameWorld))
+        .with_children(|overlay| {
+            overlay.spawn(menu_column(520.0)).with_children(|menu| {
+                spawn_heading(menu, "Rename World");
+                spawn_field(menu, "World Name", FormField::Name);
+
+                spawn_caption(menu, "", ERROR_COLOR).insert(TitleLabel::FormError);
+
+                menu.spawn(button_row()).with_children(|row| {
+                    spawn_menu_button(
+                        row,
+                        "Rename",
+                        Some(TitleAction::Confirm),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                    spawn_menu_button(
+                        row,
+                        "Cancel",
+                        Some(TitleAction::Cancel),
+                        TITLE_BUTTON,
+                        (),
+                        &textures,
+                    );
+                });
+            });
+        });
+}
+
+/// A caption above a clickable text box; clicking the box focuses it.
+fn spawn_field(parent: &mut ChildSpawnerCommands, caption: &str, field: FormField) {
+    parent
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|column| {
+            spawn_caption(column, caption, DETAIL_COLOR);
+            column
+                .spawn((
+                    Name::new(format!("{field:?} Field")),
+                    Button,
+                    TitleAction::Focus(field),
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(36.0),
+                        align_items: AlignItems::Center,
+                        padding: UiRect::horizontal(Val::Px(8.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK),
+                    BorderColor::all(DETAIL_COLOR),
+                ))
+                .with_children(|field_box| {
+                    field_box.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font_size: FontSize::Px(18.0),
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        TitleLabel::Field(field),
+                        Pickable::IGNORE,
+                    ));
+                });
+        });
+}
+
+const fn generator_label(profile: GeneratorProfile) -> &'static str {
+    match profile {
+        GeneratorProfile::OverworldV1 => "Default",
+        GeneratorProfile::GrassFloorV1 => "Superflat",
+        GeneratorProfile::CenterGlassPlatformV1 => "Void Platform",
+    }
+}
+
+/// Everything a title-screen action can touch, shared by buttons and keys.
+#[derive(SystemParam)]
+struct TitleMenu<'w, 's> {
+    commands: Commands<'w, 's>,
+    worlds: ResMut<'w, TitleWorlds>,
+    form: ResMut<'w, WorldForm>,
+    directory: Res<'w, SavesDirectory>,
+    screen: Res<'w, State<TitleScreen>>,
+    next_screen: ResMut<'w, NextState<TitleScreen>>,
+    exits: MessageWriter<'w, AppExit>,
+}
+
+impl TitleMenu<'_, '_> {
+    fn apply(&mut self, action: TitleAction) {
+        if !matches!(action, TitleAction::Delete) {
+            self.worlds.confirm_delete = false;
+        }
+
+        match action {
+            TitleAction::SelectWorld(index) => {
+                self.worlds.selected = Some(index);
+                self.worlds.status = None;
+            }
+            TitleAction::Play => {
+                if let Some(world) = self.selected_world() {
+                    let config = world.config.clone();
+                    self.commands.queue(open_world_and_play(config));
+                }
+            }
+            TitleAction::NewWorld => {
+                *self.form = WorldForm::default();
+                self.next_screen.set(TitleScreen::CreateWorld);
+            }
+            TitleAction::Rename => {
+                if let Some(world) = self.selected_world() {
+                    let name = world.name.clone();
+                    *self.form = WorldForm { name, ..default() };
+                    self.next_screen.set(TitleScreen::RenameWorld);
+                }
+            }
+            TitleAction::Delete => self.delete_selected(),
+            TitleAction::Quit => {
+                self.exits.write(AppExit::Success);
+            }
+            TitleAction::Focus(field) => self.form.focus = field,
+            TitleAction::CycleGenerator => {
+                let profiles = GeneratorProfile::ALL;
+                let current = profiles
+                    .iter()
+                    .position(|&profile| profile == self.form.generator)
+                    .unwrap_or(0);
+                self.form.generator = profiles[(current + 1) % profiles.len()];
+            }
+            TitleAction::Confirm => match *self.screen.get() {
+                TitleScreen::Worlds => {}
+                TitleScreen::CreateWorld => self.create(),
+                TitleScreen::RenameWorld => self.rename(),
+            },
+            TitleAction::Cancel => self.next_screen.set(TitleScreen::Worlds),
+        }
+    }
+
+    fn selected_world(&mut self) -> Option<&WorldSave> {
+        if self.worlds.selected().is_none() {
+            self.worlds.status = Some("Select a world first.".to_owned());
+        }
+        self.worlds.selected()
+    }
+
+    fn delete_selected(&mut self) {
+        if self.selected_world().is_none() {
+            return;
+        }
+        if !self.worlds.confirm_delete {
+            self.worlds.confirm_delete = true;
+            return;
+        }
+
+        let Some(world) = self.worlds.selected() else {
+            return;
+        };
+        match delete_world(world) {
+            Ok(()) => {
+                self.worlds.selected = None;
+                self.worlds.status = None;
+            }
+            Err(error) => {
+                error!(%error, "Failed to delete world");
+                self.worlds.status = Some(format!("Could not delete the world: {error}"));
+            }
+        }
+        self.worlds.reload(&self.directory);
+    }
+
+    fn create(&mut self) {
+        let form = &self.form;
+        let created = NewWorld::from_form(&form.name, &form.seed, &form.height, form.generator)
+            .and_then(|world| create_world(&self.directory.0, world));
+        match created {
+            Ok(world) => {
+                self.form.error = None;
+                self.commands.queue(open_world_and_play(world.config));
+            }
+            Err(error) => self.form.error = Some(capitalize(&error.to_string())),
+        }
+    }
+
+    fn rename(&mut self) {
+        let Some(index) = self.worlds.selected else {
+            self.next_screen.set(TitleScreen::Worlds);
+            return;
+        };
+        match rename_world(&mut self.worlds.worlds[index], &self.form.name) {
+            Ok(()) => {
+                self.form.error = None;
+                self.worlds.reload(&self.directory);
+                self.next_screen.set(TitleScreen::Worlds);
+            }
+            Err(error) => self.form.error = Some(capitalize(&error.to_string())),
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Opens the world once the queued command runs, then starts loading it. A
+/// store that fails to open leaves the title screen up with the error shown.
+fn open_world_and_play(config: WorldConfig) -> impl FnOnce(&mut World) + Send + 'static {
+    move |world: &mut World| match open_world(world, config) {
+        Ok(()) => world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::GenWorld),
+        Err(error) => {
+            error!(%error, "Failed to open world");
+            world.resource_mut::<TitleWorlds>().status =
+                Some(format!("Could not open the world: {error}"));
+        }
+    }
+}
+
+type TitleButtons<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static TitleAction),
+    (Changed<Interaction>, With<Button>),
+>;
+
+fn handle_title_buttons(buttons: TitleButtons, mut menu: TitleMenu) {
+    for (interaction, &action) in &buttons {
+        if *interaction == Interaction::Pressed {
+            menu.apply(action);
+        }
+    }
+}
+
+/// Text entry for the focused field. Tab moves between fields, Enter submits
+/// and Escape goes back to the world list.
+fn type_into_world_form(mut keys: MessageReader<KeyboardInput>, mut menu: TitleMenu) {
+    for key in keys.read() {
+        if !key.state.is_pressed() {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Enter => menu.apply(TitleAction::Confirm),
+            Key::Escape => menu.apply(TitleAction::Cancel),
+            Key::Tab => {
+                let screen = *menu.screen.get();
+                menu.form.focus_next(screen);
+            }
+            Key::Backspace => menu.form.erase(),
+            Key::Space => menu.form.type_text(" "),
+            Key::Character(text) => menu.form.type_text(text),
+            _ => {}
+        }
+    }
+}
+
+fn rebuild_world_list(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    worlds: Res<TitleWorlds>,
+    lists: Query<Entity, With<WorldList>>,
+) {
+    let textures = PauseMenuTextures::load(&asset_server);
+    for list in &lists {
+        commands
+            .entity(list)
+            .despawn_related::<Children>()
+            .with_children(|list| spawn_world_rows(list, &worlds, &textures));
+    }
+}
+
+fn scroll_world_list(
+    scroll: Res<AccumulatedMouseScroll>,
+    mut lists: Query<&mut ScrollPosition, With<WorldList>>,
+) {
+    if scroll.delta.y == 0.0 {
+        return;
+    }
+    let pixels = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y * SCROLL_LINE_PIXELS,
+        MouseScrollUnit::Pixel => scroll.delta.y,
+    };
+    for mut position in &mut lists {
+        position.0.y -= pixels;
+    }
+}
+
+fn refresh_title_labels(
+    worlds: Res<TitleWorlds>,
+    form: Res<WorldForm>,
+    mut labels: Query<(Ref<TitleLabel>, &mut Text)>,
+) {
+    let changed = worlds.is_changed() || form.is_changed();
+    for (label, mut text) in &mut labels {
+        if !changed && !label.is_added() {
+            continue;
+        }
+        let value = match *label {
+            TitleLabel::Field(field) if form.focus == field => format!("{}_", form.text(field)),
+            TitleLabel::Field(field) => form.text(field).to_owned(),
+            TitleLabel::Generator => format!("World Type: {}", generator_label(form.generator)),
+            TitleLabel::FormError => form.error.clone().unwrap_or_default(),
+            TitleLabel::Status => worlds.status.clone().unwrap_or_default(),
+            TitleLabel::Delete if worlds.confirm_delete => "Delete - Are You Sure?".to_owned(),
+            TitleLabel::Delete => "Delete".to_owned(),
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::world::{WorldMetadata, storage::ChunkRepository};
+
+    static NEXT_TEST_DIRECTORY_ID: AtomicUsize = AtomicUsize::new(0);
+
+    struct TestSaves(SavesDirectory);
+
+    impl TestSaves {
+        fn new() -> Self {
+            let id = NEXT_TEST_DIRECTORY_ID.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!(
+                "minecraft_clone-title-test-{}-{id}",
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            Self(SavesDirectory(path))
+        }
+    }
+
+    impl Drop for TestSaves {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0.0);
+        }
+    }
+
+    fn title_app(saves: &TestSaves) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .insert_state(GameState::MainMenu)
+            .add_sub_state::<TitleScreen>()
+            .add_message::<AppExit>()
+            .insert_resource(saves.0.clone())
+            .init_resource::<TitleWorlds>()
+            .init_resource::<WorldForm>()
+            .add_systems(OnEnter(GameState::MainMenu), reload_worlds)
+            .add_systems(Update, handle_title_buttons);
+        app.update();
+        app
+    }
+
+    fn press(app: &mut App, action: TitleAction) {
+        let button = app
+            .world_mut()
+            .spawn((Button, Interaction::Pressed, action))
+            .id();
+        app.update();
+        app.world_mut().despawn(button);
+    }
+
+    fn screen(app: &App) -> TitleScreen {
+        *app.world().resource::<State<TitleScreen>>().get()
+    }
+
+    fn create(saves: &TestSaves, name: &str) {
+        let world = NewWorld::from_form(name, "1", "2", GeneratorProfile::OverworldV1).unwrap();
+        create_world(&saves.0.0, world).unwrap();
+    }
+
+    #[test]
+    fn creating_a_world_opens_it() {
+        let saves = TestSaves::new();
+        let mut app = title_app(&saves);
+
+        press(&mut app, TitleAction::NewWorld);
+        assert_eq!(screen(&app), TitleScreen::CreateWorld);
+
+        let mut form = app.world_mut().resource_mut::<WorldForm>();
+        form.name.clear();
+        form.type_text("Highlands");
+        form.focus_next(TitleScreen::CreateWorld);
+        form.type_text("-1");
+        form.focus_next(TitleScreen::CreateWorld);
+        form.erase();
+        form.type_text("3");
+        press(&mut app, TitleAction::CycleGenerator);
+        press(&mut app, TitleAction::Confirm);
+        app.update();
+
+        let world = app.world();
+        let metadata = world.resource::<WorldMetadata>();
+        assert_eq!(metadata.seed, u64::MAX);
+        assert_eq!(metadata.height_chunks(), 3);
+        assert!(world.contains_resource::<ChunkRepository>());
+        assert_eq!(
+            *world.resource::<State<GameState>>().get(),
+            GameState::GenWorld
+        );
+
+        let saved = list_worlds(&saves.0.0);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name, "Highlands");
+        assert_eq!(saved[0].spawn_generator, GeneratorProfile::GrassFloorV1);
+    }
+
+    #[test]
+    fn invalid_forms_stay_on_the_create_screen() {
+        let saves = TestSaves::new();
+        let mut app = title_app(&saves);
+
+        press(&mut app, TitleAction::NewWorld);
+        let mut form = app.world_mut().resource_mut::<WorldForm>();
+        form.focus = FormField::Height;
+        form.type_text("0");
+        press(&mut app, TitleAction::Confirm);
+
+        assert_eq!(screen(&app), TitleScreen::CreateWorld);
+        assert!(app.world().resource::<WorldForm>().error.is_some());
+        assert!(list_worlds(&saves.0.0).is_empty());
+    }
+
+    #[test]
+    fn renaming_and_deleting_the_selected_world() {
+        let saves = TestSaves::new();
+        create(&saves, "Alpha");
+        create(&saves, "Beta");
+        let mut app = title_app(&saves);
+        assert_eq!(app.world().resource::<TitleWorlds>().worlds.len(), 2);
+
+        press(&mut app, TitleAction::SelectWorld(0));
+        press(&mut app, TitleAction::Rename);
+        assert_eq!(app.world().resource::<WorldForm>().name, "Alpha");
+        app.world_mut().resource_mut::<WorldForm>().name = "Zeta".to_owned();
+        press(&mut app, TitleAction::Confirm);
+
+        assert_eq!(screen(&app), TitleScreen::Worlds);
+        let worlds = app.world().resource::<TitleWorlds>();
+        let names = worlds
+            .worlds
+            .iter()
+            .map(|world| world.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Beta", "Zeta"]);
+        assert_eq!(worlds.selected().unwrap().name, "Zeta");
+
+        press(&mut app, TitleAction::Delete);
+        assert_eq!(list_worlds(&saves.0.0).len(), 2);
+        press(&mut app, TitleAction::Delete);
+
+        let worlds = app.world().resource::<TitleWorlds>();
+        assert_eq!(worlds.worlds.len(), 1);
+        assert_eq!(worlds.worlds[0].name, "Beta");
+        assert_eq!(worlds.selected, None);
+    }
+
+    #[test]
+    fn form_fields_respect_their_length_limits() {
+        let mut form = WorldForm {
+            name: String::new(),
+            ..default()
+        };
+        form.type_text(&"a".repeat(MAX_WORLD_NAME_CHARS + 5));
+        form.type_text("\u{8}");
+        assert_eq!(form.name.chars().count(), MAX_WORLD_NAME_CHARS);
+
+        form.focus = FormField::Height;
+        form.type_text("1234");
+        assert_eq!(form.height, "512");
+    }
+}
@@ -3,3 +3,32 @@ use bevy::math::Vec3;
 pub const fn splat_xz(v: f32) -> Vec3 {
     Vec3 { x: v, y: 0.0, z: v }
 }
+
+const FNV1A_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV1A_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a of `bytes`. It is stable across builds and platforms, which
+/// is what seeds and on-disk checksums need, but it is not collision resistant.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    fnv1a_extend(FNV1A_OFFSET_BASIS, bytes)
+}
+
+/// Continues an [`fnv1a`] hash over more bytes, so one hash can span several
+/// buffers without joining them.
+pub fn fnv1a_extend(hash: u64, bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV1A_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_the_reference_hash_and_extends_across_buffers() {
+        assert_eq!(fnv1a([]), FNV1A_OFFSET_BASIS);
+        assert_eq!(fnv1a(*b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a_extend(fnv1a(*b"fo"), *b"o"), fnv1a(*b"foo"));
+    }
+}
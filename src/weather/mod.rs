@@ -0,0 +1,513 @@
+//! World weather: clear skies, rain and thunderstorms.
+//!
+//! `Weather` advances on the fixed tick. Each spell lasts a duration drawn
+//! from a generator seeded by the world seed, and the kind, remaining ticks and
+//! generator state are kept in `world_metadata`, so a reloaded world resumes
+//! the same spell. Rendering and audio read the smoothed `precipitation` and
+//! `thunder` levels rather than the kind, so changes fade in and out.
+
+mod precipitation;
+
+use bevy::{app::AppExit, prelude::*, time::Real, window::ExitSystems};
+
+use crate::{
+    game_state::GameState,
+    world::{
+        generation::SplitMix64,
+        session::WorldSessionSystems,
+        storage::{ChunkRepository, ChunkStoreError, ChunkStoreResult},
+    },
+};
+
+pub struct WeatherPlugin;
+
+/// Mixed into the world seed so weather does not correlate with terrain.
+const WEATHER_SEED_SALT: u64 = 0x0077_6561_7468_6572;
+const WEATHER_FADE_SECONDS: f32 = 5.0;
+const WEATHER_AUTOSAVE_INTERVAL_SECONDS: f32 = 30.0;
+/// One rain spell in this many turns into a thunderstorm.
+const THUNDER_ODDS: u64 = 3;
+
+const WEATHER_KIND_KEY: &str = "weather.kind";
+const WEATHER_REMAINING_TICKS_KEY: &str = "weather.remaining_ticks";
+const WEATHER_RNG_STATE_KEY: &str = "weather.rng_state";
+
+/// How much rain and thunder each take off sky light, after Minecraft's
+/// 5/16 darkening per level.
+const RAIN_SKY_DARKENING: f32 = 5.0 / 16.0;
+const THUNDER_SKY_DARKENING: f32 = 5.0 / 16.0;
+/// How far overcast skies and fog move toward grey.
+const OVERCAST_DESATURATION: f32 = 0.6;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .init_resource::<WeatherSaveState>()
+            .register_type::<Weather>()
+            .add_plugins(precipitation::PrecipitationPlugin)
+            .add_systems(OnEnter(GameState::GenWorld), load_weather)
+            .add_systems(FixedUpdate, advance_weather)
+            .add_systems(Update, fade_weather_levels)
+            .add_systems(Last, save_weather.after(ExitSystems))
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                save_weather_on_close.in_set(WorldSessionSystems::Save),
+            );
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeatherKind {
+    #[default]
+    Clear,
+    Rain,
+    Thunder,
+}
+
+impl WeatherKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Clear => "clear",
+            Self::Rain => "rain",
+            Self::Thunder => "thunder",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "clear" => Some(Self::Clear),
+            "rain" => Some(Self::Rain),
+            "thunder" => Some(Self::Thunder),
+            _ => None,
+        }
+    }
+
+    /// Inclusive spell length in fixed ticks, after Minecraft's weather cycle.
+    const fn duration_ticks(self) -> (u32, u32) {
+        match self {
+            Self::Clear => (12_000, 180_000),
+            Self::Rain => (12_000, 24_000),
+            Self::Thunder => (3_600, 15_600),
+        }
+    }
+
+    /// Precipitation and thunder levels the smoothed values fade toward.
+    const fn target_levels(self) -> (f32, f32) {
+        match self {
+            Self::Clear => (0.0, 0.0),
+            Self::Rain => (1.0, 0.0),
+            Self::Thunder => (1.0, 1.0),
+        }
+    }
+}
+
+/// Current world weather. Registered for the F5 inspector; setting `kind`
+/// there starts that weather until `remaining_ticks` runs out.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct Weather {
+    pub kind: WeatherKind,
+    /// Fixed ticks left in the current spell.
+    pub remaining_ticks: u32,
+    /// SplitMix64 state the next spell draws from.
+    rng_state: u64,
+    /// Smoothed rain or snow strength in `0.0..=1.0`.
+    pub precipitation: f32,
+    /// Smoothed storm strength in `0.0..=1.0`, on top of `precipitation`.
+    pub thunder: f32,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+impl Weather {
+    /// Clear skies for a seeded duration, the weather of a new world.
+    pub fn seeded(world_seed: u64) -> Self {
+        let mut weather = Self {
+            kind: WeatherKind::Clear,
+            remaining_ticks: 0,
+            rng_state: world_seed ^ WEATHER_SEED_SALT,
+            precipitation: 0.0,
+            thunder: 0.0,
+        };
+        weather.remaining_ticks = weather.roll_duration(WeatherKind::Clear);
+        weather
+    }
+
+    /// Advances one fixed tick, starting the next spell when this one ends.
+    /// Returns whether the kind changed.
+    fn tick(&mut self) -> bool {
+        self.remaining_ticks = self.remaining_ticks.saturating_sub(1);
+        if self.remaining_ticks > 0 {
+            return false;
+        }
+        let next = match self.kind {
+            WeatherKind::Clear | WeatherKind::Thunder => WeatherKind::Rain,
+            WeatherKind::Rain => {
+                if self.next_random() % THUNDER_ODDS == 0 {
+                    WeatherKind::Thunder
+                } else {
+                    WeatherKind::Clear
+                }
+            }
+        };
+        self.kind = next;
+        self.remaining_ticks = self.roll_duration(next);
+        true
+    }
+
+    /// Moves the smoothed levels toward the current kind.
+    fn fade(&mut self, delta_seconds: f32) {
+        let (precipitation, thunder) = self.kind.target_levels();
+        let step = delta_seconds / WEATHER_FADE_SECONDS;
+        self.precipitation = approach(self.precipitation, precipitation, step);
+        self.thunder = approach(self.thunder, thunder, step);
+    }
+
+    /// Jumps the smoothed levels to the current kind, for a freshly loaded
+    /// world that should not fade in a storm it saved during.
+    fn settle(&mut self) {
+        (self.precipitation, self.thunder) = self.kind.target_levels();
+    }
+
+    /// Multiplier for sky light under the current cloud cover.
+    pub fn sky_light_factor(&self) -> f32 {
+        (1.0 - RAIN_SKY_DARKENING * self.precipitation)
+            * (1.0 - THUNDER_SKY_DARKENING * self.thunder)
+    }
+
+    /// `color` dimmed and greyed by the current cloud cover, for the sky and
+    /// open-air fog.
+    pub fn overcast_color(&self, color: Vec3) -> Vec3 {
+        let luminance = color.dot(vec3(0.2126, 0.7152, 0.0722));
+        let grey = Vec3::splat(luminance);
+        let desaturated = color + (grey - color) * (OVERCAST_DESATURATION * self.precipitation);
+        desaturated * self.sky_light_factor()
+    }
+
+    fn roll_duration(&mut self, kind: WeatherKind) -> u32 {
+        let (min, max) = kind.duration_ticks();
+        let span = u64::from(max - min) + 1;
+        min + (self.next_random() % span) as u32
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let mut rng = SplitMix64::new(self.rng_state);
+        let value = rng.next_u64();
+        self.rng_state = rng.state();
+        value
+    }
+
+    /// The persisted part of the weather, as `world_metadata` entries.
+    fn stored_entries(&self) -> [(&'static str, String); 3] {
+        [
+            (WEATHER_KIND_KEY, self.kind.as_str().to_owned()),
+            (
+                WEATHER_REMAINING_TICKS_KEY,
+                self.remaining_ticks.to_string(),
+            ),
+            (WEATHER_RNG_STATE_KEY, self.rng_state.to_string()),
+        ]
+    }
+
+    /// Restores weather saved by [`Self::stored_entries`], or `None` for a
+    /// world that never saved any.
+    fn from_stored(
+        mut load: impl FnMut(&str) -> ChunkStoreResult<Option<String>>,
+    ) -> ChunkStoreResult<Option<Self>> {
+        let Some(kind) = load(WEATHER_KIND_KEY)? else {
+            return Ok(None);
+        };
+        let kind = WeatherKind::parse(&kind).ok_or_else(|| invalid(WEATHER_KIND_KEY, kind))?;
+        let remaining_ticks: u32 = parse_stored(WEATHER_REMAINING_TICKS_KEY, &mut load)?;
+        let rng_state = parse_stored(WEATHER_RNG_STATE_KEY, &mut load)?;
+
+        let mut weather = Self {
+            kind,
+            remaining_ticks: remaining_ticks.max(1),
+            rng_state,
+            precipitation: 0.0,
+            thunder: 0.0,
+        };
+        weather.settle();
+        Ok(Some(weather))
+    }
+}
+
+fn parse_stored<T: std::str::FromStr>(
+    key: &'static str,
+    load: &mut impl FnMut(&str) -> ChunkStoreResult<Option<String>>,
+) -> ChunkStoreResult<T> {
+    let value = load(key)?.ok_or(ChunkStoreError::InvalidWorldMetadata {
+        key: key.to_owned(),
+        found: None,
+    })?;
+    value.parse().map_err(|_| invalid(key, value))
+}
+
+fn invalid(key: &str, found: String) -> ChunkStoreError {
+    ChunkStoreError::InvalidWorldMetadata {
+        key: key.to_owned(),
+        found: Some(found),
+    }
+}
+
+fn approach(current: f32, target: f32, step: f32) -> f32 {
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
+
+fn load_weather(repository: Option<Res<ChunkRepository>>, mut weather: ResMut<Weather>) {
+    let Some(repository) = repository else {
+        return;
+    };
+    *weather = Weather::seeded(repository.metadata().seed);
+    match Weather::from_stored(|key| repository.load_world_state(key)) {
+        Ok(Some(stored)) => *weather = stored,
+        Ok(None) => {}
+        Err(error) => {
+            error!(%error, "Failed to load saved weather; starting from clear skies");
+        }
+    }
+}
+
+fn advance_weather(mut weather: ResMut<Weather>) {
+    if weather.tick() {
+        debug!(kind = ?weather.kind, ticks = weather.remaining_ticks, "Weather changed");
+    }
+}
+
+fn fade_weather_levels(time: Res<Time>, mut weather: ResMut<Weather>) {
+    weather.fade(time.delta_secs());
+}
+
+#[derive(Resource)]
+struct WeatherSaveState {
+    timer: Timer,
+    last_saved: Option<(WeatherKind, u32)>,
+}
+
+impl Default for WeatherSaveState {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(WEATHER_AUTOSAVE_INTERVAL_SECONDS, TimerMode::Repeating),
+            last_saved: None,
+        }
+    }
+}
+
+/// Saves when the kind changes, on the autosave interval, and on exit.
+fn save_weather(
+    repository: Option<Res<ChunkRepository>>,
+    weather: Res<Weather>,
+    real_time: Res<Time<Real>>,
+    mut exits: MessageReader<AppExit>,
+    mut state: ResMut<WeatherSaveState>,
+) {
+    let exiting = exits.read().next().is_some();
+    let autosave_due = state.timer.tick(real_time.delta()).just_finished();
+    let Some(repository) = repository else {
+        return;
+    };
+
+    write_weather(&repository, &weather, &mut state, autosave_due || exiting);
+}
+
+/// Saves the weather as the world closes. The next world loads its own.
+fn save_weather_on_close(
+    repository: Option<Res<ChunkRepository>>,
+    weather: Res<Weather>,
+    mut state: ResMut<WeatherSaveState>,
+) {
+    if let Some(repository) = repository {
+        write_weather(&repository, &weather, &mut state, true);
+    }
+    *state = WeatherSaveState::default();
+}
+
+fn write_weather(
+    repository: &ChunkRepository,
+    weather: &Weather,
+    state: &mut WeatherSaveState,
+    save_due: bool,
+) {
+    let snapshot = (weather.kind, weather.remaining_ticks);
+    let kind_changed = state.last_saved.map(|(kind, _)| kind) != Some(weather.kind);
+    if state.last_saved == Some(snapshot) || !(kind_changed || save_due) {
+        return;
+    }
+
+    let saved = weather
+        .stored_entries()
+        .iter()
+        .try_for_each(|(key, value)| repository.save_world_state(key, value));
+    match saved {
+        Ok(()) => state.last_saved = Some(snapshot),
+        Err(error) => error!(%error, "Failed to save weather; the autosave will retry"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::world::{WorldMetadata, storage::InMemoryChunkStore};
+
+    #[test]
+    fn spells_are_seeded_by_the_world() {
+        let mut first = Weather::seeded(7);
+        let mut second = Weather::seeded(7);
+        let other = Weather::seeded(8);
+
+        assert_eq!(first, second);
+        assert_ne!(first.remaining_ticks, other.remaining_ticks);
+        for _ in 0..1_000_000 {
+            assert_eq!(first.tick(), second.tick());
+        }
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn spells_cycle_through_rain_and_stay_in_range() {
+        let mut weather = Weather::seeded(42);
+        let mut seen = Vec::new();
+        while seen.len() < 40 {
+            let kind = weather.kind;
+            let (min, max) = kind.duration_ticks();
+            assert!((min..=max).contains(&weather.remaining_ticks));
+            let mut ticks = 1;
+            while !weather.tick() {
+                ticks += 1;
+            }
+            assert!((min..=max).contains(&ticks), "{kind:?} lasted {ticks}");
+            assert_ne!(
+                (kind, weather.kind),
+                (WeatherKind::Clear, WeatherKind::Thunder)
+            );
+            assert_ne!(
+                (kind, weather.kind),
+                (WeatherKind::Thunder, WeatherKind::Clear)
+            );
+            seen.push(weather.kind);
+        }
+        assert!(seen.contains(&WeatherKind::Thunder));
+        assert!(seen.contains(&WeatherKind::Clear));
+    }
+
+    #[test]
+    fn levels_fade_toward_the_current_kind() {
+        let mut weather = Weather {
+            kind: WeatherKind::Thunder,
+            ..Weather::seeded(1)
+        };
+
+        weather.fade(WEATHER_FADE_SECONDS / 2.0);
+        assert_eq!((weather.precipitation, weather.thunder), (0.5, 0.5));
+        weather.fade(WEATHER_FADE_SECONDS);
+        assert_eq!((weather.precipitation, weather.thunder), (1.0, 1.0));
+
+        weather.kind = WeatherKind::Rain;
+        weather.fade(WEATHER_FADE_SECONDS);
+        assert_eq!((weather.precipitation, weather.thunder), (1.0, 0.0));
+    }
+
+    #[test]
+    fn storms_darken_and_grey_the_sky() {
+        let clear = Weather::seeded(1);
+        let storm = Weather {
+            precipitation: 1.0,
+            thunder: 1.0,
+            ..clear
+        };
+        let sky = vec3(0.455, 0.702, 1.0);
+
+        assert_eq!(clear.sky_light_factor(), 1.0);
+        assert_eq!(clear.overcast_color(sky), sky);
+        assert!(storm.sky_light_factor() < 0.5);
+        let overcast = storm.overcast_color(sky);
+        assert!(
+            overcast.max_element() - overcast.min_element() < sky.max_element() - sky.min_element()
+        );
+        assert!(overcast.length() < sky.length());
+    }
+
+    #[test]
+    fn stored_weather_round_trips_and_settles() {
+        let mut weather = Weather::seeded(3);
+        weather.kind = WeatherKind::Thunder;
+        weather.remaining_ticks = 1234;
+        let stored = weather
+            .stored_entries()
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect::<HashMap<_, _>>();
+
+        let restored = Weather::from_stored(|key| Ok(stored.get(key).cloned()))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(restored.kind, WeatherKind::Thunder);
+        assert_eq!(restored.remaining_ticks, 1234);
+        assert_eq!(restored.rng_state, weather.rng_state);
+        assert_eq!((restored.precipitation, restored.thunder), (1.0, 1.0));
+        assert_eq!(Weather::from_stored(|_| Ok(None)).unwrap(), None);
+        assert!(matches!(
+            Weather::from_stored(|key| Ok(Some(if key == WEATHER_KIND_KEY {
+                "hail".to_owned()
+            } else {
+                "1".to_owned()
+            }))),
+            Err(ChunkStoreError::InvalidWorldMetadata { key, .. }) if key == WEATHER_KIND_KEY
+        ));
+    }
+
+    #[test]
+    fn weather_is_saved_on_change_and_restored_on_startup() {
+        let repository = ChunkRepository::new(InMemoryChunkStore::new(WorldMetadata::with_seed(5)));
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(repository.clone())
+            .init_resource::<Weather>()
+            .init_resource::<WeatherSaveState>()
+            .add_systems(Startup, load_weather)
+            .add_systems(Last, save_weather);
+
+        app.update();
+        assert_eq!(*app.world().resource::<Weather>(), Weather::seeded(5));
+        assert_eq!(
+            repository
+                .load_world_state(WEATHER_KIND_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("clear")
+        );
+
+        app.world_mut().resource_mut::<Weather>().kind = WeatherKind::Rain;
+        app.update();
+        assert_eq!(
+            repository
+                .load_world_state(WEATHER_KIND_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("rain")
+        );
+
+        let mut reloaded = App::new();
+        reloaded
+            .add_plugins(MinimalPlugins)
+            .insert_resource(repository)
+            .init_resource::<Weather>()
+            .add_systems(Startup, load_weather);
+        reloaded.update();
+        let weather = reloaded.world().resource::<Weather>();
+        assert_eq!(weather.kind, WeatherKind::Rain);
+        assert_eq!(weather.precipitation, 1.0);
+    }
+}
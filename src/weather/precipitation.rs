@@ -0,0 +1,368 @@
+//! Rain and snow falling around the camera.
+//!
+//! Drops live in a box that tiles world space around the camera, so they stay
+//! put while the camera moves and wrap in from the far side. A drop is only
+//! drawn above the top sky-blocking block of its column, read from the
+//! `ChunkHeightmap`, so nothing falls under roofs or overhangs. Columns whose
+//! ground reaches `SNOW_LINE_Y` get slower, drifting snow instead of rain. All
+//! drops share one mesh that is rewritten each frame.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::world::{
+    chunk::{ChunkColumn, ChunkHeightmap, WorldBlockPos},
+    dimension::{Active, Dimension},
+    generation::SplitMix64,
+};
+
+use super::Weather;
+
+const DROP_COUNT: usize = 1_500;
+const DROP_SEED: u64 = 0x7261_696e;
+/// Half-width of the box drops fall in, in blocks.
+const DROP_RADIUS: f32 = 16.0;
+const DROP_BOX_HEIGHT: f32 = 24.0;
+/// Drops start this far above the camera's box centre so more fall in front
+/// of the view than below the feet.
+const DROP_BOX_LIFT: f32 = 6.0;
+/// Columns whose ground is at least this high get snow instead of rain.
+const SNOW_LINE_Y: f32 = 40.0;
+
+const RAIN_FALL_SPEED: f32 = 14.0;
+const SNOW_FALL_SPEED: f32 = 2.0;
+const SNOW_DRIFT_SPEED: f32 = 0.6;
+const RAIN_STREAK_SIZE: Vec2 = vec2(0.03, 0.6);
+const SNOW_FLAKE_SIZE: Vec2 = vec2(0.1, 0.1);
+const RAIN_COLOR: [f32; 4] = [0.62, 0.70, 0.86, 0.55];
+const SNOW_COLOR: [f32; 4] = [0.96, 0.97, 1.0, 0.9];
+
+pub(super) struct PrecipitationPlugin;
+
+impl Plugin for PrecipitationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_precipitation).add_systems(
+            Update,
+            update_precipitation.after(super::fade_weather_levels),
+        );
+    }
+}
+
+/// One drop's position in the tiling box, in `0..1` along each axis, and a
+/// per-drop phase that varies fall speed and snow drift.
+#[derive(Debug, Clone, Copy)]
+struct Drop {
+    cell: Vec3,
+    phase: f32,
+}
+
+#[derive(Component)]
+struct Precipitation {
+    drops: Vec<Drop>,
+    mesh: Handle<Mesh>,
+    seconds: f32,
+}
+
+fn spawn_precipitation(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(precipitation_mesh(&[], Vec3::X));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        cull_mode: None,
+        ..default()
+    });
+    commands.spawn((
+        Name::new("Precipitation"),
+        Precipitation {
+            drops: scatter_drops(DROP_SEED, DROP_COUNT),
+            mesh: mesh.clone(),
+            seconds: 0.0,
+        },
+        Mesh3d(mesh),
+        MeshMaterial3d(material),
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+}
+
+fn update_precipitation(
+    time: Res<Time>,
+    weather: Res<Weather>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+    heightmaps: Query<&ChunkHeightmap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut precipitation: Query<(&mut Precipitation, &mut Visibility)>,
+) {
+    let Ok((mut precipitation, mut visibility)) = precipitation.single_mut() else {
+        return;
+    };
+    let visible_count = (DROP_COUNT as f32 * weather.precipitation).round() as usize;
+    let Some(camera) = cameras.iter().next().filter(|_| visible_count > 0) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    precipitation.seconds += time.delta_secs();
+
+    let camera_position = camera.translation();
+    let mut surfaces = SurfaceHeights::new(|column: ChunkColumn| {
+        let dimension = dimension.as_deref()?;
+        (0..dimension.height().chunks_i32())
+            .find_map(|y| dimension.loaded_chunk_entity(column.chunk(y)))
+            .and_then(|entity| heightmaps.get(entity).ok())
+            .copied()
+    });
+    let seconds = precipitation.seconds;
+    let quads = precipitation.drops[..visible_count]
+        .iter()
+        .filter_map(|&drop| {
+            // Fall speed does not move a drop sideways, so the rain position
+            // already names the column that decides between rain and snow.
+            let mut position = drop_position(drop, camera_position, seconds, RAIN_FALL_SPEED);
+            let surface = surfaces.top(position);
+            let snow = surface >= SNOW_LINE_Y;
+            if snow {
+                position = drop_position(drop, camera_position, seconds, SNOW_FALL_SPEED);
+                position.x += snow_drift(drop, seconds);
+            }
+            (position.y >= surface).then_some(DropQuad { position, snow })
+        })
+        .collect::<Vec<_>>();
+
+    let right = camera.right().as_vec3().with_y(0.0).normalize_or(Vec3::X);
+    if let Some(mesh) = meshes.get_mut(&precipitation.mesh) {
+        *mesh = precipitation_mesh(&quads, right);
+    }
+}
+
+/// Scatters drops through the tiling box with a seeded SplitMix64.
+fn scatter_drops(seed: u64, count: usize) -> Vec<Drop> {
+    let mut rng = SplitMix64::new(seed);
+    (0..count)
+        .map(|_| Drop {
+            cell: vec3(rng.next_unit(), rng.next_unit(), rng.next_unit()),
+            phase: rng.next_unit(),
+        })
+        .collect()
+}
+
+/// World position of a drop after `seconds` of falling at about
+/// `fall_speed`, wrapped into the box around `camera`. Each drop is fixed in
+/// world space until it wraps.
+fn drop_position(drop: Drop, camera: Vec3, seconds: f32, fall_speed: f32) -> Vec3 {
+    let box_size = vec3(DROP_RADIUS * 2.0, DROP_BOX_HEIGHT, DROP_RADIUS * 2.0);
+    let box_min = camera
+        - vec3(
+            DROP_RADIUS,
+            DROP_BOX_HEIGHT / 2.0 - DROP_BOX_LIFT,
+            DROP_RADIUS,
+        );
+    let speed_scale = 0.85 + 0.3 * drop.phase;
+    let world = drop.cell * box_size - Vec3::Y * (fall_speed * speed_scale * seconds);
+
+    box_min + (world - box_min).rem_euclid(box_size)
+}
+
+/// Sideways sway of a snowflake, at most half a block.
+fn snow_drift(drop: Drop, seconds: f32) -> f32 {
+    (seconds * SNOW_DRIFT_SPEED + drop.phase * std::f32::consts::TAU).sin() * 0.5
+}
+
+/// Top of the sky-blocking terrain per column, cached for one frame.
+struct SurfaceHeights<F> {
+    load: F,
+    columns: HashMap<ChunkColumn, Option<ChunkHeightmap>>,
+}
+
+impl<F: FnMut(ChunkColumn) -> Option<ChunkHeightmap>> SurfaceHeights<F> {
+    fn new(load: F) -> Self {
+        Self {
+            load,
+            columns: HashMap::new(),
+        }
+    }
+
+    /// World Y above which the column at `position` is open to the sky.
+    /// Unloaded columns count as open.
+    fn top(&mut self, position: Vec3) -> f32 {
+        let address = WorldBlockPos::from_translation(position).split();
+        let column = address.chunk().column();
+        let heightmap = self
+            .columns
+            .entry(column)
+            .or_insert_with(|| (self.load)(column));
+        heightmap.as_ref().map_or(f32::NEG_INFINITY, |heightmap| {
+            let local = address.local();
+            surface_top(heightmap.heights[local.x()][local.z()])
+        })
+    }
+}
+
+/// The heightmap stores the Y of the highest blocking cell, or zero when
+/// there is none, so only a non-zero height has a block to stand above.
+fn surface_top(height: u8) -> f32 {
+    if height == 0 {
+        f32::NEG_INFINITY
+    } else {
+        f32::from(height) + 1.0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DropQuad {
+    position: Vec3,
+    snow: bool,
+}
+
+/// Vertical quads turned about Y toward the camera, hanging down from each
+/// drop position.
+fn precipitation_mesh(quads: &[DropQuad], right: Vec3) -> Mesh {
+    let mut positions = Vec::with_capacity(quads.len() * 4);
+    let mut colors = Vec::with_capacity(quads.len() * 4);
+    let mut indices = Vec::with_capacity(quads.len() * 6);
+    for quad in quads {
+        let (size, color) = if quad.snow {
+            (SNOW_FLAKE_SIZE, SNOW_COLOR)
+        } else {
+            (RAIN_STREAK_SIZE, RAIN_COLOR)
+        };
+        let half_width = right * (size.x / 2.0);
+        let bottom = quad.position - Vec3::Y * size.y;
+        let base = positions.len() as u32;
+        positions.extend([
+            (bottom - half_width).to_array(),
+            (bottom + half_width).to_array(),
+            (quad.position + half_width).to_array(),
+            (quad.position - half_width).to_array(),
+        ]);
+        colors.extend([color; 4]);
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_are_seeded_and_fill_the_box() {
+        let drops = scatter_drops(DROP_SEED, DROP_COUNT);
+        let again = scatter_drops(DROP_SEED, DROP_COUNT);
+
+        assert!(drops.iter().zip(&again).all(|(a, b)| a.cell == b.cell));
+        assert!(
+            drops
+                .iter()
+                .all(|drop| drop.cell.cmpge(Vec3::ZERO).all() && drop.cell.cmplt(Vec3::ONE).all())
+        );
+        let upper_half = drops.iter().filter(|drop| drop.cell.y >= 0.5).count();
+        assert!((600..900).contains(&upper_half), "{upper_half}");
+    }
+
+    #[test]
+    fn drops_stay_in_the_box_around_a_moving_camera() {
+        let drops = scatter_drops(DROP_SEED, 64);
+        for camera in [
+            Vec3::ZERO,
+            vec3(-1_000.25, 60.0, 73.5),
+            vec3(5e4, 12.0, -5e4),
+        ] {
+            for seconds in [0.0, 0.4, 123.0] {
+                for &drop in &drops {
+                    let offset = drop_position(drop, camera, seconds, RAIN_FALL_SPEED) - camera;
+                    assert!(offset.x.abs() <= DROP_RADIUS + 0.01, "{offset}");
+                    assert!(offset.z.abs() <= DROP_RADIUS + 0.01, "{offset}");
+                    assert!(
+                        (-DROP_BOX_HEIGHT / 2.0 + DROP_BOX_LIFT - 0.01
+                            ..=DROP_BOX_HEIGHT / 2.0 + DROP_BOX_LIFT + 0.01)
+                            .contains(&offset.y),
+                        "{offset}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn drops_hold_still_while_the_camera_moves() {
+        let drop = scatter_drops(DROP_SEED, 1)[0];
+        let here = drop_position(drop, Vec3::ZERO, 0.0, RAIN_FALL_SPEED);
+        let nudged = drop_position(drop, vec3(0.5, 0.25, -0.5), 0.0, RAIN_FALL_SPEED);
+
+        assert!(here.abs_diff_eq(nudged, 1e-4));
+    }
+
+    #[test]
+    fn drops_fall_over_time() {
+        let drop = Drop {
+            cell: vec3(0.5, 0.9, 0.5),
+            phase: 0.5,
+        };
+
+        let start = drop_position(drop, Vec3::ZERO, 0.0, RAIN_FALL_SPEED);
+        let rain = drop_position(drop, Vec3::ZERO, 0.1, RAIN_FALL_SPEED);
+        let snow = drop_position(drop, Vec3::ZERO, 0.1, SNOW_FALL_SPEED);
+
+        assert!(rain.y < snow.y && snow.y < start.y);
+        assert_eq!((rain.x, rain.z), (start.x, start.z));
+    }
+
+    #[test]
+    fn surfaces_follow_the_heightmap_and_load_each_column_once() {
+        let mut heightmap = ChunkHeightmap::default();
+        heightmap.heights[3][5] = 30;
+        let mut loads = 0;
+        let mut surfaces = SurfaceHeights::new(|column: ChunkColumn| {
+            loads += 1;
+            (column == ChunkColumn::new(-1, 0)).then_some(heightmap)
+        });
+
+        assert_eq!(surfaces.top(vec3(-12.5, 50.0, 5.5)), 31.0);
+        assert_eq!(surfaces.top(vec3(-12.5, 10.0, 6.5)), f32::NEG_INFINITY);
+        assert_eq!(surfaces.top(vec3(4.0, 10.0, 4.0)), f32::NEG_INFINITY);
+        assert_eq!(loads, 2);
+    }
+
+    #[test]
+    fn only_columns_with_blocks_stop_precipitation() {
+        assert_eq!(surface_top(0), f32::NEG_INFINITY);
+        assert_eq!(surface_top(20), 21.0);
+    }
+
+    #[test]
+    fn mesh_has_one_quad_per_visible_drop() {
+        let quads = [
+            DropQuad {
+                position: vec3(0.0, 30.0, 0.0),
+                snow: false,
+            },
+            DropQuad {
+                position: vec3(2.0, 50.0, 0.0),
+                snow: true,
+            },
+        ];
+
+        let mesh = precipitation_mesh(&quads, Vec3::X);
+
+        assert_eq!(mesh.count_vertices(), 8);
+        assert_eq!(mesh.indices().map(|indices| indices.len()), Some(12));
+    }
+}
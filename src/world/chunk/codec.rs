@@ -141,6 +141,8 @@ fn read_bits(buffer: &[u8], bit_pos: &mut usize, bits: u8) -> Option<u32> {
 pub enum ChunkDecodeError {
     Truncated,
     InvalidHeader,
+    /// The data was written by a different format version.
+    UnsupportedVersion(u8),
     UnknownBlock(String),
 }
 
@@ -149,6 +151,7 @@ impl std::fmt::Display for ChunkDecodeError {
         match self {
             Self::Truncated => write!(f, "chunk data truncated"),
             Self::InvalidHeader => write!(f, "invalid chunk header"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported format version {version}"),
             Self::UnknownBlock(name) => write!(f, "unknown block: {name}"),
         }
     }
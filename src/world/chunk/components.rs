@@ -31,6 +31,7 @@ pub struct ChunkPerfCounters {
     pub mesh_apply_elapsed: Duration,
     pub mesh_apply_max_elapsed: Duration,
     pub light_rebuild_targets: usize,
+    pub light_incremental_updates: usize,
     pub light_patch_runs: usize,
     pub light_patch_calculation_chunks: usize,
     pub light_patch_max_calculation_chunks: usize,
@@ -55,6 +56,9 @@ pub struct ChunkPerfCounters {
     pub light_patch_max_latency: Duration,
     pub light_patch_stale_results: usize,
     pub light_patch_cancelled: usize,
+    pub light_cache_hits: usize,
+    pub light_cache_misses: usize,
+    pub light_cache_saves: usize,
     pub light_uploads: usize,
 }
 
@@ -2,9 +2,10 @@ use std::collections::{HashMap, HashSet};
 
 use super::{
     components::ChunkContentCounts,
-    coords::{ChunkColumn, ChunkPos, LocalBlockPos},
+    coords::{ChunkBlockPos, ChunkColumn, ChunkPos, LocalBlockPos},
+    light::LightEdit,
     neighborhood::NeighborOffset,
-    state::CellDelta,
+    state::{CellDelta, ChunkCell},
 };
 
 const SAVE: u8 = 1 << 0;
@@ -112,10 +113,13 @@ struct ChunkWork {
 /// Chunk iteration covers directly addressed and halo chunks. Lighting columns
 /// are exposed separately because a cell can invalidate every loaded Y chunk in
 /// nearby XZ columns, which cannot be expanded without dimension ownership data.
+/// The cells behind those columns are kept with their pre-edit contents so the
+/// light solver can update from them instead of relighting whole regions.
 #[derive(Debug, Default, Clone)]
 pub struct ChunkInvalidationPlan {
     chunks: HashMap<ChunkPos, ChunkWork>,
     light_columns: HashSet<ChunkColumn>,
+    light_edits: HashMap<ChunkBlockPos, ChunkCell>,
 }
 
 impl ChunkInvalidationPlan {
@@ -155,9 +159,29 @@ impl ChunkInvalidationPlan {
         self.light_columns.contains(&column)
     }
 
+    /// Light-affecting cells, each with its contents before the first edit.
+    pub fn light_edits(&self) -> impl ExactSizeIterator<Item = LightEdit> + '_ {
+        self.light_edits
+            .iter()
+            .map(|(&address, &old)| LightEdit { address, old })
+    }
+
+    /// Folds another plan into this one so shared chunks get their work once.
+    /// Light edits keep the contents from before the earliest edit.
+    pub fn merge(&mut self, other: &Self) {
+        for (chunk, effects) in other.chunks() {
+            self.mark(chunk, effects);
+        }
+        self.light_columns.extend(other.light_columns());
+        for edit in other.light_edits() {
+            self.light_edits.entry(edit.address).or_insert(edit.old);
+        }
+    }
+
     pub fn clear(&mut self) {
         self.chunks.clear();
         self.light_columns.clear();
+        self.light_edits.clear();
     }
 
     pub fn record_cell_delta(
@@ -171,6 +195,9 @@ impl ChunkInvalidationPlan {
 
         if effects.needs_light_rebuild() {
             self.record_light_columns(chunk);
+            self.light_edits
+                .entry(ChunkBlockPos::new(chunk, local))
+                .or_insert(delta.old);
         }
 
         let mut halo = 0;
@@ -258,7 +285,7 @@ mod tests {
 
     use super::*;
     use crate::item::Item;
-    use crate::world::chunk::state::{ChunkCell, FluidProfile};
+    use crate::world::chunk::{CHUNK_SIZE, state::FluidProfile};
 
     fn delta(old: ChunkCell, new: ChunkCell) -> CellDelta {
         CellDelta { old, new }
@@ -507,8 +534,59 @@ mod tests {
             plan.light_columns().collect::<HashSet<_>>(),
             light_column_neighborhood(lower)
         );
+        assert_eq!(plan.light_edits().len(), 2);
 
         plan.clear();
         assert!(plan.is_empty());
     }
+
+    #[test]
+    fn light_edits_keep_the_cell_from_before_the_first_edit() {
+        let chunk = ChunkPos::new(-1, 0, 3);
+        let local = LocalBlockPos::new(4, 5, 6);
+        let mut plan = ChunkInvalidationPlan::new();
+
+        plan.record_cell_delta(chunk, local, delta(ChunkCell::EMPTY, Item::Stone.into()));
+        plan.record_cell_delta(chunk, local, delta(Item::Stone.into(), Item::Glass.into()));
+
+        assert_eq!(
+            plan.light_edits().collect::<Vec<_>>(),
+            [LightEdit {
+                address: ChunkBlockPos::new(chunk, local),
+                old: ChunkCell::EMPTY,
+            }]
+        );
+        plan.clear();
+        assert_eq!(plan.light_edits().len(), 0);
+    }
+
+    #[test]
+    fn merged_plans_match_one_plan_recording_every_edit() {
+        let chunk = ChunkPos::new(0, 0, 0);
+        let near = LocalBlockPos::new(0, 5, 5);
+        let far = LocalBlockPos::new(CHUNK_SIZE as u32 - 1, 5, 5);
+        let mut combined = ChunkInvalidationPlan::new();
+        let mut first = ChunkInvalidationPlan::new();
+        let mut second = ChunkInvalidationPlan::new();
+
+        for plan in [&mut combined, &mut first] {
+            plan.record_cell_delta(chunk, near, delta(ChunkCell::EMPTY, Item::Stone.into()));
+        }
+        for plan in [&mut combined, &mut second] {
+            plan.record_cell_delta(chunk, near, delta(Item::Stone.into(), Item::Glass.into()));
+            plan.record_cell_delta(chunk, far, delta(ChunkCell::EMPTY, Item::Dirt.into()));
+        }
+        first.merge(&second);
+
+        let chunks = |plan: &ChunkInvalidationPlan| plan.chunks().collect::<HashSet<_>>();
+        let columns = |plan: &ChunkInvalidationPlan| plan.light_columns().collect::<HashSet<_>>();
+        let edits = |plan: &ChunkInvalidationPlan| {
+            plan.light_edits()
+                .map(|edit| (edit.address, edit.old))
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(chunks(&first), chunks(&combined));
+        assert_eq!(columns(&first), columns(&combined));
+        assert_eq!(edits(&first), edits(&combined));
+    }
 }
@@ -4,5 +4,5 @@ mod storage;
 #[cfg(test)]
 mod tests;
 
-pub use region::{ChunkLightRegion, RebuiltChunkLight, SolvedChunkLightRegion};
+pub use region::{ChunkLightRegion, LightEdit, RebuiltChunkLight, SolvedChunkLightRegion};
 pub use storage::{ChunkHeightmap, ChunkLight};
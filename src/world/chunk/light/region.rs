@@ -1,6 +1,6 @@
 use bevy::platform::collections::{HashMap, HashSet};
 
-use crate::quad::Direction;
+use crate::{block::BlockLightRgb, quad::Direction};
 
 use super::super::{Chunk, ChunkBlockPos, ChunkCell, ChunkPos};
 use super::solver;
@@ -30,6 +30,16 @@ impl RebuiltChunkLight {
     }
 }
 
+/// A cell whose contents changed after its region's committed light was solved.
+///
+/// `old` is the cell the committed light was solved against; the region's
+/// chunks already hold the replacement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightEdit {
+    pub address: ChunkBlockPos,
+    pub old: ChunkCell,
+}
+
 struct CalculationChunk<'a> {
     chunk: &'a Chunk,
     light: ChunkLight,
@@ -248,6 +258,7 @@ pub struct ChunkLightRegion<'a> {
     height_chunks: usize,
     calculation_chunks: HashMap<ChunkPos, CalculationChunk<'a>>,
     commit_baselines: HashMap<ChunkPos, CommitBaseline<'a>>,
+    scratch_baselines: HashMap<ChunkPos, CommitBaseline<'a>>,
     boundary_lights: HashMap<ChunkPos, &'a ChunkLight>,
 }
 
@@ -267,6 +278,7 @@ impl<'a> ChunkLightRegion<'a> {
             height_chunks,
             calculation_chunks: HashMap::new(),
             commit_baselines: HashMap::new(),
+            scratch_baselines: HashMap::new(),
             boundary_lights: HashMap::new(),
         }
     }
@@ -306,6 +318,30 @@ impl<'a> ChunkLightRegion<'a> {
             .insert(position, CommitBaseline { light, heightmap });
     }
 
+    /// Gives an inserted scratch chunk the settled light [`Self::solve_edits`]
+    /// starts from. The chunk remains a scratch dependency.
+    pub fn mark_settled_scratch(
+        &mut self,
+        position: ChunkPos,
+        light: &'a ChunkLight,
+        heightmap: &'a ChunkHeightmap,
+    ) {
+        assert!(
+            self.calculation_chunks.contains_key(&position),
+            "settled scratch chunk must first be inserted for calculation"
+        );
+        assert!(
+            !self.commit_baselines.contains_key(&position),
+            "settled scratch chunk must not be a commit target"
+        );
+        assert!(
+            self.scratch_baselines
+                .insert(position, CommitBaseline { light, heightmap })
+                .is_none(),
+            "settled scratch chunk marked more than once"
+        );
+    }
+
     /// Adds a calculation chunk and marks it for commit.
     pub fn insert_target(
         &mut self,
@@ -339,20 +375,58 @@ impl<'a> ChunkLightRegion<'a> {
     }
 
     pub fn solve(self) -> SolvedChunkLightRegion {
+        self.solve_with(|prepared| {
+            if !prepared.calculation_chunks.is_empty() {
+                solver::rebuild(prepared);
+            }
+        })
+    }
+
+    pub fn rebuild(self) -> Vec<RebuiltChunkLight> {
+        self.solve().into_committed()
+    }
+
+    /// Updates committed light for `edits` without relighting the region.
+    ///
+    /// Every calculation chunk must be a commit target or settled scratch chunk
+    /// whose baseline is the settled light for the contents before the edits.
+    /// Removal and re-propagation start only from the edited cells, so the
+    /// region must extend at least one chunk past every edit horizontally and
+    /// cover its whole column for the result to match [`Self::solve`].
+    pub fn solve_edits(mut self, edits: &[LightEdit]) -> SolvedChunkLightRegion {
+        for (position, calculation) in &mut self.calculation_chunks {
+            let baseline = self
+                .commit_baselines
+                .get(position)
+                .or_else(|| self.scratch_baselines.get(position))
+                .expect("incremental lighting requires settled light for every calculation chunk");
+            calculation.light = baseline.light.clone();
+            calculation.heightmap = *baseline.heightmap;
+        }
+        self.solve_with(|prepared| solver::update(prepared, edits))
+    }
+
+    pub fn rebuild_edits(self, edits: &[LightEdit]) -> Vec<RebuiltChunkLight> {
+        self.solve_edits(edits).into_committed()
+    }
+
+    fn solve_with(
+        self,
+        solve: impl FnOnce(&mut PreparedChunkLightRegion<'a>),
+    ) -> SolvedChunkLightRegion {
         let Self {
             height_chunks,
             calculation_chunks,
             commit_baselines,
             boundary_lights,
+            ..
         } = self;
         let mut prepared = PreparedChunkLightRegion {
             height_chunks,
             calculation_chunks: PreparedCalculationChunks::new(calculation_chunks, height_chunks),
             boundary_lights,
         };
-        if !prepared.calculation_chunks.is_empty() {
-            solver::rebuild(&mut prepared);
-        }
+        solve(&mut prepared);
 
         let commit_changes = commit_baselines
             .iter()
@@ -389,10 +463,6 @@ impl<'a> ChunkLightRegion<'a> {
             commit_changes,
         }
     }
-
-    pub fn rebuild(self) -> Vec<RebuiltChunkLight> {
-        self.solve().into_committed()
-    }
 }
 
 impl<'a> PreparedChunkLightRegion<'a> {
@@ -437,14 +507,14 @@ impl<'a> PreparedChunkLightRegion<'a> {
         }
     }
 
-    pub(super) fn block_light(&self, address: ChunkBlockPos) -> u8 {
+    pub(super) fn block_light(&self, address: ChunkBlockPos) -> BlockLightRgb {
         if let Some(calculation) = self.calculation_chunks.get(address.chunk()) {
-            calculation.light.block_light(address.local())
+            calculation.light.block_light_rgb(address.local())
         } else {
             self.boundary_lights
                 .get(&address.chunk())
-                .map(|light| light.block_light(address.local()))
-                .unwrap_or(0)
+                .map(|light| light.block_light_rgb(address.local()))
+                .unwrap_or(BlockLightRgb::NONE)
         }
     }
 
@@ -456,11 +526,17 @@ impl<'a> PreparedChunkLightRegion<'a> {
         true
     }
 
-    pub(super) fn write_block_light(&mut self, address: ChunkBlockPos, value: u8) -> bool {
+    pub(super) fn write_block_light(
+        &mut self,
+        address: ChunkBlockPos,
+        value: BlockLightRgb,
+    ) -> bool {
         let Some(calculation) = self.calculation_chunks.get_mut(address.chunk()) else {
             return false;
         };
-        calculation.light.set_block_light(address.local(), value);
+        calculation
+            .light
+            .set_block_light_rgb(address.local(), value);
         true
     }
 }
@@ -1,11 +1,11 @@
 use std::collections::VecDeque;
 
-use bevy::platform::collections::HashSet;
+use bevy::platform::collections::{HashMap, HashSet};
 
-use crate::quad::Direction;
+use crate::{block::BlockLightRgb, quad::Direction};
 
-use super::super::{CHUNK_ISIZE, CHUNK_SIZE, ChunkBlockPos, ChunkColumn, ChunkPos, LocalBlockPos};
-use super::region::PreparedChunkLightRegion;
+use super::super::{CHUNK_SIZE, ChunkBlockPos, ChunkCell, ChunkColumn, ChunkPos, LocalBlockPos};
+use super::region::{LightEdit, PreparedChunkLightRegion};
 use super::storage::SKY_LIGHT_MAX;
 
 const _: () = assert!(CHUNK_SIZE > SKY_LIGHT_MAX as usize);
@@ -28,10 +28,208 @@ pub(super) fn rebuild(region: &mut PreparedChunkLightRegion<'_>) {
     propagate_block_increase(region, &mut block_queue);
 }
 
+/// Updates settled light after `edits` without relighting the whole region.
+///
+/// Light that may have depended on an edited cell, or on sky that reached
+/// straight down through one, is removed first. Every cell the removal cleared
+/// or stopped at then re-propagates, together with the edited cells' new
+/// emission and their neighbours, so the region settles on the same light a
+/// full rebuild of the edited contents produces.
+pub(super) fn update(region: &mut PreparedChunkLightRegion<'_>, edits: &[LightEdit]) {
+    let mut old_cells = HashMap::new();
+    for edit in edits {
+        if region.contains_calculation(edit.address.chunk()) {
+            old_cells.entry(edit.address).or_insert(edit.old);
+        }
+    }
+    if old_cells.is_empty() {
+        return;
+    }
+
+    let mut sky_columns = DirectSkyColumns::default();
+    let mut sky_seeds = old_cells.keys().copied().collect::<HashSet<_>>();
+    let edited_columns = old_cells
+        .keys()
+        .map(|&address| cell_column(address))
+        .collect::<HashSet<_>>();
+    let mut old_profile = Vec::new();
+    for key in edited_columns {
+        direct_sky_column(region, key, &old_cells, &mut old_profile);
+        let mut profile = Vec::new();
+        let highest = direct_sky_column(region, key, &HashMap::new(), &mut profile);
+        let (column, x, z) = key;
+        for chunk_y in 0..region.height_chunks() as i32 {
+            region.set_height(column.chunk(chunk_y), x, z, highest);
+        }
+        for (y, (&old, &new)) in old_profile.iter().zip(&profile).enumerate() {
+            if old != new {
+                sky_seeds.insert(column_cell(key, y));
+            }
+        }
+        sky_columns.insert(key, profile);
+    }
+
+    let mut sky_removal = VecDeque::new();
+    for &address in &sky_seeds {
+        let level = region.sky_light(address);
+        if level > 0 && region.write_sky_light(address, 0) {
+            sky_removal.push_back(PropagationEntry::new(address, level));
+        }
+    }
+    let mut block_removal = VecDeque::new();
+    for &address in old_cells.keys() {
+        let level = region.block_light(address);
+        if !level.is_dark() && region.write_block_light(address, BlockLightRgb::NONE) {
+            block_removal.push_back(PropagationEntry::new(address, level));
+        }
+    }
+
+    let mut sky_relight = sky_seeds.into_iter().collect::<Vec<_>>();
+    let mut block_relight = old_cells.keys().copied().collect::<Vec<_>>();
+    propagate_sky_decrease(region, &mut sky_removal, &mut sky_relight);
+    propagate_block_decrease(region, &mut block_removal, &mut block_relight);
+    // An edit that lets light through must pull it in from every side.
+    for &address in old_cells.keys() {
+        for direction in Direction::ALL {
+            sky_relight.push(address.neighbor(direction));
+            block_relight.push(address.neighbor(direction));
+        }
+    }
+
+    let mut sky_queue = VecDeque::new();
+    for address in sky_relight {
+        let mut level = region.sky_light(address);
+        let source = sky_columns.level(region, address);
+        if source > level && region.write_sky_light(address, source) {
+            level = source;
+        }
+        if level > 1 {
+            sky_queue.push_back(PropagationEntry::new(address, level));
+        }
+    }
+    let mut block_queue = VecDeque::new();
+    for address in block_relight {
+        let mut level = region.block_light(address);
+        let source = region.cell(address).light_emission();
+        if source.exceeds(level) && region.write_block_light(address, source.brightest(level)) {
+            level = source.brightest(level);
+        }
+        if level.level() > 1 {
+            block_queue.push_back(PropagationEntry::new(address, level));
+        }
+    }
+
+    propagate_sky_increase(region, &mut sky_queue);
+    propagate_block_increase(region, &mut block_queue);
+}
+
+/// One vertical line of cells through a column: the column and local X and Z.
+type CellColumn = (ChunkColumn, usize, usize);
+
+fn cell_column(address: ChunkBlockPos) -> CellColumn {
+    let local = address.local();
+    (ChunkColumn::from(address.chunk()), local.x(), local.z())
+}
+
+fn column_cell((column, x, z): CellColumn, y: usize) -> ChunkBlockPos {
+    column
+        .chunk((y / CHUNK_SIZE) as i32)
+        .block(LocalBlockPos::new(
+            x as u32,
+            (y % CHUNK_SIZE) as u32,
+            z as u32,
+        ))
+}
+
+/// Fills `profile` with the sky light reaching each absolute Y of a cell column
+/// straight from above and returns the column's heightmap value.
+///
+/// `overrides` replaces cells by address, so the profile can be taken as it was
+/// before an edit.
+fn direct_sky_column(
+    region: &PreparedChunkLightRegion<'_>,
+    key: CellColumn,
+    overrides: &HashMap<ChunkBlockPos, ChunkCell>,
+    profile: &mut Vec<u8>,
+) -> u8 {
+    let (column, x, z) = key;
+    let height_chunks = region.height_chunks() as i32;
+    profile.clear();
+    profile.resize(region.height_chunks() * CHUNK_SIZE, 0);
+
+    let top_chunk_loaded = region.contains_calculation(column.chunk(height_chunks - 1));
+    let mut current_sky = if top_chunk_loaded { SKY_LIGHT_MAX } else { 0 };
+    let mut highest = None;
+    for chunk_y in (0..height_chunks).rev() {
+        let position = column.chunk(chunk_y);
+        let Some(chunk) = region.calculation_chunk(position) else {
+            current_sky = 0;
+            continue;
+        };
+
+        for y in (0..CHUNK_SIZE).rev() {
+            let address = position.block(LocalBlockPos::new(x as u32, y as u32, z as u32));
+            let opacity = match overrides.get(&address) {
+                Some(cell) => cell.light_opacity(),
+                None => chunk.hot_meta_xyz(x, y, z).light_opacity,
+            };
+            let absolute_y = chunk_y as usize * CHUNK_SIZE + y;
+            if opacity >= SKY_LIGHT_MAX {
+                highest.get_or_insert(absolute_y);
+                current_sky = 0;
+                continue;
+            }
+
+            current_sky = current_sky.saturating_sub(opacity);
+            profile[absolute_y] = current_sky;
+        }
+    }
+
+    highest.map_or(0, |height| {
+        u8::try_from(height).expect("validated lighting height must fit the heightmap")
+    })
+}
+
+fn write_direct_sky_column(
+    region: &mut PreparedChunkLightRegion<'_>,
+    key: CellColumn,
+    profile: &[u8],
+) {
+    for (y, &level) in profile.iter().enumerate() {
+        if level > 0 {
+            region.write_sky_light(column_cell(key, y), level);
+        }
+    }
+}
+
+/// Direct sky profiles of the cell columns an update has looked at.
+#[derive(Default)]
+struct DirectSkyColumns(HashMap<CellColumn, Vec<u8>>);
+
+impl DirectSkyColumns {
+    fn insert(&mut self, key: CellColumn, profile: Vec<u8>) {
+        self.0.insert(key, profile);
+    }
+
+    fn level(&mut self, region: &PreparedChunkLightRegion<'_>, address: ChunkBlockPos) -> u8 {
+        if !region.contains_calculation(address.chunk()) {
+            return 0;
+        }
+        let key = cell_column(address);
+        let profile = self.0.entry(key).or_insert_with(|| {
+            let mut profile = Vec::new();
+            direct_sky_column(region, key, &HashMap::new(), &mut profile);
+            profile
+        });
+        let absolute_y = address.chunk().y() as usize * CHUNK_SIZE + address.local().y();
+        profile[absolute_y]
+    }
+}
+
 fn seed_sky_sources(
     region: &mut PreparedChunkLightRegion<'_>,
     calculation_positions: &[ChunkPos],
-    queue: &mut VecDeque<PropagationEntry>,
+    queue: &mut VecDeque<PropagationEntry<u8>>,
 ) {
     let columns = calculation_positions
         .iter()
@@ -39,44 +237,14 @@ fn seed_sky_sources(
         .map(ChunkColumn::from)
         .collect::<HashSet<_>>();
     let height_chunks = region.height_chunks() as i32;
-
+    let no_overrides = HashMap::new();
+    let mut profile = Vec::new();
     for column in columns {
-        let top_chunk_loaded = region.contains_calculation(column.chunk(height_chunks - 1));
         for x in 0..CHUNK_SIZE {
             for z in 0..CHUNK_SIZE {
-                let mut current_sky = if top_chunk_loaded { SKY_LIGHT_MAX } else { 0 };
-                let mut highest = 0u8;
-                let mut found_highest = false;
-
-                for chunk_y in (0..height_chunks).rev() {
-                    let position = column.chunk(chunk_y);
-                    let Some(chunk) = region.calculation_chunk(position) else {
-                        current_sky = 0;
-                        continue;
-                    };
-
-                    for y in (0..CHUNK_SIZE).rev() {
-                        let meta = chunk.hot_meta_xyz(x, y, z);
-                        if meta.light_opacity >= SKY_LIGHT_MAX {
-                            if !found_highest {
-                                highest = u8::try_from(chunk_y * CHUNK_ISIZE + y as i32)
-                                    .expect("validated lighting height must fit the heightmap");
-                                found_highest = true;
-                            }
-                            current_sky = 0;
-                            continue;
-                        }
-
-                        current_sky = current_sky.saturating_sub(meta.light_opacity);
-                        if current_sky > 0 {
-                            region.write_sky_light(
-                                position.block(LocalBlockPos::new(x as u32, y as u32, z as u32)),
-                                current_sky,
-                            );
-                        }
-                    }
-                }
-
+                let highest =
+                    direct_sky_column(region, (column, x, z), &no_overrides, &mut profile);
+                write_direct_sky_column(region, (column, x, z), &profile);
                 for chunk_y in 0..height_chunks {
                     region.set_height(column.chunk(chunk_y), x, z, highest);
                 }
@@ -125,7 +293,7 @@ fn seed_sky_sources(
 fn seed_block_sources(
     region: &mut PreparedChunkLightRegion<'_>,
     calculation_positions: &[ChunkPos],
-    queue: &mut VecDeque<PropagationEntry>,
+    queue: &mut VecDeque<PropagationEntry<BlockLightRgb>>,
 ) {
     for &position in calculation_positions {
         let Some(chunk) = region.calculation_chunk(position) else {
@@ -136,7 +304,7 @@ fn seed_block_sources(
             for z in 0..CHUNK_SIZE {
                 for y in 0..CHUNK_SIZE {
                     let emission = chunk.hot_meta_xyz(x, y, z).light_emission;
-                    if emission == 0 {
+                    if emission.is_dark() {
                         continue;
                     }
 
@@ -153,8 +321,8 @@ fn seed_block_sources(
 fn seed_boundary_light(
     region: &mut PreparedChunkLightRegion<'_>,
     calculation_positions: &[ChunkPos],
-    sky_queue: &mut VecDeque<PropagationEntry>,
-    block_queue: &mut VecDeque<PropagationEntry>,
+    sky_queue: &mut VecDeque<PropagationEntry<u8>>,
+    block_queue: &mut VecDeque<PropagationEntry<BlockLightRgb>>,
 ) {
     for &position in calculation_positions {
         for direction in Direction::ALL {
@@ -191,13 +359,14 @@ fn seed_boundary_light(
                         }
                     }
 
-                    if block_level > 0 {
-                        let level = block_level.saturating_sub(block.light_opacity().max(1));
-                        if level > region.block_light(address)
-                            && region.write_block_light(address, level)
-                            && level > 1
-                        {
-                            block_queue.push_back(PropagationEntry::new(address, level));
+                    if !block_level.is_dark() {
+                        let level = block_level.attenuated(block.light_opacity().max(1));
+                        let current = region.block_light(address);
+                        if level.exceeds(current) {
+                            let merged = level.brightest(current);
+                            if region.write_block_light(address, merged) && merged.level() > 1 {
+                                block_queue.push_back(PropagationEntry::new(address, merged));
+                            }
                         }
                     }
                 }
@@ -206,9 +375,75 @@ fn seed_boundary_light(
     }
 }
 
+/// Clears sky light that may have come from a removed level. Cells at least
+/// as bright as the level removed around them keep their light and are queued
+/// for re-propagation along with every cleared cell.
+fn propagate_sky_decrease(
+    region: &mut PreparedChunkLightRegion<'_>,
+    queue: &mut VecDeque<PropagationEntry<u8>>,
+    relight: &mut Vec<ChunkBlockPos>,
+) {
+    while let Some(entry) = queue.pop_front() {
+        for direction in Direction::ALL {
+            let neighbor = entry.address.neighbor(direction);
+            let current = region.sky_light(neighbor);
+            if current == 0 {
+                continue;
+            }
+
+            relight.push(neighbor);
+            if current < entry.level && region.write_sky_light(neighbor, 0) {
+                queue.push_back(PropagationEntry::new(neighbor, current));
+            }
+        }
+    }
+}
+
+/// Clears block light channel by channel, the way [`propagate_sky_decrease`]
+/// clears sky light.
+fn propagate_block_decrease(
+    region: &mut PreparedChunkLightRegion<'_>,
+    queue: &mut VecDeque<PropagationEntry<BlockLightRgb>>,
+    relight: &mut Vec<ChunkBlockPos>,
+) {
+    while let Some(entry) = queue.pop_front() {
+        for direction in Direction::ALL {
+            let neighbor = entry.address.neighbor(direction);
+            let current = region.block_light(neighbor);
+            if current.is_dark() {
+                continue;
+            }
+
+            relight.push(neighbor);
+            let removed = dimmer_channels(current, entry.level);
+            if removed.is_dark() {
+                continue;
+            }
+            let remaining = BlockLightRgb::new(
+                current.r - removed.r,
+                current.g - removed.g,
+                current.b - removed.b,
+            );
+            if region.write_block_light(neighbor, remaining) {
+                queue.push_back(PropagationEntry::new(neighbor, removed));
+            }
+        }
+    }
+}
+
+/// The channels of `light` dimmer than the same channel of `than`.
+fn dimmer_channels(light: BlockLightRgb, than: BlockLightRgb) -> BlockLightRgb {
+    let dimmer = |level: u8, than: u8| if level < than { level } else { 0 };
+    BlockLightRgb::new(
+        dimmer(light.r, than.r),
+        dimmer(light.g, than.g),
+        dimmer(light.b, than.b),
+    )
+}
+
 fn propagate_sky_increase(
     region: &mut PreparedChunkLightRegion<'_>,
-    queue: &mut VecDeque<PropagationEntry>,
+    queue: &mut VecDeque<PropagationEntry<u8>>,
 ) {
     while let Some(entry) = queue.pop_front() {
         if entry.level <= 1 {
@@ -248,12 +483,15 @@ fn propagate_sky_increase(
     }
 }
 
+/// Spreads coloured block light. Channels attenuate together but merge by
+/// per-channel maximum, so a cell lit by two colours keeps the brightest of
+/// each and re-queues whenever any channel rises.
 fn propagate_block_increase(
     region: &mut PreparedChunkLightRegion<'_>,
-    queue: &mut VecDeque<PropagationEntry>,
+    queue: &mut VecDeque<PropagationEntry<BlockLightRgb>>,
 ) {
     while let Some(entry) = queue.pop_front() {
-        if entry.level <= 1 {
+        if entry.level.level() <= 1 {
             continue;
         }
 
@@ -268,17 +506,21 @@ fn propagate_block_increase(
             }
 
             let current = region.block_light(neighbor);
-            if current >= entry.level - 1 {
+            if !entry.level.attenuated(1).exceeds(current) {
                 continue;
             }
 
             let level = entry
                 .level
-                .saturating_sub(region.cell(neighbor).light_opacity().max(1));
-            if level > current && region.write_block_light(neighbor, level) {
+                .attenuated(region.cell(neighbor).light_opacity().max(1));
+            if !level.exceeds(current) {
+                continue;
+            }
+            let merged = level.brightest(current);
+            if region.write_block_light(neighbor, merged) {
                 queue.push_back(PropagationEntry {
                     address: neighbor,
-                    level,
+                    level: merged,
                     directions: DirectionMask::ALL.without(direction.opposite()),
                 });
             }
@@ -287,14 +529,14 @@ fn propagate_block_increase(
 }
 
 #[derive(Clone, Copy)]
-struct PropagationEntry {
+struct PropagationEntry<L> {
     address: ChunkBlockPos,
-    level: u8,
+    level: L,
     directions: DirectionMask,
 }
 
-impl PropagationEntry {
-    const fn new(address: ChunkBlockPos, level: u8) -> Self {
+impl<L> PropagationEntry<L> {
+    const fn new(address: ChunkBlockPos, level: L) -> Self {
         Self {
             address,
             level,
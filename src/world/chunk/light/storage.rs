@@ -1,46 +1,94 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use super::super::{CHUNK_SIZE, LocalBlockPos};
+use crate::block::BlockLightRgb;
+
+use super::super::{CHUNK_SIZE, CHUNK_VOLUME, Chunk, LocalBlockPos};
 
 pub(super) const SKY_LIGHT_MAX: u8 = 15;
 
+/// Per-cell light for one chunk.
+///
+/// Each cell packs sky light in the high nibble and red, green and blue block
+/// light in the three nibbles below it, as `0xSRGB`.
 #[derive(Component, Debug, Clone, PartialEq, Eq)]
 pub struct ChunkLight {
-    light: [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    light: [[[u16; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
 }
 
 impl Default for ChunkLight {
     fn default() -> Self {
         Self {
-            light: [[[0u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+            light: [[[0u16; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
         }
     }
 }
 
 impl ChunkLight {
+    /// Length of [`Self::to_bytes`]: two little-endian bytes per cell.
+    pub const ENCODED_LEN: usize = CHUNK_VOLUME * 2;
+
+    const BLOCK_MASK: u16 = 0x0FFF;
+
     pub fn sky_light(&self, pos: LocalBlockPos) -> u8 {
         let packed = self.light[pos.x()][pos.z()][pos.y()];
-        packed >> 4
+        (packed >> 12) as u8
     }
 
+    /// The brightest block light channel at `pos`.
     pub fn block_light(&self, pos: LocalBlockPos) -> u8 {
+        self.block_light_rgb(pos).level()
+    }
+
+    pub fn block_light_rgb(&self, pos: LocalBlockPos) -> BlockLightRgb {
         let packed = self.light[pos.x()][pos.z()][pos.y()];
-        packed & 0x0F
+        BlockLightRgb::from_packed(packed & Self::BLOCK_MASK)
     }
 
-    pub fn packed_light(&self, pos: LocalBlockPos) -> u8 {
+    pub fn packed_light(&self, pos: LocalBlockPos) -> u16 {
         self.light[pos.x()][pos.z()][pos.y()]
     }
 
     pub fn set_sky_light(&mut self, pos: LocalBlockPos, value: u8) {
         let slot = &mut self.light[pos.x()][pos.z()][pos.y()];
-        *slot = (*slot & 0x0F) | ((value & 0x0F) << 4);
+        *slot = (*slot & Self::BLOCK_MASK) | (u16::from(value & 0x0F) << 12);
     }
 
+    /// Sets uncoloured block light, the same level on every channel.
     pub fn set_block_light(&mut self, pos: LocalBlockPos, value: u8) {
+        self.set_block_light_rgb(pos, BlockLightRgb::white(value));
+    }
+
+    pub fn set_block_light_rgb(&mut self, pos: LocalBlockPos, value: BlockLightRgb) {
         let slot = &mut self.light[pos.x()][pos.z()][pos.y()];
-        *slot = (*slot & 0xF0) | (value & 0x0F);
+        *slot = (*slot & !Self::BLOCK_MASK) | value.packed();
+    }
+
+    /// Packed cells in `[x][z][y]` order, two little-endian bytes per cell.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.light
+            .as_flattened()
+            .as_flattened()
+            .iter()
+            .flat_map(|cell| cell.to_le_bytes())
+            .collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return None;
+        }
+        let mut light = Self::default();
+        for (cell, raw) in light
+            .light
+            .as_flattened_mut()
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(bytes.chunks_exact(2))
+        {
+            *cell = u16::from_le_bytes([raw[0], raw[1]]);
+        }
+        Some(light)
     }
 }
 
@@ -50,6 +98,31 @@ pub struct ChunkHeightmap {
 }
 
 impl ChunkHeightmap {
+    /// The heightmap the light solver derives for one complete column ordered
+    /// from lowest to highest Y: the absolute Y of the highest cell that fully
+    /// blocks sky light, or zero where none does.
+    pub fn for_column(chunks: &[&Chunk]) -> Self {
+        let mut heightmap = Self::default();
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                let highest = chunks
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(chunk_y, chunk)| {
+                        (0..CHUNK_SIZE)
+                            .rev()
+                            .find(|&y| chunk.hot_meta_xyz(x, y, z).light_opacity >= SKY_LIGHT_MAX)
+                            .map(|y| chunk_y * CHUNK_SIZE + y)
+                    });
+                heightmap.heights[x][z] = highest.map_or(0, |height| {
+                    u8::try_from(height).expect("validated column height must fit the heightmap")
+                });
+            }
+        }
+        heightmap
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         bincode::serde::encode_to_vec(self, bincode::config::standard())
             .expect("ChunkHeightmap serialization is infallible")
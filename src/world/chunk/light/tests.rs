@@ -4,12 +4,16 @@ use bevy::{
 };
 
 use crate::{
+    block::BlockLightRgb,
     item::Item,
-    world::chunk::{CHUNK_SIZE, Chunk, ChunkCell, ChunkPos, LocalBlockPos},
+    world::{
+        chunk::{CHUNK_SIZE, Chunk, ChunkCell, ChunkPos, LocalBlockPos, WorldBlockPos},
+        generation::SplitMix64,
+    },
 };
 
 use super::storage::SKY_LIGHT_MAX;
-use super::{ChunkHeightmap, ChunkLight, ChunkLightRegion, RebuiltChunkLight};
+use super::{ChunkHeightmap, ChunkLight, ChunkLightRegion, LightEdit, RebuiltChunkLight};
 
 fn local(x: u32, y: u32, z: u32) -> LocalBlockPos {
     LocalBlockPos::new(x, y, z)
@@ -154,6 +158,33 @@ fn block_light_emission_respects_transparent_and_opaque_cells() {
     assert_eq!(rebuilt.light.block_light(local(9, 8, 8)), 0);
 }
 
+#[test]
+fn emitter_colour_channels_fade_independently() {
+    let mut chunk = Chunk::default();
+    chunk.set_cell_xyz(8, 8, 8, block_cell(Item::Glowstone));
+
+    let rebuilt = rebuild_single(
+        1,
+        ChunkPos::new(3, 0, -2),
+        &chunk,
+        &ChunkLight::default(),
+        &ChunkHeightmap::default(),
+    );
+
+    assert_eq!(
+        rebuilt.light.block_light_rgb(local(8, 8, 8)),
+        Item::Glowstone.light_color()
+    );
+    assert_eq!(
+        rebuilt.light.block_light_rgb(local(6, 8, 8)),
+        BlockLightRgb::new(13, 10, 6)
+    );
+    assert_eq!(
+        rebuilt.light.block_light_rgb(local(0, 8, 8)),
+        BlockLightRgb::new(7, 4, 0)
+    );
+}
+
 #[test]
 fn full_rebuild_removes_stale_light_without_harming_other_emitters() {
     let position = ChunkPos::new(-6, 0, -7);
@@ -260,6 +291,37 @@ fn vertical_sky_occlusion_spans_an_entire_loaded_column() {
     }
 }
 
+#[test]
+fn column_heightmap_matches_the_solver() {
+    let positions = [ChunkPos::new(3, 0, 5), ChunkPos::new(3, 1, 5)];
+    let lower = chunk_with_cells(|x, y, _| {
+        if y <= x {
+            block_cell(Item::Stone)
+        } else {
+            ChunkCell::EMPTY
+        }
+    });
+    let upper = chunk_with_cells(|x, y, z| match (x, z) {
+        (0, _) => block_cell(Item::Glass),
+        (1, _) if y == 4 => block_cell(Item::OakLeaves),
+        (2, 2) if y == 9 => block_cell(Item::Dirt),
+        _ => ChunkCell::EMPTY,
+    });
+    let light = ChunkLight::default();
+    let heightmap = ChunkHeightmap::default();
+
+    let mut region = ChunkLightRegion::new(2);
+    region.insert_target(positions[0], &lower, &light, &heightmap);
+    region.insert_target(positions[1], &upper, &light, &heightmap);
+    let rebuilt = rebuilt_by_position(region);
+
+    let computed = ChunkHeightmap::for_column(&[&lower, &upper]);
+    for position in positions {
+        assert_eq!(rebuilt[&position].heightmap, computed);
+    }
+    assert_eq!(computed.heights[2][2], 25);
+}
+
 #[test]
 fn sky_light_waits_for_a_missing_top_chunk() {
     let position = ChunkPos::new(-2, 0, -13);
@@ -357,6 +419,72 @@ fn boundary_light_is_read_only_and_only_targets_are_rebuilt() {
     assert_eq!(boundary_light, boundary_before);
 }
 
+#[test]
+fn coloured_boundary_light_mixes_per_channel_inside_the_target() {
+    let target_position = ChunkPos::new(-2, 0, 4);
+    let left_position = target_position.offset(IVec3::NEG_X);
+    let right_position = target_position.offset(IVec3::X);
+    let target = Chunk::default();
+    let target_light = ChunkLight::default();
+    let target_heightmap = ChunkHeightmap::default();
+    let mut left_light = ChunkLight::default();
+    left_light.set_block_light_rgb(local(15, 8, 8), BlockLightRgb::new(15, 0, 0));
+    let mut right_light = ChunkLight::default();
+    right_light.set_block_light_rgb(local(0, 8, 8), BlockLightRgb::new(0, 0, 15));
+
+    let mut region = ChunkLightRegion::new(1);
+    region.insert_target(target_position, &target, &target_light, &target_heightmap);
+    region.insert_boundary_light(left_position, &left_light);
+    region.insert_boundary_light(right_position, &right_light);
+    let rebuilt = region.rebuild();
+
+    let light = &rebuilt[0].light;
+    assert_eq!(
+        light.block_light_rgb(local(0, 8, 8)),
+        BlockLightRgb::new(14, 0, 0)
+    );
+    assert_eq!(
+        light.block_light_rgb(local(7, 8, 8)),
+        BlockLightRgb::new(7, 0, 6)
+    );
+    assert_eq!(
+        light.block_light_rgb(local(8, 8, 8)),
+        BlockLightRgb::new(6, 0, 7)
+    );
+    assert_eq!(
+        light.block_light_rgb(local(15, 8, 8)),
+        BlockLightRgb::new(0, 0, 14)
+    );
+}
+
+#[test]
+fn emitter_and_boundary_colours_mix_where_they_overlap() {
+    let target_position = ChunkPos::new(5, 1, -9);
+    let boundary_position = target_position.offset(IVec3::NEG_X);
+    let mut target = Chunk::default();
+    target.set_cell_xyz(2, 8, 8, block_cell(Item::Glowstone));
+    let target_light = ChunkLight::default();
+    let target_heightmap = ChunkHeightmap::default();
+    let mut boundary_light = ChunkLight::default();
+    boundary_light.set_block_light_rgb(local(15, 8, 8), BlockLightRgb::new(0, 0, 15));
+
+    let mut region = ChunkLightRegion::new(2);
+    region.insert_target(target_position, &target, &target_light, &target_heightmap);
+    region.insert_boundary_light(boundary_position, &boundary_light);
+    let rebuilt = region.rebuild();
+
+    let light = &rebuilt[0].light;
+    assert_eq!(
+        light.block_light_rgb(local(0, 8, 8)),
+        BlockLightRgb::new(13, 10, 14)
+    );
+    assert_eq!(
+        light.block_light_rgb(local(1, 8, 8)),
+        BlockLightRgb::new(14, 11, 13)
+    );
+    assert_eq!(light.block_light(local(0, 8, 8)), 14);
+}
+
 #[test]
 fn calculation_dependencies_influence_commits_without_being_committed() {
     let center_position = ChunkPos::new(-5, 0, -8);
@@ -568,5 +696,196 @@ fn packed_light_round_trips_through_a_local_position() {
 
     assert_eq!(light.sky_light(position), 13);
     assert_eq!(light.block_light(position), 9);
-    assert_eq!(light.packed_light(position), 0xD9);
+    assert_eq!(light.packed_light(position), 0xD999);
+
+    light.set_block_light_rgb(position, BlockLightRgb::new(4, 11, 2));
+    assert_eq!(light.sky_light(position), 13);
+    assert_eq!(light.block_light(position), 11);
+    assert_eq!(light.packed_light(position), 0xD4B2);
+
+    let bytes = light.to_bytes();
+    assert_eq!(bytes.len(), ChunkLight::ENCODED_LEN);
+    assert_eq!(ChunkLight::from_bytes(&bytes), Some(light));
+    assert_eq!(ChunkLight::from_bytes(&bytes[1..]), None);
+}
+
+type SolvedLight = HashMap<ChunkPos, (ChunkLight, ChunkHeightmap)>;
+
+fn solved_light(rebuilt: Vec<RebuiltChunkLight>) -> SolvedLight {
+    rebuilt
+        .into_iter()
+        .map(|rebuilt| (rebuilt.position, (rebuilt.light, rebuilt.heightmap)))
+        .collect()
+}
+
+fn full_rebuild(chunks: &HashMap<ChunkPos, Chunk>, height_chunks: usize) -> SolvedLight {
+    let light = ChunkLight::default();
+    let heightmap = ChunkHeightmap::default();
+    let mut region = ChunkLightRegion::new(height_chunks);
+    for (&position, chunk) in chunks {
+        region.insert_target(position, chunk, &light, &heightmap);
+    }
+    solved_light(region.rebuild())
+}
+
+fn incremental_rebuild(
+    chunks: &HashMap<ChunkPos, Chunk>,
+    settled: &SolvedLight,
+    height_chunks: usize,
+    edits: &[LightEdit],
+) -> SolvedLight {
+    let mut region = ChunkLightRegion::new(height_chunks);
+    for (&position, chunk) in chunks {
+        let (light, heightmap) = &settled[&position];
+        region.insert_target(position, chunk, light, heightmap);
+    }
+    solved_light(region.rebuild_edits(edits))
+}
+
+fn edit_cell(chunks: &mut HashMap<ChunkPos, Chunk>, world: IVec3, cell: ChunkCell) -> LightEdit {
+    let address = WorldBlockPos::from_ivec3(world).split();
+    let delta = chunks
+        .get_mut(&address.chunk())
+        .expect("edits must stay inside the test chunks")
+        .set_cell(address.local().as_uvec3(), cell);
+    LightEdit {
+        address,
+        old: delta.old,
+    }
+}
+
+/// Seeded, so every run replays the same edit sequence.
+struct EditRng(SplitMix64);
+
+impl EditRng {
+    fn below(&mut self, bound: i32) -> i32 {
+        (self.0.next_u64() % bound as u64) as i32
+    }
+}
+
+/// Two by two columns, two chunks tall, with hilly ground, a leaf canopy,
+/// buried glowstone and a tunnel crossing both chunk faces.
+fn differential_terrain() -> HashMap<ChunkPos, Chunk> {
+    let mut chunks = HashMap::new();
+    for x in -1..=0 {
+        for z in -1..=0 {
+            for y in 0..2 {
+                chunks.insert(ChunkPos::new(x, y, z), Chunk::default());
+            }
+        }
+    }
+    for x in -16..16_i32 {
+        for z in -16..16_i32 {
+            let ground = 12 + (x * 7 + z * 13).rem_euclid(9);
+            for y in 0..32 {
+                let tunnel = (x == 0 || z == 0) && (6..9).contains(&y);
+                let cell = if tunnel {
+                    ChunkCell::EMPTY
+                } else if y < ground && (x * 5 + y * 3 + z).rem_euclid(53) == 0 {
+                    block_cell(Item::Glowstone)
+                } else if y < ground {
+                    block_cell(Item::Stone)
+                } else if y == ground + 6 && (x + z).rem_euclid(4) != 0 {
+                    block_cell(Item::OakLeaves)
+                } else {
+                    ChunkCell::EMPTY
+                };
+                edit_cell(&mut chunks, IVec3::new(x, y, z), cell);
+            }
+        }
+    }
+    chunks
+}
+
+#[test]
+fn incremental_edits_match_a_full_rebuild() {
+    const EDIT_CELLS: [Option<Item>; 6] = [
+        None,
+        None,
+        Some(Item::Stone),
+        Some(Item::Glass),
+        Some(Item::OakLeaves),
+        Some(Item::Glowstone),
+    ];
+
+    for seed in [0x11, 0x5EED] {
+        let mut chunks = differential_terrain();
+        let mut settled = full_rebuild(&chunks, 2);
+        let mut rng = EditRng(SplitMix64::new(seed));
+
+        for step in 0..24 {
+            let edit_count = 1 + rng.below(3);
+            let edits = (0..edit_count)
+                .map(|_| {
+                    let world =
+                        IVec3::new(rng.below(24) - 12, rng.below(28) + 2, rng.below(24) - 12);
+                    let cell = EDIT_CELLS[rng.below(EDIT_CELLS.len() as i32) as usize]
+                        .map_or(ChunkCell::EMPTY, block_cell);
+                    edit_cell(&mut chunks, world, cell)
+                })
+                .collect::<Vec<_>>();
+
+            let incremental = incremental_rebuild(&chunks, &settled, 2, &edits);
+            let expected = full_rebuild(&chunks, 2);
+            for (position, (light, heightmap)) in &expected {
+                let (incremental_light, incremental_heightmap) = &incremental[position];
+                assert!(
+                    incremental_light == light,
+                    "seed {seed:#x} step {step}: light differs in {position:?} after {edits:?}"
+                );
+                assert_eq!(
+                    incremental_heightmap, heightmap,
+                    "seed {seed:#x} step {step}: heightmap differs in {position:?}"
+                );
+            }
+            settled = incremental;
+        }
+    }
+}
+
+#[test]
+fn incremental_removal_clears_light_across_a_chunk_face() {
+    let mut chunks = HashMap::from([
+        (
+            ChunkPos::new(0, 0, 0),
+            chunk_with_cells(|_, _, _| block_cell(Item::Stone)),
+        ),
+        (
+            ChunkPos::new(1, 0, 0),
+            chunk_with_cells(|_, _, _| block_cell(Item::Stone)),
+        ),
+    ]);
+    for x in 12..20 {
+        edit_cell(&mut chunks, IVec3::new(x, 8, 8), ChunkCell::EMPTY);
+    }
+    edit_cell(
+        &mut chunks,
+        IVec3::new(13, 8, 8),
+        block_cell(Item::Glowstone),
+    );
+    let settled = full_rebuild(&chunks, 1);
+    assert_eq!(
+        settled[&ChunkPos::new(1, 0, 0)]
+            .0
+            .block_light(local(3, 8, 8)),
+        9
+    );
+
+    let edit = edit_cell(&mut chunks, IVec3::new(13, 8, 8), ChunkCell::EMPTY);
+    let updated = incremental_rebuild(&chunks, &settled, 1, &[edit]);
+
+    for x in 0..CHUNK_SIZE as u32 {
+        assert_eq!(
+            updated[&ChunkPos::new(0, 0, 0)]
+                .0
+                .block_light(local(x, 8, 8)),
+            0
+        );
+        assert_eq!(
+            updated[&ChunkPos::new(1, 0, 0)]
+                .0
+                .block_light(local(x, 8, 8)),
+            0
+        );
+    }
 }
@@ -31,6 +31,11 @@ const AO_KEY_SHIFT: u32 = 8;
 const AO_KEY_MASK: u32 = 0xFF << AO_KEY_SHIFT;
 const WATER_CORNER_HEIGHTS_SHIFT: u32 = 16;
 const WATER_CORNER_HEIGHTS_MASK: u32 = 0xFFFF << WATER_CORNER_HEIGHTS_SHIFT;
+// Greedy-merged spans reuse the corner-height bits, which only water uses.
+const SPAN_A_SHIFT: u32 = 16;
+const SPAN_A_MASK: u32 = 0xF << SPAN_A_SHIFT;
+const SPAN_B_SHIFT: u32 = 20;
+const SPAN_B_MASK: u32 = 0xF << SPAN_B_SHIFT;
 
 const _: () = assert!(RENDER_ID_COUNT <= (1 << RENDER_ID_BITS));
 const _: () = assert!(Direction::COUNT <= (1 << 3));
@@ -88,6 +93,19 @@ impl PackedFace {
         self
     }
 
+    /// Stretch the face over `a` × `b` cells along the shader's face tangents,
+    /// starting at its own cell. Spans are stored minus one, so 1..=16.
+    #[inline]
+    pub(crate) fn with_span(mut self, a: u32, b: u32) -> Self {
+        debug_assert!(!self.has_water_geometry());
+        debug_assert!((1..=16).contains(&a));
+        debug_assert!((1..=16).contains(&b));
+        self.info = (self.info & !(SPAN_A_MASK | SPAN_B_MASK))
+            | (((a - 1) << SPAN_A_SHIFT) & SPAN_A_MASK)
+            | (((b - 1) << SPAN_B_SHIFT) & SPAN_B_MASK);
+        self
+    }
+
     /// Add the two lower-surface heights needed by a water side face.
     #[inline]
     pub(crate) fn with_water_below(mut self, lo: u32, hi: u32) -> Self {
@@ -166,6 +184,18 @@ impl PackedFace {
         )
     }
 
+    /// Cells covered along the face tangents; always `(1, 1)` for water.
+    #[inline]
+    pub const fn span(self) -> (u32, u32) {
+        if self.has_water_geometry() {
+            return (1, 1);
+        }
+        (
+            ((self.info & SPAN_A_MASK) >> SPAN_A_SHIFT) + 1,
+            ((self.info & SPAN_B_MASK) >> SPAN_B_SHIFT) + 1,
+        )
+    }
+
     #[inline]
     pub(crate) const fn words(self) -> [u32; 2] {
         [self.packed, self.info]
@@ -218,6 +248,19 @@ mod tests {
         assert_eq!(face.ao_key(), 0xFD);
     }
 
+    #[test]
+    fn span_fields_round_trip_and_default_to_one_cell() {
+        let face = PackedFace::new(3, 4, 5, 2, 0xFF, 0xFF);
+        assert_eq!(face.span(), (1, 1));
+
+        let spanned = face.with_span(16, 7);
+        assert_eq!(spanned.span(), (16, 7));
+        assert_eq!(spanned.with_span(1, 1), face);
+        assert_eq!((spanned.x(), spanned.y(), spanned.z()), (3, 4, 5));
+        assert_eq!(spanned.render_id(), 0xFF);
+        assert_eq!(spanned.ao_key(), 0xFF);
+    }
+
     #[test]
     fn packed_word_fields_do_not_overlap() {
         let water_mask = WATER_FLOWING_MASK
@@ -236,5 +279,7 @@ mod tests {
             RENDER_ID_MASK | AO_KEY_MASK | WATER_CORNER_HEIGHTS_MASK,
             u32::MAX
         );
+        assert_eq!(SPAN_A_MASK & SPAN_B_MASK, 0);
+        assert_eq!((SPAN_A_MASK | SPAN_B_MASK) & !WATER_CORNER_HEIGHTS_MASK, 0);
     }
 }
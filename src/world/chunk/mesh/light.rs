@@ -6,14 +6,14 @@ use super::super::{
 };
 use super::ChunkMeshLight;
 
-const PADDED_LIGHT_WORDS: usize = PADDED_CHUNK_VOLUME.div_ceil(4);
-const MISSING_PADDED_LIGHT_WORD: u32 = 0xF0F0F0F0;
+const PADDED_LIGHT_WORDS: usize = PADDED_CHUNK_VOLUME.div_ceil(2);
+const MISSING_PADDED_LIGHT_WORD: u32 = 0xF000_F000;
 
 impl ChunkMeshLight {
     /// Builds the one-cell light halo consumed by chunk mesh rendering.
     ///
-    /// Four packed light cells are stored in each output word. Missing chunks
-    /// use full sky light and zero block light, matching an open boundary.
+    /// Two packed `0xSRGB` light cells are stored in each output word. Missing
+    /// chunks use full sky light and zero block light, matching an open boundary.
     pub fn build_padded_data(
         center: ChunkPos,
         lights: &HashMap<ChunkPos, &ChunkLight>,
@@ -55,19 +55,20 @@ impl ChunkMeshLight {
     }
 }
 
-fn write_padded_light(data: &mut [u32], padded: usize, light: u8) {
-    let word = padded / 4;
-    let shift = (padded % 4) * 8;
-    let mask = 0xFFu32 << shift;
+fn write_padded_light(data: &mut [u32], padded: usize, light: u16) {
+    let word = padded / 2;
+    let shift = (padded % 2) * 16;
+    let mask = 0xFFFFu32 << shift;
     data[word] = (data[word] & !mask) | (u32::from(light) << shift);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::block::BlockLightRgb;
 
-    fn unpack(data: &[u32], index: usize) -> u8 {
-        ((data[index / 4] >> ((index % 4) * 8)) & 0xFF) as u8
+    fn unpack(data: &[u32], index: usize) -> u16 {
+        ((data[index / 2] >> ((index % 2) * 16)) & 0xFFFF) as u16
     }
 
     #[test]
@@ -91,7 +92,7 @@ mod tests {
         let corner_local = LocalBlockPos::MAX;
         let mut lower_left_forward = ChunkLight::default();
         lower_left_forward.set_sky_light(corner_local, 5);
-        lower_left_forward.set_block_light(corner_local, 6);
+        lower_left_forward.set_block_light_rgb(corner_local, BlockLightRgb::new(6, 0, 9));
 
         let lights = HashMap::from([
             (center_pos, &center),
@@ -108,10 +109,10 @@ mod tests {
         let data = ChunkMeshLight::build_padded_data(center_pos, &lights);
 
         assert_eq!(data.len(), PADDED_LIGHT_WORDS);
-        assert_eq!(unpack(&data, padded_chunk_index(1, 1, 1)), 0x12);
-        assert_eq!(unpack(&data, padded_chunk_index(17, 1, 1)), 0xAB);
-        assert_eq!(unpack(&data, padded_chunk_index(17, 17, 8)), 0x34);
-        assert_eq!(unpack(&data, padded_chunk_index(0, 0, 0)), 0x56);
-        assert_eq!(unpack(&data, padded_chunk_index(0, 17, 17)), 0xF0);
+        assert_eq!(unpack(&data, padded_chunk_index(1, 1, 1)), 0x1222);
+        assert_eq!(unpack(&data, padded_chunk_index(17, 1, 1)), 0xABBB);
+        assert_eq!(unpack(&data, padded_chunk_index(17, 17, 8)), 0x3444);
+        assert_eq!(unpack(&data, padded_chunk_index(0, 0, 0)), 0x5609);
+        assert_eq!(unpack(&data, padded_chunk_index(0, 17, 17)), 0xF000);
     }
 }
@@ -1,8 +1,10 @@
+use super::super::{ChunkMeshingMode, build_with_mode};
 use super::*;
 use crate::block::{
     BLOCK_FLAG_FULL_CUBE, BLOCK_FLAG_RENDERED, WATER_RENDER_ID, render_id_for_block,
 };
 use crate::item::Item;
+use crate::quad::Direction;
 use crate::world::chunk::CHUNK_SIZE;
 use crate::world::chunk::mesh::{
     ChunkMeshBlocks,
@@ -424,6 +426,151 @@ fn make_realistic_padded() -> ChunkMeshBlocks {
     make_padded(&kinds)
 }
 
+/// Splits spanned faces back into the one-cell faces they cover.
+fn expand_spans(layers: Vec<LayerMesh>) -> Vec<LayerMesh> {
+    layers
+        .into_iter()
+        .map(|layer| LayerMesh {
+            material_layer: layer.material_layer,
+            faces: layer
+                .faces
+                .into_iter()
+                .flat_map(|face| {
+                    let (span_a, span_b) = face.span();
+                    let direction = face.face_direction();
+                    let (tangent_a, tangent_b) = match direction / 2 {
+                        0 => ([0, 1, 0], [0, 0, 1]),
+                        1 => ([1, 0, 0], [0, 0, 1]),
+                        _ => ([1, 0, 0], [0, 1, 0]),
+                    };
+                    (0..span_b).flat_map(move |b| {
+                        (0..span_a).map(move |a| {
+                            let [x, y, z] = [0, 1, 2].map(|axis| {
+                                [face.x(), face.y(), face.z()][axis]
+                                    + a * tangent_a[axis]
+                                    + b * tangent_b[axis]
+                            });
+                            PackedFace::new(x, y, z, direction, face.render_id(), face.ao_key())
+                        })
+                    })
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn face_count(layers: &[LayerMesh]) -> usize {
+    layers.iter().map(|layer| layer.faces.len()).sum()
+}
+
+fn assert_greedy_covers_per_face_output(blocks: &ChunkMeshBlocks, label: &str) -> (usize, usize) {
+    let per_face = build_with_mode(blocks, ChunkMeshingMode::PerFace);
+    let greedy = build_with_mode(blocks, ChunkMeshingMode::Greedy);
+    let counts = (face_count(&per_face), face_count(&greedy));
+
+    assert!(counts.1 <= counts.0, "{label}: greedy added faces");
+    assert_eq!(
+        face_keys(expand_spans(greedy)),
+        face_keys(per_face),
+        "{label}"
+    );
+    counts
+}
+
+#[test]
+fn greedy_faces_cover_exactly_the_per_face_output() {
+    let stone = render_id_for_block(Item::Stone);
+    let mut checkerboard = [0u16; PADDED_CHUNK_VOLUME];
+    let mut ground_with_water = [0u16; PADDED_CHUNK_VOLUME];
+    let mut noise = [0u16; PADDED_CHUNK_VOLUME];
+    let mut seed = 0x2545_f491_u32;
+    let palette = [
+        0,
+        0,
+        stone,
+        render_id_for_block(Item::Dirt),
+        render_id_for_block(Item::Glass),
+        WATER_RENDER_ID,
+    ];
+    for x in 0..PADDED_CHUNK_SIZE {
+        for y in 0..PADDED_CHUNK_SIZE {
+            for z in 0..PADDED_CHUNK_SIZE {
+                let index = padded_chunk_index(x, y, z);
+                if (x + y + z) % 2 == 0 {
+                    checkerboard[index] = stone;
+                }
+                ground_with_water[index] = match y {
+                    0..=7 => stone,
+                    8 if (4..12).contains(&x) => WATER_RENDER_ID,
+                    _ => 0,
+                };
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                noise[index] = palette[seed as usize % palette.len()];
+            }
+        }
+    }
+
+    assert_greedy_covers_per_face_output(&make_realistic_padded(), "realistic");
+    assert_greedy_covers_per_face_output(&make_padded(&noise), "noise");
+    assert_greedy_covers_per_face_output(&make_padded(&ground_with_water), "ground + water");
+    let (per_face, greedy) =
+        assert_greedy_covers_per_face_output(&make_padded(&checkerboard), "checkerboard");
+    assert_eq!(greedy, per_face, "checkerboard has nothing to merge");
+}
+
+#[test]
+fn greedy_merges_open_stone_chunk_into_one_face_per_side() {
+    let mut kinds = [0u16; PADDED_CHUNK_VOLUME];
+    for x in 1..=CHUNK_SIZE {
+        for y in 1..=CHUNK_SIZE {
+            for z in 1..=CHUNK_SIZE {
+                kinds[padded_chunk_index(x, y, z)] = render_id_for_block(Item::Stone);
+            }
+        }
+    }
+    let padded = make_padded(&kinds);
+
+    let (per_face, greedy) = assert_greedy_covers_per_face_output(&padded, "open stone");
+
+    assert_eq!(per_face, DIRECTION_COUNT * CHUNK_SIZE * CHUNK_SIZE);
+    assert_eq!(greedy, DIRECTION_COUNT);
+    let layers = build_with_mode(&padded, ChunkMeshingMode::Greedy);
+    assert!(
+        layers[0]
+            .faces
+            .iter()
+            .all(|face| face.span() == (CHUNK_SIZE as u32, CHUNK_SIZE as u32))
+    );
+}
+
+#[test]
+fn greedy_keeps_faces_with_different_ao_apart() {
+    let stone = render_id_for_block(Item::Stone);
+    let mut kinds = [0u16; PADDED_CHUNK_VOLUME];
+    for x in 1..=CHUNK_SIZE {
+        for z in 1..=CHUNK_SIZE {
+            kinds[padded_chunk_index(x, 1, z)] = stone;
+        }
+    }
+    kinds[padded_chunk_index(8, 2, 8)] = stone;
+    let padded = make_padded(&kinds);
+
+    let (per_face, greedy) = assert_greedy_covers_per_face_output(&padded, "floor + pillar");
+
+    assert!(greedy < per_face);
+    let up_faces = build_with_mode(&padded, ChunkMeshingMode::Greedy)[0]
+        .faces
+        .iter()
+        .filter(|face| face.face_direction() == Direction::Up as u32 && face.y() == 0)
+        .count();
+    assert!(
+        up_faces > 1,
+        "darkened cells around the pillar stay separate"
+    );
+}
+
 #[test]
 fn perf_breakdown_binary() {
     use std::time::Instant;
@@ -0,0 +1,111 @@
+//! Greedy merging of coplanar full-cube faces.
+//!
+//! Runs after binary meshing on the opaque layer. Faces in the same slice
+//! (direction and depth along the normal) with the same render id and AO key
+//! are grown into rectangles, first along the shader's tangent A and then
+//! along tangent B. Light does not have to match: the shader re-derives smooth
+//! light per cell for spanned faces, so merging is independent of light
+//! updates that skip remeshing.
+//!
+//! Water faces carry per-corner heights and are never merged.
+
+use crate::{quad::Direction, world::chunk::CHUNK_SIZE};
+
+use super::super::face::PackedFace;
+
+/// Cell value for "no face"; merge keys are stored offset by one.
+const EMPTY_CELL: u32 = 0;
+
+/// Replaces mergeable faces with spanned faces covering the same cells.
+pub(super) fn merge_coplanar_faces(faces: &mut Vec<PackedFace>) {
+    let mut mergeable = Vec::with_capacity(faces.len());
+    faces.retain(|&face| {
+        let keep = face.has_water_geometry();
+        if !keep {
+            mergeable.push(face);
+        }
+        keep
+    });
+    if mergeable.is_empty() {
+        return;
+    }
+    mergeable.sort_unstable_by_key(|&face| slice_key(face));
+
+    let mut grid = [[EMPTY_CELL; CHUNK_SIZE]; CHUNK_SIZE];
+    for slice in mergeable.chunk_by(|a, b| slice_key(*a) == slice_key(*b)) {
+        let direction = slice[0].face_direction();
+        let depth = slice_coordinates(slice[0]).2;
+        for &face in slice {
+            let (a, b, _) = slice_coordinates(face);
+            grid[b as usize][a as usize] = merge_key(face) + 1;
+        }
+        drain_rectangles(&mut grid, |a, b, span_a, span_b, key| {
+            let (x, y, z) = cell_from_slice(direction, a, b, depth);
+            faces.push(
+                PackedFace::new(x, y, z, direction, key & 0xFF, key >> 8).with_span(span_a, span_b),
+            );
+        });
+    }
+}
+
+/// Emits every occupied rectangle of `grid`, clearing it as it goes.
+fn drain_rectangles(
+    grid: &mut [[u32; CHUNK_SIZE]; CHUNK_SIZE],
+    mut emit: impl FnMut(u32, u32, u32, u32, u32),
+) {
+    for b in 0..CHUNK_SIZE {
+        let mut a = 0;
+        while a < CHUNK_SIZE {
+            let cell = grid[b][a];
+            if cell == EMPTY_CELL {
+                a += 1;
+                continue;
+            }
+            let width = grid[b][a..]
+                .iter()
+                .take_while(|&&other| other == cell)
+                .count();
+            let height = 1 + grid[b + 1..]
+                .iter()
+                .take_while(|row| row[a..a + width].iter().all(|&other| other == cell))
+                .count();
+            for row in &mut grid[b..b + height] {
+                row[a..a + width].fill(EMPTY_CELL);
+            }
+            emit(a as u32, b as u32, width as u32, height as u32, cell - 1);
+            a += width;
+        }
+    }
+}
+
+/// Render id and AO key, the face properties a merged quad must share.
+fn merge_key(face: PackedFace) -> u32 {
+    face.render_id() | (face.ao_key() << 8)
+}
+
+fn slice_key(face: PackedFace) -> (u32, u32) {
+    (face.face_direction(), slice_coordinates(face).2)
+}
+
+/// `(tangent_a, tangent_b, depth)` of a face, matching the shader's
+/// `FACE_TANGENT_A`/`FACE_TANGENT_B` tables.
+fn slice_coordinates(face: PackedFace) -> (u32, u32, u32) {
+    let (x, y, z) = (face.x(), face.y(), face.z());
+    match direction_from_index(face.face_direction()) {
+        Direction::Left | Direction::Right => (y, z, x),
+        Direction::Down | Direction::Up => (x, z, y),
+        Direction::Forward | Direction::Backward => (x, y, z),
+    }
+}
+
+fn cell_from_slice(direction: u32, a: u32, b: u32, depth: u32) -> (u32, u32, u32) {
+    match direction_from_index(direction) {
+        Direction::Left | Direction::Right => (depth, a, b),
+        Direction::Down | Direction::Up => (a, depth, b),
+        Direction::Forward | Direction::Backward => (a, b, depth),
+    }
+}
+
+fn direction_from_index(index: u32) -> Direction {
+    Direction::ALL[index as usize]
+}
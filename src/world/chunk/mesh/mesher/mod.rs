@@ -1,19 +1,25 @@
 //! Chunk meshing algorithms.
 //!
 //! The production entry point uses binary face masks for full cubes and a
-//! scalar pass for shaped/translucent blocks. Algorithm-specific entry points
-//! remain public for benchmarks and equivalence tests, not for game systems.
+//! scalar pass for shaped/translucent blocks, optionally followed by a greedy
+//! merge of the opaque layer. Algorithm-specific entry points remain public for
+//! benchmarks and equivalence tests, not for game systems.
 
 mod ao;
 mod binary;
+mod greedy;
 mod scalar;
 mod visibility;
 mod water;
 
+use bevy::prelude::*;
+
 use crate::block::BlockMaterialLayer;
 
 use super::{blocks::ChunkMeshBlocks, face::PackedFace};
 
+pub(super) use visibility::block_mesh_flags;
+
 type FacesByLayer = [Vec<PackedFace>; BlockMaterialLayer::COUNT];
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -22,6 +28,30 @@ pub struct LayerMesh {
     pub faces: Vec<PackedFace>,
 }
 
+/// How chunk rebuilds turn visible cells into faces.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource, Default)]
+pub enum ChunkMeshingMode {
+    /// One face per visible cell face.
+    #[default]
+    PerFace,
+    /// Coalesce coplanar opaque faces with matching texture and AO.
+    Greedy,
+}
+
+/// Build all visible terrain faces in the given meshing mode.
+pub fn build_with_mode(blocks: &ChunkMeshBlocks, mode: ChunkMeshingMode) -> Vec<LayerMesh> {
+    let mut layers = build(blocks);
+    if mode == ChunkMeshingMode::Greedy
+        && let Some(opaque) = layers
+            .iter_mut()
+            .find(|layer| layer.material_layer == BlockMaterialLayer::Opaque)
+    {
+        greedy::merge_coplanar_faces(&mut opaque.faces);
+    }
+    layers
+}
+
 /// Build all visible terrain faces using the production hybrid mesher.
 pub fn build(blocks: &ChunkMeshBlocks) -> Vec<LayerMesh> {
     if blocks.can_skip_mesh() {
@@ -9,18 +9,24 @@ mod components;
 mod face;
 mod light;
 pub mod mesher;
+mod occlusion;
 mod render;
 mod systems;
+mod translucency;
 
 use bevy::prelude::*;
 
 pub use blocks::ChunkMeshBlocks;
 pub use components::{ChunkMeshFaces, ChunkMeshLayer, ChunkMeshLight};
 pub use face::PackedFace;
-pub use mesher::LayerMesh;
+pub use mesher::{ChunkMeshingMode, LayerMesh};
+pub use occlusion::ChunkFaceConnectivity;
 
 pub(crate) use blocks::DIRECTION_COUNT;
 pub(crate) use components::{PreparedChunkMeshLight, SharedLightDataKey};
+pub(crate) use occlusion::ChunkSectionVisibility;
+pub(crate) use render::{AirFogRange, FogSettings, TerrainVisualSettings};
+pub(crate) use translucency::TranslucentFaceOrder;
 
 /// Shader source exposed for CPU/GPU contract validation.
 pub const TERRAIN_SHADER_SOURCE: &str = render::VERTEX_PULLING_SHADER_SOURCE;
@@ -30,6 +36,19 @@ pub struct ChunkMeshPlugin;
 impl Plugin for ChunkMeshPlugin {
     fn build(&self, app: &mut App) {
         systems::install(app);
+        app.init_resource::<ChunkSectionVisibility>().add_systems(
+            PostUpdate,
+            occlusion::update_chunk_section_visibility
+                .after(bevy::camera::visibility::VisibilitySystems::UpdateFrusta),
+        );
+        app.init_resource::<translucency::TranslucentSortTasks>()
+            .add_systems(
+                Update,
+                (
+                    translucency::finish_translucent_sorts.before(systems::rebuild_chunk_meshes),
+                    translucency::start_translucent_sorts.after(systems::upload_chunk_lights),
+                ),
+            );
         app.add_plugins(render::TerrainRenderPlugin);
     }
 }
@@ -0,0 +1,437 @@
+//! Section-graph occlusion culling.
+//!
+//! Meshing records which faces of each chunk can see each other through
+//! connected non-occluding cells. Every frame the main world walks that graph
+//! outward from the camera chunk, and the render world only queues the chunk
+//! layers the walk reached, so caves sealed behind terrain are never drawn.
+
+use std::{collections::VecDeque, ops::RangeInclusive, sync::Arc};
+
+use bevy::{
+    camera::primitives::{Aabb, Frustum},
+    math::Affine3A,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    render::extract_resource::ExtractResource,
+};
+
+use crate::block::{BLOCK_FLAG_CUTOUT, BLOCK_FLAG_FULL_CUBE, BLOCK_FLAG_TRANSLUCENT};
+use crate::quad::Direction;
+use crate::world::dimension::{Active, Dimension, ViewDistance};
+
+use super::super::{CHUNK_SIZE, CHUNK_VOLUME, ChunkPos};
+use super::{
+    blocks::{ChunkMeshBlocks, DIRECTION_COUNT, padded_chunk_index},
+    mesher::block_mesh_flags,
+};
+
+/// Extra margin around each chunk's bounds for the traversal frustum test.
+const SECTION_FRUSTUM_PADDING: f32 = 1.0;
+
+/// Which faces of a chunk are connected through non-occluding cells, as a
+/// symmetric 6×6 bitset indexed by [`Direction`].
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkFaceConnectivity(u64);
+
+impl ChunkFaceConnectivity {
+    pub const NONE: Self = Self(0);
+    pub const ALL: Self = Self((1 << (DIRECTION_COUNT * DIRECTION_COUNT)) - 1);
+
+    /// Flood-fills the center chunk through every cell that is not an opaque
+    /// full cube. Faces touched by the same connected region can see each
+    /// other.
+    pub fn from_mesh_blocks(blocks: &ChunkMeshBlocks) -> Self {
+        if blocks.center_rendered_blocks == 0 {
+            return Self::ALL;
+        }
+
+        let mut visited = vec![false; CHUNK_VOLUME];
+        for (index, visited) in visited.iter_mut().enumerate() {
+            let [x, y, z] = cell_coordinates(index);
+            let cell = blocks.blocks[padded_chunk_index(x + 1, y + 1, z + 1)];
+            *visited = occludes(block_mesh_flags(cell));
+        }
+
+        let mut connectivity = Self::NONE;
+        let mut stack = Vec::new();
+        for start in 0..CHUNK_VOLUME {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            stack.push(start);
+            let mut faces = 0u8;
+            while let Some(index) = stack.pop() {
+                let [x, y, z] = cell_coordinates(index);
+                faces |= boundary_faces(x, y, z);
+                for direction in Direction::ALL {
+                    let Some(neighbor) = neighbor_cell(x, y, z, direction) else {
+                        continue;
+                    };
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            connectivity.connect(faces);
+            if connectivity == Self::ALL {
+                break;
+            }
+        }
+        connectivity
+    }
+
+    pub const fn connects(self, from: Direction, to: Direction) -> bool {
+        self.0 & (1 << (from.index() * DIRECTION_COUNT + to.index())) != 0
+    }
+
+    /// Faces reachable from any face in the `entered` direction mask.
+    pub const fn exits(self, entered: u8) -> u8 {
+        let mut exits = 0;
+        let mut from = 0;
+        while from < DIRECTION_COUNT {
+            if entered & (1 << from) != 0 {
+                exits |= (self.0 >> (from * DIRECTION_COUNT)) as u8 & ((1 << DIRECTION_COUNT) - 1);
+            }
+            from += 1;
+        }
+        exits
+    }
+
+    fn connect(&mut self, faces: u8) {
+        for from in 0..DIRECTION_COUNT {
+            if faces & (1 << from) != 0 {
+                self.0 |= u64::from(faces) << (from * DIRECTION_COUNT);
+            }
+        }
+    }
+}
+
+const fn occludes(flags: u8) -> bool {
+    flags & BLOCK_FLAG_FULL_CUBE != 0 && flags & (BLOCK_FLAG_CUTOUT | BLOCK_FLAG_TRANSLUCENT) == 0
+}
+
+const fn cell_coordinates(index: usize) -> [usize; 3] {
+    [
+        index % CHUNK_SIZE,
+        index / (CHUNK_SIZE * CHUNK_SIZE),
+        index / CHUNK_SIZE % CHUNK_SIZE,
+    ]
+}
+
+const fn cell_index(x: usize, y: usize, z: usize) -> usize {
+    x + CHUNK_SIZE * (z + CHUNK_SIZE * y)
+}
+
+fn neighbor_cell(x: usize, y: usize, z: usize, direction: Direction) -> Option<usize> {
+    let neighbor = IVec3::new(x as i32, y as i32, z as i32) + direction.offset();
+    let size = CHUNK_SIZE as i32;
+    (neighbor.cmpge(IVec3::ZERO).all() && neighbor.cmplt(IVec3::splat(size)).all()).then(|| {
+        cell_index(
+            neighbor.x as usize,
+            neighbor.y as usize,
+            neighbor.z as usize,
+        )
+    })
+}
+
+fn boundary_faces(x: usize, y: usize, z: usize) -> u8 {
+    let last = CHUNK_SIZE - 1;
+    [
+        (Direction::Left, x == 0),
+        (Direction::Right, x == last),
+        (Direction::Down, y == 0),
+        (Direction::Up, y == last),
+        (Direction::Forward, z == 0),
+        (Direction::Backward, z == last),
+    ]
+    .into_iter()
+    .filter(|&(_, touches)| touches)
+    .fold(0, |faces, (direction, _)| faces | 1 << direction.index())
+}
+
+/// Walks the chunk graph breadth-first from `camera`, returning every chunk
+/// visibility can reach, nearest first.
+///
+/// Visibility leaves a chunk only through faces connected to a face it
+/// entered by, and only in directions that move away from the camera on that
+/// axis, which keeps the walk from wrapping around occluders. `radius` bounds
+/// the walk horizontally; vertically it covers `heights` plus the camera's own
+/// row so a camera above or below the world still reaches it. Chunks rejected
+/// by `in_view` are neither returned nor walked through.
+pub(crate) fn walk_section_graph(
+    camera: IVec3,
+    radius: i32,
+    heights: RangeInclusive<i32>,
+    connectivity: impl Fn(IVec3) -> ChunkFaceConnectivity,
+    mut in_view: impl FnMut(IVec3) -> bool,
+) -> Vec<IVec3> {
+    let min_y = (*heights.start()).min(camera.y);
+    let max_y = (*heights.end()).max(camera.y);
+    let in_bounds = |position: IVec3| {
+        (position.x - camera.x).abs() <= radius
+            && (position.z - camera.z).abs() <= radius
+            && (min_y..=max_y).contains(&position.y)
+    };
+
+    let mut entered = HashMap::<IVec3, u8>::new();
+    let mut queue = VecDeque::new();
+    let mut visible = Vec::new();
+    entered.insert(camera, u8::MAX);
+    queue.push_back(camera);
+    while let Some(position) = queue.pop_front() {
+        visible.push(position);
+        let exits = if position == camera {
+            u8::MAX
+        } else {
+            connectivity(position).exits(entered[&position])
+        };
+        let from_camera = position - camera;
+        for direction in Direction::ALL {
+            if exits & (1 << direction.index()) == 0 || from_camera.dot(direction.offset()) < 0 {
+                continue;
+            }
+            let neighbor = position + direction.offset();
+            let entry_face = 1 << direction.opposite().index();
+            if let Some(faces) = entered.get_mut(&neighbor) {
+                // Every route into a chunk is one step longer than the route
+                // into its predecessor, so it is still queued here.
+                *faces |= entry_face;
+                continue;
+            }
+            if !in_bounds(neighbor) || !in_view(neighbor) {
+                continue;
+            }
+            entered.insert(neighbor, entry_face);
+            queue.push_back(neighbor);
+        }
+    }
+    visible
+}
+
+/// The chunks reached by this frame's section-graph walk. `None` disables
+/// occlusion culling, for example before a camera or dimension exists.
+#[derive(Resource, Default, Clone)]
+pub(crate) struct ChunkSectionVisibility {
+    visible: Option<Arc<HashSet<IVec3>>>,
+}
+
+impl ChunkSectionVisibility {
+    pub(crate) fn is_visible(&self, chunk: IVec3) -> bool {
+        self.visible
+            .as_ref()
+            .is_none_or(|visible| visible.contains(&chunk))
+    }
+}
+
+impl ExtractResource for ChunkSectionVisibility {
+    type Source = ChunkSectionVisibility;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        source.clone()
+    }
+}
+
+/// Runs in the main world after frusta update, so the walk overlaps with the
+/// previous frame's rendering instead of extending the render schedule.
+pub(super) fn update_chunk_section_visibility(
+    mut visibility: ResMut<ChunkSectionVisibility>,
+    view_distance: Res<ViewDistance>,
+    cameras: Query<(&GlobalTransform, &Frustum), With<Camera3d>>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+    connectivity_q: Query<&ChunkFaceConnectivity>,
+) {
+    let (Some(dimension), Some((camera, frustum))) = (dimension, cameras.iter().next()) else {
+        visibility.visible = None;
+        return;
+    };
+
+    let camera_chunk = ChunkPos::containing_translation(camera.translation()).as_ivec3();
+    let section_bounds = Aabb {
+        center: Vec3A::splat(CHUNK_SIZE as f32 * 0.5),
+        half_extents: Vec3A::splat(CHUNK_SIZE as f32 * 0.5 + SECTION_FRUSTUM_PADDING),
+    };
+    let visible = walk_section_graph(
+        camera_chunk,
+        view_distance.chunks(),
+        0..=dimension.height().chunks_i32() - 1,
+        |position| {
+            dimension
+                .loaded_chunk_entity(ChunkPos::from_ivec3(position))
+                .and_then(|entity| connectivity_q.get(entity).ok())
+                .copied()
+                .unwrap_or(ChunkFaceConnectivity::ALL)
+        },
+        |position| {
+            let origin = (position * CHUNK_SIZE as i32).as_vec3();
+            frustum.intersects_obb(
+                &section_bounds,
+                &Affine3A::from_translation(origin),
+                true,
+                false,
+            )
+        },
+    );
+    visibility.visible = Some(Arc::new(visible.into_iter().collect()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Item;
+    use crate::world::chunk::Chunk;
+
+    fn walk_all(
+        camera: IVec3,
+        radius: i32,
+        connectivity: impl Fn(IVec3) -> ChunkFaceConnectivity,
+    ) -> HashSet<IVec3> {
+        walk_section_graph(camera, radius, 0..=3, connectivity, |_| true)
+            .into_iter()
+            .collect()
+    }
+
+    fn connectivity_of(fill: impl Fn(usize, usize, usize) -> bool) -> ChunkFaceConnectivity {
+        let mut chunk = Chunk::default();
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    if fill(x, y, z) {
+                        chunk.set_cell_xyz(x, y, z, Item::Stone.into());
+                    }
+                }
+            }
+        }
+        ChunkFaceConnectivity::from_mesh_blocks(&ChunkMeshBlocks::from_chunk(&chunk))
+    }
+
+    #[test]
+    fn empty_and_solid_chunks_connect_all_or_no_faces() {
+        assert_eq!(connectivity_of(|_, _, _| false), ChunkFaceConnectivity::ALL);
+        assert_eq!(connectivity_of(|_, _, _| true), ChunkFaceConnectivity::NONE);
+    }
+
+    #[test]
+    fn a_solid_floor_separates_the_faces_above_and_below_it() {
+        let connectivity = connectivity_of(|_, y, _| y == 8);
+
+        assert!(!connectivity.connects(Direction::Down, Direction::Up));
+        assert!(connectivity.connects(Direction::Down, Direction::Left));
+        assert!(connectivity.connects(Direction::Up, Direction::Backward));
+        assert!(connectivity.connects(Direction::Left, Direction::Right));
+        assert_eq!(
+            connectivity.exits(1 << Direction::Down.index()),
+            !(1 << Direction::Up.index()) & 0b11_1111
+        );
+    }
+
+    #[test]
+    fn a_tunnel_only_connects_its_two_ends() {
+        let connectivity = connectivity_of(|_, y, z| !(y == 5 && z == 7));
+
+        assert!(connectivity.connects(Direction::Left, Direction::Right));
+        assert!(connectivity.connects(Direction::Right, Direction::Left));
+        assert_eq!(
+            connectivity.exits(1 << Direction::Left.index()),
+            1 << Direction::Left.index() | 1 << Direction::Right.index()
+        );
+        assert_eq!(connectivity.exits(1 << Direction::Up.index()), 0);
+    }
+
+    #[test]
+    fn glass_and_leaves_do_not_occlude() {
+        for block in [Item::Glass, Item::OakLeaves] {
+            let mut chunk = Chunk::default();
+            for x in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    chunk.set_cell_xyz(x, 8, z, block.into());
+                }
+            }
+            let connectivity =
+                ChunkFaceConnectivity::from_mesh_blocks(&ChunkMeshBlocks::from_chunk(&chunk));
+
+            assert_eq!(connectivity, ChunkFaceConnectivity::ALL, "{block:?}");
+        }
+    }
+
+    #[test]
+    fn open_space_reaches_every_chunk_in_bounds() {
+        let visible = walk_all(IVec3::new(0, 1, 0), 2, |_| ChunkFaceConnectivity::ALL);
+
+        assert_eq!(visible.len(), 5 * 5 * 4);
+    }
+
+    #[test]
+    fn sealed_walls_hide_everything_behind_them() {
+        let visible = walk_all(IVec3::ZERO, 4, |position| {
+            if position.x == 2 {
+                ChunkFaceConnectivity::NONE
+            } else {
+                ChunkFaceConnectivity::ALL
+            }
+        });
+
+        assert!(visible.contains(&IVec3::new(2, 0, 3)));
+        assert!(visible.iter().all(|position| position.x <= 2));
+        assert!(visible.contains(&IVec3::new(-4, 3, -4)));
+    }
+
+    #[test]
+    fn caves_sealed_under_terrain_are_culled_from_the_surface() {
+        let cave = IVec3::new(1, 0, 1);
+        let visible = walk_all(IVec3::new(0, 3, 0), 3, |position| match position.y {
+            0 if position == cave => ChunkFaceConnectivity::ALL,
+            0 | 1 => ChunkFaceConnectivity::NONE,
+            _ => ChunkFaceConnectivity::ALL,
+        });
+
+        assert!(visible.contains(&IVec3::new(1, 1, 1)));
+        assert!(!visible.contains(&cave));
+        assert!(visible.iter().all(|position| position.y >= 1));
+    }
+
+    #[test]
+    fn visibility_turns_corners_through_connected_faces() {
+        // A corridor from the camera along +X to x = 2, then along +Z.
+        let corridor = |position: IVec3| {
+            (position.z == 0 && (0..=2).contains(&position.x))
+                || (position.x == 2 && position.z >= 0)
+        };
+        let visible = walk_section_graph(
+            IVec3::ZERO,
+            4,
+            0..=0,
+            |position| {
+                if !corridor(position) {
+                    ChunkFaceConnectivity::NONE
+                } else if position == IVec3::new(2, 0, 0) {
+                    let mut corner = ChunkFaceConnectivity::NONE;
+                    corner.connect(1 << Direction::Left.index() | 1 << Direction::Backward.index());
+                    corner
+                } else {
+                    ChunkFaceConnectivity::ALL
+                }
+            },
+            |_| true,
+        );
+
+        assert!(visible.contains(&IVec3::new(2, 0, 4)));
+        assert!(!visible.contains(&IVec3::new(4, 0, 0)));
+        assert_eq!(visible[0], IVec3::ZERO);
+    }
+
+    #[test]
+    fn the_view_filter_stops_the_walk_and_the_camera_may_be_above_the_world() {
+        let visible = walk_section_graph(
+            IVec3::new(0, 6, 0),
+            2,
+            0..=3,
+            |_| ChunkFaceConnectivity::ALL,
+            |position| position.x >= 0,
+        );
+
+        assert!(visible.iter().all(|position| position.x >= 0));
+        assert!(visible.contains(&IVec3::new(2, 0, -2)));
+    }
+}
@@ -17,9 +17,11 @@ use bevy::{
     },
 };
 
-use super::ChunkMeshLayer;
+use super::{ChunkMeshLayer, ChunkSectionVisibility};
 use material::TerrainMaterialState;
-use visuals::{TerrainAnimationClock, TerrainVisualSettings};
+use visuals::TerrainAnimationClock;
+
+pub(crate) use visuals::{AirFogRange, FogSettings, TerrainVisualSettings};
 
 pub(super) const VERTEX_PULLING_SHADER_SOURCE: &str =
     include_str!("../../../../../assets/shaders/vertex_pulling.wgsl");
@@ -43,6 +45,7 @@ impl Plugin for TerrainRenderPlugin {
             ExtractResourcePlugin::<TerrainMaterialState>::default(),
             ExtractResourcePlugin::<TerrainVisualSettings>::default(),
             ExtractResourcePlugin::<TerrainAnimationClock>::default(),
+            ExtractResourcePlugin::<ChunkSectionVisibility>::default(),
         ));
 
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
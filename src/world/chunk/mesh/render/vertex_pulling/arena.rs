@@ -0,0 +1,570 @@
+//! Shared GPU storage for every prepared terrain mesh layer.
+//!
+//! Faces, padded light volumes and per-draw records each live in one growable
+//! storage buffer. Chunk layers own sub-ranges of those buffers, so a whole
+//! material layer can be drawn with a single bind group and one indirect
+//! multi-draw.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::*,
+        renderer::{RenderDevice, RenderQueue},
+    },
+};
+
+use crate::world::chunk::mesh::{ChunkMeshLight, PackedFace, SharedLightDataKey};
+
+const INITIAL_FACE_CAPACITY: u32 = 1 << 20;
+const INITIAL_LIGHT_WORD_CAPACITY: u32 = 1 << 20;
+const INITIAL_DRAW_CAPACITY: u32 = 1 << 12;
+
+/// First-fit free-list allocator over a linear run of elements.
+///
+/// Free ranges are kept sorted and coalesced, so releasing neighbours makes
+/// room for larger allocations again.
+#[derive(Debug, Default)]
+pub(super) struct ArenaAllocator {
+    capacity: u32,
+    free: Vec<Range<u32>>,
+}
+
+impl ArenaAllocator {
+    pub(super) fn new(capacity: u32) -> Self {
+        let mut allocator = Self::default();
+        allocator.grow(capacity);
+        allocator
+    }
+
+    pub(super) const fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Reserves `len` contiguous elements, or returns `None` when no free range
+    /// is large enough.
+    pub(super) fn allocate(&mut self, len: u32) -> Option<Range<u32>> {
+        debug_assert!(len > 0, "arena allocations must not be empty");
+        let index = self
+            .free
+            .iter()
+            .position(|range| range.end - range.start >= len)?;
+        let range = &mut self.free[index];
+        let allocated = range.start..range.start + len;
+        range.start += len;
+        if range.start == range.end {
+            self.free.remove(index);
+        }
+        Some(allocated)
+    }
+
+    /// Returns a previously allocated range to the free list.
+    pub(super) fn free(&mut self, range: Range<u32>) {
+        if range.is_empty() {
+            return;
+        }
+        debug_assert!(range.end <= self.capacity, "freed range is out of bounds");
+        let index = self.free.partition_point(|free| free.start < range.start);
+        debug_assert!(
+            index == 0 || self.free[index - 1].end <= range.start,
+            "freed range overlaps free space"
+        );
+        debug_assert!(
+            self.free
+                .get(index)
+                .is_none_or(|next| range.end <= next.start),
+            "freed range overlaps free space"
+        );
+
+        let joins_previous = index > 0 && self.free[index - 1].end == range.start;
+        let joins_next = self
+            .free
+            .get(index)
+            .is_some_and(|next| next.start == range.end);
+        match (joins_previous, joins_next) {
+            (true, true) => {
+                self.free[index - 1].end = self.free[index].end;
+                self.free.remove(index);
+            }
+            (true, false) => self.free[index - 1].end = range.end,
+            (false, true) => self.free[index].start = range.start,
+            (false, false) => self.free.insert(index, range),
+        }
+    }
+
+    /// Extends the arena to `capacity` elements. Shrinking is not supported.
+    pub(super) fn grow(&mut self, capacity: u32) {
+        if capacity <= self.capacity {
+            return;
+        }
+        let added = self.capacity..capacity;
+        self.capacity = capacity;
+        self.free(added);
+    }
+}
+
+/// Per-draw record read by the vertex shader through `instance_index`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub(super) struct ChunkDrawData {
+    origin: [f32; 3],
+    light_offset: u32,
+}
+
+/// Where a prepared chunk layer lives in the arena.
+#[derive(Clone, Debug)]
+pub(super) struct ChunkAllocation {
+    pub(super) faces: Range<u32>,
+    pub(super) draw_slot: u32,
+    light: SharedLightDataKey,
+    origin: Vec3,
+}
+
+struct SharedLight {
+    words: Range<u32>,
+    users: u32,
+}
+
+/// A storage buffer whose elements are handed out by an [`ArenaAllocator`].
+struct ArenaBuffer {
+    label: &'static str,
+    element_size: u64,
+    buffer: Buffer,
+    allocator: ArenaAllocator,
+    /// Ranges released this frame. Draw lists are queued before preparation,
+    /// so they are only reused once the frame that may still read them ends.
+    retired: Vec<Range<u32>>,
+}
+
+impl ArenaBuffer {
+    fn new(
+        render_device: &RenderDevice,
+        label: &'static str,
+        element_size: u64,
+        capacity: u32,
+    ) -> Self {
+        Self {
+            label,
+            element_size,
+            buffer: create_storage_buffer(render_device, label, element_size * u64::from(capacity)),
+            allocator: ArenaAllocator::new(capacity),
+            retired: Vec::new(),
+        }
+    }
+
+    fn retire(&mut self, range: Range<u32>) {
+        self.retired.push(range);
+    }
+
+    fn reclaim(&mut self) {
+        for range in self.retired.drain(..) {
+            self.allocator.free(range);
+        }
+    }
+
+    /// Allocates `len` elements, growing the buffer when the free list is
+    /// exhausted. Returns whether the underlying buffer was replaced.
+    fn allocate(
+        &mut self,
+        render_device: &RenderDevice,
+        render_queue: &RenderQueue,
+        len: u32,
+    ) -> (Range<u32>, bool) {
+        if let Some(range) = self.allocator.allocate(len) {
+            return (range, false);
+        }
+
+        let capacity = self.allocator.capacity();
+        let grown = capacity.saturating_mul(2).max(capacity.saturating_add(len));
+        let buffer = create_storage_buffer(
+            render_device,
+            self.label,
+            self.element_size * u64::from(grown),
+        );
+        // Writes queued into the old buffer earlier this frame are flushed by
+        // this submission, ahead of the copy.
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("vp_arena_grow"),
+        });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, self.buffer.size());
+        render_queue.submit([encoder.finish()]);
+
+        self.buffer = buffer;
+        self.allocator.grow(grown);
+        let range = self
+            .allocator
+            .allocate(len)
+            .expect("grown arena has room for the allocation");
+        (range, true)
+    }
+
+    fn write<T: bytemuck::Pod>(&self, render_queue: &RenderQueue, start: u32, data: &[T]) {
+        render_queue.0.write_buffer(
+            &self.buffer,
+            u64::from(start) * self.element_size,
+            bytemuck::cast_slice(data),
+        );
+    }
+}
+
+/// Render-world owner of the shared terrain buffers and their bind group.
+#[derive(Resource)]
+pub(super) struct TerrainArena {
+    faces: ArenaBuffer,
+    lights: ArenaBuffer,
+    draws: ArenaBuffer,
+    chunks: HashMap<Entity, ChunkAllocation>,
+    shared_lights: HashMap<SharedLightDataKey, SharedLight>,
+    layout: BindGroupLayout,
+    bind_group: BindGroup,
+}
+
+impl TerrainArena {
+    pub(super) fn new(render_device: &RenderDevice, layout: BindGroupLayout) -> Self {
+        let faces = ArenaBuffer::new(
+            render_device,
+            "vp_arena_faces",
+            std::mem::size_of::<PackedFace>() as u64,
+            INITIAL_FACE_CAPACITY,
+        );
+        let lights = ArenaBuffer::new(
+            render_device,
+            "vp_arena_light",
+            std::mem::size_of::<u32>() as u64,
+            INITIAL_LIGHT_WORD_CAPACITY,
+        );
+        let draws = ArenaBuffer::new(
+            render_device,
+            "vp_arena_draws",
+            std::mem::size_of::<ChunkDrawData>() as u64,
+            INITIAL_DRAW_CAPACITY,
+        );
+        let bind_group = create_arena_bind_group(render_device, &layout, &faces, &draws, &lights);
+        Self {
+            faces,
+            lights,
+            draws,
+            chunks: HashMap::new(),
+            shared_lights: HashMap::new(),
+            layout,
+            bind_group,
+        }
+    }
+
+    pub(super) const fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+
+    /// Uploads a chunk layer, keeping whichever of its existing face and light
+    /// ranges are not replaced.
+    ///
+    /// Returns `None` when the layer has never been uploaded and is missing
+    /// either its faces or its light.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn upload_chunk(
+        &mut self,
+        render_device: &RenderDevice,
+        render_queue: &RenderQueue,
+        entity: Entity,
+        origin: Vec3,
+        faces: Option<&[PackedFace]>,
+        light: Option<(&ChunkMeshLight, bool)>,
+        written_lights: &mut HashSet<SharedLightDataKey>,
+    ) -> Option<ChunkAllocation> {
+        let existing = self.chunks.get(&entity).cloned();
+        if existing.is_none() && (faces.is_none() || light.is_none()) {
+            return None;
+        }
+
+        let mut grew = false;
+        let face_range = match (faces, existing.as_ref()) {
+            (Some(faces), existing) => {
+                if let Some(existing) = existing {
+                    self.faces.retire(existing.faces.clone());
+                }
+                let len = u32::try_from(faces.len()).expect("chunk face count must fit in u32");
+                let (range, replaced) = self.faces.allocate(render_device, render_queue, len);
+                self.faces.write(render_queue, range.start, faces);
+                grew |= replaced;
+                range
+            }
+            (None, Some(existing)) => existing.faces.clone(),
+            (None, None) => unreachable!("checked above"),
+        };
+
+        let light_key = match (light, existing.as_ref()) {
+            (Some((light, changed)), existing) => {
+                let key = light.data_key();
+                if existing.is_none_or(|existing| existing.light != key) {
+                    grew |= self.acquire_light(
+                        render_device,
+                        render_queue,
+                        light,
+                        changed,
+                        written_lights,
+                    );
+                    if let Some(existing) = existing {
+                        self.release_light(existing.light);
+                    }
+                } else if changed {
+                    self.write_light_once(render_queue, light, written_lights);
+                }
+                key
+            }
+            (None, Some(existing)) => existing.light,
+            (None, None) => unreachable!("checked above"),
+        };
+
+        let draw_slot = match existing.as_ref() {
+            Some(existing) => existing.draw_slot,
+            None => {
+                let (range, replaced) = self.draws.allocate(render_device, render_queue, 1);
+                grew |= replaced;
+                range.start
+            }
+        };
+
+        let allocation = ChunkAllocation {
+            faces: face_range,
+            draw_slot,
+            light: light_key,
+            origin,
+        };
+        self.write_draw_data(render_queue, &allocation);
+        self.chunks.insert(entity, allocation.clone());
+        if grew {
+            self.rebuild_bind_group(render_device);
+        }
+        Some(allocation)
+    }
+
+    /// Applies a light-only change to an uploaded chunk layer. Returns `false`
+    /// when the entity has nothing in the arena yet.
+    pub(super) fn update_light(
+        &mut self,
+        render_device: &RenderDevice,
+        render_queue: &RenderQueue,
+        entity: Entity,
+        light: &ChunkMeshLight,
+        written_lights: &mut HashSet<SharedLightDataKey>,
+    ) -> bool {
+        let Some(allocation) = self.chunks.get(&entity).cloned() else {
+            return false;
+        };
+        let key = light.data_key();
+        if allocation.light == key {
+            self.write_light_once(render_queue, light, written_lights);
+            return true;
+        }
+
+        let grew = self.acquire_light(render_device, render_queue, light, true, written_lights);
+        self.release_light(allocation.light);
+        let allocation = ChunkAllocation {
+            light: key,
+            ..allocation
+        };
+        self.write_draw_data(render_queue, &allocation);
+        self.chunks.insert(entity, allocation);
+        if grew {
+            self.rebuild_bind_group(render_device);
+        }
+        true
+    }
+
+    /// Returns the ranges released during the previous frame to the free lists.
+    pub(super) fn reclaim_retired(&mut self) {
+        self.faces.reclaim();
+        self.lights.reclaim();
+        self.draws.reclaim();
+    }
+
+    /// Releases every range owned by the entity's chunk layer.
+    pub(super) fn release(&mut self, entity: Entity) {
+        let Some(allocation) = self.chunks.remove(&entity) else {
+            return;
+        };
+        self.faces.retire(allocation.faces);
+        self.draws
+            .retire(allocation.draw_slot..allocation.draw_slot + 1);
+        self.release_light(allocation.light);
+    }
+
+    fn acquire_light(
+        &mut self,
+        render_device: &RenderDevice,
+        render_queue: &RenderQueue,
+        light: &ChunkMeshLight,
+        changed: bool,
+        written_lights: &mut HashSet<SharedLightDataKey>,
+    ) -> bool {
+        let key = light.data_key();
+        if let Some(shared) = self.shared_lights.get_mut(&key) {
+            shared.users += 1;
+            if changed {
+                self.write_light_once(render_queue, light, written_lights);
+            }
+            return false;
+        }
+
+        let len = u32::try_from(light.data().len()).expect("light volume must fit in u32");
+        let (words, grew) = self.lights.allocate(render_device, render_queue, len);
+        self.lights.write(render_queue, words.start, light.data());
+        written_lights.insert(key);
+        self.shared_lights
+            .insert(key, SharedLight { words, users: 1 });
+        grew
+    }
+
+    fn release_light(&mut self, key: SharedLightDataKey) {
+        let Some(shared) = self.shared_lights.get_mut(&key) else {
+            return;
+        };
+        shared.users -= 1;
+        if shared.users == 0 {
+            let shared = self.shared_lights.remove(&key).expect("present above");
+            self.lights.retire(shared.words);
+        }
+    }
+
+    /// Light volumes are shared by every layer of a chunk, so each is written
+    /// at most once per frame.
+    fn write_light_once(
+        &self,
+        render_queue: &RenderQueue,
+        light: &ChunkMeshLight,
+        written_lights: &mut HashSet<SharedLightDataKey>,
+    ) {
+        let key = light.data_key();
+        let Some(shared) = self.shared_lights.get(&key) else {
+            return;
+        };
+        if written_lights.insert(key) {
+            debug_assert_eq!(shared.words.len(), light.data().len());
+            self.lights
+                .write(render_queue, shared.words.start, light.data());
+        }
+    }
+
+    fn write_draw_data(&self, render_queue: &RenderQueue, allocation: &ChunkAllocation) {
+        let light_offset = self
+            .shared_lights
+            .get(&allocation.light)
+            .map(|shared| shared.words.start)
+            .unwrap_or_default();
+        let data = ChunkDrawData {
+            origin: allocation.origin.to_array(),
+            light_offset,
+        };
+        self.draws.write(
+            render_queue,
+            allocation.draw_slot,
+            std::slice::from_ref(&data),
+        );
+    }
+
+    fn rebuild_bind_group(&mut self, render_device: &RenderDevice) {
+        self.bind_group = create_arena_bind_group(
+            render_device,
+            &self.layout,
+            &self.faces,
+            &self.draws,
+            &self.lights,
+        );
+    }
+}
+
+fn create_storage_buffer(render_device: &RenderDevice, label: &'static str, size: u64) -> Buffer {
+    render_device.create_buffer(&BufferDescriptor {
+        label: Some(label),
+        size,
+        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_arena_bind_group(
+    render_device: &RenderDevice,
+    layout: &BindGroupLayout,
+    faces: &ArenaBuffer,
+    draws: &ArenaBuffer,
+    lights: &ArenaBuffer,
+) -> BindGroup {
+    render_device.create_bind_group(
+        "vp_arena",
+        layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: faces.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: draws.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: lights.buffer.as_entire_binding(),
+            },
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocator_hands_out_ranges_first_fit() {
+        let mut allocator = ArenaAllocator::new(10);
+        assert_eq!(allocator.allocate(4), Some(0..4));
+        assert_eq!(allocator.allocate(4), Some(4..8));
+        assert_eq!(allocator.allocate(3), None);
+        assert_eq!(allocator.allocate(2), Some(8..10));
+        assert_eq!(allocator.allocate(1), None);
+
+        allocator.free(0..4);
+        assert_eq!(allocator.allocate(1), Some(0..1));
+        assert_eq!(allocator.allocate(3), Some(1..4));
+    }
+
+    #[test]
+    fn allocator_coalesces_neighbouring_frees() {
+        let mut allocator = ArenaAllocator::new(12);
+        let a = allocator.allocate(4).unwrap();
+        let b = allocator.allocate(4).unwrap();
+        let c = allocator.allocate(4).unwrap();
+
+        allocator.free(a);
+        allocator.free(c);
+        assert_eq!(allocator.allocate(8), None);
+
+        allocator.free(b);
+        assert_eq!(allocator.free.len(), 1);
+        assert_eq!(allocator.allocate(12), Some(0..12));
+    }
+
+    #[test]
+    fn allocator_growth_joins_trailing_free_space() {
+        let mut allocator = ArenaAllocator::new(8);
+        let head = allocator.allocate(6).unwrap();
+        assert_eq!(allocator.allocate(4), None);
+
+        allocator.grow(16);
+        assert_eq!(allocator.capacity(), 16);
+        assert_eq!(allocator.allocate(10), Some(6..16));
+
+        allocator.free(head);
+        allocator.grow(4);
+        assert_eq!(allocator.capacity(), 16);
+        assert_eq!(allocator.allocate(6), Some(0..6));
+    }
+
+    #[test]
+    fn chunk_draw_data_matches_shader_layout() {
+        assert_eq!(std::mem::size_of::<ChunkDrawData>(), 16);
+    }
+}
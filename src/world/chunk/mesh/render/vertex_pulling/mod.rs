@@ -1,5 +1,9 @@
 //! Vertex-pulling backend for terrain mesh layers.
+//!
+//! Every chunk layer's faces live in one shared arena, and each material layer
+//! is drawn with a single indirect multi-draw per view.
 
+mod arena;
 mod pipeline;
 mod prepare;
 mod queue;
@@ -22,7 +26,9 @@ pub(super) fn install(render_app: &mut bevy::app::SubApp) {
         .add_systems(
             Render,
             (
-                prepare::prepare_gpu_data.in_set(RenderSystems::PrepareResources),
+                (prepare::release_removed_meshes, prepare::prepare_gpu_data)
+                    .chain()
+                    .in_set(RenderSystems::PrepareResources),
                 queue::queue_chunk_meshes.in_set(RenderSystems::Queue),
             ),
         );
@@ -32,4 +38,5 @@ pub(super) fn finish(render_app: &mut bevy::app::SubApp) {
     render_app.add_render_command::<Opaque3d, queue::DrawChunkMeshCommands>();
     render_app.add_render_command::<Transparent3d, queue::DrawChunkMeshCommands>();
     pipeline::initialize(render_app);
+    queue::spawn_layer_batches(render_app.world_mut());
 }
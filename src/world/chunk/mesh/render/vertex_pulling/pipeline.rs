@@ -2,25 +2,31 @@ use std::borrow::Cow;
 
 use bevy::{
     prelude::*,
-    render::{render_resource::*, renderer::RenderDevice},
+    render::{render_resource::*, renderer::RenderDevice, settings::WgpuFeatures},
 };
 
 use crate::block::RENDER_ID_COUNT;
 
-use super::super::{
-    super::DIRECTION_COUNT,
-    visuals::{TerrainVisualSettings, TerrainVisualSettingsUniform},
+use super::{
+    super::{
+        super::DIRECTION_COUNT,
+        visuals::{TerrainVisualSettings, TerrainVisualSettingsUniform},
+    },
+    arena::TerrainArena,
 };
 
 const SHADER_PATH: &str = "shaders/vertex_pulling.wgsl";
 
 #[derive(Resource)]
 pub(super) struct Pipeline {
-    pub(super) chunk_bind_group_layout: BindGroupLayout,
     pub(super) global_bind_group_layout: BindGroupLayout,
     pub(super) opaque_id: CachedRenderPipelineId,
     pub(super) cutout_id: CachedRenderPipelineId,
     pub(super) translucent_id: CachedRenderPipelineId,
+    /// Indirect draws may start at a non-zero instance, which is how each draw
+    /// selects its per-chunk record. Without it every chunk layer is drawn
+    /// directly instead.
+    pub(super) indirect_first_instance: bool,
 }
 
 #[derive(Resource)]
@@ -44,18 +50,18 @@ pub(super) fn initialize(render_app: &mut bevy::app::SubApp) {
 
     let global_entries = global_bind_group_entries();
     let global_layout = render_device.create_bind_group_layout("vp_g0_globals", &global_entries);
-    let chunk_entries = chunk_bind_group_entries();
-    let chunk_layout = render_device.create_bind_group_layout("vp_g1_chunk", &chunk_entries);
+    let arena_entries = arena_bind_group_entries();
+    let arena_layout = render_device.create_bind_group_layout("vp_g1_arena", &arena_entries);
 
     let global_descriptor = BindGroupLayoutDescriptor::new("vp_g0_globals", &global_entries);
-    let chunk_descriptor = BindGroupLayoutDescriptor::new("vp_g1_chunk", &chunk_entries);
+    let arena_descriptor = BindGroupLayoutDescriptor::new("vp_g1_arena", &arena_entries);
     let pipeline_cache = render_app.world().resource::<PipelineCache>();
 
     let opaque_id = pipeline_cache.queue_render_pipeline(pipeline_descriptor(
         "vp_opaque",
         shader.clone(),
         global_descriptor.clone(),
-        chunk_descriptor.clone(),
+        arena_descriptor.clone(),
         Some(Face::Back),
         false,
         None,
@@ -65,7 +71,7 @@ pub(super) fn initialize(render_app: &mut bevy::app::SubApp) {
         "vp_cutout",
         shader.clone(),
         global_descriptor.clone(),
-        chunk_descriptor.clone(),
+        arena_descriptor.clone(),
         Some(Face::Back),
         true,
         None,
@@ -75,7 +81,7 @@ pub(super) fn initialize(render_app: &mut bevy::app::SubApp) {
         "vp_translucent",
         shader,
         global_descriptor,
-        chunk_descriptor,
+        arena_descriptor,
         None,
         false,
         Some(BlendState::ALPHA_BLENDING),
@@ -84,13 +90,17 @@ pub(super) fn initialize(render_app: &mut bevy::app::SubApp) {
 
     let placeholder_bind_group =
         create_placeholder_global_bind_group(&render_device, &global_layout);
+    let arena = TerrainArena::new(&render_device, arena_layout);
     render_app.world_mut().insert_resource(Pipeline {
-        chunk_bind_group_layout: chunk_layout,
         global_bind_group_layout: global_layout,
         opaque_id,
         cutout_id,
         translucent_id,
+        indirect_first_instance: render_device
+            .features()
+            .contains(WgpuFeatures::INDIRECT_FIRST_INSTANCE),
     });
+    render_app.world_mut().insert_resource(arena);
     render_app.world_mut().insert_resource(Globals {
         bind_group: placeholder_bind_group,
         frame_buffers: None,
@@ -115,10 +125,10 @@ fn global_bind_group_entries() -> Vec<BindGroupLayoutEntry> {
     ]
 }
 
-fn chunk_bind_group_entries() -> Vec<BindGroupLayoutEntry> {
+fn arena_bind_group_entries() -> Vec<BindGroupLayoutEntry> {
     vec![
         read_only_storage_entry(0, ShaderStages::VERTEX),
-        uniform_entry(1, ShaderStages::VERTEX, 16),
+        read_only_storage_entry(1, ShaderStages::VERTEX),
         read_only_storage_entry(2, ShaderStages::VERTEX),
     ]
 }
@@ -190,7 +200,7 @@ fn pipeline_descriptor(
     label: &'static str,
     shader: Handle<Shader>,
     global_layout: BindGroupLayoutDescriptor,
-    chunk_layout: BindGroupLayoutDescriptor,
+    arena_layout: BindGroupLayoutDescriptor,
     cull_mode: Option<Face>,
     alpha_to_coverage_enabled: bool,
     blend: Option<BlendState>,
@@ -198,7 +208,7 @@ fn pipeline_descriptor(
 ) -> RenderPipelineDescriptor {
     RenderPipelineDescriptor {
         label: Some(Cow::Borrowed(label)),
-        layout: vec![global_layout, chunk_layout],
+        layout: vec![global_layout, arena_layout],
         immediate_size: 0,
         vertex: VertexState {
             shader: shader.clone(),
@@ -343,15 +353,18 @@ mod tests {
     use super::*;
 
     #[test]
-    fn chunk_origin_binding_has_vec4_size() {
-        let entry = &chunk_bind_group_entries()[1];
-        let BindingType::Buffer {
-            min_binding_size, ..
-        } = entry.ty
-        else {
-            panic!("chunk origin should be a buffer binding");
-        };
-        assert_eq!(min_binding_size.unwrap().get(), 16);
+    fn arena_bindings_are_read_only_vertex_storage() {
+        for entry in arena_bind_group_entries() {
+            assert_eq!(entry.visibility, ShaderStages::VERTEX);
+            assert!(matches!(
+                entry.ty,
+                BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                }
+            ));
+        }
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::HashSet, ops::Range};
 
 use bevy::{
     math::Mat4,
@@ -19,26 +19,17 @@ use super::{
         material::TerrainMaterialState,
         visuals::{TerrainAnimationClock, TerrainVisualSettings, TerrainVisualSettingsUniform},
     },
+    arena::{ChunkAllocation, TerrainArena},
     pipeline::{FrameBuffers, Globals, Pipeline},
 };
-use crate::world::chunk::mesh::{
-    ChunkMeshFaces, ChunkMeshLayer, ChunkMeshLight, PackedFace, SharedLightDataKey,
-};
+use crate::world::chunk::mesh::{ChunkMeshFaces, ChunkMeshLayer, ChunkMeshLight};
 
 #[derive(Component)]
 pub(super) struct PreparedChunkMesh {
-    pub(super) bind_group: BindGroup,
-    pub(super) face_count: u32,
+    pub(super) faces: Range<u32>,
+    pub(super) draw_slot: u32,
     pub(super) material_layer: crate::block::BlockMaterialLayer,
     pub(super) chunk_origin: Vec3,
-    face_buffer: Buffer,
-    light_buffer: Buffer,
-}
-
-#[repr(C)]
-#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
-struct ChunkOriginUniform {
-    origin: [f32; 4],
 }
 
 struct MaterialBuffers {
@@ -82,6 +73,21 @@ pub(super) fn extract_changed_lights(
     );
 }
 
+/// Reclaims last frame's released arena ranges, then releases the ranges of
+/// chunk layers whose prepared mesh was removed or despawned since.
+pub(super) fn release_removed_meshes(
+    mut removed: RemovedComponents<PreparedChunkMesh>,
+    prepared_meshes: Query<(), With<PreparedChunkMesh>>,
+    mut arena: ResMut<TerrainArena>,
+) {
+    arena.reclaim_retired();
+    for entity in removed.read() {
+        if !prepared_meshes.contains(entity) {
+            arena.release(entity);
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(super) fn prepare_gpu_data(
     mut commands: Commands,
@@ -97,24 +103,26 @@ pub(super) fn prepare_gpu_data(
     faces: Query<&ChunkMeshFaces>,
     changed_lights: Query<(Entity, Ref<ChunkMeshLight>), Changed<ChunkMeshLight>>,
     all_meshes: Query<&ChunkMeshLayer>,
-    prepared_meshes: Query<&PreparedChunkMesh>,
+    mut arena: ResMut<TerrainArena>,
     cameras: Query<&ExtractedView>,
     visual_settings: Option<Res<TerrainVisualSettings>>,
     animation_clock: Option<Res<TerrainAnimationClock>>,
     mut globals: ResMut<Globals>,
 ) {
-    let mut updated_light_buffers = HashSet::new();
+    let mut written_lights = HashSet::new();
+    let mut light_only_updates = HashSet::new();
 
-    // Existing light buffers remain writable while terrain resources initialize or recover.
-    // Consume their change wakeups before the resource guards below.
+    // Uploaded chunk layers keep their light ranges writable while terrain resources initialize
+    // or recover. Consume their change wakeups before the resource guards below.
     for (entity, light) in &changed_lights {
-        if let Ok(prepared) = prepared_meshes.get(entity) {
-            write_light_buffer_once(
-                &render_queue,
-                &mut updated_light_buffers,
-                &prepared.light_buffer,
-                &light,
-            );
+        if arena.update_light(
+            &render_device,
+            &render_queue,
+            entity,
+            &light,
+            &mut written_lights,
+        ) {
+            light_only_updates.insert(entity);
         }
     }
 
@@ -139,7 +147,6 @@ pub(super) fn prepare_gpu_data(
     );
 
     let mut prepared_this_frame = HashSet::new();
-    let mut light_buffers_by_data = HashMap::new();
 
     // Face payloads are an independent invalidation source. In particular, a topology rebuild may
     // keep the same face count and origin, so preparation must not rely on metadata changing.
@@ -147,53 +154,43 @@ pub(super) fn prepare_gpu_data(
         prepared_this_frame.insert(entity);
 
         if mesh.face_count() == 0 {
+            arena.release(entity);
             commands
                 .entity(entity)
                 .remove::<(PreparedChunkMesh, ChunkMeshFaces)>();
             continue;
         }
 
-        let face_buffer = if let Ok(faces) = faces.get(entity) {
-            create_face_buffer(&render_device, faces.as_slice())
-        } else if let Ok(prepared) = prepared_meshes.get(entity) {
-            prepared.face_buffer.clone()
-        } else {
-            continue;
-        };
-        let existing = prepared_meshes.get(entity).ok();
-        let light_buffer = if let Some(light) = light.as_ref() {
-            let (buffer, created) = light_buffer_for(
-                &render_device,
-                &mut light_buffers_by_data,
-                light,
-                existing.map(|prepared| &prepared.light_buffer),
-            );
-            if light.is_changed() && !created {
-                write_light_buffer_once(&render_queue, &mut updated_light_buffers, &buffer, light);
-            }
-            buffer
-        } else if let Some(prepared) = existing {
-            prepared.light_buffer.clone()
-        } else {
-            // Rendering waits until the independently extracted light component arrives.
+        // Rendering waits until both the faces and the independently extracted light component
+        // have arrived at least once.
+        let light = light.as_ref().map(|light| {
+            (
+                &**light,
+                light.is_changed() && !light_only_updates.contains(&entity),
+            )
+        });
+        let Some(allocation) = arena.upload_chunk(
+            &render_device,
+            &render_queue,
+            entity,
+            mesh.origin(),
+            faces.get(entity).ok().map(ChunkMeshFaces::as_slice),
+            light,
+            &mut written_lights,
+        ) else {
             continue;
         };
 
-        let prepared =
-            create_prepared_mesh(&render_device, &pipeline, mesh, face_buffer, light_buffer);
         commands.entity(entity).remove::<ChunkMeshFaces>();
-        commands.entity(entity).insert(prepared);
+        commands
+            .entity(entity)
+            .insert(prepared_mesh(mesh, allocation));
     }
 
-    // A light-only change can update the fixed-size storage buffer in place. The bind group and
-    // face/origin buffers continue to reference valid resources.
+    // A light-only change updates the arena in place above. Layers whose faces arrived before
+    // their light become drawable here.
     for (entity, light) in &changed_lights {
-        if prepared_this_frame.contains(&entity) {
-            continue;
-        }
-
-        if prepared_meshes.get(entity).is_ok() {
-            // The buffer was updated before the terrain resource guards.
+        if prepared_this_frame.contains(&entity) || light_only_updates.contains(&entity) {
             continue;
         }
 
@@ -204,19 +201,28 @@ pub(super) fn prepare_gpu_data(
             continue;
         };
         if mesh.face_count() == 0 {
+            arena.release(entity);
             commands
                 .entity(entity)
                 .remove::<(PreparedChunkMesh, ChunkMeshFaces)>();
             continue;
         }
 
-        let face_buffer = create_face_buffer(&render_device, faces.as_slice());
-        let (light_buffer, _) =
-            light_buffer_for(&render_device, &mut light_buffers_by_data, &light, None);
-        let prepared =
-            create_prepared_mesh(&render_device, &pipeline, mesh, face_buffer, light_buffer);
+        let Some(allocation) = arena.upload_chunk(
+            &render_device,
+            &render_queue,
+            entity,
+            mesh.origin(),
+            Some(faces.as_slice()),
+            Some((&*light, true)),
+            &mut written_lights,
+        ) else {
+            continue;
+        };
         commands.entity(entity).remove::<ChunkMeshFaces>();
-        commands.entity(entity).insert(prepared);
+        commands
+            .entity(entity)
+            .insert(prepared_mesh(mesh, allocation));
     }
 }
 
@@ -381,117 +387,15 @@ fn create_global_bind_group(
     )
 }
 
-fn create_prepared_mesh(
-    render_device: &RenderDevice,
-    pipeline: &Pipeline,
-    mesh: &ChunkMeshLayer,
-    face_buffer: Buffer,
-    light_buffer: Buffer,
-) -> PreparedChunkMesh {
-    let origin_buffer = create_origin_buffer(render_device, mesh);
-    let bind_group = create_chunk_bind_group(
-        render_device,
-        pipeline,
-        &face_buffer,
-        &origin_buffer,
-        &light_buffer,
-    );
+fn prepared_mesh(mesh: &ChunkMeshLayer, allocation: ChunkAllocation) -> PreparedChunkMesh {
     PreparedChunkMesh {
-        bind_group,
-        face_count: mesh.face_count(),
+        faces: allocation.faces,
+        draw_slot: allocation.draw_slot,
         material_layer: mesh.material_layer(),
         chunk_origin: mesh.origin(),
-        face_buffer,
-        light_buffer,
-    }
-}
-
-fn create_face_buffer(render_device: &RenderDevice, faces: &[PackedFace]) -> Buffer {
-    render_device.create_buffer_with_data(&BufferInitDescriptor {
-        label: Some("vp_desc"),
-        contents: bytemuck::cast_slice(faces),
-        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
-    })
-}
-
-fn create_origin_buffer(render_device: &RenderDevice, mesh: &ChunkMeshLayer) -> Buffer {
-    let origin = mesh.origin();
-    let origin = ChunkOriginUniform {
-        origin: [origin.x, origin.y, origin.z, 0.0],
-    };
-    render_device.create_buffer_with_data(&BufferInitDescriptor {
-        label: Some("vp_origin"),
-        contents: bytemuck::bytes_of(&origin),
-        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-    })
-}
-
-fn create_light_buffer(render_device: &RenderDevice, light: &ChunkMeshLight) -> Buffer {
-    render_device.create_buffer_with_data(&BufferInitDescriptor {
-        label: Some("vp_light"),
-        contents: bytemuck::cast_slice(light.data()),
-        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
-    })
-}
-
-fn light_buffer_for(
-    render_device: &RenderDevice,
-    light_buffers_by_data: &mut HashMap<SharedLightDataKey, Buffer>,
-    light: &ChunkMeshLight,
-    reusable_existing: Option<&Buffer>,
-) -> (Buffer, bool) {
-    let key = light.data_key();
-    if let Some(buffer) = light_buffers_by_data.get(&key) {
-        return (buffer.clone(), false);
-    }
-
-    let (buffer, created) = reusable_existing
-        .map(|buffer| (buffer.clone(), false))
-        .unwrap_or_else(|| (create_light_buffer(render_device, light), true));
-    light_buffers_by_data.insert(key, buffer.clone());
-    (buffer, created)
-}
-
-fn write_light_buffer_once(
-    render_queue: &RenderQueue,
-    updated_light_buffers: &mut HashSet<BufferId>,
-    buffer: &Buffer,
-    light: &ChunkMeshLight,
-) {
-    if updated_light_buffers.insert(buffer.id()) {
-        let bytes = bytemuck::cast_slice(light.data());
-        debug_assert_eq!(buffer.size(), bytes.len() as u64);
-        render_queue.0.write_buffer(buffer, 0, bytes);
     }
 }
 
-fn create_chunk_bind_group(
-    render_device: &RenderDevice,
-    pipeline: &Pipeline,
-    face_buffer: &Buffer,
-    origin_buffer: &Buffer,
-    light_buffer: &Buffer,
-) -> BindGroup {
-    render_device.create_bind_group(
-        "vp_chunk",
-        &pipeline.chunk_bind_group_layout,
-        &[
-            BindGroupEntry {
-                binding: 0,
-                resource: face_buffer.as_entire_binding(),
-            },
-            BindGroupEntry {
-                binding: 1,
-                resource: origin_buffer.as_entire_binding(),
-            },
-            BindGroupEntry {
-                binding: 2,
-                resource: light_buffer.as_entire_binding(),
-            },
-        ],
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -516,11 +420,6 @@ mod tests {
         visits.0 += meshes.iter().count();
     }
 
-    #[test]
-    fn chunk_origin_uniform_matches_shader_layout() {
-        assert_eq!(std::mem::size_of::<ChunkOriginUniform>(), 16);
-    }
-
     #[test]
     fn unprepared_mesh_is_retried_after_its_change_tick_expires() {
         let mut app = App::new();
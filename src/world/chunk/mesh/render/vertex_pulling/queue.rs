@@ -1,4 +1,4 @@
-use std::any::TypeId;
+use std::{any::TypeId, collections::HashMap};
 
 use bevy::{
     asset::AssetId,
@@ -17,56 +17,163 @@ use bevy::{
             PhaseItemExtraIndex, RenderCommand, RenderCommandResult, SetItemPipeline,
             TrackedRenderPass, ViewBinnedRenderPhases, ViewSortedRenderPhases,
         },
+        render_resource::{Buffer, BufferDescriptor, BufferUsages, DrawIndirectArgs},
+        renderer::{RenderDevice, RenderQueue},
+        sync_world::MainEntity,
         view::{ExtractedView, RenderVisibleEntities},
     },
 };
 
 use crate::{
     block::BlockMaterialLayer,
-    world::chunk::{CHUNK_SIZE, mesh::ChunkMeshLayer},
+    world::chunk::{
+        CHUNK_SIZE,
+        mesh::{ChunkMeshLayer, ChunkSectionVisibility},
+    },
 };
 
 use super::{
+    arena::TerrainArena,
     pipeline::{Globals, Pipeline},
     prepare::PreparedChunkMesh,
 };
 
-pub(super) struct DrawVertexPulled;
+/// Render-world entity standing in for every chunk layer of one material
+/// layer, which is drawn as a single phase item.
+#[derive(Component)]
+pub(super) struct TerrainLayerBatch(BlockMaterialLayer);
+
+#[derive(Resource)]
+pub(super) struct TerrainLayerBatches([Entity; BlockMaterialLayer::COUNT]);
+
+/// Indirect draw lists built for each view this frame, one per material layer.
+#[derive(Resource, Default)]
+pub(super) struct TerrainViewDraws(HashMap<Entity, [LayerDraws; BlockMaterialLayer::COUNT]>);
 
-impl<P: PhaseItem> RenderCommand<P> for DrawVertexPulled {
-    type Param = SRes<Globals>;
-    type ViewQuery = ();
-    type ItemQuery = Read<PreparedChunkMesh>;
+#[derive(Default)]
+pub(super) struct LayerDraws {
+    draws: Vec<DrawIndirectArgs>,
+    buffer: Option<Buffer>,
+}
+
+impl LayerDraws {
+    fn upload(&mut self, render_device: &RenderDevice, render_queue: &RenderQueue) {
+        let bytes = self
+            .draws
+            .iter()
+            .flat_map(DrawIndirectArgs::as_bytes)
+            .copied()
+            .collect::<Vec<_>>();
+        if bytes.is_empty() {
+            return;
+        }
+        if self
+            .buffer
+            .as_ref()
+            .is_none_or(|buffer| buffer.size() < bytes.len() as u64)
+        {
+            self.buffer = Some(render_device.create_buffer(&BufferDescriptor {
+                label: Some("vp_indirect"),
+                size: (bytes.len() as u64).next_power_of_two(),
+                usage: BufferUsages::INDIRECT | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        let buffer = self.buffer.as_ref().expect("created above");
+        render_queue.0.write_buffer(buffer, 0, &bytes);
+    }
+}
+
+pub(super) fn spawn_layer_batches(world: &mut World) {
+    let entities = BlockMaterialLayer::ALL.map(|layer| world.spawn(TerrainLayerBatch(layer)).id());
+    world.insert_resource(TerrainLayerBatches(entities));
+    world.init_resource::<TerrainViewDraws>();
+}
+
+const fn layer_index(layer: BlockMaterialLayer) -> usize {
+    match layer {
+        BlockMaterialLayer::Opaque => 0,
+        BlockMaterialLayer::Cutout => 1,
+        BlockMaterialLayer::Translucent => 2,
+    }
+}
+
+fn indirect_draw(mesh: &PreparedChunkMesh) -> DrawIndirectArgs {
+    DrawIndirectArgs {
+        vertex_count: mesh.faces.len() as u32 * 6,
+        instance_count: 1,
+        first_vertex: mesh.faces.start * 6,
+        first_instance: mesh.draw_slot,
+    }
+}
+
+pub(super) struct DrawTerrainLayer;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawTerrainLayer {
+    type Param = (
+        SRes<Globals>,
+        SRes<Pipeline>,
+        SRes<TerrainArena>,
+        SRes<TerrainViewDraws>,
+    );
+    type ViewQuery = Entity;
+    type ItemQuery = Read<TerrainLayerBatch>;
 
     fn render<'w>(
         _item: &P,
-        _view: (),
-        mesh: Option<&'w PreparedChunkMesh>,
-        globals: SystemParamItem<'w, '_, Self::Param>,
+        view: Entity,
+        batch: Option<&'w TerrainLayerBatch>,
+        (globals, pipeline, arena, view_draws): SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        let Some(mesh) = mesh else {
+        let Some(batch) = batch else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(layers) = view_draws.into_inner().0.get(&view) else {
             return RenderCommandResult::Skip;
         };
-        let globals = globals.into_inner();
-        pass.set_bind_group(0, &globals.bind_group, &[]);
-        pass.set_bind_group(1, &mesh.bind_group, &[]);
-        pass.draw(0..mesh.face_count * 6, 0..1);
+        let layer = &layers[layer_index(batch.0)];
+        let Some(buffer) = layer.buffer.as_ref() else {
+            return RenderCommandResult::Skip;
+        };
+        if layer.draws.is_empty() {
+            return RenderCommandResult::Skip;
+        }
+
+        pass.set_bind_group(0, &globals.into_inner().bind_group, &[]);
+        pass.set_bind_group(1, arena.into_inner().bind_group(), &[]);
+        if pipeline.indirect_first_instance {
+            pass.multi_draw_indirect(buffer, 0, layer.draws.len() as u32);
+        } else {
+            for draw in &layer.draws {
+                pass.draw(
+                    draw.first_vertex..draw.first_vertex + draw.vertex_count,
+                    draw.first_instance..draw.first_instance + 1,
+                );
+            }
+        }
         RenderCommandResult::Success
     }
 }
 
-pub(super) type DrawChunkMeshCommands = (SetItemPipeline, DrawVertexPulled);
+pub(super) type DrawChunkMeshCommands = (SetItemPipeline, DrawTerrainLayer);
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn queue_chunk_meshes(
     pipeline: Option<Res<Pipeline>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
     mut opaque_phases: ResMut<ViewBinnedRenderPhases<Opaque3d>>,
     mut transparent_phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
-    views: Query<(&ExtractedView, &RenderVisibleEntities)>,
+    views: Query<(Entity, &ExtractedView, &RenderVisibleEntities)>,
     prepared_meshes: Query<&PreparedChunkMesh>,
+    section_visibility: Option<Res<ChunkSectionVisibility>>,
+    batches: Res<TerrainLayerBatches>,
+    mut view_draws: ResMut<TerrainViewDraws>,
     opaque_draw_functions: Res<DrawFunctions<Opaque3d>>,
     transparent_draw_functions: Res<DrawFunctions<Transparent3d>>,
 ) {
+    view_draws.0.retain(|view, _| views.contains(*view));
     let Some(pipeline) = pipeline.as_deref() else {
         return;
     };
@@ -92,8 +199,12 @@ pub(super) fn queue_chunk_meshes(
     let bin_key = Opaque3dBinKey {
         asset_id: AssetId::<Mesh>::invalid().untyped(),
     };
+    let batch_item = |layer: BlockMaterialLayer| {
+        let entity = batches.0[layer_index(layer)];
+        (entity, MainEntity::from(entity))
+    };
 
-    for (view, visible_entities) in &views {
+    for (view_entity, view, visible_entities) in &views {
         let Some(opaque_phase) = opaque_phases.get_mut(&view.retained_view_entity) else {
             continue;
         };
@@ -108,42 +219,73 @@ pub(super) fn queue_chunk_meshes(
             continue;
         };
 
-        for &(entity, main_entity) in &visible_meshes.entities_cpu_culling {
+        let layers = view_draws.0.entry(view_entity).or_default();
+        for layer in layers.iter_mut() {
+            layer.draws.clear();
+        }
+        let mut translucent = Vec::new();
+        for &(entity, _) in &visible_meshes.entities_cpu_culling {
             let Ok(mesh) = prepared_meshes.get(entity) else {
                 continue;
             };
-            match mesh.material_layer {
-                BlockMaterialLayer::Opaque => opaque_phase.add(
-                    opaque_batch_key.clone(),
-                    bin_key.clone(),
-                    (entity, main_entity),
-                    InputUniformIndex::default(),
-                    BinnedRenderPhaseType::NonMesh,
-                ),
-                BlockMaterialLayer::Cutout => opaque_phase.add(
-                    cutout_batch_key.clone(),
+            let chunk = (mesh.chunk_origin / CHUNK_SIZE as f32).floor().as_ivec3();
+            if section_visibility
+                .as_deref()
+                .is_some_and(|visibility| !visibility.is_visible(chunk))
+            {
+                continue;
+            }
+            if mesh.material_layer == BlockMaterialLayer::Translucent {
+                let mesh_center = mesh.chunk_origin + Vec3::splat(CHUNK_SIZE as f32 * 0.5);
+                translucent.push((rangefinder.distance(&mesh_center), mesh_center, mesh));
+            } else {
+                layers[layer_index(mesh.material_layer)]
+                    .draws
+                    .push(indirect_draw(mesh));
+            }
+        }
+        // Draw order within one multi-draw is preserved, so translucent chunk layers are
+        // blended back to front exactly as separate sorted phase items would be. Faces inside
+        // each layer arrive already sorted for the camera cell by the main world.
+        translucent.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+        layers[layer_index(BlockMaterialLayer::Translucent)].draws = translucent
+            .iter()
+            .map(|(_, _, mesh)| indirect_draw(mesh))
+            .collect();
+
+        for layer in layers.iter_mut() {
+            layer.upload(&render_device, &render_queue);
+        }
+
+        for (layer, batch_key) in [
+            (BlockMaterialLayer::Opaque, &opaque_batch_key),
+            (BlockMaterialLayer::Cutout, &cutout_batch_key),
+        ] {
+            if !layers[layer_index(layer)].draws.is_empty() {
+                opaque_phase.add(
+                    batch_key.clone(),
                     bin_key.clone(),
-                    (entity, main_entity),
+                    batch_item(layer),
                     InputUniformIndex::default(),
                     BinnedRenderPhaseType::NonMesh,
-                ),
-                BlockMaterialLayer::Translucent => {
-                    let mesh_center = mesh.chunk_origin + Vec3::splat(CHUNK_SIZE as f32 * 0.5);
-                    transparent_phase.add_retained(Transparent3d {
-                        sorting_info: TransparentSortingInfo3d::Sorted {
-                            mesh_center,
-                            depth_bias: 0.0,
-                        },
-                        entity: (entity, main_entity),
-                        pipeline: pipeline.translucent_id,
-                        draw_function: transparent_draw_function,
-                        distance: rangefinder.distance(&mesh_center),
-                        batch_range: 0..1,
-                        extra_index: PhaseItemExtraIndex::None,
-                        indexed: false,
-                    });
-                }
+                );
             }
         }
+        // The whole layer sorts against other transparent items by its farthest chunk.
+        if let Some(&(distance, mesh_center, _)) = translucent.first() {
+            transparent_phase.add_retained(Transparent3d {
+                sorting_info: TransparentSortingInfo3d::Sorted {
+                    mesh_center,
+                    depth_bias: 0.0,
+                },
+                entity: batch_item(BlockMaterialLayer::Translucent),
+                pipeline: pipeline.translucent_id,
+                draw_function: transparent_draw_function,
+                distance,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: false,
+            });
+        }
     }
 }
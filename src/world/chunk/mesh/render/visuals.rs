@@ -2,9 +2,11 @@ use bevy::{
     prelude::*,
     render::{extract_resource::ExtractResource, render_resource::ShaderType},
 };
+use bevy_settings::{ReflectSettingsGroup, SettingsGroup};
 
 use crate::{
     light::DayNightCycle,
+    weather::Weather,
     world::{
         chunk::{Chunk, ChunkCell, WorldBlockPos},
         dimension::{Active, Dimension},
@@ -19,6 +21,7 @@ const MINECRAFT_UNDERWATER_OVERLAY_ALPHA: f32 = 0.1;
 #[reflect(Resource)]
 pub(crate) struct TerrainVisualSettings {
     pub sky_light_color: Vec3,
+    /// Multiplies the coloured block light each emitter propagates.
     pub block_light_color: Vec3,
     pub fog_color: Vec3,
     pub fog_start: f32,
@@ -31,7 +34,7 @@ impl Default for TerrainVisualSettings {
     fn default() -> Self {
         Self {
             sky_light_color: vec3(0.94, 0.97, 1.0),
-            block_light_color: vec3(1.0, 0.78, 0.50),
+            block_light_color: Vec3::ONE,
             fog_color: vec3(0.455, 0.702, 1.0),
             fog_start: 220.0,
             fog_end: 560.0,
@@ -41,6 +44,54 @@ impl Default for TerrainVisualSettings {
     }
 }
 
+/// Player preference for open-air fog. Underwater fog is not affected, since
+/// it is what shows the camera is submerged.
+#[derive(Resource, SettingsGroup, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, SettingsGroup, Default)]
+pub(crate) struct FogSettings {
+    pub enabled: bool,
+    /// Fog opacity at the far end of the fog range, from `0.0` to `1.0`.
+    pub strength: f32,
+}
+
+impl Default for FogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strength: TerrainVisualSettings::default().fog_strength,
+        }
+    }
+}
+
+impl FogSettings {
+    pub fn air_fog_strength(self) -> f32 {
+        if !self.enabled || !self.strength.is_finite() {
+            return 0.0;
+        }
+        self.strength.clamp(0.0, 1.0)
+    }
+}
+
+/// Where fog thickens in open air, in blocks from the camera.
+///
+/// Far-field terrain pushes this past the resident view so the horizon fades
+/// out instead of the edge of loaded chunks.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct AirFogRange {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Default for AirFogRange {
+    fn default() -> Self {
+        let defaults = TerrainVisualSettings::default();
+        Self {
+            start: defaults.fog_start,
+            end: defaults.fog_end,
+        }
+    }
+}
+
 impl ExtractResource for TerrainVisualSettings {
     type Source = TerrainVisualSettings;
 
@@ -111,6 +162,8 @@ impl TerrainVisualSettingsUniform {
 pub(super) fn install(app: &mut App) {
     app.init_resource::<TerrainVisualSettings>()
         .init_resource::<TerrainAnimationClock>()
+        .init_resource::<AirFogRange>()
+        .init_resource::<FogSettings>()
         .register_type::<TerrainVisualSettings>()
         .add_systems(
             Update,
@@ -126,11 +179,18 @@ fn update_camera_fluid_visuals(
     mut settings: ResMut<TerrainVisualSettings>,
     mut clear_color: ResMut<ClearColor>,
     day_night: Res<DayNightCycle>,
+    weather: Option<Res<Weather>>,
+    air_fog: Res<AirFogRange>,
+    fog_settings: Res<FogSettings>,
     cameras: Query<&GlobalTransform, With<Camera3d>>,
     dimension: Option<Single<&Dimension, With<Active>>>,
     chunks: Query<&Chunk>,
 ) {
-    let daylight = day_night.lighting();
+    let mut daylight = day_night.lighting();
+    if let Some(weather) = weather {
+        daylight.sky_light_color *= weather.sky_light_factor();
+        daylight.sky_color = weather.overcast_color(daylight.sky_color);
+    }
     settings.sky_light_color = daylight.sky_light_color;
 
     let underwater = cameras.iter().next().is_some_and(|camera| {
@@ -152,11 +212,10 @@ fn update_camera_fluid_visuals(
             settings.fog_color.z,
         );
     } else {
-        let defaults = TerrainVisualSettings::default();
         settings.fog_color = daylight.sky_color;
-        settings.fog_start = defaults.fog_start;
-        settings.fog_end = defaults.fog_end;
-        settings.fog_strength = defaults.fog_strength;
+        settings.fog_start = air_fog.start;
+        settings.fog_end = air_fog.end;
+        settings.fog_strength = fog_settings.air_fog_strength();
         settings.screen_tint_strength = 0.0;
         clear_color.0 = Color::srgb(
             daylight.sky_color.x,
@@ -226,6 +285,23 @@ fn minecraft_water_fog_color() -> Vec3 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn fog_settings_disable_and_sanitize_air_fog() {
+        assert_eq!(FogSettings::default().air_fog_strength(), 1.0);
+        for (enabled, strength, expected) in [
+            (false, 1.0, 0.0),
+            (true, 0.4, 0.4),
+            (true, 3.0, 1.0),
+            (true, -1.0, 0.0),
+            (true, f32::NAN, 0.0),
+        ] {
+            assert_eq!(
+                FogSettings { enabled, strength }.air_fog_strength(),
+                expected
+            );
+        }
+    }
+
     #[test]
     fn visual_uniform_matches_shader_layout() {
         assert_eq!(std::mem::size_of::<TerrainVisualSettingsUniform>(), 80);
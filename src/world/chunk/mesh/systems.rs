@@ -13,19 +13,26 @@ use crate::world::dimension::{Active, Dimension, DimensionStreamingSet};
 
 use super::super::{CHUNK_SIZE, Chunk, ChunkLight, ChunkPerfCounters, ChunkPos, ChunkPosition};
 use super::{
-    ChunkMeshBlocks, ChunkMeshFaces, ChunkMeshLayer, ChunkMeshLight, PreparedChunkMeshLight,
-    mesher::{self, LayerMesh},
+    ChunkFaceConnectivity, ChunkMeshBlocks, ChunkMeshFaces, ChunkMeshLayer, ChunkMeshLight,
+    PreparedChunkMeshLight, TranslucentFaceOrder,
+    mesher::{self, ChunkMeshingMode, LayerMesh},
 };
 
 pub(super) fn install(app: &mut App) {
-    app.add_systems(
-        Update,
-        (rebuild_chunk_meshes, upload_chunk_lights)
-            .chain()
-            .after(DimensionStreamingSet)
-            .run_if(in_state(TextureState::Finished)),
-    )
-    .add_systems(PostUpdate, drop_uploaded_faces);
+    app.init_resource::<ChunkMeshingMode>()
+        .register_type::<ChunkMeshingMode>()
+        .add_systems(
+            Update,
+            (
+                remesh_on_meshing_mode_change,
+                rebuild_chunk_meshes,
+                upload_chunk_lights,
+            )
+                .chain()
+                .after(DimensionStreamingSet)
+                .run_if(in_state(TextureState::Finished)),
+        )
+        .add_systems(PostUpdate, drop_uploaded_faces);
 }
 
 pub(super) fn drop_uploaded_faces(
@@ -39,10 +46,34 @@ pub(super) fn drop_uploaded_faces(
     }
 }
 
+/// Queues every loaded chunk for a rebuild when the meshing mode is switched.
+pub(super) fn remesh_on_meshing_mode_change(
+    mode: Option<Res<ChunkMeshingMode>>,
+    dimension: Option<Single<&mut Dimension, With<Active>>>,
+) {
+    let Some(mode) = mode else {
+        return;
+    };
+    if !mode.is_changed() || mode.is_added() {
+        return;
+    }
+    let Some(mut dimension) = dimension else {
+        return;
+    };
+    let loaded = dimension
+        .iter_loaded_chunks()
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    for position in loaded {
+        dimension.enqueue_mesh_rebuild(position);
+    }
+}
+
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(super) fn rebuild_chunk_meshes(
     mut commands: Commands,
     mut perf: Option<ResMut<ChunkPerfCounters>>,
+    meshing_mode: Option<Res<ChunkMeshingMode>>,
     all_chunks_q: Query<(Entity, &ChunkPosition, &Chunk)>,
     light_q: Query<(&ChunkPosition, &ChunkLight)>,
     children_q: Query<&Children>,
@@ -111,6 +142,7 @@ pub(super) fn rebuild_chunk_meshes(
     let context_elapsed = context_started.elapsed();
 
     let build_started = Instant::now();
+    let meshing_mode = meshing_mode.as_deref().copied().unwrap_or_default();
     let builds = active_dirty
         .par_splat_map(ComputeTaskPool::get(), None, |_, targets| {
             targets
@@ -120,7 +152,8 @@ pub(super) fn rebuild_chunk_meshes(
                     ChunkMeshBuild {
                         entity,
                         chunk_pos: position,
-                        layers: mesher::build(&blocks),
+                        layers: mesher::build_with_mode(&blocks, meshing_mode),
+                        connectivity: ChunkFaceConnectivity::from_mesh_blocks(&blocks),
                     }
                 })
                 .collect::<Vec<_>>()
@@ -136,6 +169,7 @@ pub(super) fn rebuild_chunk_meshes(
             .get(build.entity)
             .map(|transform| transform.translation)
             .unwrap_or(Vec3::ZERO);
+        commands.entity(build.entity).insert(build.connectivity);
         update_chunk_mesh_children(
             &mut commands,
             &mut mesh_q,
@@ -170,6 +204,7 @@ struct ChunkMeshBuild {
     entity: Entity,
     chunk_pos: ChunkPos,
     layers: Vec<LayerMesh>,
+    connectivity: ChunkFaceConnectivity,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -210,14 +245,18 @@ fn update_chunk_mesh_children(
     {
         updated.push(material_layer);
         let faces = ChunkMeshFaces::new(faces);
+        let face_order = (material_layer == BlockMaterialLayer::Translucent)
+            .then(|| TranslucentFaceOrder::new(&faces));
 
         if let Some(entity) = existing.get(&material_layer) {
             if let Ok(mut mesh) = mesh_q.get_mut(*entity) {
                 mesh.update(material_layer, chunk_origin, &faces);
             }
-            commands
-                .entity(*entity)
-                .insert((faces, chunk_render_aabb()));
+            let mut layer = commands.entity(*entity);
+            layer.insert((faces, chunk_render_aabb()));
+            if let Some(face_order) = face_order {
+                layer.insert(face_order);
+            }
             continue;
         }
 
@@ -225,7 +264,7 @@ fn update_chunk_mesh_children(
             light_data_for_new_mesh_child(&mut shared_light_data, chunk_pos, lights_by_pos);
         let mesh = ChunkMeshLayer::new(material_layer, chunk_origin, &faces);
 
-        commands.spawn((
+        let mut layer = commands.spawn((
             ChildOf(chunk_entity),
             Transform::default(),
             Visibility::default(),
@@ -234,6 +273,9 @@ fn update_chunk_mesh_children(
             faces,
             ChunkMeshLight::new(light_data),
         ));
+        if let Some(face_order) = face_order {
+            layer.insert(face_order);
+        }
     }
 
     for (layer, entity) in existing {
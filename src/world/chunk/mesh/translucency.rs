@@ -0,0 +1,205 @@
+//! Back-to-front ordering of faces inside translucent chunk layers.
+//!
+//! The renderer already draws translucent chunk layers far to near, but faces
+//! inside one layer blend in mesh order, so ice in front of water can land
+//! behind it. Translucent layers keep their unsorted faces here and are
+//! re-sorted on the chunk task pool whenever the camera enters another block
+//! cell. Layers far from the camera keep their last order, which barely
+//! changes at that distance.
+
+use std::{cmp::Reverse, sync::Arc};
+
+use bevy::{
+    platform::collections::HashMap,
+    prelude::*,
+    tasks::{Task, futures::check_ready},
+};
+
+use crate::{
+    quad::Direction,
+    world::{chunk::CHUNK_ISIZE, dimension::ChunkTaskPool},
+};
+
+use super::{ChunkMeshFaces, ChunkMeshLayer, PackedFace};
+
+/// Chunks around the camera whose layers re-sort on every cell change.
+const TRANSLUCENT_SORT_RADIUS_CHUNKS: i32 = 4;
+const MAX_TRANSLUCENT_SORT_TASKS: usize = 32;
+
+/// Unsorted faces of a translucent layer and the camera cell, relative to
+/// the chunk origin, they were last uploaded in order for.
+#[derive(Component, Clone)]
+pub(crate) struct TranslucentFaceOrder {
+    faces: Arc<[PackedFace]>,
+    sorted_for: Option<IVec3>,
+}
+
+impl TranslucentFaceOrder {
+    pub(super) fn new(faces: &ChunkMeshFaces) -> Self {
+        Self {
+            faces: Arc::from(faces.as_slice()),
+            sorted_for: None,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub(super) struct TranslucentSortTasks(HashMap<Entity, Task<TranslucentSort>>);
+
+pub(super) struct TranslucentSort {
+    source: Arc<[PackedFace]>,
+    camera_cell: IVec3,
+    faces: Vec<PackedFace>,
+}
+
+/// Squared distance from the centre of `camera_cell` to the centre of the
+/// face, in half blocks so it stays integral. Both are chunk-local.
+pub(super) fn translucent_sort_key(face: PackedFace, camera_cell: IVec3) -> u64 {
+    let cell = IVec3::new(face.x() as i32, face.y() as i32, face.z() as i32);
+    let normal = IVec3::from(Direction::ALL[face.face_direction() as usize]);
+    let face_center = (cell * 2 + IVec3::ONE + normal).as_i64vec3();
+    let camera_center = (camera_cell * 2 + IVec3::ONE).as_i64vec3();
+    (face_center - camera_center).length_squared() as u64
+}
+
+/// Orders faces farthest first, keeping mesh order between equal distances.
+pub(super) fn sort_translucent_faces(faces: &[PackedFace], camera_cell: IVec3) -> Vec<PackedFace> {
+    let mut sorted = faces.to_vec();
+    sorted.sort_by_cached_key(|&face| Reverse(translucent_sort_key(face, camera_cell)));
+    sorted
+}
+
+pub(super) fn start_translucent_sorts(
+    mut tasks: ResMut<TranslucentSortTasks>,
+    task_pool: Res<ChunkTaskPool>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    layers: Query<(Entity, &ChunkMeshLayer, &TranslucentFaceOrder)>,
+) {
+    let Some(camera) = cameras.iter().next() else {
+        return;
+    };
+    let camera_cell = camera.translation().floor().as_ivec3();
+    for (entity, layer, order) in &layers {
+        if tasks.0.len() >= MAX_TRANSLUCENT_SORT_TASKS {
+            break;
+        }
+        let local_camera = camera_cell - layer.origin().as_ivec3();
+        if order.sorted_for == Some(local_camera) || tasks.0.contains_key(&entity) {
+            continue;
+        }
+        let chunk_distance = local_camera
+            .div_euclid(IVec3::splat(CHUNK_ISIZE))
+            .abs()
+            .max_element();
+        if order.sorted_for.is_some() && chunk_distance > TRANSLUCENT_SORT_RADIUS_CHUNKS {
+            continue;
+        }
+
+        let source = Arc::clone(&order.faces);
+        let task = task_pool.spawn(async move {
+            TranslucentSort {
+                faces: sort_translucent_faces(&source, local_camera),
+                source,
+                camera_cell: local_camera,
+            }
+        });
+        tasks.0.insert(entity, task);
+    }
+}
+
+/// Uploads finished orders, dropping ones whose layer was rebuilt meanwhile.
+pub(super) fn finish_translucent_sorts(
+    mut commands: Commands,
+    mut tasks: ResMut<TranslucentSortTasks>,
+    mut layers: Query<&mut TranslucentFaceOrder>,
+) {
+    tasks.0.retain(|&entity, task| {
+        let Some(sort) = check_ready(task) else {
+            return true;
+        };
+        if let Ok(mut order) = layers.get_mut(entity)
+            && Arc::ptr_eq(&order.faces, &sort.source)
+        {
+            order.sorted_for = Some(sort.camera_cell);
+            commands
+                .entity(entity)
+                .insert(ChunkMeshFaces::new(sort.faces));
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::chunk::CHUNK_SIZE;
+
+    fn face(x: u32, y: u32, z: u32, direction: Direction) -> PackedFace {
+        PackedFace::new(x, y, z, direction as u32, 1, 0)
+    }
+
+    #[test]
+    fn sort_key_is_squared_half_block_distance_to_the_face_centre() {
+        let camera = IVec3::new(4, 4, 4);
+
+        // Top of the block below the camera: centre (4.5, 4.0, 4.5).
+        assert_eq!(
+            translucent_sort_key(face(4, 3, 4, Direction::Up), camera),
+            1
+        );
+        // Bottom of that same block, one block further away.
+        assert_eq!(
+            translucent_sort_key(face(4, 3, 4, Direction::Down), camera),
+            9
+        );
+        // Side face two cells along x: centre (6.0, 4.5, 4.5).
+        assert_eq!(
+            translucent_sort_key(face(6, 4, 4, Direction::Left), camera),
+            9
+        );
+    }
+
+    #[test]
+    fn sort_key_handles_cameras_outside_the_chunk() {
+        let face = face(0, 0, 0, Direction::Left);
+        let far = IVec3::new(-40_000, 0, 0);
+
+        assert!(translucent_sort_key(face, far) > translucent_sort_key(face, IVec3::new(-3, 0, 0)));
+    }
+
+    #[test]
+    fn faces_are_ordered_farthest_first() {
+        let faces = (0..CHUNK_SIZE as u32)
+            .map(|z| face(8, 8, z, Direction::Up))
+            .collect::<Vec<_>>();
+
+        let sorted = sort_translucent_faces(&faces, IVec3::new(8, 9, 0));
+
+        let depths = sorted.iter().map(|face| face.z()).collect::<Vec<_>>();
+        assert_eq!(depths, (0..CHUNK_SIZE as u32).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ice_in_front_of_water_is_drawn_after_it() {
+        // Looking along +x through an ice face at x=2 into a water face at x=5.
+        let water = face(5, 0, 0, Direction::Left);
+        let ice = face(2, 0, 0, Direction::Left);
+
+        let sorted = sort_translucent_faces(&[ice, water], IVec3::new(1, 0, 0));
+
+        assert_eq!(sorted, vec![water, ice]);
+    }
+
+    #[test]
+    fn equal_distances_keep_mesh_order() {
+        let camera = IVec3::new(8, 8, 8);
+        let faces = [
+            face(8, 8, 9, Direction::Backward),
+            face(8, 8, 7, Direction::Forward),
+            face(9, 8, 8, Direction::Right),
+            face(7, 8, 8, Direction::Left),
+        ];
+
+        assert_eq!(sort_translucent_faces(&faces, camera), faces.to_vec());
+    }
+}
@@ -4,8 +4,8 @@ use bevy::prelude::Reflect;
 use strum::{Display, EnumCount, EnumString};
 
 use crate::block::{
-    BLOCK_FLAG_FULL_CUBE, BLOCK_FLAG_RENDERED, BLOCK_FLAG_TRANSLUCENT, WATER_RENDER_ID,
-    render_id_for_block,
+    BLOCK_FLAG_FULL_CUBE, BLOCK_FLAG_RENDERED, BLOCK_FLAG_TRANSLUCENT, BlockLightRgb,
+    WATER_RENDER_ID, render_id_for_block,
 };
 use crate::item::Item;
 
@@ -18,7 +18,7 @@ pub struct HotCellMeta {
     pub render_id: u16,
     pub mesh_flags: u8,
     pub light_opacity: u8,
-    pub light_emission: u8,
+    pub light_emission: BlockLightRgb,
     pub fluid_level: u8,
 }
 
@@ -27,7 +27,7 @@ impl HotCellMeta {
         render_id: 0,
         mesh_flags: 0,
         light_opacity: 0,
-        light_emission: 0,
+        light_emission: BlockLightRgb::NONE,
         fluid_level: 0,
     };
 
@@ -36,7 +36,7 @@ impl HotCellMeta {
             render_id: render_id_for_block(block),
             mesh_flags: block.mesh_flags(),
             light_opacity: block.light_opacity(),
-            light_emission: block.light_emission(),
+            light_emission: block.light_color(),
             fluid_level: 0,
         }
     }
@@ -46,7 +46,7 @@ impl HotCellMeta {
             render_id: WATER_RENDER_ID,
             mesh_flags: BLOCK_FLAG_RENDERED | BLOCK_FLAG_TRANSLUCENT,
             light_opacity: 0,
-            light_emission: 0,
+            light_emission: BlockLightRgb::NONE,
             fluid_level: level,
         }
     }
@@ -398,7 +398,7 @@ impl ChunkCell {
     }
 
     #[inline(always)]
-    pub const fn light_emission(self) -> u8 {
+    pub const fn light_emission(self) -> BlockLightRgb {
         self.hot_meta().light_emission
     }
 
@@ -161,7 +161,7 @@ fn chunk_can_set_cells_by_cell_state_id() {
         })
     );
     assert_eq!(chunk.state_id(pos), state);
-    assert_eq!(chunk.hot_meta(pos).light_emission, 15);
+    assert_eq!(chunk.hot_meta(pos).light_emission.level(), 15);
     assert_eq!(
         chunk.set_state(pos, CellStateId(u32::MAX), &CELL_REGISTRY),
         None
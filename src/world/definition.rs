@@ -118,6 +118,12 @@ pub enum GeneratorProfile {
 }
 
 impl GeneratorProfile {
+    pub const ALL: [Self; 3] = [
+        Self::OverworldV1,
+        Self::GrassFloorV1,
+        Self::CenterGlassPlatformV1,
+    ];
+
     /// Stable, human-readable generator family used at persistence boundaries.
     pub const fn family(self) -> &'static str {
         match self {
@@ -132,6 +138,13 @@ impl GeneratorProfile {
             Self::OverworldV1 | Self::GrassFloorV1 | Self::CenterGlassPlatformV1 => 1,
         }
     }
+
+    /// The profile written as `family`, if this build knows it.
+    pub fn from_family(family: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.family() == family)
+    }
 }
 
 /// Immutable configuration for one logical dimension.
@@ -329,6 +342,17 @@ mod tests {
         assert!(profiles.iter().all(|profile| profile.version() == 1));
     }
 
+    #[test]
+    fn generator_profiles_round_trip_through_their_family() {
+        for profile in GeneratorProfile::ALL {
+            assert_eq!(
+                GeneratorProfile::from_family(profile.family()),
+                Some(profile)
+            );
+        }
+        assert_eq!(GeneratorProfile::from_family("amplified"), None);
+    }
+
     #[test]
     fn catalog_uses_the_world_height_and_seeded_overworld_arrival() {
         let metadata = WorldMetadata::with_seed(42).with_height_chunks(3).unwrap();
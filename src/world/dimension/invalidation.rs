@@ -15,6 +15,9 @@ pub(crate) fn apply_chunk_invalidations(
     for column in plan.light_columns() {
         dimension.mark_column_light_pending(column);
     }
+    for edit in plan.light_edits() {
+        dimension.record_light_edit(edit);
+    }
 
     for (position, effects) in plan.chunks() {
         let Some(entity) = dimension.published_chunk_entity(position) else {
@@ -158,5 +161,14 @@ mod tests {
                 .unwrap()
                 .has_pending_collider_rebuild(origin)
         );
+
+        let mut light_edits = |dimension| {
+            app.world_mut()
+                .get_mut::<Dimension>(dimension)
+                .unwrap()
+                .take_unstreamed_light_edits()
+        };
+        assert_eq!(light_edits(active_dimension).len(), 1);
+        assert!(light_edits(other_dimension).is_empty());
     }
 }
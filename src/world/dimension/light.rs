@@ -1,16 +1,27 @@
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use bevy::{platform::collections::HashSet, prelude::*};
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
 
-use crate::world::chunk::{
-    Chunk, ChunkColumn, ChunkHeightmap, ChunkInvalidationPlan, ChunkLight, ChunkNeedsLightRebuild,
-    ChunkPerfCounters, ChunkPos, ChunkPosition, light::ChunkLightRegion,
-    mesh::PreparedChunkMeshLight,
+use crate::world::{
+    chunk::{
+        Chunk, ChunkColumn, ChunkHeightmap, ChunkInvalidationPlan, ChunkLight,
+        ChunkNeedsLightRebuild, ChunkPerfCounters, ChunkPos, ChunkPosition,
+        light::{ChunkLightRegion, LightEdit},
+        mesh::PreparedChunkMeshLight,
+    },
+    definition::ColumnAddress,
+    storage::{ChunkRepository, ColumnContentRevision, LightNeighborhoodStamp, StoredColumnLight},
 };
 
 use super::{
     Active, ChunkTaskPool, ColumnLightBudget, ColumnLoadBudget, DesiredColumnView, Dimension,
-    apply_chunk_invalidations,
+    LoadedColumnContent, apply_chunk_invalidations,
     light_patch::{InitialLightColumnState, LightPatchPlan},
     light_task::{
         FinishedLightPatchTask, LightChunkInputStamp, LightColumnInputStamp, LightCommitBaseline,
@@ -47,6 +58,7 @@ pub(crate) fn rebuild_chunk_light(
     patch_budget: Option<Res<ColumnLightBudget>>,
     load_budget: Option<Res<ColumnLoadBudget>>,
     task_pool: Option<Res<ChunkTaskPool>>,
+    repository: Option<Res<ChunkRepository>>,
 ) {
     let (mut dimension, desired_view) = dimension.into_inner();
     if let Some(task_pool) = task_pool {
@@ -58,12 +70,14 @@ pub(crate) fn rebuild_chunk_light(
             desired_view,
             StreamedLightAdmission::from_budgets(patch_budget.as_deref(), load_budget.as_deref()),
             &task_pool,
+            repository.as_deref(),
         );
     }
 
     if needs_rebuild.is_empty() {
         return;
     }
+    let edits = dimension.take_unstreamed_light_edits();
     let dirty_chunks = dimension
         .iter_published_chunks()
         .filter_map(|(registered_position, entity)| {
@@ -133,8 +147,17 @@ pub(crate) fn rebuild_chunk_light(
         region.insert_boundary_light(position, light);
     }
 
+    let rebuilt_chunks =
+        if !edits.is_empty() && edits_are_contained(&edits, &targets, height_chunks) {
+            if let Some(perf) = perf.as_deref_mut() {
+                perf.light_incremental_updates += 1;
+            }
+            region.rebuild_edits(&edits)
+        } else {
+            region.rebuild()
+        };
     let mut invalidations = ChunkInvalidationPlan::new();
-    for rebuilt in region.rebuild() {
+    for rebuilt in rebuilt_chunks {
         let entity = dimension
             .loaded_chunk_entity(rebuilt.position)
             .expect("rebuilt light target must remain in the active dimension");
@@ -176,6 +199,7 @@ pub(crate) fn cancel_inactive_dimension_light_tasks(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_streamed_column_light(
     commands: &mut Commands,
     mut perf: Option<&mut ChunkPerfCounters>,
@@ -184,7 +208,16 @@ fn process_streamed_column_light(
     desired_view: &DesiredColumnView,
     admission: StreamedLightAdmission,
     task_pool: &ChunkTaskPool,
+    repository: Option<&ChunkRepository>,
 ) {
+    dimension.light_tasks_mut().poll_cache_saves();
+    apply_cached_column_light(
+        commands,
+        perf.as_deref_mut(),
+        dimension,
+        all_chunks,
+        desired_view,
+    );
     cancel_unclaimed_light_task(dimension, perf.as_deref_mut());
     if let Some(finished) = dimension.light_tasks_mut().take_ready() {
         let collect_started = Instant::now();
@@ -195,13 +228,22 @@ fn process_streamed_column_light(
                 FinishedLightPatchDisposition::Cancelled,
             );
         } else {
-            apply_finished_light_patch(
+            let cached = apply_finished_light_patch(
                 commands,
                 perf.as_deref_mut(),
                 dimension,
                 all_chunks,
                 finished,
             );
+            if let Some(repository) = repository {
+                save_cached_column_light(
+                    perf.as_deref_mut(),
+                    dimension,
+                    task_pool,
+                    repository,
+                    cached,
+                );
+            }
         }
         record_light_patch_collect_perf(perf.as_deref_mut(), collect_started.elapsed());
     }
@@ -252,6 +294,21 @@ fn next_light_patch_plan(
     height_chunks: usize,
     admission: StreamedLightAdmission,
 ) -> LightPatchPlan {
+    let edited = LightPatchPlan::build_edit_patch(
+        &dimension.light_edit_columns(),
+        height_chunks,
+        admission.calculation_chunk_budget,
+        |column| {
+            dimension.column_lighting(column) == Some(ColumnLighting::Pending)
+                && dimension.column_exposure(column) == Some(ColumnExposure::Published)
+                && dimension.has_complete_resident_light_neighborhood(column)
+        },
+        |column| dimension.column_lighting(column) == Some(ColumnLighting::Lit),
+    );
+    if !edited.is_empty() {
+        return edited;
+    }
+
     let runtime = LightPatchPlan::build(
         desired_view.visible_columns(),
         height_chunks,
@@ -325,9 +382,11 @@ fn start_light_patch(
     height_chunks: usize,
 ) {
     let snapshot_started = Instant::now();
+    let mut known_revisions = HashMap::with_capacity(plan.calculation_columns().len());
     let mut column_inputs = Vec::with_capacity(plan.calculation_columns().len());
     let mut chunk_inputs = Vec::with_capacity(plan.calculation_chunk_count(height_chunks));
     let mut owned_chunks = Vec::with_capacity(plan.calculation_chunk_count(height_chunks));
+    let edits = dimension.light_edits_in(plan.edit_columns());
     for &column in plan.calculation_columns() {
         let state = dimension
             .resident_column_state(column)
@@ -339,6 +398,9 @@ fn start_light_patch(
                 .expect("lighting calculation column must retain its incarnation"),
             commit_light_revision: plan.commits(column).then(|| state.light_revision()),
         });
+        if let Some(revision) = current_column_content_revision(dimension, all_chunks, column) {
+            known_revisions.insert(column, revision);
+        }
 
         for y in 0..height_chunks as i32 {
             let position = column.chunk(y);
@@ -358,13 +420,16 @@ fn start_light_patch(
                 entity,
                 content_revision: chunk.content_revision(),
             });
+            let baseline = || LightCommitBaseline {
+                light: light.clone(),
+                heightmap: *heightmap,
+            };
+            let commits = plan.commits(column);
             owned_chunks.push(OwnedLightCalculationChunk {
                 position,
                 chunk: chunk.clone(),
-                commit_baseline: plan.commits(column).then(|| LightCommitBaseline {
-                    light: light.clone(),
-                    heightmap: *heightmap,
-                }),
+                commit_baseline: commits.then(baseline),
+                scratch_baseline: (!commits && !edits.is_empty()).then(baseline),
             });
         }
     }
@@ -373,6 +438,7 @@ fn start_light_patch(
     let commit_columns = plan.commit_columns().to_vec();
     let calculation_chunks = plan.calculation_chunk_count(height_chunks);
     let scratch_chunks = plan.scratch_chunk_count(height_chunks);
+    let incremental = !edits.is_empty();
     let ticket = dimension
         .begin_column_light_patch(&commit_columns)
         .expect("prevalidated pending light cores must be claimable atomically");
@@ -383,11 +449,15 @@ fn start_light_patch(
             commit_columns,
             column_inputs,
             chunk_inputs,
-            input: OwnedLightPatchInput::new(height_chunks, owned_chunks),
+            input: OwnedLightPatchInput::new(height_chunks, owned_chunks, known_revisions)
+                .with_edits(edits),
         },
     );
     if let Some(perf) = perf {
         perf.light_patch_runs += 1;
+        if incremental {
+            perf.light_incremental_updates += 1;
+        }
         perf.light_patch_calculation_chunks += calculation_chunks;
         perf.light_patch_max_calculation_chunks = perf
             .light_patch_max_calculation_chunks
@@ -405,12 +475,12 @@ fn apply_finished_light_patch(
     dimension: &mut Dimension,
     all_chunks: &Query<(&ChunkPosition, &Chunk, &ChunkLight, &ChunkHeightmap)>,
     finished: FinishedLightPatchTask,
-) {
+) -> Vec<(ChunkColumn, StoredColumnLight)> {
     let current = light_patch_inputs_are_current(dimension, all_chunks, &finished);
     if !current {
         dimension.cancel_column_light_patch(finished.ticket);
         record_finished_light_patch_perf(perf, &finished, FinishedLightPatchDisposition::Stale);
-        return;
+        return Vec::new();
     }
 
     let committed = dimension
@@ -425,6 +495,7 @@ fn apply_finished_light_patch(
         "lighting authority must preserve the planned commit set"
     );
     record_finished_light_patch_perf(perf, &finished, FinishedLightPatchDisposition::Accepted);
+    dimension.discard_light_edits_near(&finished.commit_columns);
 
     for prepared in finished.result.prepared {
         let entity = dimension
@@ -452,6 +523,113 @@ fn apply_finished_light_patch(
         }
         entity_commands.remove::<ChunkNeedsLightRebuild>();
     }
+
+    for (column, _) in &finished.result.cached {
+        dimension.discard_stored_column_light(*column);
+    }
+    finished.result.cached
+}
+
+fn save_cached_column_light(
+    perf: Option<&mut ChunkPerfCounters>,
+    dimension: &mut Dimension,
+    task_pool: &ChunkTaskPool,
+    repository: &ChunkRepository,
+    cached: Vec<(ChunkColumn, StoredColumnLight)>,
+) {
+    let saves = cached.len();
+    let id = dimension.id();
+    for (column, light) in cached {
+        dimension.light_tasks_mut().start_cache_save(
+            task_pool,
+            repository,
+            ColumnAddress::new(id, column),
+            light,
+        );
+    }
+    if let Some(perf) = perf {
+        perf.light_cache_saves += saves;
+    }
+}
+
+/// Commits staged columns whose cached light still matches the contents of
+/// their whole H1 neighbourhood, without running the solver.
+fn apply_cached_column_light(
+    commands: &mut Commands,
+    perf: Option<&mut ChunkPerfCounters>,
+    dimension: &mut Dimension,
+    all_chunks: &Query<(&ChunkPosition, &Chunk, &ChunkLight, &ChunkHeightmap)>,
+    desired_view: &DesiredColumnView,
+) {
+    let mut hits = 0;
+    let mut misses = 0;
+    for &column in desired_view.visible_columns() {
+        let Some(stored) = dimension
+            .loaded_column_content(column)
+            .and_then(LoadedColumnContent::stored_light)
+        else {
+            continue;
+        };
+        if dimension.column_lighting(column) != Some(ColumnLighting::Pending)
+            || dimension.column_exposure(column) != Some(ColumnExposure::Staged)
+            || !dimension.has_complete_resident_light_neighborhood(column)
+        {
+            continue;
+        }
+        let current = LightNeighborhoodStamp::try_from_fn(column, |dependency| {
+            current_column_content_revision(dimension, all_chunks, dependency)
+        });
+        if current.as_ref() != Some(stored.stamp()) {
+            dimension.discard_stored_column_light(column);
+            misses += 1;
+            continue;
+        }
+
+        let stored = stored.clone();
+        dimension
+            .commit_stored_column_light(column)
+            .expect("validated pending column must accept its cached light");
+        dimension.discard_light_edits_near(&[column]);
+        for (y, (light, padded)) in stored.chunks().iter().zip(stored.padded()).enumerate() {
+            let entity = dimension
+                .loaded_chunk_entity(column.chunk(y as i32))
+                .expect("cached light target must remain loaded");
+            commands
+                .entity(entity)
+                .insert((
+                    light.clone(),
+                    *stored.heightmap(),
+                    PreparedChunkMeshLight::new(Arc::clone(padded)),
+                ))
+                .remove::<ChunkNeedsLightRebuild>();
+        }
+        hits += 1;
+    }
+
+    if let Some(perf) = perf {
+        perf.light_cache_hits += hits;
+        perf.light_cache_misses += misses;
+    }
+}
+
+/// The content revision of a resident column whose chunks are unchanged since
+/// it was loaded.
+fn current_column_content_revision(
+    dimension: &Dimension,
+    all_chunks: &Query<(&ChunkPosition, &Chunk, &ChunkLight, &ChunkHeightmap)>,
+    column: ChunkColumn,
+) -> Option<ColumnContentRevision> {
+    let content = dimension.loaded_column_content(column)?;
+    let revisions = (0..dimension.height().chunks_i32())
+        .map(|y| {
+            let entity = dimension.loaded_chunk_entity(column.chunk(y))?;
+            all_chunks
+                .get(entity)
+                .ok()
+                .map(|(_, chunk, _, _)| chunk.content_revision())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    content.current_revision(revisions)
 }
 
 fn light_patch_inputs_are_current(
@@ -596,13 +774,27 @@ fn light_rebuild_targets(
     targets
 }
 
+/// Whether every chunk the edits can relight is a target with settled light,
+/// which lets the solver update from the edited cells alone.
+fn edits_are_contained(
+    edits: &[LightEdit],
+    targets: &HashSet<ChunkPos>,
+    height_chunks: usize,
+) -> bool {
+    edits.iter().all(|edit| {
+        ChunkColumn::from(edit.address.chunk())
+            .chebyshev_neighborhood(1)
+            .all(|column| (0..height_chunks as i32).all(|y| targets.contains(&column.chunk(y))))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         item::Item,
         world::{
-            chunk::{CHUNK_SIZE, ChunkCell, ChunkNeedsLightRebuild, LocalBlockPos},
+            chunk::{CHUNK_SIZE, ChunkBlockPos, ChunkCell, ChunkNeedsLightRebuild, LocalBlockPos},
             generation::WorldMetadata,
         },
     };
@@ -702,6 +894,103 @@ mod tests {
         assert!(world.get::<ChunkNeedsLightRebuild>(lower_entity).is_none());
     }
 
+    #[test]
+    fn contained_edits_update_light_incrementally_and_match_a_full_rebuild() {
+        let mut app = app_with_light_system(1);
+        app.init_resource::<ChunkPerfCounters>();
+        let center = ChunkPos::new(0, 0, 0);
+        let mut entities = Vec::new();
+        for column in ChunkColumn::from(center).chebyshev_neighborhood(1) {
+            let position = column.chunk(0);
+            let mut chunk = Chunk::default();
+            for x in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    chunk.set_cell_xyz(x, 0, z, Item::Stone.into());
+                    if position == center {
+                        chunk.set_cell_xyz(x, 12, z, Item::Stone.into());
+                    }
+                }
+            }
+            let entity = app
+                .world_mut()
+                .spawn((
+                    ChunkPosition::from(position.as_ivec3()),
+                    chunk,
+                    ChunkLight::default(),
+                    ChunkHeightmap::default(),
+                    ChunkNeedsLightRebuild,
+                ))
+                .id();
+            register_chunk(&mut app, position.as_ivec3(), entity);
+            entities.push((position, entity));
+        }
+        app.update();
+        assert_eq!(
+            app.world()
+                .resource::<ChunkPerfCounters>()
+                .light_incremental_updates,
+            0
+        );
+
+        let center_entity = entities[4].1;
+        let edits = [
+            (LocalBlockPos::new(8, 4, 8), Item::Glowstone.into()),
+            (LocalBlockPos::new(0, 12, 3), ChunkCell::EMPTY),
+        ];
+        for (local, cell) in edits {
+            let delta = app
+                .world_mut()
+                .get_mut::<Chunk>(center_entity)
+                .unwrap()
+                .set_cell(local.as_uvec3(), cell);
+            let dimension = app.world().resource::<TestDimension>().0;
+            app.world_mut()
+                .get_mut::<Dimension>(dimension)
+                .unwrap()
+                .record_light_edit(LightEdit {
+                    address: ChunkBlockPos::new(center, local),
+                    old: delta.old,
+                });
+        }
+        for &(_, entity) in &entities {
+            app.world_mut()
+                .entity_mut(entity)
+                .insert(ChunkNeedsLightRebuild);
+        }
+        app.update();
+
+        let world = app.world();
+        assert_eq!(
+            world
+                .resource::<ChunkPerfCounters>()
+                .light_incremental_updates,
+            1
+        );
+        let chunks = entities
+            .iter()
+            .map(|&(position, entity)| (position, world.get::<Chunk>(entity).unwrap().clone()))
+            .collect::<Vec<_>>();
+        let (empty_light, empty_heightmap) = (ChunkLight::default(), ChunkHeightmap::default());
+        let mut region = ChunkLightRegion::new(1);
+        for (position, chunk) in &chunks {
+            region.insert_target(*position, chunk, &empty_light, &empty_heightmap);
+        }
+        let expected = region.rebuild();
+        assert_eq!(expected.len(), entities.len());
+        for rebuilt in expected {
+            let entity = entities
+                .iter()
+                .find(|(position, _)| *position == rebuilt.position)
+                .unwrap()
+                .1;
+            assert_eq!(world.get::<ChunkLight>(entity), Some(&rebuilt.light));
+            assert_eq!(
+                world.get::<ChunkHeightmap>(entity),
+                Some(&rebuilt.heightmap)
+            );
+        }
+    }
+
     #[test]
     fn changed_light_marks_padded_neighbor_light_upload_dirty() {
         let mut app = app_with_light_system(1);
@@ -27,6 +27,7 @@ impl Default for ColumnLightBudget {
 pub(crate) struct LightPatchPlan {
     commit_columns: Vec<ChunkColumn>,
     calculation_columns: Vec<ChunkColumn>,
+    edit_columns: Vec<ChunkColumn>,
 }
 
 /// How a visible column participates in dependency-complete initial lighting.
@@ -207,6 +208,70 @@ impl LightPatchPlan {
         Self {
             commit_columns,
             calculation_columns,
+            edit_columns: Vec::new(),
+        }
+    }
+
+    /// Builds one patch that updates light around block edits instead of
+    /// relighting their columns.
+    ///
+    /// Edits whose H1 neighbourhoods come within one column of each other form
+    /// an indivisible group that commits exactly the union of those
+    /// neighbourhoods. Its calculation adds one scratch ring, which must be
+    /// settled because the update starts from its current light. The first
+    /// ready group is admitted even when it exceeds the target budget.
+    pub(crate) fn build_edit_patch(
+        edited_columns: &[ChunkColumn],
+        height_chunks: usize,
+        target_budget: usize,
+        mut is_pending: impl FnMut(ChunkColumn) -> bool,
+        mut is_settled: impl FnMut(ChunkColumn) -> bool,
+    ) -> Self {
+        if target_budget == 0 {
+            return Self::default();
+        }
+
+        let mut commit_set = HashSet::new();
+        let mut calculation_set = HashSet::new();
+        let mut edit_columns = Vec::new();
+        for group in edit_groups(edited_columns) {
+            let commits = group
+                .iter()
+                .flat_map(|column| column.chebyshev_neighborhood(1))
+                .collect::<HashSet<_>>();
+            let calculation = commits
+                .iter()
+                .flat_map(|column| column.chebyshev_neighborhood(1))
+                .collect::<HashSet<_>>();
+            let ready = commits.iter().all(|&column| is_pending(column))
+                && calculation
+                    .iter()
+                    .filter(|column| !commits.contains(column))
+                    .all(|&column| is_settled(column));
+            if !ready {
+                continue;
+            }
+            let target_chunks = calculation_set
+                .union(&calculation)
+                .count()
+                .saturating_mul(height_chunks);
+            if !edit_columns.is_empty() && target_chunks > target_budget {
+                continue;
+            }
+            commit_set.extend(commits);
+            calculation_set.extend(calculation);
+            edit_columns.extend(group);
+        }
+
+        let mut commit_columns = commit_set.into_iter().collect::<Vec<_>>();
+        commit_columns.sort_unstable_by_key(|column| (column.z(), column.x()));
+        let mut calculation_columns = calculation_set.into_iter().collect::<Vec<_>>();
+        calculation_columns.sort_unstable_by_key(|column| (column.z(), column.x()));
+        edit_columns.sort_unstable_by_key(|column| (column.z(), column.x()));
+        Self {
+            commit_columns,
+            calculation_columns,
+            edit_columns,
         }
     }
 
@@ -222,6 +287,12 @@ impl LightPatchPlan {
         &self.calculation_columns
     }
 
+    /// Columns whose recorded block edits this patch updates from. Empty for a
+    /// relight.
+    pub(crate) fn edit_columns(&self) -> &[ChunkColumn] {
+        &self.edit_columns
+    }
+
     pub(crate) fn commits(&self, column: ChunkColumn) -> bool {
         self.commit_columns.contains(&column)
     }
@@ -234,6 +305,30 @@ impl LightPatchPlan {
         (self.calculation_columns.len() - self.commit_columns.len()) * height_chunks
     }
 
+    /// Splits commit columns into initial-light tiles anchored to the world
+    /// chunk grid, each with its complete H1 calculation closure.
+    ///
+    /// Tiles are ordered by Z and then X so a caller walking them in order can
+    /// drop columns that lie more than one column behind the current tile row.
+    pub(crate) fn grid_tiles(commit_columns: impl IntoIterator<Item = ChunkColumn>) -> Vec<Self> {
+        let mut tiles = HashMap::<InitialLightTile, Vec<ChunkColumn>>::new();
+        for column in commit_columns {
+            tiles
+                .entry(InitialLightTile::containing(column))
+                .or_default()
+                .push(column);
+        }
+        let mut tiles = tiles.into_iter().collect::<Vec<_>>();
+        tiles.sort_unstable_by_key(|(tile, _)| (tile.z, tile.x));
+        tiles
+            .into_iter()
+            .map(|(_, mut columns)| {
+                columns.sort_unstable_by_key(|column| (column.z(), column.x()));
+                Self::from_initial_commits(columns)
+            })
+            .collect()
+    }
+
     fn from_initial_commits(commit_columns: Vec<ChunkColumn>) -> Self {
         let mut calculation_columns = commit_columns
             .iter()
@@ -245,8 +340,34 @@ impl LightPatchPlan {
         Self {
             commit_columns,
             calculation_columns,
+            edit_columns: Vec::new(),
+        }
+    }
+}
+
+/// Groups edited columns whose scratch ring would otherwise overlap another
+/// group's commits, preserving the order of each group's first column.
+fn edit_groups(edited_columns: &[ChunkColumn]) -> Vec<Vec<ChunkColumn>> {
+    let mut groups = Vec::<Vec<ChunkColumn>>::new();
+    for &column in edited_columns {
+        let mut merged = vec![column];
+        let mut first = None;
+        let mut index = 0;
+        while index < groups.len() {
+            if groups[index]
+                .iter()
+                .any(|&other| column_chebyshev_distance(column, other) <= 3)
+            {
+                merged.extend(groups.remove(index));
+                first.get_or_insert(index);
+            } else {
+                index += 1;
+            }
         }
+        let at = first.unwrap_or(groups.len());
+        groups.insert(at, merged);
     }
+    groups
 }
 
 fn column_chebyshev_distance(left: ChunkColumn, right: ChunkColumn) -> i64 {
@@ -585,4 +706,60 @@ mod tests {
         assert_eq!(plan.commit_columns(), &[first]);
         assert!(!plan.commits(disconnected));
     }
+
+    #[test]
+    fn edit_patch_commits_each_edit_neighbourhood_with_a_settled_scratch_ring() {
+        let edited = ChunkColumn::new(-1, 4);
+        let near = ChunkColumn::new(2, 4);
+        let far = ChunkColumn::new(9, 4);
+        let pending = [edited, near, far]
+            .iter()
+            .flat_map(|column| column.chebyshev_neighborhood(1))
+            .collect::<HashSet<_>>();
+        let plan = LightPatchPlan::build_edit_patch(
+            &[edited, near, far],
+            5,
+            1,
+            |column| pending.contains(&column),
+            |column| !pending.contains(&column),
+        );
+
+        assert_eq!(plan.edit_columns(), &[edited, near]);
+        assert_eq!(plan.commit_columns(), rectangle(-2, 3, 3, 5));
+        assert_eq!(plan.calculation_columns(), rectangle(-3, 4, 2, 6));
+
+        let unsettled = ChunkColumn::new(1, 6);
+        let blocked = LightPatchPlan::build_edit_patch(
+            &[edited, near],
+            5,
+            usize::MAX,
+            |column| pending.contains(&column),
+            |column| column != unsettled && !pending.contains(&column),
+        );
+        assert!(blocked.is_empty());
+        assert!(LightPatchPlan::build_edit_patch(&[edited], 5, 0, |_| true, |_| true).is_empty());
+    }
+
+    #[test]
+    fn grid_tiles_split_commits_on_world_tile_boundaries_in_row_order() {
+        let columns = rectangle(-1, 6, 4, 7);
+        let tiles = LightPatchPlan::grid_tiles(columns.iter().rev().copied());
+
+        let commits = tiles
+            .iter()
+            .map(|tile| tile.commit_columns().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commits,
+            vec![
+                rectangle(-1, -1, 4, 5),
+                rectangle(0, 5, 4, 5),
+                rectangle(6, 6, 4, 5),
+                rectangle(-1, -1, 6, 7),
+                rectangle(0, 5, 6, 7),
+                rectangle(6, 6, 6, 7),
+            ]
+        );
+        assert_eq!(tiles[1].calculation_columns(), rectangle(-1, 6, 3, 6));
+    }
 }
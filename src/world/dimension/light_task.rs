@@ -4,14 +4,20 @@ use std::{
 };
 
 use bevy::{
+    log::warn,
     platform::collections::HashMap,
     prelude::Entity,
     tasks::{Task, futures::check_ready},
 };
 
-use crate::world::chunk::{
-    Chunk, ChunkColumn, ChunkHeightmap, ChunkLight, ChunkPos, ChunkRevision,
-    light::RebuiltChunkLight, mesh::ChunkMeshLight,
+use crate::world::{
+    chunk::{
+        Chunk, ChunkColumn, ChunkHeightmap, ChunkLight, ChunkPos, ChunkRevision,
+        light::{LightEdit, RebuiltChunkLight},
+        mesh::ChunkMeshLight,
+    },
+    definition::ColumnAddress,
+    storage::{ChunkRepository, ColumnContentRevision, LightNeighborhoodStamp, StoredColumnLight},
 };
 
 use super::{
@@ -22,21 +28,41 @@ use super::{
 pub(crate) struct OwnedLightPatchInput {
     height_chunks: usize,
     chunks: Vec<OwnedLightCalculationChunk>,
+    /// Content revisions already known for unchanged calculation columns. The
+    /// worker derives the rest from the snapshot to stamp cached light.
+    known_revisions: HashMap<ChunkColumn, ColumnContentRevision>,
+    /// Block edits since the calculation chunks' baselines were settled. When
+    /// present the worker updates from them instead of relighting.
+    edits: Vec<LightEdit>,
 }
 
 impl OwnedLightPatchInput {
-    pub(crate) fn new(height_chunks: usize, chunks: Vec<OwnedLightCalculationChunk>) -> Self {
+    pub(crate) fn new(
+        height_chunks: usize,
+        chunks: Vec<OwnedLightCalculationChunk>,
+        known_revisions: HashMap<ChunkColumn, ColumnContentRevision>,
+    ) -> Self {
         Self {
             height_chunks,
             chunks,
+            known_revisions,
+            edits: Vec::new(),
         }
     }
+
+    /// Updates light from `edits`, which requires every scratch chunk to carry
+    /// its settled light.
+    pub(crate) fn with_edits(mut self, edits: Vec<LightEdit>) -> Self {
+        self.edits = edits;
+        self
+    }
 }
 
 pub(crate) struct OwnedLightCalculationChunk {
     pub(crate) position: ChunkPos,
     pub(crate) chunk: Chunk,
     pub(crate) commit_baseline: Option<LightCommitBaseline>,
+    pub(crate) scratch_baseline: Option<LightCommitBaseline>,
 }
 
 pub(crate) struct LightCommitBaseline {
@@ -74,6 +100,7 @@ pub(crate) struct PreparedLightPayload {
 pub(crate) struct SolvedLightPatch {
     pub(crate) rebuilt: Vec<RebuiltChunkLight>,
     pub(crate) prepared: Vec<PreparedLightPayload>,
+    pub(crate) cached: Vec<(ChunkColumn, StoredColumnLight)>,
     pub(crate) elapsed: Duration,
     pub(crate) solve_elapsed: Duration,
     pub(crate) prepare_elapsed: Duration,
@@ -104,6 +131,7 @@ pub(crate) struct FinishedLightPatchTask {
 pub(crate) struct DimensionLightTasks {
     active: Option<ActiveLightPatchTask>,
     cancelled_since_last_take: usize,
+    cache_saves: Vec<Task<()>>,
 }
 
 impl DimensionLightTasks {
@@ -172,6 +200,34 @@ impl DimensionLightTasks {
         std::mem::take(&mut self.cancelled_since_last_take)
     }
 
+    /// Writes solved light to the store's light cache in the background.
+    ///
+    /// Failures only cost a relight on the next load, so they are logged
+    /// instead of retried.
+    pub(crate) fn start_cache_save(
+        &mut self,
+        task_pool: &ChunkTaskPool,
+        repository: &ChunkRepository,
+        address: ColumnAddress,
+        light: StoredColumnLight,
+    ) {
+        let repository = repository.clone();
+        self.cache_saves.push(task_pool.spawn(async move {
+            if let Err(error) = repository.save_column_light(address, &light) {
+                warn!(%error, ?address, "Failed to cache column light");
+            }
+        }));
+    }
+
+    pub(crate) fn poll_cache_saves(&mut self) {
+        self.cache_saves
+            .retain_mut(|task| check_ready(task).is_none());
+    }
+
+    pub(crate) fn pending_light_cache_saves(&self) -> usize {
+        self.cache_saves.len()
+    }
+
     pub(crate) fn take_ready(&mut self) -> Option<FinishedLightPatchTask> {
         let active = self.active.as_mut()?;
         let result = check_ready(&mut active.task)?;
@@ -191,7 +247,10 @@ impl DimensionLightTasks {
     }
 }
 
-fn solve_light_patch(input: OwnedLightPatchInput, queue_elapsed: Duration) -> SolvedLightPatch {
+pub(crate) fn solve_light_patch(
+    input: OwnedLightPatchInput,
+    queue_elapsed: Duration,
+) -> SolvedLightPatch {
     let started = Instant::now();
     let mut region = crate::world::chunk::light::ChunkLightRegion::new(input.height_chunks);
     for chunk in &input.chunks {
@@ -199,10 +258,17 @@ fn solve_light_patch(input: OwnedLightPatchInput, queue_elapsed: Duration) -> So
         if let Some(baseline) = &chunk.commit_baseline {
             region.mark_commit_target(chunk.position, &baseline.light, &baseline.heightmap);
         }
+        if let Some(baseline) = &chunk.scratch_baseline {
+            region.mark_settled_scratch(chunk.position, &baseline.light, &baseline.heightmap);
+        }
     }
 
     let solve_started = Instant::now();
-    let solved = region.solve();
+    let solved = if input.edits.is_empty() {
+        region.solve()
+    } else {
+        region.solve_edits(&input.edits)
+    };
     let solve_elapsed = solve_started.elapsed();
     let prepare_started = Instant::now();
     let prepared = {
@@ -222,13 +288,76 @@ fn solve_light_patch(input: OwnedLightPatchInput, queue_elapsed: Duration) -> So
     };
     let prepare_elapsed = prepare_started.elapsed();
     let rebuilt = solved.into_committed();
+    let cached = stamp_committed_light(&input, &rebuilt, &prepared);
 
     SolvedLightPatch {
         rebuilt,
         prepared,
+        cached,
         elapsed: started.elapsed(),
         solve_elapsed,
         prepare_elapsed,
         queue_elapsed,
     }
 }
+
+/// Packages committed light per column with the content revisions of its H1
+/// neighbourhood, all of which are calculation columns of the same patch.
+fn stamp_committed_light(
+    input: &OwnedLightPatchInput,
+    rebuilt: &[RebuiltChunkLight],
+    prepared: &[PreparedLightPayload],
+) -> Vec<(ChunkColumn, StoredColumnLight)> {
+    let padded = prepared
+        .iter()
+        .map(|payload| (payload.position, &payload.data))
+        .collect::<HashMap<_, _>>();
+    let mut columns = HashMap::<ChunkColumn, Vec<&Chunk>>::new();
+    for chunk in &input.chunks {
+        columns
+            .entry(chunk.position.column())
+            .or_default()
+            .push(&chunk.chunk);
+    }
+    let revisions = columns
+        .into_iter()
+        .map(|(column, chunks)| {
+            let revision = input
+                .known_revisions
+                .get(&column)
+                .copied()
+                .unwrap_or_else(|| ColumnContentRevision::of_chunks(chunks));
+            (column, revision)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut committed = HashMap::<ChunkColumn, Vec<&RebuiltChunkLight>>::new();
+    for chunk in rebuilt {
+        committed
+            .entry(chunk.position.column())
+            .or_default()
+            .push(chunk);
+    }
+    committed
+        .into_iter()
+        .filter_map(|(column, mut chunks)| {
+            chunks.sort_unstable_by_key(|chunk| chunk.position.y());
+            if chunks.len() != input.height_chunks {
+                return None;
+            }
+            let stamp = LightNeighborhoodStamp::try_from_fn(column, |dependency| {
+                revisions.get(&dependency).copied()
+            })?;
+            let light = StoredColumnLight::new(
+                stamp,
+                chunks[0].heightmap,
+                chunks.iter().map(|chunk| chunk.light.clone()).collect(),
+                chunks
+                    .iter()
+                    .map(|chunk| Arc::clone(padded[&chunk.position]))
+                    .collect(),
+            );
+            Some((column, light))
+        })
+        .collect()
+}
@@ -12,6 +12,8 @@ mod light;
 mod light_patch;
 mod light_task;
 mod persistence;
+mod pregeneration;
+mod region_edit;
 mod streaming;
 mod switching;
 mod view;
@@ -33,7 +35,9 @@ use self::{
     fluid::DimensionFluidPlugin,
     light::{cancel_inactive_dimension_light_tasks, rebuild_chunk_light},
     light_task::DimensionLightTasks,
-    persistence::{ChunkSaveBudget, finish_chunk_save_tasks, start_chunk_save_tasks},
+    persistence::{
+        ChunkSaveBudget, finish_chunk_save_tasks, flush_chunk_saves, start_chunk_save_tasks,
+    },
     streaming::{
         ColumnExposure, ColumnLightRevision, ColumnLighting, DimensionStreamState,
         LightPatchTicket, ResidentColumnState, finish_column_loads, maintain_column_residency,
@@ -41,9 +45,11 @@ use self::{
     },
 };
 use super::{
-    chunk::{ChunkColumn, ChunkPos},
+    chunk::{ChunkBlockPos, ChunkCell, ChunkColumn, ChunkPos, ChunkRevision, light::LightEdit},
     definition::{DimensionCatalog, DimensionDefinition, DimensionId},
     generation::WorldHeight,
+    session::WorldSessionSystems,
+    storage::{ChunkRepository, ColumnContentRevision, StoredColumnLight},
 };
 
 #[cfg(test)]
@@ -53,11 +59,18 @@ pub(crate) use self::derived_work::ChunkDerivedWork;
 pub(crate) use self::persistence::ChunkSaveTasks;
 pub use self::{
     light_patch::ColumnLightBudget,
+    pregeneration::{PregenerationProgress, PregenerationRequest, pregenerate_columns},
+    region_edit::{
+        BlockRegion, RegionEdit, RegionEditChunks, RegionEditError, RegionEditReport,
+        apply_region_edit,
+    },
     streaming::{ColumnActivationBudget, ColumnLoadBudget, ColumnStagingBudget},
+    switching::DimensionSwitchRequest,
     view::{DesiredColumnView, ViewDistance},
 };
 pub(crate) use invalidation::apply_chunk_invalidations;
 pub(crate) use streaming::{ColumnEvictionTicket, ColumnLoadTaskStats, ColumnLoadTicket};
+pub(crate) use view::columns_in_radius;
 
 /// Update-phase boundary after dimension streaming and publication complete.
 ///
@@ -108,11 +121,53 @@ pub struct Dimension {
     stream: DimensionStreamState,
     light_tasks: DimensionLightTasks,
     derived_work: DimensionDerivedWork,
+    /// Light-affecting cell edits since the last synchronous light rebuild,
+    /// each with the cell it replaced.
+    light_edits: HashMap<ChunkBlockPos, ChunkCell>,
 }
 
 #[derive(Debug)]
 struct LoadedColumnHandle {
     incarnation: Entity,
+    content: LoadedColumnContent,
+}
+
+/// Block content identity and cached light captured when a column was loaded.
+#[derive(Debug)]
+pub(crate) struct LoadedColumnContent {
+    revision: ColumnContentRevision,
+    chunk_revisions: Vec<ChunkRevision>,
+    stored_light: Option<StoredColumnLight>,
+}
+
+impl LoadedColumnContent {
+    pub(crate) fn new(
+        revision: ColumnContentRevision,
+        chunk_revisions: Vec<ChunkRevision>,
+        stored_light: Option<StoredColumnLight>,
+    ) -> Self {
+        Self {
+            revision,
+            chunk_revisions,
+            stored_light,
+        }
+    }
+
+    /// Returns the load-time content revision while every chunk still has the
+    /// runtime revision it was installed with.
+    pub(crate) fn current_revision(
+        &self,
+        chunk_revisions: impl IntoIterator<Item = ChunkRevision>,
+    ) -> Option<ColumnContentRevision> {
+        chunk_revisions
+            .into_iter()
+            .eq(self.chunk_revisions.iter().copied())
+            .then_some(self.revision)
+    }
+
+    pub(crate) const fn stored_light(&self) -> Option<&StoredColumnLight> {
+        self.stored_light.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -137,6 +192,7 @@ impl Dimension {
             stream: DimensionStreamState::new(owner),
             light_tasks: DimensionLightTasks::default(),
             derived_work: DimensionDerivedWork::new(),
+            light_edits: HashMap::default(),
         }
     }
 
@@ -296,6 +352,11 @@ impl Dimension {
         self.published_chunks.len()
     }
 
+    /// Cached column light writes that are still in flight.
+    pub fn pending_light_cache_saves(&self) -> usize {
+        self.light_tasks.pending_light_cache_saves()
+    }
+
     pub fn chunk_registry_capacity(&self) -> usize {
         self.loaded_chunks.capacity() + self.published_chunks.capacity()
     }
@@ -414,6 +475,63 @@ impl Dimension {
     /// Durable save obligations live outside this queue and are unaffected.
     pub(crate) fn clear_disposable_work(&mut self) {
         self.derived_work.clear();
+        self.light_edits.clear();
+    }
+
+    /// Keeps the earliest old cell when the same address is edited again
+    /// before the light rebuild runs.
+    pub(crate) fn record_light_edit(&mut self, edit: LightEdit) {
+        self.light_edits.entry(edit.address).or_insert(edit.old);
+    }
+
+    /// Drains the edits of columns outside the streamed ledger. Streamed
+    /// columns keep theirs for their column light patches.
+    pub(crate) fn take_unstreamed_light_edits(&mut self) -> Vec<LightEdit> {
+        let mut edits = Vec::new();
+        let stream = &self.stream;
+        self.light_edits.retain(|&address, &mut old| {
+            if stream.resident_state(address.chunk().column()).is_some() {
+                return true;
+            }
+            edits.push(LightEdit { address, old });
+            false
+        });
+        edits
+    }
+
+    /// Columns holding recorded light edits, ordered by Z and then X.
+    pub(crate) fn light_edit_columns(&self) -> Vec<ChunkColumn> {
+        let mut columns = self
+            .light_edits
+            .keys()
+            .map(|address| address.chunk().column())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        columns.sort_unstable_by_key(|column| (column.z(), column.x()));
+        columns
+    }
+
+    pub(crate) fn light_edits_in(&self, columns: &[ChunkColumn]) -> Vec<LightEdit> {
+        self.light_edits
+            .iter()
+            .filter(|(address, _)| columns.contains(&address.chunk().column()))
+            .map(|(&address, &old)| LightEdit { address, old })
+            .collect()
+    }
+
+    /// Forgets the edits that committed light in `columns` already accounts
+    /// for: each edit only changes light within its own H1 neighbourhood.
+    pub(crate) fn discard_light_edits_near(&mut self, columns: &[ChunkColumn]) {
+        if self.light_edits.is_empty() || columns.is_empty() {
+            return;
+        }
+        let near = columns
+            .iter()
+            .flat_map(|column| column.chebyshev_neighborhood(1))
+            .collect::<HashSet<_>>();
+        self.light_edits
+            .retain(|address, _| !near.contains(&address.chunk().column()));
     }
 
     fn enqueue_published_derived_work(
@@ -508,8 +626,10 @@ impl Dimension {
         ticket: ColumnLoadTicket,
         incarnation: Entity,
         entities: Vec<Entity>,
+        content: LoadedColumnContent,
     ) {
         assert_eq!(entities.len(), self.height().chunks());
+        assert_eq!(content.chunk_revisions.len(), self.height().chunks());
         assert_eq!(ticket.owner(), self.stream.owner());
         assert!(
             (0..self.height().chunks_i32())
@@ -531,8 +651,13 @@ impl Dimension {
                 .insert(ticket.column().chunk(y as i32), entity);
             debug_assert!(previous.is_none());
         }
-        self.loaded_columns
-            .insert(ticket.column(), LoadedColumnHandle { incarnation });
+        self.loaded_columns.insert(
+            ticket.column(),
+            LoadedColumnHandle {
+                incarnation,
+                content,
+            },
+        );
     }
 
     fn expose_loaded_column(&mut self, column: ChunkColumn) -> bool {
@@ -654,6 +779,39 @@ impl Dimension {
         true
     }
 
+    pub(crate) fn loaded_column_content(
+        &self,
+        column: ChunkColumn,
+    ) -> Option<&LoadedColumnContent> {
+        self.loaded_columns
+            .get(&column)
+            .map(|handle| &handle.content)
+    }
+
+    /// Drops a column's cached light once it has been applied or can no
+    /// longer match, so staged columns do not retain it while resident.
+    pub(crate) fn discard_stored_column_light(&mut self, column: ChunkColumn) -> bool {
+        self.loaded_columns
+            .get_mut(&column)
+            .and_then(|handle| handle.content.stored_light.take())
+            .is_some()
+    }
+
+    /// Marks a pending column lit from validated cached light without running
+    /// the solver, through the same authority transition as a solved patch.
+    pub(crate) fn commit_stored_column_light(
+        &mut self,
+        column: ChunkColumn,
+    ) -> Option<ColumnLightRevision> {
+        let ticket = self.stream.begin_light_patch(&[column])?;
+        let committed = self
+            .stream
+            .finish_light_patch(ticket)
+            .expect("freshly claimed single-column light patch must commit");
+        self.discard_stored_column_light(column);
+        committed.into_iter().next().map(|(_, revision)| revision)
+    }
+
     pub(crate) fn column_incarnation(&self, column: ChunkColumn) -> Option<Entity> {
         self.loaded_columns
             .get(&column)
@@ -790,6 +948,7 @@ impl Plugin for DimensionPlugin {
             .init_resource::<ChunkSaveBudget>()
             .init_resource::<ChunkSaveTasks>()
             .init_resource::<ViewDistance>()
+            .init_resource::<StartingDimension>()
             .add_plugins(DimensionFluidPlugin);
 
         app.add_systems(
@@ -822,16 +981,41 @@ impl Plugin for DimensionPlugin {
         );
         app.add_systems(
             PostUpdate,
-            (finish_chunk_save_tasks, start_chunk_save_tasks).chain(),
+            (finish_chunk_save_tasks, start_chunk_save_tasks)
+                .chain()
+                .run_if(resource_exists::<ChunkRepository>),
+        );
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            (
+                flush_chunk_saves.in_set(WorldSessionSystems::Save),
+                despawn_dimensions.in_set(WorldSessionSystems::Close),
+            ),
         );
         switching::install(app);
     }
 }
 
-fn setup(mut commands: Commands, catalog: Res<DimensionCatalog>) {
+/// The dimension made active when a world loads. A saved player position
+/// still decides where that player spawns.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartingDimension(pub DimensionId);
+
+impl Default for StartingDimension {
+    fn default() -> Self {
+        Self(DimensionId::OVERWORLD)
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    catalog: Res<DimensionCatalog>,
+    starting: Option<Res<StartingDimension>>,
+) {
+    let starting = starting.map_or(DimensionId::OVERWORLD, |starting| starting.0);
     for &definition in catalog.definitions() {
         let entity = commands.spawn_empty().id();
-        let active = definition.id() == DimensionId::OVERWORLD;
+        let active = definition.id() == starting;
         let mut root = commands.entity(entity);
         root.insert(Dimension::root_components(entity, definition));
         if active {
@@ -845,6 +1029,13 @@ fn setup(mut commands: Commands, catalog: Res<DimensionCatalog>) {
 #[derive(Component)]
 pub struct Active;
 
+/// Despawns every dimension root with its columns and chunks.
+fn despawn_dimensions(mut commands: Commands, roots: Query<Entity, With<Dimension>>) {
+    for root in &roots {
+        commands.entity(root).despawn();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -904,6 +1095,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn setup_activates_the_starting_dimension() {
+        let catalog = DimensionCatalog::for_world(&crate::world::WorldMetadata::default());
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(catalog)
+            .insert_resource(StartingDimension(DimensionId::GRASS_FLOOR))
+            .add_systems(Update, setup);
+
+        app.update();
+
+        let mut active = app.world_mut().query_filtered::<&Dimension, With<Active>>();
+        let active = active
+            .iter(app.world())
+            .map(Dimension::id)
+            .collect::<Vec<_>>();
+        assert_eq!(active, [DimensionId::GRASS_FLOOR]);
+    }
+
     #[test]
     fn dimension_registry_retains_typed_chunk_positions() {
         let position = ChunkPos::new(-5, 2, 9);
@@ -969,6 +1179,11 @@ mod tests {
             column,
             LoadedColumnHandle {
                 incarnation: Entity::from_bits(10),
+                content: LoadedColumnContent::new(
+                    ColumnContentRevision::from_raw(0),
+                    vec![ChunkRevision::INITIAL; height.chunks()],
+                    None,
+                ),
             },
         );
         for (y, entity) in entities.into_iter().enumerate() {
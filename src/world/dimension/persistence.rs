@@ -3,7 +3,7 @@ use std::{mem::size_of, sync::Arc};
 use bevy::{
     platform::collections::HashMap,
     prelude::*,
-    tasks::{Task, futures::check_ready},
+    tasks::{Task, block_on, futures::check_ready},
 };
 
 use super::{Active, ChunkTaskPool, DesiredColumnView, Dimension};
@@ -440,6 +440,60 @@ pub(crate) fn start_chunk_save_tasks(
     }
 }
 
+/// Writes every dirty chunk of every dimension before the world closes.
+///
+/// In-flight saves are awaited first, so an older snapshot can never land on
+/// top of the newer pending one written after it. A failed in-flight save is
+/// retried here unless a newer pending snapshot replaces it.
+pub(crate) fn flush_chunk_saves(
+    dimensions: Query<(Entity, &Dimension)>,
+    chunks: Query<(
+        &ChunkPosition,
+        &Chunk,
+        &ChunkHeightmap,
+        Option<&ChunkNeedsSave>,
+    )>,
+    repository: Option<Res<ChunkRepository>>,
+    mut save_tasks: ResMut<ChunkSaveTasks>,
+) {
+    let Some(repository) = repository else {
+        return;
+    };
+    for (owner, dimension) in &dimensions {
+        capture_dimension_save_snapshots(
+            &mut save_tasks,
+            dimension,
+            SaveSnapshotContext::Detached,
+            owner,
+            &chunks,
+        );
+    }
+
+    let ChunkSaveTasks {
+        in_flight, pending, ..
+    } = std::mem::take(&mut *save_tasks);
+    let mut retries = Vec::new();
+    for (address, in_flight) in in_flight {
+        let Err(error) = block_on(in_flight.task) else {
+            continue;
+        };
+        error!(%error, ?address, "In-flight chunk save failed while closing the world");
+        if !pending.contains_key(&address) {
+            retries.push((address, in_flight.snapshot));
+        }
+    }
+    let snapshots = retries.into_iter().chain(
+        pending
+            .into_iter()
+            .map(|(address, pending)| (address, pending.snapshot)),
+    );
+    for (address, snapshot) in snapshots {
+        if let Err(error) = save_chunk_snapshot(address, &snapshot, &repository) {
+            error!(%error, ?address, "Failed to save chunk while closing the world");
+        }
+    }
+}
+
 /// Transfers every dirty registered chunk in one runtime dimension into the
 /// persistence queue. Switching code must call this before despawning the
 /// outgoing column entities; capture is independent of `Active` and the I/O
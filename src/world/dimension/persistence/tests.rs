@@ -571,6 +571,128 @@ fn dirty_chunks_are_persisted_and_marked_clean() {
     assert_eq!(loaded, chunk);
 }
 
+#[test]
+fn closing_the_world_flushes_dirty_chunks_of_every_dimension() {
+    let metadata = WorldMetadata::with_seed(9);
+    let repository = ChunkRepository::new(InMemoryChunkStore::new(metadata));
+    let position = ChunkPos::new(-3, 1, 4);
+    let mut chunk = Chunk::default();
+    chunk.set_cell_xyz(0, 0, 0, Item::Glass.into());
+    // No save budget, so only the flush can write anything.
+    let (mut app, active) = save_app(repository.clone(), 0);
+    let definition = *repository.catalog().get(DimensionId::GRASS_FLOOR).unwrap();
+    let inactive = spawn_defined_dimension(&mut app, definition, false);
+    spawn_dirty_chunk(
+        &mut app,
+        active,
+        position,
+        chunk.clone(),
+        ChunkHeightmap::default(),
+    );
+    spawn_dirty_chunk(
+        &mut app,
+        inactive,
+        position,
+        chunk.clone(),
+        ChunkHeightmap::default(),
+    );
+    app.update();
+    assert!(
+        repository
+            .load_chunk(overworld(position))
+            .unwrap()
+            .is_none()
+    );
+
+    app.add_systems(Last, flush_chunk_saves);
+    app.update();
+
+    for dimension in [DimensionId::OVERWORLD, DimensionId::GRASS_FLOOR] {
+        let address = ChunkAddress::new(dimension, position);
+        let (loaded, _) = repository.load_chunk(address).unwrap().unwrap();
+        assert_eq!(loaded, chunk);
+    }
+    assert_eq!(app.world().resource::<ChunkSaveTasks>().stats().tasks, 0);
+}
+
+#[test]
+fn closing_the_world_awaits_in_flight_saves_before_writing_newer_snapshots() {
+    let metadata = WorldMetadata::with_seed(9);
+    let (store, control) = GatedFirstSaveStore::new(metadata, None);
+    let repository = ChunkRepository::new(store);
+    let position = ChunkPos::new(2, 0, -1);
+    let address = overworld(position);
+    let mut original = Chunk::default();
+    original.set_cell_xyz(0, 0, 0, Item::OakLog.into());
+    let (mut app, owner) = save_app(repository.clone(), usize::MAX);
+    let entity = spawn_dirty_chunk(
+        &mut app,
+        owner,
+        position,
+        original,
+        ChunkHeightmap::default(),
+    );
+
+    app.update();
+    control.wait_until_first_started();
+    app.world_mut()
+        .get_mut::<Chunk>(entity)
+        .unwrap()
+        .set_cell_xyz(1, 0, 0, Item::Stone.into());
+    let expected = app.world().get::<Chunk>(entity).unwrap().clone();
+    app.update();
+    assert!(
+        app.world()
+            .resource::<ChunkSaveTasks>()
+            .pending
+            .contains_key(&address)
+    );
+
+    // The stale save only finishes while the flush is already running.
+    let release = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        control.release_first();
+        control
+    });
+    app.add_systems(Last, flush_chunk_saves);
+    app.update();
+    let control = release.join().unwrap();
+
+    assert_eq!(control.calls.load(Ordering::SeqCst), 2);
+    let (stored, _) = repository.load_chunk(address).unwrap().unwrap();
+    assert_eq!(stored, expected);
+    assert_eq!(app.world().resource::<ChunkSaveTasks>().stats().tasks, 0);
+}
+
+#[test]
+fn closing_the_world_retries_a_failed_in_flight_save() {
+    let metadata = WorldMetadata::with_seed(9);
+    let (store, control) = GatedFirstSaveStore::new(metadata, Some(ErrorKind::TimedOut));
+    let repository = ChunkRepository::new(store);
+    let position = ChunkPos::new(2, 0, -1);
+    let address = overworld(position);
+    let mut expected = Chunk::default();
+    expected.set_cell_xyz(0, 0, 0, Item::OakLog.into());
+    let (mut app, owner) = save_app(repository.clone(), usize::MAX);
+    spawn_dirty_chunk(
+        &mut app,
+        owner,
+        position,
+        expected.clone(),
+        ChunkHeightmap::default(),
+    );
+
+    app.update();
+    control.wait_until_first_started();
+    control.release_first();
+    app.add_systems(Last, flush_chunk_saves);
+    app.update();
+
+    assert_eq!(control.calls.load(Ordering::SeqCst), 2);
+    let (stored, _) = repository.load_chunk(address).unwrap().unwrap();
+    assert_eq!(stored, expected);
+}
+
 #[test]
 fn dirty_chunks_are_persisted_under_their_root_dimension() {
     let metadata = WorldMetadata::with_seed(9);
@@ -0,0 +1,319 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    tasks::block_on,
+};
+
+use crate::world::{
+    chunk::{ChunkColumn, ChunkHeightmap, ChunkLight},
+    definition::{ChunkAddress, ColumnAddress, DimensionId},
+    loading::{ChunkLoadSource, LoadedColumn, load_or_generate_column},
+    storage::{ChunkRepository, ChunkStoreError, ChunkStoreResult},
+};
+
+use super::{
+    ChunkTaskPool,
+    light_patch::LightPatchPlan,
+    light_task::{
+        LightCommitBaseline, OwnedLightCalculationChunk, OwnedLightPatchInput, solve_light_patch,
+    },
+    view::columns_in_radius,
+};
+
+/// Light tiles whose loads and solves are in flight together.
+const PREGENERATION_BATCH_TILES: usize = 4;
+
+/// Columns to generate, light and save ahead of play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PregenerationRequest {
+    pub dimension: DimensionId,
+    pub center: ChunkColumn,
+    /// Radius in columns, using the same circular footprint as the streaming
+    /// view.
+    pub radius: i32,
+}
+
+/// Running totals reported after every solved light tile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PregenerationProgress {
+    /// Columns inside the requested radius.
+    pub total_columns: usize,
+    /// Columns that were already stored with cached light when the run began.
+    pub skipped_columns: usize,
+    /// Columns lit and saved by this run.
+    pub completed_columns: usize,
+    /// Chunks generated and saved by this run.
+    pub generated_chunks: usize,
+    pub elapsed: Duration,
+}
+
+impl PregenerationProgress {
+    pub const fn finished_columns(&self) -> usize {
+        self.skipped_columns + self.completed_columns
+    }
+
+    pub const fn is_complete(&self) -> bool {
+        self.finished_columns() == self.total_columns
+    }
+}
+
+/// Generates, lights and saves every column within the requested radius on
+/// the global async compute pool, which must already be initialised.
+///
+/// A column counts as done once it is stored with cached light, so an
+/// interrupted run resumes where it stopped. Loading a pregenerated column
+/// later skips both generation and the light solver.
+pub fn pregenerate_columns(
+    repository: &ChunkRepository,
+    request: PregenerationRequest,
+    progress: impl FnMut(&PregenerationProgress),
+) -> ChunkStoreResult<PregenerationProgress> {
+    pregenerate_columns_on(&ChunkTaskPool::global(), repository, request, progress)
+}
+
+pub(crate) fn pregenerate_columns_on(
+    task_pool: &ChunkTaskPool,
+    repository: &ChunkRepository,
+    request: PregenerationRequest,
+    mut progress: impl FnMut(&PregenerationProgress),
+) -> ChunkStoreResult<PregenerationProgress> {
+    let started = Instant::now();
+    let dimension = request.dimension;
+    let stored = repository
+        .list_columns(dimension)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let height_chunks = repository
+        .catalog()
+        .get(dimension)
+        .ok_or(ChunkStoreError::UnknownDimension { dimension })?
+        .height()
+        .chunks();
+
+    let targets = columns_in_radius(request.center, request.radius);
+    let mut report = PregenerationProgress {
+        total_columns: targets.len(),
+        ..PregenerationProgress::default()
+    };
+    let mut pending = Vec::new();
+    for column in targets {
+        let address = ColumnAddress::new(dimension, column);
+        // Light cached by an older format decodes as an error; relight it.
+        let lit = stored.contains(&column)
+            && match repository.load_column_light(address) {
+                Ok(light) => light.is_some(),
+                Err(error) if error.is_transient() => return Err(error),
+                Err(_) => false,
+            };
+        if lit {
+            report.skipped_columns += 1;
+        } else {
+            pending.push(column);
+        }
+    }
+    progress(&report);
+
+    let tiles = LightPatchPlan::grid_tiles(pending);
+    // Lowest calculation Z still needed by each tile onwards, used to drop
+    // loaded columns once every remaining tile has moved past them.
+    let mut remaining_min_z = tiles
+        .iter()
+        .rev()
+        .scan(i32::MAX, |min_z, tile| {
+            *min_z = (*min_z).min(tile.calculation_columns()[0].z());
+            Some(*min_z)
+        })
+        .collect::<Vec<_>>();
+    remaining_min_z.reverse();
+
+    let mut loaded = HashMap::<ChunkColumn, LoadedColumn>::new();
+    for (batch_index, batch) in tiles.chunks(PREGENERATION_BATCH_TILES).enumerate() {
+        let missing = batch
+            .iter()
+            .flat_map(|tile| tile.calculation_columns())
+            .copied()
+            .filter(|column| !loaded.contains_key(column))
+            .collect::<HashSet<_>>();
+        let loads = missing
+            .into_iter()
+            .map(|column| {
+                let repository = repository.clone();
+                let address = ColumnAddress::new(dimension, column);
+                task_pool.spawn(async move { load_or_generate_column(address, repository) })
+            })
+            .collect::<Vec<_>>();
+        for load in loads {
+            let column = block_on(load).map_err(|error| error.source)?;
+            loaded.insert(column.position(), column);
+        }
+
+        let solves = batch
+            .iter()
+            .map(|tile| {
+                let input = tile_light_input(tile, &loaded, height_chunks);
+                task_pool.spawn(async move { solve_light_patch(input, Duration::ZERO) })
+            })
+            .collect::<Vec<_>>();
+        for (tile, solve) in batch.iter().zip(solves) {
+            let mut cached = block_on(solve)
+                .cached
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+            for &column in tile.commit_columns() {
+                let light = cached
+                    .remove(&column)
+                    .expect("pregeneration tiles include the H1 neighbourhood of every commit");
+                for chunk in loaded[&column]
+                    .chunks()
+                    .iter()
+                    .filter(|chunk| chunk.source == ChunkLoadSource::Generated)
+                {
+                    repository.save_chunk(
+                        ChunkAddress::new(dimension, chunk.position),
+                        &chunk.chunk,
+                        light.heightmap(),
+                    )?;
+                    report.generated_chunks += 1;
+                }
+                repository.save_column_light(ColumnAddress::new(dimension, column), &light)?;
+                report.completed_columns += 1;
+            }
+            report.elapsed = started.elapsed();
+            progress(&report);
+        }
+
+        if let Some(&min_z) = remaining_min_z.get((batch_index + 1) * PREGENERATION_BATCH_TILES) {
+            loaded.retain(|column, _| column.z() >= min_z);
+        }
+    }
+
+    report.elapsed = started.elapsed();
+    Ok(report)
+}
+
+fn tile_light_input(
+    tile: &LightPatchPlan,
+    loaded: &HashMap<ChunkColumn, LoadedColumn>,
+    height_chunks: usize,
+) -> OwnedLightPatchInput {
+    let mut chunks = Vec::with_capacity(tile.calculation_chunk_count(height_chunks));
+    let mut known_revisions = HashMap::new();
+    for &column in tile.calculation_columns() {
+        let loaded = &loaded[&column];
+        known_revisions.insert(column, loaded.content_revision);
+        let commits = tile.commits(column);
+        chunks.extend(
+            loaded
+                .chunks()
+                .iter()
+                .map(|chunk| OwnedLightCalculationChunk {
+                    position: chunk.position,
+                    chunk: chunk.chunk.clone(),
+                    commit_baseline: commits.then(|| LightCommitBaseline {
+                        light: ChunkLight::default(),
+                        heightmap: ChunkHeightmap::default(),
+                    }),
+                    scratch_baseline: None,
+                }),
+        );
+    }
+    OwnedLightPatchInput::new(height_chunks, chunks, known_revisions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{
+        generation::WorldMetadata,
+        storage::{InMemoryChunkStore, LightNeighborhoodStamp},
+    };
+
+    fn pregeneration_repository() -> ChunkRepository {
+        let metadata = WorldMetadata::with_seed(7).with_height_chunks(2).unwrap();
+        ChunkRepository::new(InMemoryChunkStore::new(metadata))
+    }
+
+    fn request(radius: i32) -> PregenerationRequest {
+        PregenerationRequest {
+            dimension: DimensionId::OVERWORLD,
+            center: ChunkColumn::new(-1, 2),
+            radius,
+        }
+    }
+
+    #[test]
+    fn pregeneration_stores_every_column_with_light_that_loading_trusts() {
+        let repository = pregeneration_repository();
+        let task_pool = ChunkTaskPool::new_for_test();
+        let mut reports = Vec::new();
+
+        let summary = pregenerate_columns_on(&task_pool, &repository, request(2), |report| {
+            reports.push(*report)
+        })
+        .unwrap();
+
+        assert_eq!(summary.total_columns, 13);
+        assert_eq!(summary.completed_columns, 13);
+        assert_eq!(summary.generated_chunks, 26);
+        assert!(summary.is_complete());
+        assert!(
+            reports
+                .windows(2)
+                .all(|pair| pair[0].finished_columns() <= pair[1].finished_columns())
+        );
+        let mut targets = columns_in_radius(request(2).center, 2);
+        targets.sort_unstable_by_key(|column| (column.x(), column.z()));
+        assert_eq!(
+            repository.list_columns(DimensionId::OVERWORLD).unwrap(),
+            targets
+        );
+
+        let revision = |column| {
+            let address = ColumnAddress::new(DimensionId::OVERWORLD, column);
+            Some(
+                load_or_generate_column(address, repository.clone())
+                    .unwrap()
+                    .content_revision,
+            )
+        };
+        for column in targets {
+            let address = ColumnAddress::new(DimensionId::OVERWORLD, column);
+            let loaded = load_or_generate_column(address, repository.clone()).unwrap();
+            assert!(
+                loaded
+                    .chunks()
+                    .iter()
+                    .all(|chunk| chunk.source == ChunkLoadSource::Stored)
+            );
+            let light = loaded.stored_light.expect("pregenerated column has light");
+            assert_eq!(
+                Some(*light.stamp()),
+                LightNeighborhoodStamp::try_from_fn(column, revision)
+            );
+            assert_eq!(*light.heightmap(), loaded.heightmap);
+        }
+    }
+
+    #[test]
+    fn pregeneration_resumes_from_columns_already_stored_with_light() {
+        let repository = pregeneration_repository();
+        let task_pool = ChunkTaskPool::new_for_test();
+        pregenerate_columns_on(&task_pool, &repository, request(1), |_| {}).unwrap();
+        repository
+            .delete_column(ColumnAddress::new(
+                DimensionId::OVERWORLD,
+                request(1).center,
+            ))
+            .unwrap();
+
+        let resumed = pregenerate_columns_on(&task_pool, &repository, request(2), |_| {}).unwrap();
+
+        assert_eq!(resumed.skipped_columns, 4);
+        assert_eq!(resumed.completed_columns, 9);
+        assert_eq!(resumed.generated_chunks, 18);
+        let repeated = pregenerate_columns_on(&task_pool, &repository, request(2), |_| {}).unwrap();
+        assert_eq!(repeated.skipped_columns, 13);
+        assert_eq!(repeated.completed_columns, 0);
+    }
+}
@@ -0,0 +1,508 @@
+//! Batched edits over boxes of blocks.
+//!
+//! A region edit touches each chunk in one [`ChunkEditor`] transaction and
+//! merges the per-chunk invalidation plans, so a large edit remeshes and
+//! relights every affected chunk once instead of once per cell.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::world::chunk::{
+    CHUNK_ISIZE, Chunk, ChunkCell, ChunkContentCounts, ChunkEditor, ChunkInvalidationPlan,
+    ChunkPos, WorldBlockPos,
+};
+
+use super::{Dimension, apply_chunk_invalidations};
+
+/// An inclusive box of world blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRegion {
+    min: IVec3,
+    max: IVec3,
+}
+
+impl BlockRegion {
+    /// The box spanning two opposite corners, given in any order.
+    pub fn new(corner: IVec3, opposite: IVec3) -> Self {
+        Self {
+            min: corner.min(opposite),
+            max: corner.max(opposite),
+        }
+    }
+
+    pub const fn min(self) -> IVec3 {
+        self.min
+    }
+
+    pub const fn max(self) -> IVec3 {
+        self.max
+    }
+
+    /// Edge lengths in blocks. A box can span the whole `i32` range, so
+    /// they are counted in `i64`.
+    pub fn size(self) -> I64Vec3 {
+        self.max.as_i64vec3() - self.min.as_i64vec3() + I64Vec3::ONE
+    }
+
+    /// Saturates rather than wrapping for boxes too large to count.
+    pub fn volume(self) -> u64 {
+        let size = self.size().as_u64vec3();
+        size.x.saturating_mul(size.y).saturating_mul(size.z)
+    }
+
+    /// The box moved by `by`, or `None` when it would leave the `i32` range.
+    pub fn checked_offset(self, by: IVec3) -> Option<Self> {
+        let shift = |corner: IVec3| IVec3::try_from(corner.as_i64vec3() + by.as_i64vec3()).ok();
+        Some(Self {
+            min: shift(self.min)?,
+            max: shift(self.max)?,
+        })
+    }
+
+    fn intersection(self, other: Self) -> Option<Self> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        min.cmple(max).all().then_some(Self { min, max })
+    }
+
+    fn is_on_boundary(self, block: IVec3) -> bool {
+        block.cmpeq(self.min).any() || block.cmpeq(self.max).any()
+    }
+
+    /// Every block, x fastest.
+    fn blocks(self) -> impl Iterator<Item = IVec3> {
+        let Self { min, max } = self;
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| ivec3(x, y, z)))
+        })
+    }
+
+    /// The index of `block` in [`Self::blocks`] order.
+    fn index_of(self, block: IVec3) -> usize {
+        let size = self.size();
+        let local = block.as_i64vec3() - self.min.as_i64vec3();
+        (local.x + size.x * (local.y + size.y * local.z)) as usize
+    }
+
+    fn chunks(self) -> impl Iterator<Item = ChunkPos> {
+        let min = WorldBlockPos::from_ivec3(self.min).chunk().as_ivec3();
+        let max = WorldBlockPos::from_ivec3(self.max).chunk().as_ivec3();
+        Self { min, max }
+            .blocks()
+            .map(|chunk| ChunkPos::new(chunk.x, chunk.y, chunk.z))
+    }
+
+    fn of_chunk(chunk: ChunkPos) -> Self {
+        let min = chunk.origin().as_ivec3();
+        Self {
+            min,
+            max: min + IVec3::splat(CHUNK_ISIZE - 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionEdit {
+    /// Sets every cell in the region.
+    Fill {
+        region: BlockRegion,
+        cell: ChunkCell,
+    },
+    /// Sets the outer shell of the region and empties its inside.
+    Hollow {
+        region: BlockRegion,
+        cell: ChunkCell,
+    },
+    /// Swaps every `from` cell in the region for `to`.
+    Replace {
+        region: BlockRegion,
+        from: ChunkCell,
+        to: ChunkCell,
+    },
+    /// Copies the region `offset` blocks away. The source is read in full
+    /// first, so overlapping boxes copy the original contents.
+    Clone { source: BlockRegion, offset: IVec3 },
+}
+
+impl RegionEdit {
+    /// The blocks the edit may write, or `None` when a clone would move
+    /// its source out of the `i32` range.
+    pub fn target(self) -> Option<BlockRegion> {
+        match self {
+            Self::Fill { region, .. }
+            | Self::Hollow { region, .. }
+            | Self::Replace { region, .. } => Some(region),
+            Self::Clone { source, offset } => source.checked_offset(offset),
+        }
+    }
+
+    /// The blocks the edit reads before writing anything.
+    fn source(self) -> Option<BlockRegion> {
+        match self {
+            Self::Clone { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// What a region edit changed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RegionEditReport {
+    pub changed_cells: usize,
+    pub changed_chunks: usize,
+}
+
+/// Why a region edit wrote nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionEditError {
+    /// The edit reads or writes a chunk that is not published.
+    NotLoaded(ChunkPos),
+    /// A clone target lies past the edge of block coordinates.
+    OutOfRange,
+}
+
+impl fmt::Display for RegionEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotLoaded(chunk) => {
+                let chunk = chunk.as_ivec3();
+                write!(
+                    f,
+                    "chunk {}, {}, {} is not loaded",
+                    chunk.x, chunk.y, chunk.z
+                )
+            }
+            Self::OutOfRange => write!(f, "the edit reaches past the edge of the world"),
+        }
+    }
+}
+
+impl std::error::Error for RegionEditError {}
+
+pub type RegionEditChunks<'w, 's> =
+    Query<'w, 's, (&'static mut Chunk, &'static mut ChunkContentCounts)>;
+
+/// Applies `edit` to the published chunks of `dimension`. Every chunk the
+/// edit reads or writes must be published, so an edit is never left half
+/// done.
+pub fn apply_region_edit(
+    commands: &mut Commands,
+    dimension: &mut Dimension,
+    chunks: &mut RegionEditChunks,
+    edit: RegionEdit,
+) -> Result<RegionEditReport, RegionEditError> {
+    let target = edit.target().ok_or(RegionEditError::OutOfRange)?;
+    for chunk in target
+        .chunks()
+        .chain(edit.source().into_iter().flat_map(BlockRegion::chunks))
+    {
+        let loaded = dimension
+            .published_chunk_entity(chunk)
+            .is_some_and(|entity| chunks.contains(entity));
+        if !loaded {
+            return Err(RegionEditError::NotLoaded(chunk));
+        }
+    }
+
+    let copied = edit
+        .source()
+        .map(|source| read_region(dimension, chunks, source))
+        .unwrap_or_default();
+    let new_cell = |block: IVec3, old: ChunkCell| match edit {
+        RegionEdit::Fill { cell, .. } => Some(cell),
+        RegionEdit::Hollow { region, cell } => Some(if region.is_on_boundary(block) {
+            cell
+        } else {
+            ChunkCell::EMPTY
+        }),
+        RegionEdit::Replace { from, to, .. } => (old == from).then_some(to),
+        RegionEdit::Clone { source, offset } => Some(copied[source.index_of(block - offset)]),
+    };
+
+    let mut invalidations = ChunkInvalidationPlan::new();
+    let mut report = RegionEditReport::default();
+    for position in target.chunks() {
+        let Some(entity) = dimension.published_chunk_entity(position) else {
+            continue;
+        };
+        let Ok((mut chunk, mut counts)) = chunks.get_mut(entity) else {
+            continue;
+        };
+        let Some(cells) = target.intersection(BlockRegion::of_chunk(position)) else {
+            continue;
+        };
+
+        let mut chunk_invalidations = ChunkInvalidationPlan::new();
+        let mut editor =
+            ChunkEditor::new(position, &mut chunk, &mut counts, &mut chunk_invalidations);
+        let mut changed = 0;
+        for block in cells.blocks() {
+            let local = WorldBlockPos::from_ivec3(block).local();
+            let Some(cell) = new_cell(block, editor.cell(local)) else {
+                continue;
+            };
+            if editor.set_cell(local, cell).is_some() {
+                changed += 1;
+            }
+        }
+
+        if changed > 0 {
+            report.changed_cells += changed;
+            report.changed_chunks += 1;
+            invalidations.merge(&chunk_invalidations);
+        }
+    }
+
+    apply_chunk_invalidations(commands, dimension, &invalidations);
+    Ok(report)
+}
+
+fn read_region(
+    dimension: &Dimension,
+    chunks: &RegionEditChunks,
+    region: BlockRegion,
+) -> Vec<ChunkCell> {
+    region
+        .blocks()
+        .map(|block| {
+            let position = WorldBlockPos::from_ivec3(block).split();
+            dimension
+                .published_chunk_entity(position.chunk())
+                .and_then(|entity| chunks.get(entity).ok())
+                .map_or(ChunkCell::EMPTY, |(chunk, _)| chunk.cell(position.local()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::{
+        item::Item,
+        world::{
+            DimensionCatalog, DimensionId, WorldMetadata,
+            chunk::{ChunkNeedsSave, ChunkPosition},
+            dimension::Active,
+        },
+    };
+
+    /// A world with an active dimension whose chunks at `positions` are
+    /// published and empty.
+    fn world_with_chunks(positions: &[ChunkPos]) -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let metadata = WorldMetadata::with_seed(3).with_height_chunks(2).unwrap();
+        let catalog = DimensionCatalog::for_world(&metadata);
+        let root = Dimension::spawn_in_world(&mut world, &catalog, DimensionId::OVERWORLD);
+        world.entity_mut(root).insert(Active);
+        let entities = positions
+            .iter()
+            .map(|&position| {
+                let chunk = world
+                    .spawn((
+                        ChunkPosition::from(position),
+                        Chunk::default(),
+                        ChunkContentCounts::default(),
+                    ))
+                    .id();
+                world
+                    .get_mut::<Dimension>(root)
+                    .unwrap()
+                    .register_published_chunk(position, chunk);
+                chunk
+            })
+            .collect();
+        (world, entities)
+    }
+
+    fn edit(world: &mut World, edit: RegionEdit) -> Result<RegionEditReport, RegionEditError> {
+        let mut state: SystemState<(
+            Commands,
+            Single<&mut Dimension, With<Active>>,
+            RegionEditChunks,
+        )> = SystemState::new(world);
+        let (mut commands, dimension, mut chunks) = state.get_mut(world);
+        let result = apply_region_edit(
+            &mut commands,
+            &mut dimension.into_inner(),
+            &mut chunks,
+            edit,
+        );
+        state.apply(world);
+        result
+    }
+
+    fn cell_at(world: &World, chunks: &[Entity], block: IVec3) -> ChunkCell {
+        let position = WorldBlockPos::from_ivec3(block).split();
+        let index = if position.chunk() == ChunkPos::ZERO {
+            0
+        } else {
+            1
+        };
+        world
+            .get::<Chunk>(chunks[index])
+            .unwrap()
+            .cell(position.local())
+    }
+
+    fn region(corner: [i32; 3], opposite: [i32; 3]) -> BlockRegion {
+        BlockRegion::new(IVec3::from_array(corner), IVec3::from_array(opposite))
+    }
+
+    #[test]
+    fn fills_across_chunks_invalidating_each_chunk_once() {
+        let neighbor = ChunkPos::new(1, 0, 0);
+        let (mut world, chunks) = world_with_chunks(&[ChunkPos::ZERO, neighbor]);
+        let stone = ChunkCell::block(Item::Stone);
+
+        let report = edit(
+            &mut world,
+            RegionEdit::Fill {
+                region: region([14, 0, 0], [17, 1, 1]),
+                cell: stone,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            report,
+            RegionEditReport {
+                changed_cells: 16,
+                changed_chunks: 2,
+            }
+        );
+        assert_eq!(cell_at(&world, &chunks, ivec3(15, 1, 1)), stone);
+        assert_eq!(cell_at(&world, &chunks, ivec3(16, 0, 0)), stone);
+        assert_eq!(cell_at(&world, &chunks, ivec3(13, 0, 0)), ChunkCell::EMPTY);
+        for &chunk in &chunks {
+            assert!(world.get::<ChunkNeedsSave>(chunk).is_some());
+            assert_eq!(world.get::<ChunkContentCounts>(chunk).unwrap().solid, 8);
+        }
+        let mut dimension = world.query::<&Dimension>();
+        let dimension = dimension.single(&world).unwrap();
+        assert_eq!(dimension.pending_mesh_rebuild_count(), 2);
+
+        let again = edit(
+            &mut world,
+            RegionEdit::Fill {
+                region: region([14, 0, 0], [17, 1, 1]),
+                cell: stone,
+            },
+        );
+        assert_eq!(again, Ok(RegionEditReport::default()));
+    }
+
+    #[test]
+    fn hollow_and_replace_only_touch_matching_cells() {
+        let (mut world, chunks) = world_with_chunks(&[ChunkPos::ZERO]);
+        let glass = ChunkCell::block(Item::Glass);
+        let dirt = ChunkCell::block(Item::Dirt);
+
+        let hollow = edit(
+            &mut world,
+            RegionEdit::Hollow {
+                region: region([0, 0, 0], [2, 2, 2]),
+                cell: glass,
+            },
+        )
+        .unwrap();
+        assert_eq!(hollow.changed_cells, 26);
+        assert_eq!(cell_at(&world, &chunks, IVec3::ONE), ChunkCell::EMPTY);
+
+        let replaced = edit(
+            &mut world,
+            RegionEdit::Replace {
+                region: region([0, 0, 0], [4, 1, 4]),
+                from: glass,
+                to: dirt,
+            },
+        )
+        .unwrap();
+        assert_eq!(replaced.changed_cells, 17);
+        assert_eq!(cell_at(&world, &chunks, ivec3(2, 1, 2)), dirt);
+        assert_eq!(cell_at(&world, &chunks, ivec3(2, 2, 2)), glass);
+        assert_eq!(cell_at(&world, &chunks, ivec3(3, 0, 0)), ChunkCell::EMPTY);
+    }
+
+    #[test]
+    fn clone_copies_the_original_source_into_an_overlapping_target() {
+        let (mut world, chunks) = world_with_chunks(&[ChunkPos::ZERO]);
+        let stone = ChunkCell::block(Item::Stone);
+        let glass = ChunkCell::block(Item::Glass);
+        for (x, cell) in [(0, stone), (1, glass)] {
+            edit(
+                &mut world,
+                RegionEdit::Fill {
+                    region: region([x, 0, 0], [x, 0, 0]),
+                    cell,
+                },
+            )
+            .unwrap();
+        }
+
+        let report = edit(
+            &mut world,
+            RegionEdit::Clone {
+                source: region([0, 0, 0], [2, 0, 0]),
+                offset: IVec3::X,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.changed_cells, 2);
+        let row = (0..4)
+            .map(|x| cell_at(&world, &chunks, ivec3(x, 0, 0)))
+            .collect::<Vec<_>>();
+        assert_eq!(row, [stone, stone, glass, ChunkCell::EMPTY]);
+    }
+
+    #[test]
+    fn edits_reaching_unloaded_chunks_change_nothing() {
+        let (mut world, chunks) = world_with_chunks(&[ChunkPos::ZERO]);
+
+        let result = edit(
+            &mut world,
+            RegionEdit::Fill {
+                region: region([0, 0, 0], [16, 0, 0]),
+                cell: ChunkCell::block(Item::Stone),
+            },
+        );
+
+        assert_eq!(
+            result,
+            Err(RegionEditError::NotLoaded(ChunkPos::new(1, 0, 0)))
+        );
+        assert_eq!(cell_at(&world, &chunks, IVec3::ZERO), ChunkCell::EMPTY);
+        let clone = edit(
+            &mut world,
+            RegionEdit::Clone {
+                source: region([0, -1, 0], [0, 0, 0]),
+                offset: IVec3::X,
+            },
+        );
+        assert_eq!(
+            clone,
+            Err(RegionEditError::NotLoaded(ChunkPos::new(0, -1, 0)))
+        );
+        let past_the_edge = edit(
+            &mut world,
+            RegionEdit::Clone {
+                source: region([0, 0, 0], [1, 0, 0]),
+                offset: IVec3::new(i32::MAX, 0, 0),
+            },
+        );
+        assert_eq!(past_the_edge, Err(RegionEditError::OutOfRange));
+    }
+
+    #[test]
+    fn regions_spanning_every_coordinate_measure_without_overflow() {
+        let everything = region([i32::MIN, 0, 0], [i32::MAX, 0, 0]);
+
+        assert_eq!(everything.size(), I64Vec3::new(1 << 32, 1, 1));
+        assert_eq!(everything.volume(), 1 << 32);
+        let huge = region([i32::MIN; 3], [i32::MAX; 3]);
+        assert_eq!(huge.volume(), u64::MAX);
+    }
+}
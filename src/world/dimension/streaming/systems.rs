@@ -22,8 +22,8 @@ use super::{
     CompletedColumnLoad,
 };
 use crate::world::dimension::{
-    Active, ChunkSaveTasks, ChunkTaskPool, DesiredColumnView, Dimension, ViewDistance,
-    apply_chunk_invalidations,
+    Active, ChunkSaveTasks, ChunkTaskPool, DesiredColumnView, Dimension, LoadedColumnContent,
+    ViewDistance, apply_chunk_invalidations,
 };
 
 #[derive(Component)]
@@ -153,7 +153,7 @@ pub(crate) fn finish_column_loads(
                 "Accepting a still-desired column from an older view revision"
             );
         }
-        let loaded = match completed.result {
+        let mut loaded = match completed.result {
             Ok(loaded) => loaded,
             Err(error) => {
                 warn!(%error, column = ?ticket.column(), "Failed to load chunk column");
@@ -170,6 +170,15 @@ pub(crate) fn finish_column_loads(
         }
 
         let heightmap = loaded.heightmap;
+        let content = LoadedColumnContent::new(
+            loaded.content_revision,
+            loaded
+                .chunks()
+                .iter()
+                .map(|loaded_chunk| loaded_chunk.chunk.content_revision())
+                .collect(),
+            loaded.stored_light.take(),
+        );
         let incarnation = commands
             .spawn((
                 ChildOf(owner),
@@ -197,7 +206,7 @@ pub(crate) fn finish_column_loads(
             entities.push(entity);
         }
 
-        dimension.install_accepted_column(ticket, incarnation, entities);
+        dimension.install_accepted_column(ticket, incarnation, entities, content);
     }
 }
 
@@ -14,7 +14,8 @@ use crate::{
             mesh::{PreparedChunkMeshLight, padded_chunk_index},
         },
         definition::{
-            ChunkAddress, DimensionCatalog, DimensionDefinition, DimensionId, GeneratorProfile,
+            ChunkAddress, ColumnAddress, DimensionCatalog, DimensionDefinition, DimensionId,
+            GeneratorProfile,
         },
         dimension::{
             Active, ChunkSaveTasks, ChunkTaskPool, ColumnLightBudget, DesiredColumnView, Dimension,
@@ -22,7 +23,7 @@ use crate::{
             light::{cancel_inactive_dimension_light_tasks, rebuild_chunk_light},
         },
         generation::WorldMetadata,
-        storage::{ChunkRepository, NoopChunkStore},
+        storage::{ChunkRepository, InMemoryChunkStore, NoopChunkStore},
     },
 };
 
@@ -103,8 +104,15 @@ fn streaming_app(height_chunks: usize) -> (App, Entity, Entity) {
 }
 
 fn staged_lighting_app(height_chunks: usize) -> (App, Entity, Entity) {
+    let repository = ChunkRepository::new(NoopChunkStore::new(metadata(height_chunks)));
+    staged_lighting_app_with_repository(height_chunks, repository)
+}
+
+fn staged_lighting_app_with_repository(
+    height_chunks: usize,
+    repository: ChunkRepository,
+) -> (App, Entity, Entity) {
     let metadata = metadata(height_chunks);
-    let repository = ChunkRepository::new(NoopChunkStore::new(metadata.clone()));
     let mut app = App::new();
     app.add_plugins(MinimalPlugins)
         .insert_resource(metadata.clone())
@@ -1374,3 +1382,164 @@ fn outgoing_published_column_becomes_hidden_lit_support_without_relighting() {
     assert!(!dimension_ref.contains_published_chunk(center.chunk(0)));
     assert!(!dimension_ref.has_complete_resident_light_neighborhood(center));
 }
+
+#[test]
+fn reloaded_column_reuses_cached_light_without_a_light_patch() {
+    let height_chunks = 2;
+    let center = ChunkColumn::new(0, 0);
+    let repository = ChunkRepository::new(InMemoryChunkStore::new(metadata(height_chunks)));
+
+    let (mut first, dimension, _) =
+        staged_lighting_app_with_repository(height_chunks, repository.clone());
+    update_until(&mut first, |world| {
+        let dimension = world.get::<Dimension>(dimension).unwrap();
+        dimension.contains_published_chunk(center.chunk(0))
+            && dimension.pending_light_cache_saves() == 0
+    });
+    let solved_light = (0..height_chunks as i32)
+        .map(|y| {
+            let dimension_ref = first.world().get::<Dimension>(dimension).unwrap();
+            let entity = dimension_ref.loaded_chunk_entity(center.chunk(y)).unwrap();
+            first.world().get::<ChunkLight>(entity).unwrap().clone()
+        })
+        .collect::<Vec<_>>();
+    let perf = first
+        .world()
+        .resource::<crate::world::chunk::ChunkPerfCounters>();
+    assert_eq!(perf.light_cache_hits, 0);
+    assert!(perf.light_cache_saves >= 1);
+    assert!(
+        repository
+            .load_column_light(ColumnAddress::new(DimensionId::OVERWORLD, center))
+            .unwrap()
+            .is_some()
+    );
+
+    let (mut second, dimension, _) = staged_lighting_app_with_repository(height_chunks, repository);
+    update_until(&mut second, |world| {
+        world
+            .get::<Dimension>(dimension)
+            .unwrap()
+            .contains_published_chunk(center.chunk(0))
+    });
+
+    let world = second.world();
+    let dimension_ref = world.get::<Dimension>(dimension).unwrap();
+    assert!(
+        dimension_ref
+            .resident_column_state(center)
+            .unwrap()
+            .is_lit()
+    );
+    for (y, expected) in solved_light.iter().enumerate() {
+        let entity = dimension_ref
+            .loaded_chunk_entity(center.chunk(y as i32))
+            .unwrap();
+        assert_eq!(world.get::<ChunkLight>(entity).unwrap(), expected);
+        assert!(world.get::<PreparedChunkMeshLight>(entity).is_some());
+    }
+    let perf = world.resource::<crate::world::chunk::ChunkPerfCounters>();
+    assert_eq!(perf.light_cache_hits, 1);
+    assert_eq!(perf.light_cache_misses, 0);
+    assert_eq!(perf.light_patch_runs, 0);
+}
+
+#[test]
+fn runtime_edit_updates_streamed_light_from_the_edited_cell() {
+    let center = ChunkColumn::new(0, 0);
+    let (mut app, dimension, _) = staged_lighting_app(1);
+    app.insert_resource(ViewDistance::new(3));
+    update_until(&mut app, |world| {
+        let dimension_ref = world.get::<Dimension>(dimension).unwrap();
+        let visible = world
+            .get::<DesiredColumnView>(dimension)
+            .unwrap()
+            .visible_columns();
+        visible.len() > 9
+            && visible.iter().all(|&column| {
+                dimension_ref
+                    .resident_column_state(column)
+                    .is_some_and(|state| state.is_lit() && state.is_published())
+            })
+    });
+    let runs_before = app
+        .world()
+        .resource::<crate::world::chunk::ChunkPerfCounters>()
+        .light_patch_runs;
+
+    let center_entity = app
+        .world()
+        .get::<Dimension>(dimension)
+        .unwrap()
+        .loaded_chunk_entity(center.chunk(0))
+        .unwrap();
+    let old = app
+        .world()
+        .get::<Chunk>(center_entity)
+        .unwrap()
+        .cell_xyz(8, 8, 8);
+    let replacement = if old == crate::world::chunk::ChunkCell::EMPTY {
+        Item::Glowstone.into()
+    } else {
+        crate::world::chunk::ChunkCell::EMPTY
+    };
+    let delta = app
+        .world_mut()
+        .get_mut::<Chunk>(center_entity)
+        .unwrap()
+        .set_cell_xyz(8, 8, 8, replacement);
+    let mut plan = crate::world::chunk::ChunkInvalidationPlan::new();
+    plan.record_cell_delta(center.chunk(0), LocalBlockPos::new(8, 8, 8), delta);
+    {
+        let mut dimension_mut = app.world_mut().get_mut::<Dimension>(dimension).unwrap();
+        for column in plan.light_columns() {
+            dimension_mut.mark_column_light_pending(column);
+        }
+        for edit in plan.light_edits() {
+            dimension_mut.record_light_edit(edit);
+        }
+    }
+
+    update_until(&mut app, |world| {
+        let dimension_ref = world.get::<Dimension>(dimension).unwrap();
+        center.chebyshev_neighborhood(1).all(|column| {
+            dimension_ref
+                .resident_column_state(column)
+                .is_some_and(|state| state.is_lit())
+        })
+    });
+
+    let world = app.world();
+    let dimension_ref = world.get::<Dimension>(dimension).unwrap();
+    assert!(dimension_ref.light_edit_columns().is_empty());
+    let perf = world.resource::<crate::world::chunk::ChunkPerfCounters>();
+    assert_eq!(perf.light_patch_runs, runs_before + 1);
+    assert_eq!(perf.light_incremental_updates, 1);
+
+    let chunks = center
+        .chebyshev_neighborhood(2)
+        .map(|column| {
+            let entity = dimension_ref.loaded_chunk_entity(column.chunk(0)).unwrap();
+            (column, world.get::<Chunk>(entity).unwrap().clone())
+        })
+        .collect::<Vec<_>>();
+    let (empty_light, empty_heightmap) = (ChunkLight::default(), ChunkHeightmap::default());
+    let mut region = crate::world::chunk::light::ChunkLightRegion::new(1);
+    let commits = center.chebyshev_neighborhood(1).collect::<HashSet<_>>();
+    for (column, chunk) in &chunks {
+        region.insert_calculation_chunk(column.chunk(0), chunk);
+        if commits.contains(column) {
+            region.mark_commit_target(column.chunk(0), &empty_light, &empty_heightmap);
+        }
+    }
+    let expected = region.rebuild();
+    assert_eq!(expected.len(), 9);
+    for rebuilt in expected {
+        let entity = dimension_ref.loaded_chunk_entity(rebuilt.position).unwrap();
+        assert_eq!(world.get::<ChunkLight>(entity), Some(&rebuilt.light));
+        assert_eq!(
+            world.get::<ChunkHeightmap>(entity),
+            Some(&rebuilt.heightmap)
+        );
+    }
+}
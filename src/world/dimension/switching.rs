@@ -22,25 +22,33 @@ use super::{
 };
 
 pub(super) fn install(app: &mut App) {
-    app.add_systems(
-        PreUpdate,
-        begin_dimension_switch
-            .after(InputSystems)
-            .run_if(in_state(GameState::Playing))
-            .run_if(gameplay_input_active),
-    )
-    .add_systems(
-        FixedUpdate,
-        suppress_transition_interactions
-            .before(BlockInteractionSystems::EmitRequests)
-            .run_if(in_state(GameState::Playing)),
-    )
-    .add_systems(
-        Update,
-        finish_dimension_switch
-            .after(DimensionStreamingSet)
-            .run_if(in_state(GameState::Playing)),
-    );
+    app.add_message::<DimensionSwitchRequest>()
+        .add_systems(
+            PreUpdate,
+            begin_dimension_switch
+                .after(InputSystems)
+                .run_if(in_state(GameState::Playing))
+                .run_if(gameplay_input_active.or(on_message::<DimensionSwitchRequest>)),
+        )
+        .add_systems(
+            FixedUpdate,
+            suppress_transition_interactions
+                .before(BlockInteractionSystems::EmitRequests)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            finish_dimension_switch
+                .after(DimensionStreamingSet)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
+
+/// Moves the player to `target` as the switch key would, without cycling
+/// through the dimensions in between.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DimensionSwitchRequest {
+    pub target: crate::world::DimensionId,
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,10 +89,15 @@ fn begin_dimension_switch(
     >,
     current_target: Option<ResMut<CurrentBlockTarget>>,
     requests: Option<ResMut<Messages<BlockInteractionRequest>>>,
+    switch_requests: Option<ResMut<Messages<DimensionSwitchRequest>>>,
 ) {
-    let (Some(keys), Some(bindings), Some(player)) = (keys, bindings, player) else {
+    let requested = switch_requests.and_then(|mut requests| requests.drain().last());
+    let Some(player) = player else {
         return;
     };
+    let key_pressed = keys
+        .zip(bindings)
+        .is_some_and(|(keys, bindings)| keys.just_pressed(bindings.switch_dimension));
     let (
         player_entity,
         mut player_dimension,
@@ -94,7 +107,7 @@ fn begin_dimension_switch(
         had_character_controller,
         transitioning,
     ) = player.into_inner();
-    if transitioning || !keys.just_pressed(bindings.switch_dimension) {
+    if transitioning || (requested.is_none() && !key_pressed) {
         return;
     }
 
@@ -119,9 +132,15 @@ fn begin_dimension_switch(
         "player membership must match the active dimension before switching"
     );
 
-    let target_id = catalog
-        .next_id(outgoing_id)
-        .expect("active dimension must belong to the world catalog");
+    let target_id = match requested {
+        Some(request) => request.target,
+        None => catalog
+            .next_id(outgoing_id)
+            .expect("active dimension must belong to the world catalog"),
+    };
+    if target_id == outgoing_id {
+        return;
+    }
     let target_root = roots
         .iter()
         .find_map(|&(entity, id, _)| (id == target_id).then_some(entity))
@@ -535,6 +554,30 @@ mod tests {
         assert_eq!(active_root(app.world_mut()), grass);
     }
 
+    #[test]
+    fn a_switch_request_goes_straight_to_its_target() {
+        let mut app = switch_app();
+        app.add_message::<DimensionSwitchRequest>();
+        let roots = app.world().resource::<TestRoots>();
+        let (glass, player) = (roots.glass, roots.player);
+        app.world_mut().write_message(DimensionSwitchRequest {
+            target: crate::world::DimensionId::CENTER_GLASS_PLATFORM,
+        });
+
+        app.update();
+
+        assert_eq!(active_root(app.world_mut()), glass);
+        assert_eq!(
+            app.world().get::<PlayerDimension>(player).unwrap().id(),
+            crate::world::DimensionId::CENTER_GLASS_PLATFORM
+        );
+        assert!(
+            app.world()
+                .resource::<Messages<DimensionSwitchRequest>>()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn world_only_apps_leave_switching_dormant_without_player_input_resources() {
         let metadata = WorldMetadata::with_seed(7);
@@ -225,7 +225,7 @@ fn resident_closure(visible: &OrderedColumnSet, center: ChunkColumn) -> Vec<Chun
     closure
 }
 
-fn columns_in_radius(center: ChunkColumn, radius: i32) -> Vec<ChunkColumn> {
+pub(crate) fn columns_in_radius(center: ChunkColumn, radius: i32) -> Vec<ChunkColumn> {
     let radius = radius.max(0);
     let radius_squared = i64::from(radius) * i64::from(radius);
     let offsets = (-radius..=radius)
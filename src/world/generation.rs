@@ -220,6 +220,29 @@ fn generate_center_glass_platform_chunk(chunk_pos: ChunkPos) -> Chunk {
     })
 }
 
+/// The highest generated cell of one world column and its absolute Y.
+///
+/// This skips decorations such as trees and the spawn marker, so far-field
+/// terrain can summarize columns without generating any chunks.
+pub fn generated_surface(
+    metadata: &WorldMetadata,
+    profile: GeneratorProfile,
+    world_x: i32,
+    world_z: i32,
+) -> Option<(i32, ChunkCell)> {
+    match profile {
+        GeneratorProfile::OverworldV1 => Some((
+            terrain_height(metadata, world_x, world_z),
+            Item::Grass.into(),
+        )),
+        GeneratorProfile::GrassFloorV1 => Some((0, Item::Grass.into())),
+        GeneratorProfile::CenterGlassPlatformV1 => {
+            (WorldBlockPos::new(world_x, 0, world_z).split().chunk() == ChunkPos::ZERO)
+                .then(|| (0, Item::Glass.into()))
+        }
+    }
+}
+
 pub fn terrain_height(metadata: &WorldMetadata, world_x: i32, world_z: i32) -> i32 {
     let broad = value_noise_2d(metadata.seed, world_x, world_z, 32);
     let detail = value_noise_2d(metadata.seed ^ 0x9e37_79b9_7f4a_7c15, world_x, world_z, 11);
@@ -417,7 +440,7 @@ fn hash_coords(seed: u64, x: i32, y: i32, z: i32, salt: u64) -> u64 {
     mix_u64(value ^ (z as i64 as u64).wrapping_mul(0x94d0_49bb_1331_11eb))
 }
 
-fn mix_u64(mut value: u64) -> u64 {
+pub(crate) fn mix_u64(mut value: u64) -> u64 {
     value ^= value >> 30;
     value = value.wrapping_mul(0xbf58_476d_1ce4_e5b9);
     value ^= value >> 27;
@@ -425,6 +448,32 @@ fn mix_u64(mut value: u64) -> u64 {
     value ^ (value >> 31)
 }
 
+/// SplitMix64, a small seeded generator that replays the same sequence on
+/// every platform. Cosmetic randomness uses it instead of an RNG crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// The state to persist, which [`Self::new`] resumes from.
+    pub(crate) const fn state(self) -> u64 {
+        self.0
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix_u64(self.0)
+    }
+
+    /// A value in `0.0..1.0` with 24 bits of precision.
+    pub(crate) fn next_unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
 fn div_floor(value: i32, divisor: i32) -> i32 {
     value.div_euclid(divisor)
 }
@@ -465,6 +514,17 @@ mod tests {
         (bytes.len(), fingerprint)
     }
 
+    #[test]
+    fn split_mix_matches_the_reference_sequence() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut resumed = SplitMix64::new(rng.state());
+        assert_eq!(resumed.next_u64(), rng.next_u64());
+        assert!((0.0..1.0).contains(&resumed.next_unit()));
+    }
+
     #[test]
     fn world_height_accepts_persistable_bounds() {
         let minimum = WorldHeight::new(MIN_WORLD_HEIGHT_CHUNKS).unwrap();
@@ -697,6 +757,35 @@ mod tests {
         assert_eq!(neighbor.get_cell(uvec3(15, 14, 8)), ChunkCell::EMPTY);
     }
 
+    #[test]
+    fn generated_surfaces_match_the_top_of_generated_terrain() {
+        let metadata = WorldMetadata::with_seed(7);
+        for (world_x, world_z) in [(3, 5), (-17, 40), (15, -1)] {
+            let (y, cell) =
+                generated_surface(&metadata, GeneratorProfile::OverworldV1, world_x, world_z)
+                    .unwrap();
+            let address = WorldBlockPos::new(world_x, y, world_z).split();
+            let chunk = generate_terrain_chunk(&metadata, address.chunk().as_ivec3());
+            let local = address.local().as_uvec3();
+
+            assert_eq!(chunk.get_cell(local), cell);
+            assert_eq!(terrain_cell_at(y + 1, y), ChunkCell::EMPTY);
+        }
+
+        assert_eq!(
+            generated_surface(&metadata, GeneratorProfile::GrassFloorV1, -900, 12),
+            Some((0, Item::Grass.into()))
+        );
+        assert_eq!(
+            generated_surface(&metadata, GeneratorProfile::CenterGlassPlatformV1, 15, 0),
+            Some((0, Item::Glass.into()))
+        );
+        assert_eq!(
+            generated_surface(&metadata, GeneratorProfile::CenterGlassPlatformV1, 16, 0),
+            None
+        );
+    }
+
     #[test]
     fn negative_world_coordinates_use_floor_chunk_math() {
         let sources = candidate_oak_tree_source_chunks(ivec3(-1, 0, -1));
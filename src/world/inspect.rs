@@ -0,0 +1,351 @@
+//! Read-only inspection of persisted worlds, shared by the `worldtool` binary.
+//!
+//! Everything here goes through [`ChunkRepository`], so the checks apply to
+//! every store backend and never need a running app.
+
+use std::collections::HashMap;
+
+use crate::item::Item;
+use crate::world::{
+    chunk::{
+        CHUNK_SIZE, Chunk, ChunkCell, ChunkColumn, ChunkDecodeError, ChunkHeightmap, ChunkPos,
+    },
+    definition::ColumnAddress,
+    loading::load_or_generate_column,
+    storage::{ChunkRepository, ChunkStoreError},
+};
+
+const AIR_GLYPH: char = '.';
+const FLUID_GLYPH: char = '~';
+const UNKNOWN_GLYPH: char = '?';
+/// The glyph of each block. A block missing here shows as [`UNKNOWN_GLYPH`].
+const BLOCK_GLYPHS: &[(Item, char)] = &[
+    (Item::Grass, 'G'),
+    (Item::Dirt, 'D'),
+    (Item::Stone, '#'),
+    (Item::Sand, ':'),
+    (Item::Glass, 'O'),
+    (Item::OakLog, 'L'),
+    (Item::OakLeaves, '*'),
+    (Item::Glowstone, '@'),
+    (Item::Ice, 'I'),
+];
+
+/// A stable, human-readable name for one cell state.
+pub fn cell_label(cell: ChunkCell) -> String {
+    match cell {
+        ChunkCell::Empty => "air".to_owned(),
+        ChunkCell::Block(block) => block.name(),
+        ChunkCell::Fluid(fluid) => format!("{}_{}", fluid.ty(), fluid.form()),
+    }
+}
+
+/// The glyph used for one cell in [`ascii_slice`].
+pub fn cell_glyph(cell: ChunkCell) -> char {
+    match cell {
+        ChunkCell::Empty => AIR_GLYPH,
+        ChunkCell::Block(block) => BLOCK_GLYPHS
+            .iter()
+            .find(|(item, _)| *item == block)
+            .map_or(UNKNOWN_GLYPH, |&(_, glyph)| glyph),
+        ChunkCell::Fluid(_) => FLUID_GLYPH,
+    }
+}
+
+/// Counts every cell of a chunk by [`cell_label`], most common first.
+pub fn block_histogram(chunk: &Chunk) -> Vec<(String, usize)> {
+    let mut counts = HashMap::<String, usize>::new();
+    for (cell, _) in chunk.iter() {
+        *counts.entry(cell_label(cell)).or_default() += 1;
+    }
+    let mut histogram = counts.into_iter().collect::<Vec<_>>();
+    histogram.sort_unstable_by(|left, right| right.1.cmp(&left.1).then(left.0.cmp(&right.0)));
+    histogram
+}
+
+/// Renders one horizontal layer of a chunk, one row per Z from north to south
+/// and one glyph per X from west to east.
+pub fn ascii_slice(chunk: &Chunk, y: usize) -> String {
+    assert!(y < CHUNK_SIZE, "slice height must lie inside the chunk");
+    let mut slice = String::with_capacity(CHUNK_SIZE * (CHUNK_SIZE + 1));
+    for z in 0..CHUNK_SIZE {
+        slice.extend((0..CHUNK_SIZE).map(|x| cell_glyph(chunk.cell_xyz(x, y, z))));
+        slice.push('\n');
+    }
+    slice
+}
+
+/// Stored columns whose horizontal distance from `center` exceeds `radius`,
+/// using the same circular footprint as the streaming view.
+pub fn columns_outside_radius(
+    columns: &[ChunkColumn],
+    center: ChunkColumn,
+    radius: i32,
+) -> Vec<ChunkColumn> {
+    let radius_squared = i64::from(radius.max(0)).pow(2);
+    columns
+        .iter()
+        .copied()
+        .filter(|column| {
+            let x = i64::from(column.x()) - i64::from(center.x());
+            let z = i64::from(column.z()) - i64::from(center.z());
+            x * x + z * z > radius_squared
+        })
+        .collect()
+}
+
+/// One problem found while verifying a stored column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnIssue {
+    /// A stored chunk blob does not decode.
+    UndecodableChunk {
+        position: ChunkPos,
+        error: ChunkStoreError,
+    },
+    /// The column could not be read, but no single chunk was at fault.
+    UnreadableColumn(ChunkStoreError),
+    /// The cached column light does not decode. It is only a cache, so the
+    /// column still loads and is relit.
+    UnreadableLight(ChunkStoreError),
+    /// The stored heightmap differs from the one recomputed from the blocks.
+    HeightmapMismatch {
+        differing_cells: usize,
+        stored: ChunkHeightmap,
+        expected: ChunkHeightmap,
+    },
+}
+
+impl std::fmt::Display for ColumnIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UndecodableChunk { position, error } => {
+                write!(f, "chunk {position:?} does not decode: {error}")
+            }
+            Self::UnreadableColumn(error) => write!(f, "column is unreadable: {error}"),
+            Self::UnreadableLight(error) => write!(f, "cached light is unreadable: {error}"),
+            Self::HeightmapMismatch {
+                differing_cells, ..
+            } => write!(
+                f,
+                "stored heightmap differs from recomputed heights in {differing_cells} cells"
+            ),
+        }
+    }
+}
+
+/// Result of verifying one stored column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnVerification {
+    pub address: ColumnAddress,
+    pub issues: Vec<ColumnIssue>,
+}
+
+impl ColumnVerification {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks that every stored chunk of a column decodes and that its persisted
+/// heightmap matches the heightmap recomputed from the complete column,
+/// generating missing Y chunks exactly as loading would.
+pub fn verify_column(repository: &ChunkRepository, address: ColumnAddress) -> ColumnVerification {
+    let mut issues = Vec::new();
+    match load_or_generate_column(address, repository.clone()) {
+        Ok(loaded) => {
+            let chunks = loaded
+                .chunks()
+                .iter()
+                .map(|loaded| &loaded.chunk)
+                .collect::<Vec<_>>();
+            let expected = ChunkHeightmap::for_column(&chunks);
+            let differing_cells = expected
+                .heights
+                .as_flattened()
+                .iter()
+                .zip(loaded.heightmap.heights.as_flattened())
+                .filter(|(expected, stored)| expected != stored)
+                .count();
+            if differing_cells > 0 {
+                issues.push(ColumnIssue::HeightmapMismatch {
+                    differing_cells,
+                    stored: loaded.heightmap,
+                    expected,
+                });
+            }
+        }
+        Err(error) => {
+            let height = column_height_chunks(repository, address);
+            issues.extend((0..height).filter_map(|y| {
+                let position = address.column().chunk(y);
+                repository
+                    .load_chunk(address.chunk(y))
+                    .err()
+                    .map(|error| ColumnIssue::UndecodableChunk { position, error })
+            }));
+            if issues.is_empty() {
+                issues.push(ColumnIssue::UnreadableColumn(error.source));
+            }
+        }
+    }
+    match repository.load_column_light(address) {
+        // Light cached by an older format is stale rather than damaged:
+        // loading discards it and relights the column.
+        Ok(_) | Err(ChunkStoreError::Decode(ChunkDecodeError::UnsupportedVersion(_))) => {}
+        Err(error) => issues.push(ColumnIssue::UnreadableLight(error)),
+    }
+
+    ColumnVerification { address, issues }
+}
+
+fn column_height_chunks(repository: &ChunkRepository, address: ColumnAddress) -> i32 {
+    repository
+        .dimension_height(address.dimension())
+        .map_or(0, |height| height.chunks_i32())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{
+        definition::DimensionId, generation::WorldMetadata, storage::InMemoryChunkStore,
+    };
+
+    #[test]
+    fn histogram_counts_every_cell_most_common_first() {
+        let mut chunk = Chunk::default();
+        chunk.set_cell_xyz(0, 0, 0, Item::Stone.into());
+        chunk.set_cell_xyz(1, 0, 0, Item::Stone.into());
+        chunk.set_cell_xyz(2, 0, 0, ChunkCell::water_source());
+
+        let histogram = block_histogram(&chunk);
+
+        assert_eq!(
+            histogram,
+            vec![
+                ("air".to_owned(), CHUNK_SIZE.pow(3) - 3),
+                ("stone".to_owned(), 2),
+                ("water_source".to_owned(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn ascii_slice_renders_x_across_and_z_down() {
+        let mut chunk = Chunk::default();
+        chunk.set_cell_xyz(3, 5, 0, Item::Stone.into());
+        chunk.set_cell_xyz(0, 5, 2, Item::Glass.into());
+
+        let slice = ascii_slice(&chunk, 5);
+        let rows = slice.lines().collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), CHUNK_SIZE);
+        assert_eq!(&rows[0][..4], "...#");
+        assert_eq!(&rows[2][..2], "O.");
+        assert!(
+            ascii_slice(&chunk, 4)
+                .chars()
+                .all(|c| c == '.' || c == '\n')
+        );
+    }
+
+    #[test]
+    fn every_block_has_a_distinct_glyph() {
+        let glyphs = Item::BLOCKS
+            .iter()
+            .map(|&block| cell_glyph(block.into()))
+            .collect::<std::collections::HashSet<_>>();
+
+        assert_eq!(glyphs.len(), Item::BLOCK_COUNT);
+        assert!(!glyphs.contains(&AIR_GLYPH));
+        assert!(!glyphs.contains(&UNKNOWN_GLYPH));
+    }
+
+    #[test]
+    fn prune_selection_keeps_the_circular_view_footprint() {
+        let columns = [
+            ChunkColumn::new(0, 0),
+            ChunkColumn::new(3, 0),
+            ChunkColumn::new(2, 2),
+            ChunkColumn::new(3, 3),
+            ChunkColumn::new(-4, 0),
+        ];
+
+        assert_eq!(
+            columns_outside_radius(&columns, ChunkColumn::new(0, 0), 3),
+            vec![ChunkColumn::new(3, 3), ChunkColumn::new(-4, 0)]
+        );
+    }
+
+    #[test]
+    fn verification_reports_heightmap_drift_and_undecodable_chunks() {
+        let metadata = WorldMetadata::with_seed(5).with_height_chunks(2).unwrap();
+        let repository = ChunkRepository::new(InMemoryChunkStore::new(metadata));
+        let address = ColumnAddress::new(DimensionId::OVERWORLD, ChunkColumn::new(1, -2));
+        let loaded = load_or_generate_column(address, repository.clone()).unwrap();
+        let chunks = loaded
+            .chunks()
+            .iter()
+            .map(|loaded| &loaded.chunk)
+            .collect::<Vec<_>>();
+        let heightmap = ChunkHeightmap::for_column(&chunks);
+        repository
+            .save_chunk(address.chunk(0), chunks[0], &heightmap)
+            .unwrap();
+
+        assert!(verify_column(&repository, address).is_ok());
+
+        let mut drifted = heightmap;
+        drifted.heights[4][7] = drifted.heights[4][7].wrapping_add(1);
+        repository
+            .save_chunk(address.chunk(0), chunks[0], &drifted)
+            .unwrap();
+        assert!(matches!(
+            verify_column(&repository, address).issues.as_slice(),
+            [ColumnIssue::HeightmapMismatch {
+                differing_cells: 1,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn verification_pinpoints_the_undecodable_chunk() {
+        let metadata = WorldMetadata::with_seed(5).with_height_chunks(2).unwrap();
+        let store = InMemoryChunkStore::new(metadata);
+        let address = ColumnAddress::new(DimensionId::OVERWORLD, ChunkColumn::new(0, 0));
+        store.overwrite_chunk_bytes_for_test(address.chunk(1), &[0xff]);
+
+        let verification = verify_column(&ChunkRepository::new(store), address);
+
+        assert!(matches!(
+            verification.issues.as_slice(),
+            [ColumnIssue::UndecodableChunk {
+                position,
+                error: ChunkStoreError::Decode(_),
+            }] if *position == address.column().chunk(1)
+        ));
+    }
+
+    #[test]
+    fn verification_skips_stale_light_but_reports_damaged_light() {
+        let address = ColumnAddress::new(DimensionId::OVERWORLD, ChunkColumn::new(0, 0));
+        let light_issues = |bytes: &[u8]| {
+            let store = InMemoryChunkStore::new(WorldMetadata::with_seed(5));
+            store.overwrite_column_light_bytes_for_test(address, bytes);
+            verify_column(&ChunkRepository::new(store), address)
+                .issues
+                .into_iter()
+                .filter(|issue| matches!(issue, ColumnIssue::UnreadableLight(_)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(light_issues(&[1, 2]), []);
+        assert!(matches!(
+            light_issues(&[0xff]).as_slice(),
+            [ColumnIssue::UnreadableLight(ChunkStoreError::Decode(
+                ChunkDecodeError::Truncated
+            ))]
+        ));
+    }
+}
@@ -4,7 +4,7 @@ use crate::world::{
     chunk::{Chunk, ChunkColumn, ChunkContentCounts, ChunkHeightmap, ChunkPos},
     definition::ColumnAddress,
     generation::{WorldHeight, generate_dimension_chunk},
-    storage::{ChunkRepository, ChunkStoreError},
+    storage::{ChunkRepository, ChunkStoreError, ColumnContentRevision, StoredColumnLight},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -77,6 +77,11 @@ pub struct LoadedColumn {
     pub address: ColumnAddress,
     pub height: WorldHeight,
     pub heightmap: ChunkHeightmap,
+    /// Revision of the exact block contents below, stored or generated.
+    pub content_revision: ColumnContentRevision,
+    /// Cached light from an earlier solve. It is only valid if its stamp still
+    /// matches this column and its resident neighbours.
+    pub stored_light: Option<StoredColumnLight>,
     chunks: Vec<LoadedColumnChunk>,
 }
 
@@ -85,6 +90,7 @@ impl LoadedColumn {
         address: ColumnAddress,
         height: WorldHeight,
         heightmap: ChunkHeightmap,
+        stored_light: Option<StoredColumnLight>,
         chunks: Vec<LoadedColumnChunk>,
     ) -> Self {
         assert_eq!(
@@ -99,10 +105,14 @@ impl LoadedColumn {
                 .all(|(y, loaded)| loaded.position == address.column().chunk(y as i32)),
             "loaded column chunks must be contiguous and ordered by Y"
         );
+        let content_revision =
+            ColumnContentRevision::of_chunks(chunks.iter().map(|loaded| &loaded.chunk));
         Self {
             address,
             height,
             heightmap,
+            content_revision,
+            stored_light,
             chunks,
         }
     }
@@ -124,6 +134,10 @@ pub type ColumnLoadResult = Result<LoadedColumn, ChunkLoadError>;
 
 /// Loads persisted chunks in one store call and deterministically generates
 /// every missing Y position, producing a complete configured-height column.
+///
+/// Cached column light is loaded alongside. Because it can always be rebuilt,
+/// only transient light read failures fail the load; anything else discards
+/// the cache and leaves the column to the light solver.
 pub fn load_or_generate_column(
     address: ColumnAddress,
     repository: ChunkRepository,
@@ -131,6 +145,14 @@ pub fn load_or_generate_column(
     let stored = repository
         .load_stored_column(address)
         .map_err(classify_load_error)?;
+    let stored_light = match repository.load_column_light(address) {
+        Ok(light) => light,
+        Err(error) if error.is_transient() => return Err(ChunkLoadError::transient(error)),
+        Err(error) => {
+            warn!(%error, ?address, "Discarding unreadable stored column light");
+            None
+        }
+    };
     let definition = *repository
         .catalog()
         .get(address.dimension())
@@ -168,7 +190,13 @@ pub fn load_or_generate_column(
         "validated stored column must be fully consumed"
     );
 
-    Ok(LoadedColumn::new(address, height, heightmap, chunks))
+    Ok(LoadedColumn::new(
+        address,
+        height,
+        heightmap,
+        stored_light,
+        chunks,
+    ))
 }
 
 pub fn classify_load_error(error: ChunkStoreError) -> ChunkLoadError {
@@ -195,10 +223,12 @@ mod tests {
     use crate::{
         item::Item,
         world::{
+            chunk::ChunkLight,
             definition::{ChunkAddress, ColumnAddress, DimensionId},
             generation::{WorldMetadata, generate_dimension_chunk},
             storage::{
-                ChunkStore, ChunkStoreResult, InMemoryChunkStore, StoredChunk, StoredColumn,
+                ChunkStore, ChunkStoreResult, InMemoryChunkStore, LightNeighborhoodStamp,
+                StoredChunk, StoredColumn,
             },
         },
     };
@@ -332,6 +362,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn column_loading_returns_cached_light_and_a_content_derived_revision() {
+        let metadata = WorldMetadata::with_seed(91).with_height_chunks(2).unwrap();
+        let repository = ChunkRepository::new(InMemoryChunkStore::new(metadata.clone()));
+        let address = ColumnAddress::new(DimensionId::OVERWORLD, ChunkColumn::new(-1, 4));
+
+        let generated = load_or_generate_column(address, repository.clone()).unwrap();
+        assert_eq!(generated.stored_light, None);
+
+        let light = StoredColumnLight::new(
+            LightNeighborhoodStamp::new([generated.content_revision; 9]),
+            generated.heightmap,
+            vec![ChunkLight::default(); 2],
+            vec![Arc::from(vec![0u32; 4]); 2],
+        );
+        repository.save_column_light(address, &light).unwrap();
+        for loaded in generated.chunks() {
+            repository
+                .save_chunk(
+                    ChunkAddress::new(address.dimension(), loaded.position),
+                    &loaded.chunk,
+                    &generated.heightmap,
+                )
+                .unwrap();
+        }
+
+        let stored = load_or_generate_column(address, repository.clone()).unwrap();
+        assert!(
+            stored
+                .chunks()
+                .iter()
+                .all(|chunk| chunk.source == ChunkLoadSource::Stored)
+        );
+        assert_eq!(stored.content_revision, generated.content_revision);
+        assert_eq!(stored.stored_light, Some(light));
+
+        let mut edited = stored.chunks()[1].chunk.clone();
+        edited.set_cell_xyz(8, 8, 8, Item::Glowstone.into());
+        repository
+            .save_chunk(address.chunk(1), &edited, &stored.heightmap)
+            .unwrap();
+        let edited = load_or_generate_column(address, repository).unwrap();
+        assert_ne!(edited.content_revision, generated.content_revision);
+    }
+
     #[test]
     fn empty_storage_uses_the_grass_floor_generator_at_every_height() {
         let metadata = WorldMetadata::with_seed(91).with_height_chunks(2).unwrap();
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::world::{
+    chunk::{CHUNK_SIZE, ChunkCell},
+    definition::{ColumnAddress, GeneratorProfile},
+    generation::{generate_chunk_for_profile, generated_surface},
+    storage::{
+        ChunkRepository, ChunkStoreResult, SURFACE_CELL_BLOCKS, StoredColumn, StoredColumnSurface,
+        SurfaceCell,
+    },
+};
+
+type BlockSurface = Option<(i32, ChunkCell)>;
+
+/// The far-field surface of one column, cached in the store once derived.
+///
+/// Columns that were never saved are summarized straight from the generator
+/// without building chunks. Store failures fall back to that summary, which is
+/// only wrong where players have edited terrain.
+pub(super) fn column_surface(
+    repository: &ChunkRepository,
+    profile: GeneratorProfile,
+    address: ColumnAddress,
+) -> StoredColumnSurface {
+    match load_or_derive_surface(repository, profile, address) {
+        Ok(surface) => surface,
+        Err(error) => {
+            warn!(%error, ?address, "Summarizing far column from its generator");
+            summarize(|x, z| generated_block_surface(repository, profile, address, x, z))
+        }
+    }
+}
+
+fn load_or_derive_surface(
+    repository: &ChunkRepository,
+    profile: GeneratorProfile,
+    address: ColumnAddress,
+) -> ChunkStoreResult<StoredColumnSurface> {
+    if let Some(surface) = repository.load_column_surface(address)? {
+        return Ok(surface);
+    }
+
+    let stored = repository.load_stored_column(address)?;
+    let surface = if stored.chunks().is_empty() {
+        summarize(|x, z| generated_block_surface(repository, profile, address, x, z))
+    } else {
+        let blocks = scan_block_surfaces(repository, profile, &stored);
+        summarize(|x, z| blocks[x][z])
+    };
+    repository.save_column_surface(address, &surface)?;
+    Ok(surface)
+}
+
+fn generated_block_surface(
+    repository: &ChunkRepository,
+    profile: GeneratorProfile,
+    address: ColumnAddress,
+    x: usize,
+    z: usize,
+) -> BlockSurface {
+    let column = address.column();
+    generated_surface(
+        repository.metadata(),
+        profile,
+        column.x() * CHUNK_SIZE as i32 + x as i32,
+        column.z() * CHUNK_SIZE as i32 + z as i32,
+    )
+}
+
+/// Highest rendered cell of each block column, reading stored chunks where
+/// present and generating the rest from the top down.
+fn scan_block_surfaces(
+    repository: &ChunkRepository,
+    profile: GeneratorProfile,
+    stored: &StoredColumn,
+) -> [[BlockSurface; CHUNK_SIZE]; CHUNK_SIZE] {
+    let mut blocks = [[None; CHUNK_SIZE]; CHUNK_SIZE];
+    let mut remaining = CHUNK_SIZE * CHUNK_SIZE;
+    for chunk_y in (0..stored.height().chunks_i32()).rev() {
+        let position = stored.position().chunk(chunk_y);
+        let generated;
+        let chunk = match stored
+            .chunks()
+            .iter()
+            .find(|chunk| chunk.position() == position)
+        {
+            Some(stored) => &stored.chunk,
+            None => {
+                generated = generate_chunk_for_profile(repository.metadata(), profile, position);
+                &generated
+            }
+        };
+        for (x, row) in blocks.iter_mut().enumerate() {
+            for (z, block) in row.iter_mut().enumerate() {
+                if block.is_some() {
+                    continue;
+                }
+                *block = (0..CHUNK_SIZE).rev().find_map(|y| {
+                    let cell = chunk.cell_xyz(x, y, z);
+                    cell.is_rendered()
+                        .then_some((chunk_y * CHUNK_SIZE as i32 + y as i32, cell))
+                });
+                if block.is_some() {
+                    remaining -= 1;
+                }
+            }
+        }
+        if remaining == 0 {
+            break;
+        }
+    }
+    blocks
+}
+
+/// Merges block surfaces into summary cells, keeping the highest of each
+/// footprint.
+fn summarize(mut block: impl FnMut(usize, usize) -> BlockSurface) -> StoredColumnSurface {
+    StoredColumnSurface::from_fn(|cx, cz| {
+        (0..SURFACE_CELL_BLOCKS)
+            .flat_map(|dx| (0..SURFACE_CELL_BLOCKS).map(move |dz| (dx, dz)))
+            .filter_map(|(dx, dz)| {
+                block(cx * SURFACE_CELL_BLOCKS + dx, cz * SURFACE_CELL_BLOCKS + dz)
+            })
+            .max_by_key(|&(y, _)| y)
+            .map_or(SurfaceCell::EMPTY, |(y, cell)| SurfaceCell::new(y, cell))
+    })
+}
@@ -0,0 +1,290 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    color::{LinearRgba, Srgba},
+    math::Vec3,
+    mesh::{Indices, Mesh, PrimitiveTopology},
+};
+
+use crate::{
+    item::Item,
+    quad::Direction,
+    world::{chunk::ChunkCell, storage::SurfaceCell},
+};
+
+use super::tile::{LOD_TILE_CELLS, LodTileGrid};
+
+/// Matches the terrain shader's per-direction face shading, in
+/// [`Direction`] order.
+const FACE_BRIGHTNESS: [f32; Direction::COUNT] = [0.80, 0.80, 0.62, 1.0, 0.90, 0.90];
+
+/// Horizontal sides checked for walls, with their cell offsets.
+const SIDES: [(Direction, isize, isize); 4] = [
+    (Direction::Left, -1, 0),
+    (Direction::Right, 1, 0),
+    (Direction::Forward, 0, -1),
+    (Direction::Backward, 0, 1),
+];
+
+/// Far-field geometry of one tile in tile-local block coordinates.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct LodMeshData {
+    pub(super) positions: Vec<[f32; 3]>,
+    pub(super) normals: Vec<[f32; 3]>,
+    pub(super) colours: Vec<[f32; 4]>,
+    pub(super) indices: Vec<u32>,
+}
+
+impl LodMeshData {
+    pub(super) fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub(super) fn quad_count(&self) -> usize {
+        self.indices.len() / 6
+    }
+
+    pub(super) fn into_mesh(self) -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, self.colours)
+        .with_inserted_indices(Indices::U32(self.indices))
+    }
+
+    /// Pushes one quad whose corners wind counter-clockwise seen from the
+    /// side `direction` points to.
+    fn push_quad(&mut self, corners: [Vec3; 4], direction: Direction, colour: [f32; 4]) {
+        let base = self.positions.len() as u32;
+        let normal = Vec3::from(direction).to_array();
+        for corner in corners {
+            self.positions.push(corner.to_array());
+            self.normals.push(normal);
+            self.colours.push(colour);
+        }
+        self.indices
+            .extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+}
+
+/// Meshes a tile as flat cell tops with walls down to lower neighbours.
+///
+/// Cells on the tile border, and cells next to columns left to resident
+/// terrain, hang a skirt down to the world floor instead of a wall, since the
+/// geometry on the other side may come from another level or from full
+/// chunks. Skirts hide the cracks between them without knowing their heights.
+pub(super) fn mesh_lod_tile(grid: &LodTileGrid) -> LodMeshData {
+    let cell_blocks = grid.tile().key.level().cell_blocks() as f32;
+    let mut mesh = LodMeshData::default();
+    for z in 0..LOD_TILE_CELLS {
+        for x in 0..LOD_TILE_CELLS {
+            let cell = grid.cell(x, z);
+            if cell.is_empty() {
+                continue;
+            }
+            let top = f32::from(cell.top());
+            let min = Vec3::new(x as f32 * cell_blocks, 0.0, z as f32 * cell_blocks);
+            let max = min + Vec3::new(cell_blocks, 0.0, cell_blocks);
+            mesh.push_quad(
+                [
+                    Vec3::new(min.x, top, min.z),
+                    Vec3::new(min.x, top, max.z),
+                    Vec3::new(max.x, top, max.z),
+                    Vec3::new(max.x, top, min.z),
+                ],
+                Direction::Up,
+                surface_colour(cell, Direction::Up),
+            );
+
+            for (side, dx, dz) in SIDES {
+                let neighbour = x
+                    .checked_add_signed(dx)
+                    .zip(z.checked_add_signed(dz))
+                    .filter(|&(nx, nz)| nx < LOD_TILE_CELLS && nz < LOD_TILE_CELLS);
+                let bottom = match neighbour {
+                    Some((nx, nz)) if !grid.is_cell_hidden(nx, nz) => {
+                        f32::from(grid.cell(nx, nz).top())
+                    }
+                    _ => 0.0,
+                };
+                if bottom < top {
+                    mesh.push_quad(
+                        wall_corners(side, min, max, bottom, top),
+                        side,
+                        surface_colour(cell, side),
+                    );
+                }
+            }
+        }
+    }
+    mesh
+}
+
+fn wall_corners(side: Direction, min: Vec3, max: Vec3, bottom: f32, top: f32) -> [Vec3; 4] {
+    match side {
+        Direction::Left => [
+            Vec3::new(min.x, bottom, max.z),
+            Vec3::new(min.x, top, max.z),
+            Vec3::new(min.x, top, min.z),
+            Vec3::new(min.x, bottom, min.z),
+        ],
+        Direction::Right => [
+            Vec3::new(max.x, bottom, min.z),
+            Vec3::new(max.x, top, min.z),
+            Vec3::new(max.x, top, max.z),
+            Vec3::new(max.x, bottom, max.z),
+        ],
+        Direction::Forward => [
+            Vec3::new(min.x, bottom, min.z),
+            Vec3::new(min.x, top, min.z),
+            Vec3::new(max.x, top, min.z),
+            Vec3::new(max.x, bottom, min.z),
+        ],
+        Direction::Backward => [
+            Vec3::new(max.x, bottom, max.z),
+            Vec3::new(max.x, top, max.z),
+            Vec3::new(min.x, top, max.z),
+            Vec3::new(min.x, bottom, max.z),
+        ],
+        Direction::Up | Direction::Down => unreachable!("walls only face horizontally"),
+    }
+}
+
+/// Linear vertex colour of a far surface seen from `side`, approximating the
+/// average of its tinted texture and shaded like terrain faces.
+fn surface_colour(cell: SurfaceCell, side: Direction) -> [f32; 4] {
+    let hex = match cell.cell() {
+        ChunkCell::Empty => unreachable!("empty surface cells are not meshed"),
+        ChunkCell::Fluid(_) => "3F76E4",
+        ChunkCell::Block(item) => match item {
+            Item::Grass if side == Direction::Up => "5B8C32",
+            Item::Grass | Item::Dirt => "866043",
+            Item::Stone => "7D7D7D",
+            Item::Sand => "DBD3A0",
+            Item::Glass => "C0DDE4",
+            Item::OakLog if side == Direction::Up => "A0824E",
+            Item::OakLog => "6B5534",
+            Item::OakLeaves => "4A7A22",
+            Item::Glowstone => "D5B46A",
+            Item::Ice => "91B7FD",
+        },
+    };
+    let colour = LinearRgba::from(Srgba::hex(hex).expect("map colours are valid hex"));
+    let brightness = FACE_BRIGHTNESS[side.index()];
+    [
+        colour.red * brightness,
+        colour.green * brightness,
+        colour.blue * brightness,
+        1.0,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{
+        chunk::ChunkColumn,
+        storage::{SURFACE_CELLS, StoredColumnSurface},
+    };
+
+    use super::super::tile::{LodTile, desired_lod_tiles};
+
+    fn flat_tile_grid(
+        tile: LodTile,
+        surface_at: impl Fn(ChunkColumn) -> SurfaceCell,
+    ) -> LodTileGrid {
+        LodTileGrid::build(tile, |column| {
+            StoredColumnSurface::from_fn(|_, _| surface_at(column))
+        })
+    }
+
+    fn tile_with_mask(hidden: bool) -> LodTile {
+        desired_lod_tiles(ChunkColumn::new(0, 0), 4)
+            .into_iter()
+            .find(|tile| (tile.hidden_columns != 0) == hidden)
+            .unwrap()
+    }
+
+    fn assert_faces_point_along_normals(mesh: &LodMeshData) {
+        for quad in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(mesh.positions[quad[i] as usize]));
+            let normal = Vec3::from(mesh.normals[quad[0] as usize]);
+            assert!((b - a).cross(c - a).normalize().dot(normal) > 0.99);
+        }
+    }
+
+    #[test]
+    fn flat_tiles_emit_one_top_per_cell_and_skirts_only_at_borders() {
+        let tile = tile_with_mask(false);
+        let grid = flat_tile_grid(tile, |_| SurfaceCell::new(20, Item::Grass.into()));
+
+        let mesh = mesh_lod_tile(&grid);
+
+        let cells = LOD_TILE_CELLS * LOD_TILE_CELLS;
+        assert_eq!(mesh.quad_count(), cells + 4 * LOD_TILE_CELLS);
+        assert!(
+            mesh.positions
+                .iter()
+                .all(|position| position[1] == 0.0 || position[1] == 21.0)
+        );
+        assert_faces_point_along_normals(&mesh);
+    }
+
+    #[test]
+    fn steps_between_cells_get_walls_on_the_higher_side_only() {
+        let tile = tile_with_mask(false);
+        let first = tile.key.min_column();
+        let grid = flat_tile_grid(tile, |column| {
+            let y = if column == first { 30 } else { 10 };
+            SurfaceCell::new(y, Item::Stone.into())
+        });
+
+        let mesh = mesh_lod_tile(&grid);
+
+        let cells_per_column = SURFACE_CELLS / (tile.key.level().cell_blocks() / 2);
+        let inner_walls = 2 * cells_per_column;
+        let cells = LOD_TILE_CELLS * LOD_TILE_CELLS;
+        assert_eq!(mesh.quad_count(), cells + 4 * LOD_TILE_CELLS + inner_walls);
+        assert_faces_point_along_normals(&mesh);
+    }
+
+    #[test]
+    fn hidden_columns_are_skipped_and_skirted() {
+        let tile = tile_with_mask(true);
+        let grid = flat_tile_grid(tile, |_| SurfaceCell::new(5, Item::Sand.into()));
+
+        let mesh = mesh_lod_tile(&grid);
+
+        let drawn: Vec<_> = (0..LOD_TILE_CELLS)
+            .flat_map(|z| (0..LOD_TILE_CELLS).map(move |x| (x, z)))
+            .filter(|&(x, z)| !grid.is_cell_hidden(x, z))
+            .collect();
+        assert!(drawn.len() < LOD_TILE_CELLS * LOD_TILE_CELLS);
+        let skirts = drawn
+            .iter()
+            .flat_map(|&(x, z)| SIDES.map(|(_, dx, dz)| (x as isize + dx, z as isize + dz)))
+            .filter(|&(x, z)| {
+                let outside = !(0..LOD_TILE_CELLS as isize).contains(&x)
+                    || !(0..LOD_TILE_CELLS as isize).contains(&z);
+                outside || grid.is_cell_hidden(x as usize, z as usize)
+            })
+            .count();
+        let tops = mesh
+            .normals
+            .chunks_exact(4)
+            .filter(|normals| normals[0] == [0.0, 1.0, 0.0])
+            .count();
+        assert_eq!(tops, drawn.len());
+        assert_eq!(mesh.quad_count(), drawn.len() + skirts);
+        assert_faces_point_along_normals(&mesh);
+    }
+
+    #[test]
+    fn empty_surfaces_produce_no_geometry() {
+        let grid = flat_tile_grid(tile_with_mask(false), |_| SurfaceCell::EMPTY);
+
+        assert!(mesh_lod_tile(&grid).is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+//! Far-field terrain beyond the view distance.
+//!
+//! Columns outside the resident view are summarized into cached surfaces and
+//! drawn as coarse height tiles in rings that coarsen with distance. Tiles are
+//! planned whenever the view center moves, meshed on the chunk task pool, and
+//! replaced only once their successors are ready so the horizon never flickers.
+
+mod column;
+mod mesh;
+mod tile;
+
+use std::collections::VecDeque;
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::ShaderRef,
+    tasks::{Task, futures::check_ready},
+};
+
+use crate::{
+    game_state::{GameState, Playing},
+    world::{
+        chunk::{
+            CHUNK_SIZE, ChunkColumn,
+            mesh::{AirFogRange, TerrainVisualSettings},
+        },
+        definition::{ColumnAddress, DimensionId},
+        dimension::{
+            Active, ChunkSaveTasks, ChunkTaskPool, DesiredColumnView, Dimension,
+            DimensionStreamingSet, ViewDistance,
+        },
+        session::WorldSessionSystems,
+        storage::ChunkRepository,
+    },
+};
+
+use column::column_surface;
+use mesh::{LodMeshData, mesh_lod_tile};
+use tile::{LodTile, LodTileGrid, desired_lod_tiles, lod_horizon_columns, lod_reach_columns};
+
+const LOD_TERRAIN_SHADER_PATH: &str = "shaders/lod_terrain.wgsl";
+/// Tiles meshed concurrently; each one summarizes up to 256 columns.
+const MAX_LOD_TILE_TASKS: usize = 4;
+/// Extra far-plane distance past the corner of the farthest tile.
+const LOD_FAR_PLANE_MARGIN: f32 = 64.0;
+
+pub struct LodPlugin;
+
+impl Plugin for LodPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<LodTerrainMaterial>::default())
+            .init_resource::<LodTiles>()
+            .add_systems(Startup, setup_lod_material)
+            .add_systems(
+                Update,
+                (
+                    plan_lod_tiles,
+                    fit_camera_far_plane,
+                    start_lod_tile_tasks,
+                    finish_lod_tile_tasks,
+                    update_lod_material,
+                )
+                    .chain()
+                    .after(DimensionStreamingSet)
+                    .in_set(Playing),
+            )
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                discard_lod_tiles.in_set(WorldSessionSystems::Close),
+            );
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct LodTerrainMaterial {
+    #[uniform(0)]
+    settings: LodTerrainSettings,
+}
+
+impl Material for LodTerrainMaterial {
+    fn fragment_shader() -> ShaderRef {
+        LOD_TERRAIN_SHADER_PATH.into()
+    }
+}
+
+#[derive(ShaderType, Debug, Clone, Copy, PartialEq)]
+struct LodTerrainSettings {
+    sky_light_color: Vec4,
+    /// RGB fog colour with the screen tint strength in alpha.
+    fog_color: Vec4,
+    /// Fog start, end and strength.
+    fog_params: Vec4,
+}
+
+impl From<&TerrainVisualSettings> for LodTerrainSettings {
+    fn from(settings: &TerrainVisualSettings) -> Self {
+        Self {
+            sky_light_color: settings.sky_light_color.extend(0.0),
+            fog_color: settings.fog_color.extend(settings.screen_tint_strength),
+            fog_params: vec4(
+                settings.fog_start,
+                settings.fog_end,
+                settings.fog_strength,
+                0.0,
+            ),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct LodRenderAssets {
+    material: Handle<LodTerrainMaterial>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LodPlanKey {
+    dimension: DimensionId,
+    center: ChunkColumn,
+    view_distance: i32,
+}
+
+/// Planned, meshing and drawn far-field tiles of the active dimension.
+#[derive(Resource, Default)]
+struct LodTiles {
+    plan: Option<LodPlanKey>,
+    queued: VecDeque<LodTile>,
+    in_flight: HashMap<LodTile, Task<LodMeshData>>,
+    /// Finished tiles; empty tiles are remembered without an entity.
+    built: HashMap<LodTile, Option<Entity>>,
+    /// Tiles left out of the plan, kept on screen until the plan is built.
+    retired: Vec<Entity>,
+}
+
+impl LodTiles {
+    fn is_settled(&self) -> bool {
+        self.queued.is_empty() && self.in_flight.is_empty()
+    }
+
+    fn clear(&mut self, commands: &mut Commands) {
+        let built = self.built.drain().filter_map(|(_, entity)| entity);
+        for entity in built.chain(self.retired.drain(..)) {
+            commands.entity(entity).despawn();
+        }
+        self.queued.clear();
+        self.in_flight.clear();
+    }
+}
+
+fn discard_lod_tiles(mut commands: Commands, mut tiles: ResMut<LodTiles>) {
+    tiles.clear(&mut commands);
+    tiles.plan = None;
+}
+
+fn setup_lod_material(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<LodTerrainMaterial>>,
+    settings: Res<TerrainVisualSettings>,
+) {
+    let material = materials.add(LodTerrainMaterial {
+        settings: LodTerrainSettings::from(&*settings),
+    });
+    commands.insert_resource(LodRenderAssets { material });
+}
+
+fn plan_lod_tiles(
+    mut commands: Commands,
+    mut tiles: ResMut<LodTiles>,
+    mut air_fog: ResMut<AirFogRange>,
+    view_distance: Res<ViewDistance>,
+    dimension: Option<Single<(&Dimension, &DesiredColumnView), With<Active>>>,
+) {
+    let Some((dimension, desired_view)) = dimension.as_deref().copied() else {
+        return;
+    };
+    let Some(center) = desired_view.center() else {
+        return;
+    };
+    let key = LodPlanKey {
+        dimension: dimension.id(),
+        center,
+        view_distance: view_distance.chunks(),
+    };
+    if tiles.plan == Some(key) {
+        return;
+    }
+    if tiles
+        .plan
+        .is_some_and(|plan| plan.dimension != key.dimension)
+    {
+        tiles.clear(&mut commands);
+    }
+    tiles.plan = Some(key);
+
+    let desired = desired_lod_tiles(center, key.view_distance);
+    let wanted = desired.iter().copied().collect::<HashSet<_>>();
+    let LodTiles {
+        queued,
+        in_flight,
+        built,
+        retired,
+        ..
+    } = &mut *tiles;
+    built.retain(|tile, entity| {
+        let keep = wanted.contains(tile);
+        if !keep {
+            retired.extend(entity.take());
+        }
+        keep
+    });
+    in_flight.retain(|tile, _| wanted.contains(tile));
+    queued.clear();
+    queued.extend(
+        desired
+            .into_iter()
+            .filter(|tile| !built.contains_key(tile) && !in_flight.contains_key(tile)),
+    );
+
+    let block = CHUNK_SIZE as f32;
+    *air_fog = AirFogRange {
+        start: key.view_distance as f32 * block,
+        end: lod_horizon_columns(key.view_distance) as f32 * block,
+    };
+}
+
+/// Pushes the far plane of perspective cameras, including ones spawned after
+/// the plan, past the corner of the farthest tile.
+fn fit_camera_far_plane(
+    tiles: Res<LodTiles>,
+    mut projections: Query<&mut Projection, With<Camera3d>>,
+) {
+    let Some(plan) = tiles.plan else {
+        return;
+    };
+    let far =
+        lod_reach_columns(plan.view_distance) as f32 * CHUNK_SIZE as f32 * std::f32::consts::SQRT_2
+            + LOD_FAR_PLANE_MARGIN;
+    for mut projection in &mut projections {
+        let Projection::Perspective(perspective) = &*projection else {
+            continue;
+        };
+        if perspective.far < far
+            && let Projection::Perspective(perspective) = &mut *projection
+        {
+            perspective.far = far;
+        }
+    }
+}
+
+fn start_lod_tile_tasks(
+    mut tiles: ResMut<LodTiles>,
+    repository: Res<ChunkRepository>,
+    save_tasks: Res<ChunkSaveTasks>,
+    task_pool: Res<ChunkTaskPool>,
+    dimension: Option<Single<&Dimension, With<Active>>>,
+) {
+    let Some(dimension) = dimension else {
+        return;
+    };
+    // Surfaces derived while chunk saves are in flight could outlive the
+    // save that was meant to drop them.
+    if save_tasks.has_uncommitted_dimension(dimension.id()) {
+        return;
+    }
+
+    let dimension_id = dimension.id();
+    let profile = dimension.definition().generator();
+    while tiles.in_flight.len() < MAX_LOD_TILE_TASKS {
+        let Some(tile) = tiles.queued.pop_front() else {
+            break;
+        };
+        let repository = repository.clone();
+        let task = task_pool.spawn(async move {
+            let grid = LodTileGrid::build(tile, |column| {
+                column_surface(
+                    &repository,
+                    profile,
+                    ColumnAddress::new(dimension_id, column),
+                )
+            });
+            mesh_lod_tile(&grid)
+        });
+        tiles.in_flight.insert(tile, task);
+    }
+}
+
+fn finish_lod_tile_tasks(
+    mut commands: Commands,
+    mut tiles: ResMut<LodTiles>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<LodRenderAssets>,
+) {
+    let mut finished = Vec::new();
+    tiles
+        .in_flight
+        .retain(|&tile, task| match check_ready(task) {
+            Some(mesh) => {
+                finished.push((tile, mesh));
+                false
+            }
+            None => true,
+        });
+
+    for (tile, mesh) in finished {
+        let entity = (!mesh.is_empty()).then(|| {
+            let origin = tile.key.origin_block();
+            commands
+                .spawn((
+                    Name::new("LOD terrain tile"),
+                    Mesh3d(meshes.add(mesh.into_mesh())),
+                    MeshMaterial3d(assets.material.clone()),
+                    Transform::from_xyz(origin.x as f32, 0.0, origin.y as f32),
+                ))
+                .id()
+        });
+        tiles.built.insert(tile, entity);
+    }
+
+    if tiles.is_settled() {
+        for entity in tiles.retired.drain(..) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn update_lod_material(
+    settings: Res<TerrainVisualSettings>,
+    assets: Res<LodRenderAssets>,
+    mut materials: ResMut<Assets<LodTerrainMaterial>>,
+) {
+    let wanted = LodTerrainSettings::from(&*settings);
+    if materials
+        .get(&assets.material)
+        .is_some_and(|material| material.settings != wanted)
+        && let Some(material) = materials.get_mut(&assets.material)
+    {
+        material.settings = wanted;
+    }
+}
@@ -0,0 +1,414 @@
+use bevy::math::IVec2;
+
+use crate::world::{
+    chunk::{CHUNK_SIZE, ChunkColumn},
+    storage::{SURFACE_CELLS, StoredColumnSurface, SurfaceCell},
+};
+
+/// Cells along each edge of one far-field tile, at every level.
+pub(super) const LOD_TILE_CELLS: usize = 32;
+
+/// Outer radius of each level's ring, in quarters of the view distance. The
+/// last ring is the far edge of everything drawn.
+const LOD_RING_QUARTERS: [i32; LodLevel::COUNT] = [5, 8, 16];
+
+/// A far-field detail level whose cells span `2^level` blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(super) struct LodLevel(u8);
+
+impl LodLevel {
+    pub(super) const COUNT: usize = 3;
+    pub(super) const FINEST: Self = Self(1);
+    pub(super) const COARSEST: Self = Self(Self::COUNT as u8);
+
+    pub(super) const fn cell_blocks(self) -> usize {
+        1 << self.0
+    }
+
+    /// Surface summary cells merged into one cell of this level.
+    const fn surface_cells_per_cell(self) -> usize {
+        self.cell_blocks() / (CHUNK_SIZE / SURFACE_CELLS)
+    }
+
+    pub(super) const fn tile_columns(self) -> i32 {
+        (LOD_TILE_CELLS * self.cell_blocks() / CHUNK_SIZE) as i32
+    }
+
+    const fn finer(self) -> Option<Self> {
+        if self.0 > Self::FINEST.0 {
+            Some(Self(self.0 - 1))
+        } else {
+            None
+        }
+    }
+
+    /// Columns from the view center to the outer edge of this level's ring.
+    fn ring_outer_columns(self, view_distance: i32) -> i32 {
+        // Rounds up; view distances are always positive.
+        (view_distance * LOD_RING_QUARTERS[self.0 as usize - 1] + 3) / 4
+    }
+}
+
+/// Radius in columns out to which the far field covers every column.
+pub(super) fn lod_horizon_columns(view_distance: i32) -> i32 {
+    LodLevel::COARSEST.ring_outer_columns(view_distance.max(1))
+}
+
+/// The farthest any far-field tile can reach in columns, since edge tiles
+/// overhang the horizon by up to one coarsest tile.
+pub(super) fn lod_reach_columns(view_distance: i32) -> i32 {
+    lod_horizon_columns(view_distance) + LodLevel::COARSEST.tile_columns()
+}
+
+/// A square tile of one level, addressed in whole tiles of that level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct LodTileKey {
+    level: LodLevel,
+    x: i32,
+    z: i32,
+}
+
+impl LodTileKey {
+    const fn new(level: LodLevel, x: i32, z: i32) -> Self {
+        Self { level, x, z }
+    }
+
+    pub(super) const fn level(self) -> LodLevel {
+        self.level
+    }
+
+    pub(super) const fn min_column(self) -> ChunkColumn {
+        let size = self.level.tile_columns();
+        ChunkColumn::new(self.x * size, self.z * size)
+    }
+
+    /// World block X and Z of the tile's minimum corner.
+    pub(super) const fn origin_block(self) -> IVec2 {
+        let column = self.min_column();
+        IVec2::new(
+            column.x() * CHUNK_SIZE as i32,
+            column.z() * CHUNK_SIZE as i32,
+        )
+    }
+
+    /// Columns covered by the tile in Z-major order.
+    pub(super) fn columns(self) -> impl Iterator<Item = ChunkColumn> {
+        let min = self.min_column();
+        let size = self.level.tile_columns();
+        (0..size)
+            .flat_map(move |z| (0..size).map(move |x| ChunkColumn::new(min.x() + x, min.z() + z)))
+    }
+
+    fn children(self) -> Option<[Self; 4]> {
+        let level = self.level.finer()?;
+        let (x, z) = (self.x * 2, self.z * 2);
+        Some([
+            Self::new(level, x, z),
+            Self::new(level, x + 1, z),
+            Self::new(level, x, z + 1),
+            Self::new(level, x + 1, z + 1),
+        ])
+    }
+
+    /// Squared column distance from `center` to the tile's nearest column.
+    fn nearest_distance_squared(self, center: ChunkColumn) -> i64 {
+        let min = self.min_column();
+        let max = ChunkColumn::new(
+            min.x() + self.level.tile_columns() - 1,
+            min.z() + self.level.tile_columns() - 1,
+        );
+        let dx = i64::from((min.x() - center.x()).max(center.x() - max.x()).max(0));
+        let dz = i64::from((min.z() - center.z()).max(center.z() - max.z()).max(0));
+        dx * dx + dz * dz
+    }
+}
+
+/// One tile to draw, with the columns it leaves to resident terrain.
+///
+/// Only finest tiles can reach into the resident view, so the mask has one
+/// bit per column of a finest tile, Z-major.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct LodTile {
+    pub(super) key: LodTileKey,
+    pub(super) hidden_columns: u16,
+}
+
+impl LodTile {
+    /// Whether the column at offset `dx`, `dz` from the tile's minimum
+    /// column is left to resident terrain.
+    pub(super) fn is_column_hidden(self, dx: i32, dz: i32) -> bool {
+        if self.key.level != LodLevel::FINEST {
+            return false;
+        }
+        let size = self.key.level.tile_columns();
+        self.hidden_columns & (1 << (dz * size + dx)) != 0
+    }
+}
+
+const _: () = assert!(LodLevel::FINEST.tile_columns().pow(2) <= u16::BITS as i32);
+
+/// Tiles covering the rings around `center` beyond `view_distance`,
+/// nearest first.
+///
+/// Coarsest tiles are split into quadrants while they reach into the ring of
+/// the next finer level, so detail falls off with distance and only finest
+/// tiles ever border the resident view.
+pub(super) fn desired_lod_tiles(center: ChunkColumn, view_distance: i32) -> Vec<LodTile> {
+    let view_distance = view_distance.max(1);
+    let reach = LodLevel::COARSEST.ring_outer_columns(view_distance);
+    let size = LodLevel::COARSEST.tile_columns();
+    let min = IVec2::new(center.x() - reach, center.z() - reach).div_euclid(IVec2::splat(size));
+    let max = IVec2::new(center.x() + reach, center.z() + reach).div_euclid(IVec2::splat(size));
+
+    let mut tiles = Vec::new();
+    for z in min.y..=max.y {
+        for x in min.x..=max.x {
+            collect_tiles(
+                LodTileKey::new(LodLevel::COARSEST, x, z),
+                center,
+                view_distance,
+                &mut tiles,
+            );
+        }
+    }
+    tiles.sort_by_key(|tile| {
+        (
+            tile.key.nearest_distance_squared(center),
+            tile.key.level,
+            tile.key.z,
+            tile.key.x,
+        )
+    });
+    tiles
+}
+
+fn collect_tiles(
+    key: LodTileKey,
+    center: ChunkColumn,
+    view_distance: i32,
+    tiles: &mut Vec<LodTile>,
+) {
+    let distance = key.nearest_distance_squared(center);
+    let reach = i64::from(LodLevel::COARSEST.ring_outer_columns(view_distance));
+    if distance > reach * reach {
+        return;
+    }
+
+    if let Some(children) = key.children() {
+        let finer_outer = i64::from(
+            key.level
+                .finer()
+                .map_or(0, |level| level.ring_outer_columns(view_distance)),
+        );
+        if distance < finer_outer * finer_outer {
+            for child in children {
+                collect_tiles(child, center, view_distance, tiles);
+            }
+            return;
+        }
+    }
+
+    let mut hidden_columns = 0u16;
+    if key.level == LodLevel::FINEST {
+        let radius_squared = i64::from(view_distance) * i64::from(view_distance);
+        for (index, column) in key.columns().enumerate() {
+            let dx = i64::from(column.x() - center.x());
+            let dz = i64::from(column.z() - center.z());
+            if dx * dx + dz * dz <= radius_squared {
+                hidden_columns |= 1 << index;
+            }
+        }
+        if hidden_columns == u16::MAX {
+            return;
+        }
+    }
+    tiles.push(LodTile {
+        key,
+        hidden_columns,
+    });
+}
+
+/// The downsampled surface of one tile, one cell per [`LodLevel::cell_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LodTileGrid {
+    tile: LodTile,
+    cells: Vec<SurfaceCell>,
+}
+
+impl LodTileGrid {
+    /// Merges column surfaces into tile cells, keeping the highest surface
+    /// of each cell so distant hills keep their silhouettes. Hidden columns
+    /// are left empty and never asked for.
+    pub(super) fn build(
+        tile: LodTile,
+        mut surface: impl FnMut(ChunkColumn) -> StoredColumnSurface,
+    ) -> Self {
+        let level = tile.key.level;
+        let merge = level.surface_cells_per_cell();
+        let cells_per_column = SURFACE_CELLS / merge;
+        let mut cells = vec![SurfaceCell::EMPTY; LOD_TILE_CELLS * LOD_TILE_CELLS];
+        let min = tile.key.min_column();
+        for column in tile.key.columns() {
+            let (dx, dz) = (column.x() - min.x(), column.z() - min.z());
+            if tile.is_column_hidden(dx, dz) {
+                continue;
+            }
+            let surface = surface(column);
+            for cx in 0..cells_per_column {
+                for cz in 0..cells_per_column {
+                    let highest = (0..merge)
+                        .flat_map(|sx| (0..merge).map(move |sz| (sx, sz)))
+                        .map(|(sx, sz)| surface.cell(cx * merge + sx, cz * merge + sz))
+                        .max_by_key(|cell| cell.top())
+                        .unwrap_or(SurfaceCell::EMPTY);
+                    let x = dx as usize * cells_per_column + cx;
+                    let z = dz as usize * cells_per_column + cz;
+                    cells[z * LOD_TILE_CELLS + x] = highest;
+                }
+            }
+        }
+        Self { tile, cells }
+    }
+
+    pub(super) const fn tile(&self) -> LodTile {
+        self.tile
+    }
+
+    pub(super) fn cell(&self, x: usize, z: usize) -> SurfaceCell {
+        self.cells[z * LOD_TILE_CELLS + x]
+    }
+
+    /// Whether a tile cell lies in a column left to resident terrain.
+    pub(super) fn is_cell_hidden(&self, x: usize, z: usize) -> bool {
+        let cells_per_column = LOD_TILE_CELLS / self.tile.key.level.tile_columns() as usize;
+        self.tile
+            .is_column_hidden((x / cells_per_column) as i32, (z / cells_per_column) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Item;
+    use crate::world::dimension::columns_in_radius;
+
+    fn level(value: u8) -> LodLevel {
+        LodLevel(value)
+    }
+
+    #[test]
+    fn tiles_span_the_same_cells_at_doubling_sizes() {
+        assert_eq!(LodLevel::FINEST.cell_blocks(), 2);
+        assert_eq!(LodLevel::COARSEST.cell_blocks(), 8);
+        assert_eq!(
+            [1, 2, 3].map(|value| level(value).tile_columns()),
+            [4, 8, 16]
+        );
+        let key = LodTileKey::new(level(2), -1, 3);
+        assert_eq!(key.min_column(), ChunkColumn::new(-8, 24));
+        assert_eq!(key.origin_block(), IVec2::new(-128, 384));
+        assert_eq!(key.columns().count(), 64);
+    }
+
+    #[test]
+    fn tiles_cover_each_far_column_exactly_once_and_skip_the_view() {
+        let center = ChunkColumn::new(-5, 9);
+        let view_distance = 6;
+        let tiles = desired_lod_tiles(center, view_distance);
+
+        let mut covered = std::collections::HashMap::new();
+        for tile in &tiles {
+            let min = tile.key.min_column();
+            for column in tile.key.columns() {
+                if !tile.is_column_hidden(column.x() - min.x(), column.z() - min.z()) {
+                    *covered.entry(column).or_insert(0) += 1;
+                }
+            }
+        }
+        assert!(covered.values().all(|&count| count == 1));
+
+        for column in columns_in_radius(center, view_distance) {
+            assert!(!covered.contains_key(&column), "{column:?} is resident");
+        }
+        let reach = LodLevel::COARSEST.ring_outer_columns(view_distance);
+        for column in columns_in_radius(center, reach) {
+            let dx = column.x() - center.x();
+            let dz = column.z() - center.z();
+            if dx * dx + dz * dz > view_distance * view_distance {
+                assert!(covered.contains_key(&column), "{column:?} is uncovered");
+            }
+        }
+    }
+
+    #[test]
+    fn rings_coarsen_with_distance_and_only_finest_tiles_are_masked() {
+        let center = ChunkColumn::new(0, 0);
+        let view_distance = 8;
+        let tiles = desired_lod_tiles(center, view_distance);
+
+        for pair in tiles.windows(2) {
+            assert!(
+                pair[0].key.nearest_distance_squared(center)
+                    <= pair[1].key.nearest_distance_squared(center)
+            );
+        }
+        for tile in &tiles {
+            let distance = tile.key.nearest_distance_squared(center);
+            if tile.key.level != LodLevel::FINEST {
+                assert_eq!(tile.hidden_columns, 0);
+                let finer = tile.key.level.finer().unwrap();
+                let outer = i64::from(finer.ring_outer_columns(view_distance));
+                assert!(distance >= outer * outer);
+            }
+        }
+        assert!(
+            tiles
+                .iter()
+                .any(|tile| tile.key.level == LodLevel::FINEST && tile.hidden_columns != 0)
+        );
+        assert!(
+            tiles
+                .iter()
+                .any(|tile| tile.key.level == LodLevel::COARSEST)
+        );
+    }
+
+    #[test]
+    fn grids_keep_the_highest_surface_of_each_merged_cell() {
+        let tile = LodTile {
+            key: LodTileKey::new(level(2), 0, 0),
+            hidden_columns: 0,
+        };
+        let grid = LodTileGrid::build(tile, |column| {
+            StoredColumnSurface::from_fn(|x, z| {
+                if column == ChunkColumn::new(1, 0) && (x, z) == (3, 1) {
+                    SurfaceCell::new(40, Item::Stone.into())
+                } else {
+                    SurfaceCell::new(10, Item::Grass.into())
+                }
+            })
+        });
+
+        // Column 1 starts at tile cell 4; surface cell 3 falls in its cell 1.
+        assert_eq!(grid.cell(5, 0), SurfaceCell::new(40, Item::Stone.into()));
+        assert_eq!(grid.cell(4, 0), SurfaceCell::new(10, Item::Grass.into()));
+        assert_eq!(grid.cell(5, 1), SurfaceCell::new(10, Item::Grass.into()));
+    }
+
+    #[test]
+    fn grids_leave_hidden_columns_empty_without_loading_them() {
+        let tile = LodTile {
+            key: LodTileKey::new(LodLevel::FINEST, 0, 0),
+            hidden_columns: 1 << 5,
+        };
+        let grid = LodTileGrid::build(tile, |column| {
+            assert_ne!(column, ChunkColumn::new(1, 1), "hidden column was loaded");
+            StoredColumnSurface::from_fn(|_, _| SurfaceCell::new(3, Item::Sand.into()))
+        });
+
+        assert!(grid.is_cell_hidden(8, 15));
+        assert!(grid.cell(8, 15).is_empty());
+        assert!(!grid.is_cell_hidden(16, 15));
+        assert!(!grid.cell(16, 15).is_empty());
+    }
+}
@@ -2,19 +2,24 @@ pub mod chunk;
 pub mod definition;
 pub mod dimension;
 pub mod generation;
+pub mod inspect;
 pub mod loading;
+pub mod lod;
+pub mod saves;
+pub mod session;
 pub mod storage;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use avian3d::prelude::CollisionLayers;
 use bevy::prelude::*;
 
 use chunk::ChunkPlugin;
 use dimension::DimensionPlugin;
+use lod::LodPlugin;
 use storage::{
-    ChunkRepository, ChunkStoreResult, InMemoryChunkStore, NoopChunkStore, SqliteChunkStore,
-    development_world_path,
+    ChunkRepository, ChunkStoreResult, InMemoryChunkStore, NoopChunkStore, RegionChunkStore,
+    SqliteChunkStore, development_region_path, development_world_path,
 };
 #[cfg(feature = "turso-store")]
 use storage::{TursoChunkStore, development_turso_path};
@@ -61,6 +66,13 @@ impl WorldConfig {
         }
     }
 
+    pub fn region(metadata: WorldMetadata, path: impl Into<PathBuf>) -> Self {
+        Self {
+            metadata,
+            storage: WorldStorageConfig::Region { path: path.into() },
+        }
+    }
+
     #[cfg(feature = "turso-store")]
     pub fn turso(metadata: WorldMetadata, path: impl Into<PathBuf>) -> Self {
         Self {
@@ -74,6 +86,11 @@ impl WorldConfig {
         Self::sqlite(metadata, path)
     }
 
+    pub fn development_region(metadata: WorldMetadata) -> Self {
+        let path = development_region_path(&metadata);
+        Self::region(metadata, path)
+    }
+
     #[cfg(feature = "turso-store")]
     pub fn development_turso(metadata: WorldMetadata) -> Self {
         let path = development_turso_path(&metadata);
@@ -94,12 +111,28 @@ pub enum WorldStorageConfig {
     Sqlite {
         path: PathBuf,
     },
+    /// A directory of region files with a write-ahead journal.
+    Region {
+        path: PathBuf,
+    },
     #[cfg(feature = "turso-store")]
     Turso {
         path: PathBuf,
     },
 }
 
+impl WorldStorageConfig {
+    /// The store's file or directory, for stores kept on disk.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::InMemory | Self::Noop => None,
+            Self::Sqlite { path } | Self::Region { path } => Some(path),
+            #[cfg(feature = "turso-store")]
+            Self::Turso { path } => Some(path),
+        }
+    }
+}
+
 pub const WORLD_LAYER: u32 = 1 << 0;
 pub const ACTOR_LAYER: u32 = 1 << 1;
 pub const ITEM_LAYER: u32 = 1 << 2;
@@ -118,7 +151,8 @@ impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         ensure_world_resources(app);
         configure_chunk_simulation(app);
-        app.add_plugins((DimensionPlugin, ChunkPlugin));
+        session::install(app);
+        app.add_plugins((DimensionPlugin, ChunkPlugin, LodPlugin));
     }
 }
 
@@ -189,7 +223,7 @@ fn ensure_world_resources(app: &mut App) {
     app.insert_resource(repository);
 }
 
-fn build_chunk_repository(config: &WorldConfig) -> ChunkStoreResult<ChunkRepository> {
+pub(crate) fn build_chunk_repository(config: &WorldConfig) -> ChunkStoreResult<ChunkRepository> {
     match &config.storage {
         WorldStorageConfig::InMemory => {
             info!(seed = config.metadata.seed, "Using in-memory chunk store");
@@ -212,6 +246,15 @@ fn build_chunk_repository(config: &WorldConfig) -> ChunkStoreResult<ChunkReposit
             );
             Ok(ChunkRepository::new(store))
         }
+        WorldStorageConfig::Region { path } => {
+            let store = RegionChunkStore::open(path, &config.metadata)?;
+            info!(
+                seed = config.metadata.seed,
+                path = %path.display(),
+                "Using region-file chunk store"
+            );
+            Ok(ChunkRepository::new(store))
+        }
         #[cfg(feature = "turso-store")]
         WorldStorageConfig::Turso { path } => {
             let store = TursoChunkStore::open(path, &config.metadata)?;
@@ -295,6 +338,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn development_region_config_uses_seeded_directory() {
+        let metadata = WorldMetadata::with_seed(42);
+        let config = WorldConfig::development_region(metadata.clone());
+
+        assert_eq!(config.metadata, metadata);
+        assert_eq!(
+            config.storage,
+            WorldStorageConfig::Region {
+                path: development_region_path(&metadata)
+            }
+        );
+    }
+
     #[test]
     fn noop_world_config_is_selectable() {
         let metadata = WorldMetadata::with_seed(42);
@@ -0,0 +1,490 @@
+//! Worlds kept in the saves directory, as listed by the title screen.
+//!
+//! Each world is one store: an SQLite file, a region directory or, with the
+//! `turso-store` feature, a Turso file. Its seed and height come from the
+//! store's `world_metadata`; the display name and the generator players start
+//! on are mutable world-state entries next to them.
+
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+
+use super::{
+    DimensionId, GeneratorProfile, InvalidWorldHeight, WorldConfig, WorldMetadata,
+    WorldStorageConfig, build_chunk_repository,
+    generation::mix_u64,
+    storage::{ChunkRepository, ChunkStoreError, WorldStoreKind},
+};
+use crate::util::fnv1a;
+
+pub const SAVES_DIRECTORY: &str = "saves";
+pub const MAX_WORLD_NAME_CHARS: usize = 32;
+
+const WORLD_NAME_KEY: &str = "world.name";
+const WORLD_SPAWN_GENERATOR_KEY: &str = "world.spawn_generator";
+/// Earlier builds kept one world per seed here; they are still listed.
+const DEVELOPMENT_SUBDIRECTORY: &str = "dev";
+
+/// Where the title screen looks for and creates worlds.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct SavesDirectory(pub PathBuf);
+
+impl Default for SavesDirectory {
+    fn default() -> Self {
+        Self(PathBuf::from(SAVES_DIRECTORY))
+    }
+}
+
+/// One world found in the saves directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldSave {
+    pub name: String,
+    pub config: WorldConfig,
+    pub spawn_generator: GeneratorProfile,
+}
+
+/// A validated create-world form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewWorld {
+    pub name: String,
+    pub metadata: WorldMetadata,
+    pub spawn_generator: GeneratorProfile,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorldSaveError {
+    EmptyName,
+    NameTooLong,
+    HeightNotANumber { text: String },
+    InvalidHeight(InvalidWorldHeight),
+    Store(ChunkStoreError),
+}
+
+impl std::fmt::Display for WorldSaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "world name cannot be empty"),
+            Self::NameTooLong => {
+                write!(
+                    f,
+                    "world name must be at most {MAX_WORLD_NAME_CHARS} characters"
+                )
+            }
+            Self::HeightNotANumber { text } => {
+                write!(f, "world height {text:?} is not a whole number of chunks")
+            }
+            Self::InvalidHeight(error) => error.fmt(f),
+            Self::Store(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for WorldSaveError {}
+
+impl From<InvalidWorldHeight> for WorldSaveError {
+    fn from(value: InvalidWorldHeight) -> Self {
+        Self::InvalidHeight(value)
+    }
+}
+
+impl From<ChunkStoreError> for WorldSaveError {
+    fn from(value: ChunkStoreError) -> Self {
+        Self::Store(value)
+    }
+}
+
+impl From<std::io::Error> for WorldSaveError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Store(value.into())
+    }
+}
+
+impl WorldSave {
+    pub fn metadata(&self) -> &WorldMetadata {
+        &self.config.metadata
+    }
+
+    fn read(path: PathBuf) -> Option<Result<Self, ChunkStoreError>> {
+        let kind = WorldStoreKind::from_extension(path.extension()?)?;
+        if path.is_dir() != (kind == WorldStoreKind::Region) {
+            return None;
+        }
+        let entries = kind.read_metadata_entries(&path);
+        let storage = match kind {
+            WorldStoreKind::Sqlite => WorldStorageConfig::Sqlite { path },
+            WorldStoreKind::Region => WorldStorageConfig::Region { path },
+            #[cfg(feature = "turso-store")]
+            WorldStoreKind::Turso => WorldStorageConfig::Turso { path },
+        };
+
+        Some(entries.and_then(|entries| {
+            let metadata = super::storage::world_metadata_from_entries(&entries)?;
+            let entry = |key: &str| {
+                entries
+                    .iter()
+                    .find(|(entry, _)| entry == key)
+                    .map(|(_, value)| value.as_str())
+            };
+            let name = entry(WORLD_NAME_KEY)
+                .map(str::to_owned)
+                .unwrap_or_else(|| fallback_world_name(&storage));
+            let spawn_generator = entry(WORLD_SPAWN_GENERATOR_KEY)
+                .and_then(GeneratorProfile::from_family)
+                .unwrap_or(GeneratorProfile::OverworldV1);
+
+            Ok(Self {
+                name,
+                config: WorldConfig { metadata, storage },
+                spawn_generator,
+            })
+        }))
+    }
+}
+
+impl NewWorld {
+    /// Validates the create-world form. A blank seed picks a random one.
+    pub fn from_form(
+        name: &str,
+        seed: &str,
+        height_chunks: &str,
+        spawn_generator: GeneratorProfile,
+    ) -> Result<Self, WorldSaveError> {
+        let name = validate_world_name(name)?;
+        let height_chunks = height_chunks.trim();
+        let height_chunks =
+            height_chunks
+                .parse::<usize>()
+                .map_err(|_| WorldSaveError::HeightNotANumber {
+                    text: height_chunks.to_owned(),
+                })?;
+        let seed = seed_from_text(seed).unwrap_or_else(random_seed);
+        let metadata = WorldMetadata::with_seed(seed).with_height_chunks(height_chunks)?;
+
+        Ok(Self {
+            name,
+            metadata,
+            spawn_generator,
+        })
+    }
+}
+
+/// Turns seed text into a world seed: whole numbers are used as-is, anything
+/// else is hashed. Blank text has no seed.
+pub fn seed_from_text(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(seed) = text.parse::<u64>() {
+        return Some(seed);
+    }
+    if let Ok(seed) = text.parse::<i64>() {
+        return Some(seed as u64);
+    }
+
+    // FNV-1a, so the same text gives the same world on every build.
+    Some(mix_u64(fnv1a(text.bytes())))
+}
+
+fn random_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    mix_u64(nanos ^ u64::from(std::process::id()).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+fn validate_world_name(name: &str) -> Result<String, WorldSaveError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(WorldSaveError::EmptyName);
+    }
+    if name.chars().count() > MAX_WORLD_NAME_CHARS {
+        return Err(WorldSaveError::NameTooLong);
+    }
+    Ok(name.to_owned())
+}
+
+/// Lists the worlds in `directory` and its development subdirectory, sorted
+/// by name. Stores that cannot be read are logged and left out.
+pub fn list_worlds(directory: &Path) -> Vec<WorldSave> {
+    let mut worlds = [
+        directory.to_path_buf(),
+        directory.join(DEVELOPMENT_SUBDIRECTORY),
+    ]
+    .iter()
+    .filter_map(|directory| match fs::read_dir(directory) {
+        Ok(entries) => Some(entries),
+        Err(error) if error.kind() == ErrorKind::NotFound => None,
+        Err(error) => {
+            warn!(%error, directory = %directory.display(), "Failed to list saved worlds");
+            None
+        }
+    })
+    .flatten()
+    .filter_map(|entry| WorldSave::read(entry.ok()?.path()))
+    .filter_map(|world| {
+        world
+            .inspect_err(|error| warn!(%error, "Skipping a saved world that cannot be read"))
+            .ok()
+    })
+    .collect::<Vec<_>>();
+    worlds.sort_by_cached_key(|world| world.name.to_lowercase());
+    worlds
+}
+
+/// Creates a new world store in `directory`, named after the world.
+pub fn create_world(directory: &Path, world: NewWorld) -> Result<WorldSave, WorldSaveError> {
+    let config = new_world_config(directory, &world.name, world.metadata);
+    let repository = build_chunk_repository(&config)?;
+    repository.save_world_state(WORLD_NAME_KEY, &world.name)?;
+    repository.save_world_state(WORLD_SPAWN_GENERATOR_KEY, world.spawn_generator.family())?;
+    info!(
+        name = world.name,
+        seed = config.metadata.seed,
+        "Created world"
+    );
+
+    Ok(WorldSave {
+        name: world.name,
+        config,
+        spawn_generator: world.spawn_generator,
+    })
+}
+
+fn new_world_config(directory: &Path, name: &str, metadata: WorldMetadata) -> WorldConfig {
+    #[cfg(feature = "turso-store")]
+    const EXTENSION: &str = "turso";
+    #[cfg(not(feature = "turso-store"))]
+    const EXTENSION: &str = "sqlite3";
+
+    let mut stem = name
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() {
+                character.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() {
+        stem = "world".to_owned();
+    }
+
+    let mut path = directory.join(format!("{stem}.{EXTENSION}"));
+    for suffix in 2.. {
+        if !path.exists() {
+            break;
+        }
+        path = directory.join(format!("{stem}-{suffix}.{EXTENSION}"));
+    }
+
+    #[cfg(feature = "turso-store")]
+    {
+        WorldConfig::turso(metadata, path)
+    }
+    #[cfg(not(feature = "turso-store"))]
+    {
+        WorldConfig::sqlite(metadata, path)
+    }
+}
+
+/// Changes the name the title screen shows. The store keeps its file name.
+pub fn rename_world(world: &mut WorldSave, name: &str) -> Result<(), WorldSaveError> {
+    let name = validate_world_name(name)?;
+    build_chunk_repository(&world.config)?.save_world_state(WORLD_NAME_KEY, &name)?;
+    world.name = name;
+    Ok(())
+}
+
+/// Removes a world's store from disk.
+pub fn delete_world(world: &WorldSave) -> Result<(), WorldSaveError> {
+    match &world.config.storage {
+        WorldStorageConfig::InMemory | WorldStorageConfig::Noop => {}
+        WorldStorageConfig::Region { path } => fs::remove_dir_all(path)?,
+        WorldStorageConfig::Sqlite { path } => remove_database_files(path)?,
+        #[cfg(feature = "turso-store")]
+        WorldStorageConfig::Turso { path } => remove_database_files(path)?,
+    }
+    info!(name = world.name, "Deleted world");
+    Ok(())
+}
+
+fn remove_database_files(path: &Path) -> std::io::Result<()> {
+    fs::remove_file(path)?;
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        match fs::remove_file(sidecar) {
+            Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// The dimension new players start in: the one using the generator picked
+/// when the world was created, or the overworld.
+pub fn starting_dimension(repository: &ChunkRepository) -> DimensionId {
+    let generator = match repository.load_world_state(WORLD_SPAWN_GENERATOR_KEY) {
+        Ok(family) => family.as_deref().and_then(GeneratorProfile::from_family),
+        Err(error) => {
+            warn!(%error, "Failed to read the world's starting generator");
+            None
+        }
+    };
+    generator
+        .and_then(|generator| {
+            repository
+                .catalog()
+                .iter()
+                .find(|definition| definition.generator() == generator)
+        })
+        .map_or(DimensionId::OVERWORLD, |definition| definition.id())
+}
+
+fn fallback_world_name(storage: &WorldStorageConfig) -> String {
+    storage.path().and_then(Path::file_stem).map_or_else(
+        || "World".to_owned(),
+        |stem| stem.to_string_lossy().into_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::world::storage::InMemoryChunkStore;
+
+    static NEXT_TEST_DIRECTORY_ID: AtomicUsize = AtomicUsize::new(0);
+
+    struct TestSavesDirectory(PathBuf);
+
+    impl TestSavesDirectory {
+        fn new() -> Self {
+            let id = NEXT_TEST_DIRECTORY_ID.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!(
+                "minecraft_clone-saves-test-{}-{id}",
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TestSavesDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn new_world(name: &str, seed: &str) -> NewWorld {
+        NewWorld::from_form(name, seed, "4", GeneratorProfile::GrassFloorV1).unwrap()
+    }
+
+    #[test]
+    fn seed_text_is_numeric_or_hashed() {
+        assert_eq!(seed_from_text(" 42 "), Some(42));
+        assert_eq!(seed_from_text("-1"), Some(u64::MAX));
+        assert_eq!(seed_from_text("glacier"), seed_from_text("glacier"));
+        assert_ne!(seed_from_text("glacier"), seed_from_text("Glacier"));
+        assert_eq!(seed_from_text("   "), None);
+    }
+
+    #[test]
+    fn form_validates_name_and_height() {
+        let world =
+            NewWorld::from_form("  Island ", "7", "3", GeneratorProfile::OverworldV1).unwrap();
+        assert_eq!(world.name, "Island");
+        assert_eq!(world.metadata.seed, 7);
+        assert_eq!(world.metadata.height_chunks(), 3);
+
+        let form = |name: &str, height: &str| {
+            NewWorld::from_form(name, "", height, GeneratorProfile::OverworldV1)
+        };
+        assert_eq!(form(" ", "3"), Err(WorldSaveError::EmptyName));
+        assert_eq!(form(&"x".repeat(40), "3"), Err(WorldSaveError::NameTooLong));
+        assert!(matches!(
+            form("World", "tall"),
+            Err(WorldSaveError::HeightNotANumber { .. })
+        ));
+        assert_eq!(
+            form("World", "0"),
+            Err(WorldSaveError::InvalidHeight(InvalidWorldHeight {
+                chunks: 0
+            }))
+        );
+    }
+
+    #[test]
+    fn created_worlds_are_listed_renamed_and_deleted() {
+        let directory = TestSavesDirectory::new();
+        assert!(list_worlds(&directory.0).is_empty());
+
+        let first = create_world(&directory.0, new_world("My World", "1")).unwrap();
+        let second = create_world(&directory.0, new_world("my world!", "2")).unwrap();
+        assert_ne!(first.config.storage.path(), second.config.storage.path());
+
+        let mut worlds = list_worlds(&directory.0);
+        assert_eq!(worlds.len(), 2);
+        let listed = worlds
+            .iter()
+            .find(|world| world.name == "My World")
+            .unwrap();
+        assert_eq!(listed, &first);
+        assert_eq!(listed.spawn_generator, GeneratorProfile::GrassFloorV1);
+        assert_eq!(listed.metadata().height_chunks(), 4);
+
+        rename_world(&mut worlds[0], "Archive").unwrap();
+        assert!(
+            list_worlds(&directory.0)
+                .iter()
+                .any(|world| world.name == "Archive")
+        );
+
+        delete_world(&first).unwrap();
+        let remaining = list_worlds(&directory.0);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].metadata().seed, 2);
+    }
+
+    #[test]
+    fn unreadable_and_unrelated_entries_are_skipped() {
+        let directory = TestSavesDirectory::new();
+        fs::create_dir_all(directory.0.join(DEVELOPMENT_SUBDIRECTORY)).unwrap();
+        fs::write(directory.0.join("notes.txt"), "not a world").unwrap();
+        fs::write(directory.0.join("broken.sqlite3"), "not a database").unwrap();
+        create_world(&directory.0, new_world("Kept", "3")).unwrap();
+
+        let worlds = list_worlds(&directory.0);
+        assert_eq!(worlds.len(), 1);
+        assert_eq!(worlds[0].name, "Kept");
+    }
+
+    #[test]
+    fn starting_dimension_follows_the_stored_generator() {
+        let repository = ChunkRepository::new(InMemoryChunkStore::new(WorldMetadata::default()));
+        assert_eq!(starting_dimension(&repository), DimensionId::OVERWORLD);
+
+        repository
+            .save_world_state(
+                WORLD_SPAWN_GENERATOR_KEY,
+                GeneratorProfile::CenterGlassPlatformV1.family(),
+            )
+            .unwrap();
+        assert_eq!(
+            starting_dimension(&repository),
+            DimensionId::CENTER_GLASS_PLATFORM
+        );
+    }
+}
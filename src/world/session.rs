@@ -0,0 +1,108 @@
+//! Opening a world picked on the title screen and closing it on the way back.
+
+use bevy::prelude::*;
+
+use crate::game_state::GameState;
+
+use super::{
+    DimensionCatalog, WorldConfig, WorldMetadata, build_chunk_repository,
+    dimension::StartingDimension,
+    saves::starting_dimension,
+    storage::{ChunkRepository, ChunkStoreResult},
+};
+
+/// Work done as a world closes, when the game returns to the title screen.
+///
+/// `Save` still sees every world entity and resource. `Close` despawns the
+/// entities, then the world resources are removed.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorldSessionSystems {
+    Save,
+    Close,
+}
+
+pub(super) fn install(app: &mut App) {
+    app.configure_sets(
+        OnEnter(GameState::MainMenu),
+        (WorldSessionSystems::Save, WorldSessionSystems::Close).chain(),
+    )
+    .add_systems(
+        OnEnter(GameState::MainMenu),
+        close_world.after(WorldSessionSystems::Close),
+    );
+}
+
+/// Installs the resources of the world described by `config`, replacing any
+/// open world. Entering `GameState::GenWorld` afterwards loads it.
+pub fn open_world(world: &mut World, config: WorldConfig) -> ChunkStoreResult<()> {
+    let repository = build_chunk_repository(&config)?;
+    world.insert_resource(StartingDimension(starting_dimension(&repository)));
+    world.insert_resource(repository.catalog().clone());
+    world.insert_resource(config.metadata.clone());
+    world.insert_resource(config);
+    world.insert_resource(repository);
+    Ok(())
+}
+
+fn close_world(world: &mut World) {
+    world.remove_resource::<ChunkRepository>();
+    world.remove_resource::<DimensionCatalog>();
+    world.remove_resource::<WorldMetadata>();
+    world.remove_resource::<WorldConfig>();
+    world.insert_resource(StartingDimension::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::world::{DimensionId, storage::InMemoryChunkStore};
+
+    #[test]
+    fn opened_world_resources_are_removed_on_the_title_screen() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<GameState>();
+        install(&mut app);
+        app.update();
+
+        let metadata = WorldMetadata::with_seed(42).with_height_chunks(2).unwrap();
+        open_world(app.world_mut(), WorldConfig::in_memory(metadata.clone())).unwrap();
+        let world = app.world();
+        assert_eq!(world.resource::<WorldMetadata>(), &metadata);
+        assert_eq!(
+            world.resource::<DimensionCatalog>(),
+            &DimensionCatalog::for_world(&metadata)
+        );
+        assert_eq!(world.resource::<ChunkRepository>().metadata(), &metadata);
+        assert_eq!(
+            world.resource::<StartingDimension>().0,
+            DimensionId::OVERWORLD
+        );
+
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::MainMenu);
+        app.update();
+
+        let world = app.world();
+        assert!(!world.contains_resource::<ChunkRepository>());
+        assert!(!world.contains_resource::<DimensionCatalog>());
+        assert!(!world.contains_resource::<WorldMetadata>());
+        assert!(!world.contains_resource::<WorldConfig>());
+    }
+
+    #[test]
+    fn opening_a_world_replaces_the_previous_one() {
+        let mut world = World::new();
+        world.insert_resource(ChunkRepository::new(InMemoryChunkStore::new(
+            WorldMetadata::with_seed(1),
+        )));
+
+        open_world(&mut world, WorldConfig::noop(WorldMetadata::with_seed(2))).unwrap();
+
+        assert_eq!(world.resource::<ChunkRepository>().metadata().seed, 2);
+        assert_eq!(world.resource::<WorldConfig>().metadata.seed, 2);
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use crate::util::{fnv1a, fnv1a_extend};
+use crate::world::chunk::{
+    CHUNK_SIZE, Chunk, ChunkColumn, ChunkDecodeError, ChunkHeightmap, ChunkLight,
+};
+
+const STORED_COLUMN_LIGHT_VERSION: u8 = 2;
+
+/// Number of columns whose contents can influence one column's light.
+pub const LIGHT_NEIGHBORHOOD_COLUMNS: usize = 9;
+
+/// A content revision identifying the exact blocks of one complete column.
+///
+/// The revision is derived from the canonical storage encoding of every Y
+/// chunk, so a regenerated column and its previously saved copy agree without a
+/// persisted counter, and any block change produces a different revision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnContentRevision(u64);
+
+impl ColumnContentRevision {
+    pub fn of_chunks<'a>(chunks: impl IntoIterator<Item = &'a Chunk>) -> Self {
+        let mut hash = fnv1a([]);
+        for chunk in chunks {
+            let bytes = chunk.to_storage_bytes();
+            hash = fnv1a_extend(
+                hash,
+                (bytes.len() as u32).to_le_bytes().into_iter().chain(bytes),
+            );
+        }
+        Self(hash)
+    }
+
+    pub const fn from_raw(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+/// Content revisions of a column's complete H1 light neighbourhood, in
+/// `ChunkColumn::chebyshev_neighborhood(1)` order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightNeighborhoodStamp([ColumnContentRevision; LIGHT_NEIGHBORHOOD_COLUMNS]);
+
+impl LightNeighborhoodStamp {
+    pub const fn new(revisions: [ColumnContentRevision; LIGHT_NEIGHBORHOOD_COLUMNS]) -> Self {
+        Self(revisions)
+    }
+
+    /// Builds a stamp only when every neighbourhood column has a revision.
+    pub fn try_from_fn(
+        center: ChunkColumn,
+        mut revision: impl FnMut(ChunkColumn) -> Option<ColumnContentRevision>,
+    ) -> Option<Self> {
+        let mut revisions = [ColumnContentRevision(0); LIGHT_NEIGHBORHOOD_COLUMNS];
+        for (slot, column) in revisions.iter_mut().zip(center.chebyshev_neighborhood(1)) {
+            *slot = revision(column)?;
+        }
+        Some(Self(revisions))
+    }
+
+    pub const fn revisions(&self) -> &[ColumnContentRevision; LIGHT_NEIGHBORHOOD_COLUMNS] {
+        &self.0
+    }
+}
+
+/// Solved light for every configured Y chunk of one column.
+///
+/// Stored light is a cache: it is only trusted while the stamp still matches
+/// the contents of the column and its neighbours, and can always be recomputed.
+/// The padded render light is kept with it because its borders come from
+/// neighbouring columns that may not be lit yet when the cache is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredColumnLight {
+    stamp: LightNeighborhoodStamp,
+    heightmap: ChunkHeightmap,
+    chunks: Vec<ChunkLight>,
+    padded: Vec<Arc<[u32]>>,
+}
+
+impl StoredColumnLight {
+    pub fn new(
+        stamp: LightNeighborhoodStamp,
+        heightmap: ChunkHeightmap,
+        chunks: Vec<ChunkLight>,
+        padded: Vec<Arc<[u32]>>,
+    ) -> Self {
+        assert!(
+            !chunks.is_empty() && chunks.len() <= u8::MAX as usize,
+            "stored column light must cover a valid column height"
+        );
+        assert_eq!(
+            chunks.len(),
+            padded.len(),
+            "stored column light must pad every Y chunk"
+        );
+        Self {
+            stamp,
+            heightmap,
+            chunks,
+            padded,
+        }
+    }
+
+    pub const fn stamp(&self) -> &LightNeighborhoodStamp {
+        &self.stamp
+    }
+
+    pub const fn heightmap(&self) -> &ChunkHeightmap {
+        &self.heightmap
+    }
+
+    pub fn chunks(&self) -> &[ChunkLight] {
+        &self.chunks
+    }
+
+    /// Padded render light words for each Y chunk, as uploaded for meshing.
+    pub fn padded(&self) -> &[Arc<[u32]>] {
+        &self.padded
+    }
+
+    pub fn height_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Encodes a version byte, the height, the stamp, raw heightmap bytes,
+    /// each chunk's packed light cells from lowest to highest Y, and finally
+    /// each chunk's length-prefixed padded render words.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let padded_bytes = self
+            .padded
+            .iter()
+            .map(|words| 4 + words.len() * 4)
+            .sum::<usize>();
+        let mut bytes = Vec::with_capacity(Self::fixed_len(self.chunks.len()) + padded_bytes);
+        bytes.push(STORED_COLUMN_LIGHT_VERSION);
+        bytes.push(self.chunks.len() as u8);
+        for revision in self.stamp.revisions() {
+            bytes.extend_from_slice(&revision.get().to_le_bytes());
+        }
+        bytes.extend_from_slice(self.heightmap.heights.as_flattened());
+        for light in &self.chunks {
+            bytes.extend_from_slice(&light.to_bytes());
+        }
+        for words in &self.padded {
+            bytes.extend_from_slice(&(words.len() as u32).to_le_bytes());
+            for word in words.iter() {
+                bytes.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, ChunkDecodeError> {
+        let [version, height_chunks, ..] = *bytes else {
+            return Err(ChunkDecodeError::Truncated);
+        };
+        if version != STORED_COLUMN_LIGHT_VERSION {
+            return Err(ChunkDecodeError::UnsupportedVersion(version));
+        }
+        if height_chunks == 0 {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+        let height_chunks = height_chunks as usize;
+        if bytes.len() < Self::fixed_len(height_chunks) {
+            return Err(ChunkDecodeError::Truncated);
+        }
+
+        let mut pos = 2;
+        let mut revisions = [ColumnContentRevision(0); LIGHT_NEIGHBORHOOD_COLUMNS];
+        for revision in &mut revisions {
+            let raw = bytes[pos..pos + 8]
+                .try_into()
+                .expect("stamp slice length was validated");
+            *revision = ColumnContentRevision(u64::from_le_bytes(raw));
+            pos += 8;
+        }
+
+        let mut heightmap = ChunkHeightmap::default();
+        heightmap
+            .heights
+            .as_flattened_mut()
+            .copy_from_slice(&bytes[pos..pos + CHUNK_SIZE * CHUNK_SIZE]);
+        pos += CHUNK_SIZE * CHUNK_SIZE;
+
+        let chunks = bytes[pos..pos + height_chunks * ChunkLight::ENCODED_LEN]
+            .chunks_exact(ChunkLight::ENCODED_LEN)
+            .map(|light| ChunkLight::from_bytes(light).ok_or(ChunkDecodeError::Truncated))
+            .collect::<Result<Vec<_>, _>>()?;
+        pos += height_chunks * ChunkLight::ENCODED_LEN;
+
+        let mut padded = Vec::with_capacity(height_chunks);
+        for _ in 0..height_chunks {
+            let len = bytes.get(pos..pos + 4).ok_or(ChunkDecodeError::Truncated)?;
+            let len = u32::from_le_bytes(len.try_into().expect("length slice is four bytes"));
+            pos += 4;
+            let body = bytes
+                .get(pos..pos + len as usize * 4)
+                .ok_or(ChunkDecodeError::Truncated)?;
+            padded.push(
+                body.chunks_exact(4)
+                    .map(|word| u32::from_le_bytes(word.try_into().expect("word is four bytes")))
+                    .collect::<Arc<[u32]>>(),
+            );
+            pos += body.len();
+        }
+        if pos != bytes.len() {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+
+        Ok(Self::new(
+            LightNeighborhoodStamp(revisions),
+            heightmap,
+            chunks,
+            padded,
+        ))
+    }
+
+    const fn fixed_len(height_chunks: usize) -> usize {
+        2 + LIGHT_NEIGHBORHOOD_COLUMNS * 8
+            + CHUNK_SIZE * CHUNK_SIZE
+            + height_chunks * ChunkLight::ENCODED_LEN
+    }
+}
@@ -2,13 +2,14 @@ use std::{collections::HashMap, sync::Mutex};
 
 use crate::player::PlayerId;
 use crate::world::{
-    chunk::{Chunk, ChunkHeightmap},
-    definition::{ChunkAddress, ColumnAddress},
+    chunk::{Chunk, ChunkColumn, ChunkHeightmap},
+    definition::{ChunkAddress, ColumnAddress, DimensionId},
     generation::{WorldHeight, WorldMetadata},
 };
 
 use super::{
-    ChunkStore, ChunkStoreError, ChunkStoreResult, StoredChunk, StoredColumn, StoredPlayer,
+    ChunkStore, ChunkStoreError, ChunkStoreResult, StoredChunk, StoredColumn, StoredColumnLight,
+    StoredColumnSurface, StoredPlayer,
 };
 
 pub struct InMemoryChunkStore {
@@ -20,12 +21,15 @@ pub struct InMemoryChunkStore {
 struct InMemoryChunkStoreInner {
     columns: HashMap<ColumnAddress, InMemoryStoredColumn>,
     players: HashMap<PlayerId, StoredPlayer>,
+    world_state: HashMap<String, String>,
 }
 
 #[derive(Default)]
 struct InMemoryStoredColumn {
     chunks: HashMap<i32, Vec<u8>>,
     heightmap: Vec<u8>,
+    light: Option<Vec<u8>>,
+    surface: Option<Vec<u8>>,
 }
 
 impl InMemoryChunkStore {
@@ -35,6 +39,33 @@ impl InMemoryChunkStore {
             inner: Mutex::default(),
         }
     }
+
+    #[cfg(test)]
+    pub(crate) fn overwrite_chunk_bytes_for_test(&self, address: ChunkAddress, bytes: &[u8]) {
+        self.inner
+            .lock()
+            .expect("test store lock must not be poisoned")
+            .columns
+            .entry(address.column())
+            .or_default()
+            .chunks
+            .insert(address.position().y(), bytes.to_vec());
+    }
+
+    #[cfg(test)]
+    pub(crate) fn overwrite_column_light_bytes_for_test(
+        &self,
+        address: ColumnAddress,
+        bytes: &[u8],
+    ) {
+        self.inner
+            .lock()
+            .expect("test store lock must not be poisoned")
+            .columns
+            .entry(address)
+            .or_default()
+            .light = Some(bytes.to_vec());
+    }
 }
 
 impl Default for InMemoryChunkStore {
@@ -127,6 +158,111 @@ impl ChunkStore for InMemoryChunkStore {
             .chunks
             .insert(address.position().y(), chunk.to_storage_bytes());
         column.heightmap = heightmap.to_bytes();
+        column.surface = None;
+        Ok(())
+    }
+
+    fn load_column_light(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnLight>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+
+        inner
+            .columns
+            .get(&address)
+            .and_then(|column| column.light.as_deref())
+            .map(StoredColumnLight::try_from_bytes)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_column_light(
+        &self,
+        address: ColumnAddress,
+        light: &StoredColumnLight,
+    ) -> ChunkStoreResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+
+        inner.columns.entry(address).or_default().light = Some(light.to_bytes());
+        Ok(())
+    }
+
+    fn load_column_surface(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnSurface>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+
+        inner
+            .columns
+            .get(&address)
+            .and_then(|column| column.surface.as_deref())
+            .map(StoredColumnSurface::try_from_bytes)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_column_surface(
+        &self,
+        address: ColumnAddress,
+        surface: &StoredColumnSurface,
+    ) -> ChunkStoreResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+
+        inner.columns.entry(address).or_default().surface = Some(surface.to_bytes());
+        Ok(())
+    }
+
+    fn list_columns(&self, dimension: DimensionId) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+
+        let mut columns = inner
+            .columns
+            .iter()
+            .filter(|(address, column)| {
+                address.dimension() == dimension && !column.chunks.is_empty()
+            })
+            .map(|(address, _)| address.column())
+            .collect::<Vec<_>>();
+        columns.sort_unstable_by_key(|column| (column.x(), column.z()));
+        Ok(columns)
+    }
+
+    fn delete_column(&self, address: ColumnAddress) -> ChunkStoreResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+
+        inner.columns.remove(&address);
         Ok(())
     }
 
@@ -150,6 +286,27 @@ impl ChunkStore for InMemoryChunkStore {
         inner.players.insert(player.id(), player.clone());
         Ok(())
     }
+
+    fn load_world_state(&self, key: &str) -> ChunkStoreResult<Option<String>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+        Ok(inner.world_state.get(key).cloned())
+    }
+
+    fn save_world_state(&self, key: &str, value: &str) -> ChunkStoreResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "in-memory chunk store",
+            })?;
+        inner.world_state.insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
 }
 
 pub struct NoopChunkStore {
@@ -1,5 +1,8 @@
+mod light;
 mod memory;
+mod region;
 mod sqlite;
+mod surface;
 
 #[cfg(test)]
 mod tests;
@@ -8,7 +11,7 @@ mod tests;
 #[path = "turso.rs"]
 mod turso_backend;
 
-use std::{io::ErrorKind, sync::Arc};
+use std::{ffi::OsStr, io::ErrorKind, path::Path, sync::Arc};
 
 use bevy::{math::DVec3, prelude::*};
 use rusqlite::ErrorCode;
@@ -20,8 +23,13 @@ use crate::world::{
     generation::{WorldHeight, WorldMetadata},
 };
 
+pub use light::{
+    ColumnContentRevision, LIGHT_NEIGHBORHOOD_COLUMNS, LightNeighborhoodStamp, StoredColumnLight,
+};
 pub use memory::{InMemoryChunkStore, NoopChunkStore};
+pub use region::{RegionChunkStore, development_region_path};
 pub use sqlite::{SqliteChunkStore, development_world_path};
+pub use surface::{SURFACE_CELL_BLOCKS, SURFACE_CELLS, StoredColumnSurface, SurfaceCell};
 
 #[cfg(feature = "turso-store")]
 pub use turso_backend::{TursoChunkStore, development_turso_path};
@@ -46,6 +54,9 @@ pub(crate) const SQL_SELECT_METADATA_VALUE: &str =
     "SELECT value FROM world_metadata WHERE key = ?1";
 pub(crate) const SQL_INSERT_METADATA_VALUE: &str =
     "INSERT INTO world_metadata (key, value) VALUES (?1, ?2)";
+pub(crate) const SQL_UPSERT_METADATA_VALUE: &str = "INSERT INTO world_metadata (key, value)
+VALUES (?1, ?2)
+ON CONFLICT(key) DO UPDATE SET value = excluded.value";
 
 /// A precision-preserving player position at the persistence boundary.
 ///
@@ -300,6 +311,11 @@ pub enum StoredColumnError {
         returned: WorldHeight,
     },
     DuplicateAddress(ChunkAddress),
+    LightHeightMismatch {
+        address: ColumnAddress,
+        expected: WorldHeight,
+        returned: usize,
+    },
 }
 
 impl std::fmt::Display for StoredColumnError {
@@ -330,6 +346,16 @@ impl std::fmt::Display for StoredColumnError {
             Self::DuplicateAddress(address) => {
                 write!(f, "stored column contains duplicate chunk {address:?}")
             }
+            Self::LightHeightMismatch {
+                address,
+                expected,
+                returned,
+            } => write!(
+                f,
+                "stored light for column {address:?} covers {returned} chunks, \
+                 configured height is {}",
+                expected.chunks()
+            ),
         }
     }
 }
@@ -369,6 +395,56 @@ pub trait ChunkStore: Send + Sync + 'static {
         heightmap: &ChunkHeightmap,
     ) -> ChunkStoreResult<()>;
 
+    /// Loads the cached solved light of one column. Stores without a light
+    /// cache report every column as unlit, which forces a relight on load.
+    fn load_column_light(
+        &self,
+        _address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnLight>> {
+        Ok(None)
+    }
+
+    /// Replaces the cached solved light of one column.
+    fn save_column_light(
+        &self,
+        _address: ColumnAddress,
+        _light: &StoredColumnLight,
+    ) -> ChunkStoreResult<()> {
+        Ok(())
+    }
+
+    /// Loads the cached far-field surface summary of one column. Stores
+    /// without a surface cache report every column as missing, which makes
+    /// far-field terrain derive it again.
+    fn load_column_surface(
+        &self,
+        _address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnSurface>> {
+        Ok(None)
+    }
+
+    /// Replaces the cached surface summary of one column. Saving any chunk of
+    /// the column must drop this record, since it no longer matches.
+    fn save_column_surface(
+        &self,
+        _address: ColumnAddress,
+        _surface: &StoredColumnSurface,
+    ) -> ChunkStoreResult<()> {
+        Ok(())
+    }
+
+    /// Lists columns with at least one stored chunk in one dimension, ordered
+    /// by X and then Z. Stores that keep nothing report no columns.
+    fn list_columns(&self, _dimension: DimensionId) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        Ok(Vec::new())
+    }
+
+    /// Removes every stored chunk of one column with its heightmap, cached
+    /// light and surface, so the column is regenerated the next time it loads.
+    fn delete_column(&self, _address: ColumnAddress) -> ChunkStoreResult<()> {
+        Ok(())
+    }
+
     /// Loads one player record. The default preserves lightweight test stores
     /// and backends that intentionally discard all persistence.
     fn load_player(&self, _id: PlayerId) -> ChunkStoreResult<Option<StoredPlayer>> {
@@ -379,6 +455,18 @@ pub trait ChunkStore: Send + Sync + 'static {
     fn save_player(&self, _player: &StoredPlayer) -> ChunkStoreResult<()> {
         Ok(())
     }
+
+    /// Loads one mutable `world_metadata` entry, such as the current weather.
+    /// Stores that keep nothing report every entry as missing.
+    fn load_world_state(&self, _key: &str) -> ChunkStoreResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Inserts or replaces one mutable `world_metadata` entry. Unlike the keys
+    /// from [`metadata_entries`], these may change for the life of the world.
+    fn save_world_state(&self, _key: &str, _value: &str) -> ChunkStoreResult<()> {
+        Ok(())
+    }
 }
 
 #[derive(Resource, Clone)]
@@ -432,6 +520,63 @@ impl ChunkRepository {
         self.store.save_chunk(address, chunk, heightmap)
     }
 
+    /// Loads cached column light, discarding records written for a different
+    /// column height since they can never match the current column.
+    pub fn load_column_light(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnLight>> {
+        let height = self.dimension_height(address.dimension())?;
+        Ok(self
+            .store
+            .load_column_light(address)?
+            .filter(|light| light.height_chunks() == height.chunks()))
+    }
+
+    pub fn save_column_light(
+        &self,
+        address: ColumnAddress,
+        light: &StoredColumnLight,
+    ) -> ChunkStoreResult<()> {
+        let height = self.dimension_height(address.dimension())?;
+        if light.height_chunks() != height.chunks() {
+            return Err(StoredColumnError::LightHeightMismatch {
+                address,
+                expected: height,
+                returned: light.height_chunks(),
+            }
+            .into());
+        }
+        self.store.save_column_light(address, light)
+    }
+
+    pub fn load_column_surface(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnSurface>> {
+        self.dimension_height(address.dimension())?;
+        self.store.load_column_surface(address)
+    }
+
+    pub fn save_column_surface(
+        &self,
+        address: ColumnAddress,
+        surface: &StoredColumnSurface,
+    ) -> ChunkStoreResult<()> {
+        self.dimension_height(address.dimension())?;
+        self.store.save_column_surface(address, surface)
+    }
+
+    pub fn list_columns(&self, dimension: DimensionId) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        self.dimension_height(dimension)?;
+        self.store.list_columns(dimension)
+    }
+
+    pub fn delete_column(&self, address: ColumnAddress) -> ChunkStoreResult<()> {
+        self.dimension_height(address.dimension())?;
+        self.store.delete_column(address)
+    }
+
     pub fn load_player(&self, id: PlayerId) -> ChunkStoreResult<Option<StoredPlayer>> {
         let Some(player) = self.store.load_player(id)? else {
             return Ok(None);
@@ -451,6 +596,24 @@ impl ChunkRepository {
         self.store.save_player(player)
     }
 
+    pub fn load_world_state(&self, key: &str) -> ChunkStoreResult<Option<String>> {
+        self.store.load_world_state(key)
+    }
+
+    /// Saves one mutable world entry. Keys that identify the world itself are
+    /// rejected, since rewriting them would make the store fail to reopen.
+    pub fn save_world_state(&self, key: &str, value: &str) -> ChunkStoreResult<()> {
+        if metadata_entries(&self.metadata)
+            .iter()
+            .any(|(entry, _)| entry == key)
+        {
+            return Err(ChunkStoreError::ImmutableWorldMetadata {
+                key: key.to_owned(),
+            });
+        }
+        self.store.save_world_state(key, value)
+    }
+
     pub fn dimension_height(&self, dimension: DimensionId) -> ChunkStoreResult<WorldHeight> {
         self.catalog
             .get(dimension)
@@ -482,6 +645,69 @@ impl Default for ChunkRepository {
     }
 }
 
+/// The backend a world store on disk uses, told apart by its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldStoreKind {
+    Sqlite,
+    Region,
+    #[cfg(feature = "turso-store")]
+    Turso,
+}
+
+impl WorldStoreKind {
+    /// The backend a store file extension names, if any.
+    pub fn from_extension(extension: &OsStr) -> Option<Self> {
+        match extension.to_str()? {
+            "sqlite3" => Some(Self::Sqlite),
+            "region" => Some(Self::Region),
+            #[cfg(feature = "turso-store")]
+            "turso" => Some(Self::Turso),
+            _ => None,
+        }
+    }
+
+    /// The backend for `path`: directories are region stores and anything
+    /// without a known extension is SQLite.
+    pub fn from_path(path: &Path) -> Self {
+        if path.is_dir() {
+            return Self::Region;
+        }
+        path.extension()
+            .and_then(Self::from_extension)
+            .unwrap_or(Self::Sqlite)
+    }
+
+    pub fn read_metadata_entries(self, path: &Path) -> ChunkStoreResult<Vec<(String, String)>> {
+        match self {
+            Self::Sqlite => SqliteChunkStore::read_metadata_entries(path),
+            Self::Region => RegionChunkStore::read_metadata_entries(path),
+            #[cfg(feature = "turso-store")]
+            Self::Turso => TursoChunkStore::read_metadata_entries(path),
+        }
+    }
+
+    pub fn read_metadata(self, path: &Path) -> ChunkStoreResult<WorldMetadata> {
+        world_metadata_from_entries(&self.read_metadata_entries(path)?)
+    }
+
+    /// Opens or creates the store at `path` for `metadata`.
+    pub fn open(self, path: &Path, metadata: &WorldMetadata) -> ChunkStoreResult<ChunkRepository> {
+        Ok(match self {
+            Self::Sqlite => ChunkRepository::new(SqliteChunkStore::open(path, metadata)?),
+            Self::Region => ChunkRepository::new(RegionChunkStore::open(path, metadata)?),
+            #[cfg(feature = "turso-store")]
+            Self::Turso => ChunkRepository::new(TursoChunkStore::open(path, metadata)?),
+        })
+    }
+}
+
+/// Opens an existing world store with the metadata it was created with,
+/// picking the backend from the path.
+pub fn open_world_store(path: &Path) -> ChunkStoreResult<ChunkRepository> {
+    let kind = WorldStoreKind::from_path(path);
+    kind.open(path, &kind.read_metadata(path)?)
+}
+
 pub(crate) fn metadata_entries(metadata: &WorldMetadata) -> Vec<(String, String)> {
     let catalog = DimensionCatalog::for_world(metadata);
     let dimension_ids = catalog
@@ -527,6 +753,39 @@ pub(crate) fn metadata_entries(metadata: &WorldMetadata) -> Vec<(String, String)
     entries
 }
 
+/// Rebuilds world metadata from `world_metadata` rows written by
+/// [`metadata_entries`], so tools can open a store without knowing its seed.
+///
+/// Only the world-level keys are read. Dimension rows are still checked
+/// against the derived catalog when the store is opened with the result.
+pub(crate) fn world_metadata_from_entries(
+    entries: &[(String, String)],
+) -> ChunkStoreResult<WorldMetadata> {
+    fn value<T: std::str::FromStr>(entries: &[(String, String)], key: &str) -> ChunkStoreResult<T> {
+        let found = entries
+            .iter()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value);
+        found.and_then(|value| value.parse().ok()).ok_or_else(|| {
+            ChunkStoreError::InvalidWorldMetadata {
+                key: key.to_owned(),
+                found: found.cloned(),
+            }
+        })
+    }
+
+    let height_chunks = value::<usize>(entries, "height_chunks")?;
+    let mut metadata = WorldMetadata::with_seed(value(entries, "seed")?)
+        .with_height_chunks(height_chunks)
+        .map_err(|_| ChunkStoreError::InvalidWorldMetadata {
+            key: "height_chunks".to_owned(),
+            found: Some(height_chunks.to_string()),
+        })?;
+    metadata.generator_version = value(entries, "generator_version")?;
+    metadata.chunk_format_version = value(entries, "chunk_format_version")?;
+    Ok(metadata)
+}
+
 #[cfg(feature = "turso-store")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TursoStoreErrorKind {
@@ -574,6 +833,13 @@ pub enum ChunkStoreError {
         expected: String,
         found: String,
     },
+    InvalidWorldMetadata {
+        key: String,
+        found: Option<String>,
+    },
+    ImmutableWorldMetadata {
+        key: String,
+    },
     #[cfg(feature = "turso-store")]
     Turso {
         kind: TursoStoreErrorKind,
@@ -628,6 +894,16 @@ impl std::fmt::Display for ChunkStoreError {
                 f,
                 "world metadata mismatch for {key}: expected {expected}, found {found}"
             ),
+            Self::InvalidWorldMetadata { key, found: None } => {
+                write!(f, "world metadata is missing {key}")
+            }
+            Self::InvalidWorldMetadata {
+                key,
+                found: Some(found),
+            } => write!(f, "world metadata {key} has invalid value {found}"),
+            Self::ImmutableWorldMetadata { key } => {
+                write!(f, "world metadata {key} cannot change after creation")
+            }
             #[cfg(feature = "turso-store")]
             Self::Turso { kind, message } => write!(f, "turso error {kind:?}: {message}"),
             #[cfg(feature = "turso-store")]
@@ -0,0 +1,450 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::world::chunk::ChunkColumn;
+
+/// Columns along each horizontal edge of one region file.
+pub(super) const REGION_WIDTH: i32 = 32;
+pub(super) const REGION_COLUMNS: usize = (REGION_WIDTH * REGION_WIDTH) as usize;
+pub(super) const SECTOR_BYTES: u64 = 4096;
+const ENTRY_BYTES: usize = 8;
+const HEADER_BYTES: usize = REGION_COLUMNS * ENTRY_BYTES;
+const HEADER_SECTORS: u32 = HEADER_BYTES.div_ceil(SECTOR_BYTES as usize) as u32;
+
+/// Which record family a region file holds. Light lives in its own files so a
+/// chunk save never rewrites the much larger cached light of its column, and
+/// far-field surfaces are kept apart because most of them cover columns that
+/// have no stored chunks at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum RegionKind {
+    Chunks,
+    Light,
+    Surface,
+}
+
+impl RegionKind {
+    pub(super) const ALL: [Self; 3] = [Self::Chunks, Self::Light, Self::Surface];
+
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Chunks => "chunks",
+            Self::Light => "light",
+            Self::Surface => "surface",
+        }
+    }
+}
+
+/// A square group of [`REGION_WIDTH`]² columns stored in one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct RegionPos {
+    x: i32,
+    z: i32,
+}
+
+impl RegionPos {
+    /// Returns the region holding `column` and the column's slot inside it.
+    pub(super) const fn containing(column: ChunkColumn) -> (Self, usize) {
+        let region = Self {
+            x: column.x().div_euclid(REGION_WIDTH),
+            z: column.z().div_euclid(REGION_WIDTH),
+        };
+        let slot = column.z().rem_euclid(REGION_WIDTH) * REGION_WIDTH
+            + column.x().rem_euclid(REGION_WIDTH);
+        (region, slot as usize)
+    }
+
+    pub(super) const fn column(self, slot: usize) -> ChunkColumn {
+        let slot = slot as i32;
+        ChunkColumn::new(
+            self.x * REGION_WIDTH + slot % REGION_WIDTH,
+            self.z * REGION_WIDTH + slot / REGION_WIDTH,
+        )
+    }
+
+    pub(super) fn file_name(self, kind: RegionKind) -> String {
+        format!("r.{}.{}.{}", self.x, self.z, kind.extension())
+    }
+
+    pub(super) fn parse_file_name(name: &str, kind: RegionKind) -> Option<Self> {
+        let rest = name.strip_prefix("r.")?;
+        let rest = rest.strip_suffix(kind.extension())?.strip_suffix('.')?;
+        let (x, z) = rest.split_once('.')?;
+        Some(Self {
+            x: x.parse().ok()?,
+            z: z.parse().ok()?,
+        })
+    }
+}
+
+/// First-fit allocation over the fixed-size sectors of one region file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct SectorAllocator {
+    used: Vec<bool>,
+}
+
+impl SectorAllocator {
+    /// Starts with `reserved` leading sectors in use, for the file header.
+    pub(super) fn new(reserved: u32) -> Self {
+        Self {
+            used: vec![true; reserved as usize],
+        }
+    }
+
+    /// Sectors the file must span to cover every allocation.
+    pub(super) fn len(&self) -> u32 {
+        self.used
+            .iter()
+            .rposition(|&used| used)
+            .map_or(0, |last| last + 1) as u32
+    }
+
+    /// Marks an existing record's sectors as used, returning `false` if any
+    /// of them already belongs to another record.
+    pub(super) fn claim(&mut self, first: u32, count: u32) -> bool {
+        let range = first as usize..(first + count) as usize;
+        if self.used.len() < range.end {
+            self.used.resize(range.end, false);
+        }
+        if self.used[range.clone()].iter().any(|&used| used) {
+            return false;
+        }
+        self.used[range].fill(true);
+        true
+    }
+
+    /// Returns the first run of `count` free sectors, growing the file when no
+    /// gap is large enough.
+    pub(super) fn allocate(&mut self, count: u32) -> u32 {
+        let count = count as usize;
+        let mut run_start = 0;
+        let mut run_len = 0;
+        for (sector, &used) in self.used.iter().enumerate() {
+            if used {
+                run_len = 0;
+                continue;
+            }
+            if run_len == 0 {
+                run_start = sector;
+            }
+            run_len += 1;
+            if run_len == count {
+                break;
+            }
+        }
+        if run_len < count {
+            // Extend a trailing free run, or append after the last sector.
+            if run_len == 0 || run_start + run_len != self.used.len() {
+                run_start = self.used.len();
+            }
+            self.used.resize(run_start + count, false);
+        }
+        self.used[run_start..run_start + count].fill(true);
+        run_start as u32
+    }
+
+    pub(super) fn free(&mut self, first: u32, count: u32) {
+        let end = ((first + count) as usize).min(self.used.len());
+        self.used[first as usize..end].fill(false);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RegionEntry {
+    first_sector: u32,
+    length: u32,
+}
+
+impl RegionEntry {
+    const fn is_empty(self) -> bool {
+        self.length == 0
+    }
+
+    fn sectors(self) -> u32 {
+        self.length.div_ceil(SECTOR_BYTES as u32)
+    }
+}
+
+/// One region file: a header table of `(first sector, byte length)` entries
+/// per column slot followed by sector-aligned records.
+///
+/// Writes are only made while checkpointing the store's journal, which holds
+/// every record being written, so a torn write is repaired by replay. Sectors a
+/// write gives up stay reserved until [`Self::sync`], because a header on disk
+/// may still point at them until then.
+pub(super) struct RegionFile {
+    file: File,
+    entries: Vec<RegionEntry>,
+    allocator: SectorAllocator,
+    /// `(first sector, count)` runs released since the last sync.
+    unsynced_frees: Vec<(u32, u32)>,
+}
+
+impl RegionFile {
+    pub(super) fn open(path: &Path) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let file_len = file.metadata()?.len();
+        if file_len == 0 {
+            file.write_all(&[0; HEADER_BYTES])?;
+        } else if file_len < HEADER_BYTES as u64 {
+            return Err(invalid_region(path, "header is truncated"));
+        }
+
+        let mut header = vec![0; HEADER_BYTES];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        let mut allocator = SectorAllocator::new(HEADER_SECTORS);
+        let file_sectors = file_len.div_ceil(SECTOR_BYTES);
+        let entries = header
+            .chunks_exact(ENTRY_BYTES)
+            .map(|bytes| RegionEntry {
+                first_sector: u32::from_le_bytes(bytes[..4].try_into().expect("entry field")),
+                length: u32::from_le_bytes(bytes[4..].try_into().expect("entry field")),
+            })
+            .collect::<Vec<_>>();
+        for entry in entries.iter().filter(|entry| !entry.is_empty()) {
+            let end = u64::from(entry.first_sector) + u64::from(entry.sectors());
+            if entry.first_sector < HEADER_SECTORS
+                || end > file_sectors
+                || !allocator.claim(entry.first_sector, entry.sectors())
+            {
+                return Err(invalid_region(path, "sector table is inconsistent"));
+            }
+        }
+
+        Ok(Self {
+            file,
+            entries,
+            allocator,
+            unsynced_frees: Vec::new(),
+        })
+    }
+
+    pub(super) fn read(&self, slot: usize) -> io::Result<Option<Vec<u8>>> {
+        let entry = self.entries[slot];
+        if entry.is_empty() {
+            return Ok(None);
+        }
+        let mut bytes = vec![0; entry.length as usize];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(
+            u64::from(entry.first_sector) * SECTOR_BYTES,
+        ))?;
+        file.read_exact(&mut bytes)?;
+        Ok(Some(bytes))
+    }
+
+    /// Replaces or removes one slot's record, reusing sectors freed before the
+    /// last sync first.
+    pub(super) fn write(&mut self, slot: usize, record: Option<&[u8]>) -> io::Result<()> {
+        let previous = self.entries[slot];
+        if !previous.is_empty() {
+            self.unsynced_frees
+                .push((previous.first_sector, previous.sectors()));
+        }
+        let entry = match record.filter(|record| !record.is_empty()) {
+            Some(record) => {
+                let length = u32::try_from(record.len()).map_err(|_| {
+                    io::Error::new(ErrorKind::InvalidInput, "region record too large")
+                })?;
+                let entry = RegionEntry {
+                    first_sector: 0,
+                    length,
+                };
+                let first_sector = self.allocator.allocate(entry.sectors());
+                self.file
+                    .seek(SeekFrom::Start(u64::from(first_sector) * SECTOR_BYTES))?;
+                self.file.write_all(record)?;
+                RegionEntry {
+                    first_sector,
+                    length,
+                }
+            }
+            None => RegionEntry::default(),
+        };
+
+        let padded_len = u64::from(self.allocator.len()) * SECTOR_BYTES;
+        if self.file.metadata()?.len() < padded_len {
+            self.file.set_len(padded_len)?;
+        }
+        let mut bytes = [0; ENTRY_BYTES];
+        bytes[..4].copy_from_slice(&entry.first_sector.to_le_bytes());
+        bytes[4..].copy_from_slice(&entry.length.to_le_bytes());
+        self.file
+            .seek(SeekFrom::Start((slot * ENTRY_BYTES) as u64))?;
+        self.file.write_all(&bytes)?;
+        self.entries[slot] = entry;
+        Ok(())
+    }
+
+    pub(super) fn occupied_slots(&self) -> impl Iterator<Item = usize> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !entry.is_empty())
+            .map(|(slot, _)| slot)
+    }
+
+    /// Makes every write durable, after which the sectors they replaced can
+    /// be reused.
+    pub(super) fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()?;
+        for (first, count) in self.unsynced_frees.drain(..) {
+            self.allocator.free(first, count);
+        }
+        Ok(())
+    }
+}
+
+fn invalid_region(path: &Path, reason: &str) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("region file {} {reason}", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_region_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "minecraft_clone-region-{name}-{}.chunks",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn region_positions_cover_negative_columns_with_distinct_slots() {
+        for column in [
+            ChunkColumn::new(0, 0),
+            ChunkColumn::new(31, 31),
+            ChunkColumn::new(-1, -1),
+            ChunkColumn::new(-32, 33),
+        ] {
+            let (region, slot) = RegionPos::containing(column);
+            assert!(slot < REGION_COLUMNS);
+            assert_eq!(region.column(slot), column);
+        }
+        assert_eq!(
+            RegionPos::containing(ChunkColumn::new(-1, 0)).0,
+            RegionPos { x: -1, z: 0 }
+        );
+
+        let region = RegionPos { x: -3, z: 12 };
+        let name = region.file_name(RegionKind::Light);
+        assert_eq!(name, "r.-3.12.light");
+        assert_eq!(
+            RegionPos::parse_file_name(&name, RegionKind::Light),
+            Some(region)
+        );
+        assert_eq!(RegionPos::parse_file_name(&name, RegionKind::Chunks), None);
+    }
+
+    #[test]
+    fn sector_allocator_reuses_the_first_gap_that_fits() {
+        let mut allocator = SectorAllocator::new(2);
+        assert_eq!(allocator.allocate(1), 2);
+        assert_eq!(allocator.allocate(3), 3);
+        assert_eq!(allocator.allocate(1), 6);
+        assert_eq!(allocator.len(), 7);
+
+        allocator.free(3, 3);
+        assert_eq!(allocator.allocate(2), 3);
+        assert_eq!(allocator.allocate(2), 7);
+        assert_eq!(allocator.allocate(1), 5);
+        assert_eq!(allocator.len(), 9);
+    }
+
+    #[test]
+    fn sector_allocator_extends_a_trailing_gap_and_rejects_overlapping_claims() {
+        let mut allocator = SectorAllocator::new(2);
+        assert!(allocator.claim(2, 2));
+        assert!(allocator.claim(6, 1));
+        assert!(!allocator.claim(3, 2));
+
+        allocator.free(6, 1);
+        assert_eq!(allocator.len(), 4);
+        assert_eq!(allocator.allocate(3), 4);
+        assert_eq!(allocator.len(), 7);
+    }
+
+    #[test]
+    fn region_file_grows_shrinks_and_reopens_records() {
+        let path = test_region_path("records");
+        let large = vec![7; SECTOR_BYTES as usize * 2 + 5];
+        {
+            let mut region = RegionFile::open(&path).unwrap();
+            region.write(0, Some(b"first")).unwrap();
+            region.write(5, Some(&large)).unwrap();
+            region.write(0, Some(&large)).unwrap();
+            region.write(5, Some(b"small")).unwrap();
+            region.write(9, Some(b"removed")).unwrap();
+            region.write(9, None).unwrap();
+        }
+
+        let region = RegionFile::open(&path).unwrap();
+        assert_eq!(region.read(0).unwrap(), Some(large));
+        assert_eq!(region.read(5).unwrap(), Some(b"small".to_vec()));
+        assert_eq!(region.read(9).unwrap(), None);
+        assert_eq!(region.occupied_slots().collect::<Vec<_>>(), [0, 5]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn interrupted_checkpoint_never_reuses_sectors_an_old_header_points_at() {
+        let path = test_region_path("interrupted");
+        let mut old_entry = [0; ENTRY_BYTES];
+        {
+            let mut region = RegionFile::open(&path).unwrap();
+            region.write(0, Some(b"first")).unwrap();
+            region.sync().unwrap();
+            let mut file = &region.file;
+            file.seek(SeekFrom::Start(0)).unwrap();
+            file.read_exact(&mut old_entry).unwrap();
+
+            region
+                .write(0, Some(&[7; SECTOR_BYTES as usize + 1]))
+                .unwrap();
+            region.write(1, Some(b"second")).unwrap();
+        }
+        // Power was lost before the moved slot's new header reached the disk.
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.write_all(&old_entry).unwrap();
+        drop(file);
+
+        let region = RegionFile::open(&path).unwrap();
+        assert_eq!(region.read(0).unwrap(), Some(b"first".to_vec()));
+        assert_eq!(region.read(1).unwrap(), Some(b"second".to_vec()));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn region_file_rejects_overlapping_sector_tables() {
+        let path = test_region_path("overlap");
+        {
+            let mut region = RegionFile::open(&path).unwrap();
+            region.write(0, Some(b"first")).unwrap();
+            region.write(1, Some(b"second")).unwrap();
+        }
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(ENTRY_BYTES as u64)).unwrap();
+        file.write_all(&HEADER_SECTORS.to_le_bytes()).unwrap();
+        drop(file);
+
+        assert_eq!(
+            RegionFile::open(&path).err().map(|error| error.kind()),
+            Some(ErrorKind::InvalidData)
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::util::fnv1a;
+use crate::world::{
+    chunk::{ChunkColumn, ChunkDecodeError},
+    definition::{ColumnAddress, DimensionId},
+};
+
+use super::file::RegionKind;
+
+/// Bytes before each frame payload: a `u32` payload length and a `u64`
+/// FNV-1a checksum of the payload, which is enough to tell a torn or
+/// partially flushed frame apart from a complete one.
+const FRAME_HEADER_BYTES: usize = 12;
+const RECORD_CHUNKS: u8 = 0;
+const RECORD_LIGHT: u8 = 1;
+const RECORD_PLAYERS: u8 = 2;
+const RECORD_SURFACE: u8 = 3;
+
+/// One logical write. An empty column record deletes the column's entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum JournalRecord {
+    Column {
+        kind: RegionKind,
+        address: ColumnAddress,
+        bytes: Vec<u8>,
+    },
+    /// The complete encoded player table after the write.
+    Players { bytes: Vec<u8> },
+}
+
+/// An append-only redo log of committed store writes.
+///
+/// Each commit appends one checksummed frame holding all of its records, so a
+/// commit is either replayed whole or not at all. A frame whose length or
+/// checksum does not match ends the log: it is the torn tail of a write that
+/// never completed, and every later byte is discarded with it.
+pub(super) struct Journal {
+    file: File,
+    len: u64,
+}
+
+impl Journal {
+    /// Opens or creates the journal, returning the frames committed before the
+    /// previous process stopped and truncating anything after the last one.
+    pub(super) fn open(path: &Path) -> io::Result<(Self, Vec<Vec<JournalRecord>>)> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut frames = Vec::new();
+        let mut pos = 0;
+        while let Some((records, frame_len)) = decode_frame(&bytes[pos..]) {
+            frames.push(records);
+            pos += frame_len;
+        }
+        if pos != bytes.len() {
+            file.set_len(pos as u64)?;
+            file.sync_data()?;
+        }
+        file.seek(SeekFrom::Start(pos as u64))?;
+
+        Ok((
+            Self {
+                file,
+                len: pos as u64,
+            },
+            frames,
+        ))
+    }
+
+    pub(super) const fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Appends one frame without syncing it; [`Self::sync`] makes every
+    /// appended frame durable.
+    pub(super) fn append(&mut self, records: &[JournalRecord]) -> io::Result<()> {
+        let frame = encode_frame(records);
+        if let Err(error) = self.file.write_all(&frame) {
+            // Drop the partial frame so later commits are not hidden behind it.
+            self.file.set_len(self.len)?;
+            self.file.seek(SeekFrom::Start(self.len))?;
+            return Err(error);
+        }
+        self.len += frame.len() as u64;
+        Ok(())
+    }
+
+    pub(super) fn sync(&self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    /// Empties the journal once every frame is durable elsewhere.
+    pub(super) fn reset(&mut self) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.sync_data()?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+fn encode_frame(records: &[JournalRecord]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&(records.len() as u32).to_le_bytes());
+    for record in records {
+        let bytes = match record {
+            JournalRecord::Column {
+                kind,
+                address,
+                bytes,
+            } => {
+                payload.push(match kind {
+                    RegionKind::Chunks => RECORD_CHUNKS,
+                    RegionKind::Light => RECORD_LIGHT,
+                    RegionKind::Surface => RECORD_SURFACE,
+                });
+                payload.extend_from_slice(&address.dimension().get().to_le_bytes());
+                payload.extend_from_slice(&address.column().x().to_le_bytes());
+                payload.extend_from_slice(&address.column().z().to_le_bytes());
+                bytes
+            }
+            JournalRecord::Players { bytes } => {
+                payload.push(RECORD_PLAYERS);
+                bytes
+            }
+        };
+        payload.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        payload.extend_from_slice(bytes);
+    }
+
+    let mut frame = Vec::with_capacity(FRAME_HEADER_BYTES + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&fnv1a(payload.iter().copied()).to_le_bytes());
+    frame.extend_from_slice(&payload);
+    frame
+}
+
+/// Decodes the frame at the start of `bytes`, returning its records and its
+/// total length, or `None` if it is incomplete or damaged.
+fn decode_frame(bytes: &[u8]) -> Option<(Vec<JournalRecord>, usize)> {
+    let header = bytes.get(..FRAME_HEADER_BYTES)?;
+    let payload_len = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+    let expected = u64::from_le_bytes(header[4..].try_into().ok()?);
+    let payload = bytes.get(FRAME_HEADER_BYTES..FRAME_HEADER_BYTES + payload_len)?;
+    if fnv1a(payload.iter().copied()) != expected {
+        return None;
+    }
+    let records = decode_records(payload).ok()?;
+    Some((records, FRAME_HEADER_BYTES + payload_len))
+}
+
+fn decode_records(payload: &[u8]) -> Result<Vec<JournalRecord>, ChunkDecodeError> {
+    let mut reader = ByteReader(payload);
+    let count = reader.u32()?;
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let tag = reader.take(1)?[0];
+        let record = match tag {
+            RECORD_CHUNKS | RECORD_LIGHT | RECORD_SURFACE => {
+                let dimension = DimensionId::new(reader.u32()?);
+                let column = ChunkColumn::new(reader.i32()?, reader.i32()?);
+                let len = reader.u32()? as usize;
+                JournalRecord::Column {
+                    kind: match tag {
+                        RECORD_CHUNKS => RegionKind::Chunks,
+                        RECORD_LIGHT => RegionKind::Light,
+                        _ => RegionKind::Surface,
+                    },
+                    address: ColumnAddress::new(dimension, column),
+                    bytes: reader.take(len)?.to_vec(),
+                }
+            }
+            RECORD_PLAYERS => {
+                let len = reader.u32()? as usize;
+                JournalRecord::Players {
+                    bytes: reader.take(len)?.to_vec(),
+                }
+            }
+            _ => return Err(ChunkDecodeError::InvalidHeader),
+        };
+        records.push(record);
+    }
+    if !reader.0.is_empty() {
+        return Err(ChunkDecodeError::InvalidHeader);
+    }
+    Ok(records)
+}
+
+/// Little-endian reads over a byte slice for the store's binary records.
+pub(super) struct ByteReader<'a>(pub(super) &'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    pub(super) fn take(&mut self, len: usize) -> Result<&'a [u8], ChunkDecodeError> {
+        if self.0.len() < len {
+            return Err(ChunkDecodeError::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, ChunkDecodeError> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().expect("four bytes"),
+        ))
+    }
+
+    pub(super) fn i32(&mut self) -> Result<i32, ChunkDecodeError> {
+        Ok(i32::from_le_bytes(
+            self.take(4)?.try_into().expect("four bytes"),
+        ))
+    }
+
+    pub(super) fn i64(&mut self) -> Result<i64, ChunkDecodeError> {
+        Ok(i64::from_le_bytes(
+            self.take(8)?.try_into().expect("eight bytes"),
+        ))
+    }
+
+    pub(super) fn f64(&mut self) -> Result<f64, ChunkDecodeError> {
+        Ok(f64::from_le_bytes(
+            self.take(8)?.try_into().expect("eight bytes"),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_journal_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "minecraft_clone-journal-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn column_record(x: i32, bytes: &[u8]) -> JournalRecord {
+        JournalRecord::Column {
+            kind: RegionKind::Chunks,
+            address: ColumnAddress::new(DimensionId::OVERWORLD, ChunkColumn::new(x, -x)),
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn journal_replays_committed_frames_in_order() {
+        let path = test_journal_path("replay");
+        let first = vec![column_record(1, b"one"), column_record(2, b"")];
+        let second = vec![JournalRecord::Players {
+            bytes: b"players".to_vec(),
+        }];
+        {
+            let (mut journal, frames) = Journal::open(&path).unwrap();
+            assert!(frames.is_empty());
+            journal.append(&first).unwrap();
+            journal.append(&second).unwrap();
+        }
+
+        let (journal, frames) = Journal::open(&path).unwrap();
+        assert_eq!(frames, [first, second]);
+        assert_eq!(journal.len(), std::fs::metadata(&path).unwrap().len());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn journal_discards_a_torn_tail_and_everything_after_damage() {
+        let path = test_journal_path("torn");
+        let kept = vec![column_record(1, b"kept")];
+        {
+            let (mut journal, _) = Journal::open(&path).unwrap();
+            journal.append(&kept).unwrap();
+            journal.append(&[column_record(2, b"damaged")]).unwrap();
+            journal.append(&[column_record(3, b"after")]).unwrap();
+        }
+        let kept_len = encode_frame(&kept).len();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[kept_len + FRAME_HEADER_BYTES + 8] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+
+        let (mut journal, frames) = Journal::open(&path).unwrap();
+        assert_eq!(frames, std::slice::from_ref(&kept));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), kept_len as u64);
+
+        journal.append(&[column_record(4, b"new")]).unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 2);
+        std::fs::write(&path, &bytes).unwrap();
+        drop(journal);
+
+        let (_, frames) = Journal::open(&path).unwrap();
+        assert_eq!(frames, [kept]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,677 @@
+//! A `ChunkStore` that keeps each world in a directory of region files.
+//!
+//! Columns are grouped into fixed 32×32 regions, one file per region and
+//! record kind, each with a sector allocation table in front of its records.
+//! Every commit is first appended to a checksummed journal and served from
+//! memory until a checkpoint copies it into the region files, so small saves
+//! cost one buffered append instead of a database transaction.
+//!
+//! Durability matches the SQLite store's WAL with `synchronous = NORMAL`:
+//! commits are atomic and survive a process crash, while a power loss can
+//! roll back to the last checkpoint but never leaves a torn record behind.
+//! Unlike SQLite there is no cross-process locking, so a world directory must
+//! only be open in one process at a time.
+
+mod file;
+mod journal;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, File},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use bevy::{log::warn, math::DVec3};
+
+use crate::player::PlayerId;
+use crate::world::{
+    chunk::{Chunk, ChunkColumn, ChunkDecodeError, ChunkHeightmap, ChunkPos},
+    definition::{ChunkAddress, ColumnAddress, DimensionId},
+    generation::{WorldHeight, WorldMetadata},
+};
+
+use super::{
+    ChunkStore, ChunkStoreError, ChunkStoreResult, StoredChunk, StoredColumn, StoredColumnLight,
+    StoredColumnSurface, StoredPlayer, StoredPlayerPosition, metadata_entries,
+    world_metadata_from_entries,
+};
+use file::{RegionFile, RegionKind, RegionPos};
+use journal::{ByteReader, Journal, JournalRecord};
+
+const METADATA_FILE: &str = "world_metadata";
+const JOURNAL_FILE: &str = "journal";
+const PLAYERS_FILE: &str = "players.dat";
+const COLUMN_RECORD_VERSION: u8 = 1;
+/// Journal size that triggers a checkpoint into the region files.
+const REGION_CHECKPOINT_JOURNAL_BYTES: u64 = 16 * 1024 * 1024;
+
+pub struct RegionChunkStore {
+    root: PathBuf,
+    metadata: WorldMetadata,
+    inner: Mutex<RegionChunkStoreInner>,
+}
+
+struct RegionChunkStoreInner {
+    journal: Journal,
+    /// Column records committed to the journal since the last checkpoint.
+    /// An empty record marks a deleted column.
+    pending: HashMap<(RegionKind, ColumnAddress), Vec<u8>>,
+    players: HashMap<PlayerId, StoredPlayer>,
+    players_dirty: bool,
+    regions: HashMap<(RegionKind, DimensionId, RegionPos), RegionFile>,
+}
+
+impl RegionChunkStore {
+    /// Opens or creates the world directory at `path`, replaying any journal
+    /// left by a process that stopped before its last checkpoint.
+    pub fn open(path: impl AsRef<Path>, metadata: &WorldMetadata) -> ChunkStoreResult<Self> {
+        let root = path.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        ensure_metadata(&root, metadata)?;
+
+        let players = read_players(&root)?;
+        let (journal, frames) = Journal::open(&root.join(JOURNAL_FILE))?;
+        let mut inner = RegionChunkStoreInner {
+            journal,
+            pending: HashMap::new(),
+            players,
+            players_dirty: false,
+            regions: HashMap::new(),
+        };
+        for records in frames {
+            inner.apply(records)?;
+        }
+        inner.checkpoint(&root)?;
+
+        Ok(Self {
+            root,
+            metadata: metadata.clone(),
+            inner: Mutex::new(inner),
+        })
+    }
+
+    /// Reads the metadata an existing world was created with, without creating
+    /// or modifying the directory.
+    pub fn read_metadata(path: impl AsRef<Path>) -> ChunkStoreResult<WorldMetadata> {
+        world_metadata_from_entries(&Self::read_metadata_entries(path)?)
+    }
+
+    /// Reads every raw `world_metadata` entry of an existing world, ordered by
+    /// key.
+    pub fn read_metadata_entries(
+        path: impl AsRef<Path>,
+    ) -> ChunkStoreResult<Vec<(String, String)>> {
+        let text = fs::read_to_string(path.as_ref().join(METADATA_FILE))?;
+        let mut entries = text
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split_once('=')
+                    .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                    .ok_or_else(|| invalid_data(format!("malformed world_metadata line {line:?}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn lock(&self) -> ChunkStoreResult<MutexGuard<'_, RegionChunkStoreInner>> {
+        self.inner
+            .lock()
+            .map_err(|_| ChunkStoreError::LockPoisoned {
+                store: "region chunk store",
+            })
+    }
+
+    fn commit(&self, records: Vec<JournalRecord>) -> ChunkStoreResult<()> {
+        self.lock()?.commit(&self.root, records)
+    }
+
+    fn load_column_record(
+        &self,
+        kind: RegionKind,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<Vec<u8>>> {
+        self.lock()?.read(&self.root, kind, address)
+    }
+
+    /// Replaces one chunk's stored bytes without validating them, for
+    /// corruption tests.
+    #[cfg(test)]
+    pub(super) fn overwrite_chunk_bytes_for_test(&self, address: ChunkAddress, bytes: &[u8]) {
+        let column = address.column();
+        let mut record = self
+            .load_column_record(RegionKind::Chunks, column)
+            .unwrap()
+            .map(|bytes| ColumnRecord::decode(&bytes).unwrap())
+            .unwrap_or_default();
+        record.chunks.insert(address.position().y(), bytes.to_vec());
+        self.commit(vec![JournalRecord::Column {
+            kind: RegionKind::Chunks,
+            address: column,
+            bytes: record.encode(),
+        }])
+        .unwrap();
+    }
+
+    /// Drops the store without checkpointing, leaving the journal exactly as
+    /// a crashed process would.
+    #[cfg(test)]
+    pub(super) fn abandon_for_test(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for RegionChunkStore {
+    fn drop(&mut self) {
+        let Ok(inner) = self.inner.get_mut() else {
+            return;
+        };
+        if let Err(error) = inner.checkpoint(&self.root) {
+            warn!(
+                "Failed to checkpoint region world {}: {error}",
+                self.root.display()
+            );
+        }
+    }
+}
+
+impl RegionChunkStoreInner {
+    fn commit(&mut self, root: &Path, records: Vec<JournalRecord>) -> ChunkStoreResult<()> {
+        self.journal.append(&records)?;
+        self.apply(records)?;
+        if self.journal.len() >= REGION_CHECKPOINT_JOURNAL_BYTES {
+            self.checkpoint(root)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, records: Vec<JournalRecord>) -> ChunkStoreResult<()> {
+        for record in records {
+            match record {
+                JournalRecord::Column {
+                    kind,
+                    address,
+                    bytes,
+                } => {
+                    self.pending.insert((kind, address), bytes);
+                }
+                JournalRecord::Players { bytes } => {
+                    self.players = decode_players(&bytes)?;
+                    self.players_dirty = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read(
+        &mut self,
+        root: &Path,
+        kind: RegionKind,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<Vec<u8>>> {
+        if let Some(bytes) = self.pending.get(&(kind, address)) {
+            return Ok((!bytes.is_empty()).then(|| bytes.clone()));
+        }
+        let (region, slot) = RegionPos::containing(address.column());
+        let Some(file) = self.region(root, kind, address.dimension(), region, false)? else {
+            return Ok(None);
+        };
+        file.read(slot).map_err(Into::into)
+    }
+
+    /// Returns an open region file, opening it on first use. Missing files are
+    /// only created when `create` is set, so reads never litter the world.
+    fn region(
+        &mut self,
+        root: &Path,
+        kind: RegionKind,
+        dimension: DimensionId,
+        region: RegionPos,
+        create: bool,
+    ) -> io::Result<Option<&mut RegionFile>> {
+        let key = (kind, dimension, region);
+        if !self.regions.contains_key(&key) {
+            let directory = dimension_directory(root, dimension);
+            let path = directory.join(region.file_name(kind));
+            if !create && !path.exists() {
+                return Ok(None);
+            }
+            fs::create_dir_all(&directory)?;
+            self.regions.insert(key, RegionFile::open(&path)?);
+        }
+        Ok(self.regions.get_mut(&key))
+    }
+
+    fn list_columns(
+        &mut self,
+        root: &Path,
+        dimension: DimensionId,
+    ) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        let mut columns = HashSet::new();
+        let directory = dimension_directory(root, dimension);
+        if directory.exists() {
+            for entry in fs::read_dir(&directory)? {
+                let name = entry?.file_name();
+                let Some(region) = name
+                    .to_str()
+                    .and_then(|name| RegionPos::parse_file_name(name, RegionKind::Chunks))
+                else {
+                    continue;
+                };
+                if let Some(file) =
+                    self.region(root, RegionKind::Chunks, dimension, region, false)?
+                {
+                    columns.extend(file.occupied_slots().map(|slot| region.column(slot)));
+                }
+            }
+        }
+        for ((kind, address), bytes) in &self.pending {
+            if *kind != RegionKind::Chunks || address.dimension() != dimension {
+                continue;
+            }
+            if bytes.is_empty() {
+                columns.remove(&address.column());
+            } else {
+                columns.insert(address.column());
+            }
+        }
+
+        let mut columns = columns.into_iter().collect::<Vec<_>>();
+        columns.sort_unstable_by_key(|column| (column.x(), column.z()));
+        Ok(columns)
+    }
+
+    /// Makes the journal durable, copies its records into the region files
+    /// and player table, syncs them, and only then empties the journal. A
+    /// crash at any point leaves either the journal or the region files
+    /// complete, and replaying the journal is idempotent.
+    fn checkpoint(&mut self, root: &Path) -> ChunkStoreResult<()> {
+        if self.journal.len() == 0 && self.pending.is_empty() && !self.players_dirty {
+            return Ok(());
+        }
+        self.journal.sync()?;
+
+        let pending = std::mem::take(&mut self.pending);
+        let result = self.write_pending(root, &pending);
+        if let Err(error) = result {
+            // Keep serving the journaled records; the next checkpoint retries.
+            self.pending = pending;
+            return Err(error);
+        }
+        if self.players_dirty {
+            write_atomically(&root.join(PLAYERS_FILE), &encode_players(&self.players))?;
+            self.players_dirty = false;
+        }
+        self.journal.reset()?;
+        Ok(())
+    }
+
+    fn write_pending(
+        &mut self,
+        root: &Path,
+        pending: &HashMap<(RegionKind, ColumnAddress), Vec<u8>>,
+    ) -> ChunkStoreResult<()> {
+        let mut touched = HashSet::new();
+        for (&(kind, address), bytes) in pending {
+            let (region, slot) = RegionPos::containing(address.column());
+            let key = (kind, address.dimension(), region);
+            let create = !bytes.is_empty();
+            if let Some(file) = self.region(root, kind, address.dimension(), region, create)? {
+                file.write(slot, create.then_some(bytes.as_slice()))?;
+                touched.insert(key);
+            }
+        }
+        for key in touched {
+            self.regions
+                .get_mut(&key)
+                .expect("touched region file must stay open")
+                .sync()?;
+        }
+        Ok(())
+    }
+}
+
+impl ChunkStore for RegionChunkStore {
+    fn metadata(&self) -> &WorldMetadata {
+        &self.metadata
+    }
+
+    fn load_chunk(
+        &self,
+        address: ChunkAddress,
+    ) -> ChunkStoreResult<Option<(Chunk, ChunkHeightmap)>> {
+        let Some(bytes) = self.load_column_record(RegionKind::Chunks, address.column())? else {
+            return Ok(None);
+        };
+        let record = ColumnRecord::decode(&bytes)?;
+        let Some(blocks) = record.chunks.get(&address.position().y()) else {
+            return Ok(None);
+        };
+        let chunk = Chunk::try_from_storage_bytes(blocks)?;
+        Ok(Some((chunk, ChunkHeightmap::from_bytes(&record.heightmap))))
+    }
+
+    fn load_stored_column(
+        &self,
+        address: ColumnAddress,
+        height: WorldHeight,
+    ) -> ChunkStoreResult<StoredColumn> {
+        let Some(bytes) = self.load_column_record(RegionKind::Chunks, address)? else {
+            return StoredColumn::empty(address, height).map_err(Into::into);
+        };
+        let record = ColumnRecord::decode(&bytes)?;
+        let mut chunks = Vec::new();
+        for (&y, blocks) in record.chunks.range(0..height.chunks_i32()) {
+            let chunk = Chunk::try_from_storage_bytes(blocks)?;
+            chunks.push(StoredChunk::new(address.chunk(y), chunk));
+        }
+        let heightmap = ChunkHeightmap::from_bytes(&record.heightmap);
+
+        StoredColumn::try_new(address, height, heightmap, chunks).map_err(Into::into)
+    }
+
+    fn save_chunk(
+        &self,
+        address: ChunkAddress,
+        chunk: &Chunk,
+        heightmap: &ChunkHeightmap,
+    ) -> ChunkStoreResult<()> {
+        let column = address.column();
+        let mut inner = self.lock()?;
+        let mut record = inner
+            .read(&self.root, RegionKind::Chunks, column)?
+            .map(|bytes| ColumnRecord::decode(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+        record
+            .chunks
+            .insert(address.position().y(), chunk.to_storage_bytes());
+        record.heightmap = heightmap.to_bytes();
+
+        let mut records = vec![JournalRecord::Column {
+            kind: RegionKind::Chunks,
+            address: column,
+            bytes: record.encode(),
+        }];
+        if inner
+            .read(&self.root, RegionKind::Surface, column)?
+            .is_some()
+        {
+            records.push(JournalRecord::Column {
+                kind: RegionKind::Surface,
+                address: column,
+                bytes: Vec::new(),
+            });
+        }
+        inner.commit(&self.root, records)
+    }
+
+    fn load_column_light(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnLight>> {
+        self.load_column_record(RegionKind::Light, address)?
+            .as_deref()
+            .map(StoredColumnLight::try_from_bytes)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_column_light(
+        &self,
+        address: ColumnAddress,
+        light: &StoredColumnLight,
+    ) -> ChunkStoreResult<()> {
+        self.commit(vec![JournalRecord::Column {
+            kind: RegionKind::Light,
+            address,
+            bytes: light.to_bytes(),
+        }])
+    }
+
+    fn load_column_surface(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnSurface>> {
+        self.load_column_record(RegionKind::Surface, address)?
+            .as_deref()
+            .map(StoredColumnSurface::try_from_bytes)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_column_surface(
+        &self,
+        address: ColumnAddress,
+        surface: &StoredColumnSurface,
+    ) -> ChunkStoreResult<()> {
+        self.commit(vec![JournalRecord::Column {
+            kind: RegionKind::Surface,
+            address,
+            bytes: surface.to_bytes(),
+        }])
+    }
+
+    fn list_columns(&self, dimension: DimensionId) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        self.lock()?.list_columns(&self.root, dimension)
+    }
+
+    fn delete_column(&self, address: ColumnAddress) -> ChunkStoreResult<()> {
+        self.commit(
+            RegionKind::ALL
+                .map(|kind| JournalRecord::Column {
+                    kind,
+                    address,
+                    bytes: Vec::new(),
+                })
+                .into(),
+        )
+    }
+
+    fn load_player(&self, id: PlayerId) -> ChunkStoreResult<Option<StoredPlayer>> {
+        Ok(self.lock()?.players.get(&id).cloned())
+    }
+
+    fn save_player(&self, player: &StoredPlayer) -> ChunkStoreResult<()> {
+        let mut inner = self.lock()?;
+        let mut players = inner.players.clone();
+        players.insert(player.id(), player.clone());
+        inner.commit(
+            &self.root,
+            vec![JournalRecord::Players {
+                bytes: encode_players(&players),
+            }],
+        )
+    }
+
+    fn load_world_state(&self, key: &str) -> ChunkStoreResult<Option<String>> {
+        let _inner = self.lock()?;
+        Ok(Self::read_metadata_entries(&self.root)?
+            .into_iter()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value))
+    }
+
+    /// Rewrites the metadata file directly rather than journaling, so the
+    /// value is durable as soon as this returns. Weather and similar state
+    /// is saved rarely enough that one small atomic write costs nothing.
+    fn save_world_state(&self, key: &str, value: &str) -> ChunkStoreResult<()> {
+        let _inner = self.lock()?;
+        let mut entries = Self::read_metadata_entries(&self.root)?;
+        match entries.iter_mut().find(|(entry, _)| entry == key) {
+            Some((_, existing)) if existing == value => return Ok(()),
+            Some((_, existing)) => *existing = value.to_owned(),
+            None => entries.push((key.to_owned(), value.to_owned())),
+        }
+        write_metadata_entries(&self.root, &entries)
+    }
+}
+
+/// Every stored chunk of one column together with the column heightmap.
+#[derive(Debug, Default)]
+struct ColumnRecord {
+    heightmap: Vec<u8>,
+    chunks: BTreeMap<i32, Vec<u8>>,
+}
+
+impl ColumnRecord {
+    /// Encodes a version byte, the length-prefixed heightmap, then each chunk
+    /// as its Y and length-prefixed storage bytes from lowest to highest Y.
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![COLUMN_RECORD_VERSION];
+        bytes.extend_from_slice(&(self.heightmap.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.heightmap);
+        bytes.extend_from_slice(&(self.chunks.len() as u32).to_le_bytes());
+        for (y, blocks) in &self.chunks {
+            bytes.extend_from_slice(&y.to_le_bytes());
+            bytes.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(blocks);
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ChunkDecodeError> {
+        let mut reader = ByteReader(bytes);
+        if reader.take(1)?[0] != COLUMN_RECORD_VERSION {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+        let heightmap_len = reader.u32()? as usize;
+        let heightmap = reader.take(heightmap_len)?.to_vec();
+        let mut chunks = BTreeMap::new();
+        for _ in 0..reader.u32()? {
+            let y = reader.i32()?;
+            let len = reader.u32()? as usize;
+            if chunks.insert(y, reader.take(len)?.to_vec()).is_some() {
+                return Err(ChunkDecodeError::InvalidHeader);
+            }
+        }
+        if !reader.0.is_empty() {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+        Ok(Self { heightmap, chunks })
+    }
+}
+
+/// Encodes the player count followed by each player's id, dimension, chunk
+/// X, Y, Z and local X, Y, Z.
+fn encode_players(players: &HashMap<PlayerId, StoredPlayer>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(players.len() as u32).to_le_bytes());
+    for player in players.values() {
+        let position = player.position();
+        let chunk = position.chunk();
+        let local = position.local();
+        bytes.extend_from_slice(&player.id().get().to_le_bytes());
+        bytes.extend_from_slice(&position.dimension().get().to_le_bytes());
+        for coordinate in [chunk.x(), chunk.y(), chunk.z()] {
+            bytes.extend_from_slice(&coordinate.to_le_bytes());
+        }
+        for coordinate in [local.x, local.y, local.z] {
+            bytes.extend_from_slice(&coordinate.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+fn decode_players(bytes: &[u8]) -> ChunkStoreResult<HashMap<PlayerId, StoredPlayer>> {
+    let mut reader = ByteReader(bytes);
+    let mut players = HashMap::new();
+    for _ in 0..reader.u32()? {
+        let id = PlayerId::new(reader.i64()?);
+        let dimension = DimensionId::new(reader.u32()?);
+        let chunk = ChunkPos::new(reader.i32()?, reader.i32()?, reader.i32()?);
+        let local = DVec3::new(reader.f64()?, reader.f64()?, reader.f64()?);
+        let position = StoredPlayerPosition::try_new(dimension, chunk, local)?;
+        players.insert(id, StoredPlayer::new(id, position));
+    }
+    if !reader.0.is_empty() {
+        return Err(ChunkDecodeError::InvalidHeader.into());
+    }
+    Ok(players)
+}
+
+fn read_players(root: &Path) -> ChunkStoreResult<HashMap<PlayerId, StoredPlayer>> {
+    match fs::read(root.join(PLAYERS_FILE)) {
+        Ok(bytes) => decode_players(&bytes),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Checks `metadata` against the world's `world_metadata` file, adding any
+/// missing keys the same way the SQL stores insert missing rows.
+fn ensure_metadata(root: &Path, metadata: &WorldMetadata) -> ChunkStoreResult<()> {
+    let mut existing = match RegionChunkStore::read_metadata_entries(root) {
+        Ok(entries) => entries,
+        Err(ChunkStoreError::Io {
+            kind: ErrorKind::NotFound,
+            ..
+        }) => Vec::new(),
+        Err(error) => return Err(error),
+    };
+
+    let mut changed = false;
+    for (key, expected) in metadata_entries(metadata) {
+        match existing.iter().find(|(entry, _)| *entry == key) {
+            Some((_, found)) if *found == expected => {}
+            Some((_, found)) => {
+                return Err(ChunkStoreError::WorldMetadataMismatch {
+                    key,
+                    expected,
+                    found: found.clone(),
+                });
+            }
+            None => {
+                existing.push((key, expected));
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        write_metadata_entries(root, &existing)?;
+    }
+    Ok(())
+}
+
+fn write_metadata_entries(root: &Path, entries: &[(String, String)]) -> ChunkStoreResult<()> {
+    let text = entries
+        .iter()
+        .map(|(key, value)| format!("{key}={value}\n"))
+        .collect::<String>();
+    write_atomically(&root.join(METADATA_FILE), text.as_bytes())?;
+    Ok(())
+}
+
+/// Replaces `path` through a synced temporary file, so readers only ever see
+/// the old or the new contents.
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let temporary = path.with_extension("tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+    if let Some(parent) = path.parent() {
+        // Directory fsync persists the rename; not every platform supports it.
+        let _ = File::open(parent).and_then(|directory| directory.sync_all());
+    }
+    Ok(())
+}
+
+fn dimension_directory(root: &Path, dimension: DimensionId) -> PathBuf {
+    root.join(format!("dim{dimension}"))
+}
+
+fn invalid_data(message: String) -> ChunkStoreError {
+    io::Error::new(ErrorKind::InvalidData, message).into()
+}
+
+pub fn development_region_path(metadata: &WorldMetadata) -> PathBuf {
+    PathBuf::from("saves").join("dev").join(format!(
+        "{}.region",
+        super::development_store_stem(metadata)
+    ))
+}
@@ -3,19 +3,20 @@ use std::{
     time::Duration,
 };
 
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 
 use crate::player::PlayerId;
 use crate::world::{
-    chunk::{Chunk, ChunkHeightmap, ChunkPos},
+    chunk::{Chunk, ChunkColumn, ChunkHeightmap, ChunkPos},
     definition::{ChunkAddress, ColumnAddress, DimensionId},
     generation::{WorldHeight, WorldMetadata},
 };
 
 use super::{
     ChunkStore, ChunkStoreResult, SQL_CREATE_WORLD_METADATA, SQL_INSERT_METADATA_VALUE,
-    SQL_SELECT_METADATA_VALUE, StoredChunk, StoredColumn, StoredPlayer, StoredPlayerPosition,
-    metadata_entries,
+    SQL_SELECT_METADATA_VALUE, SQL_UPSERT_METADATA_VALUE, StoredChunk, StoredColumn,
+    StoredColumnLight, StoredColumnSurface, StoredPlayer, StoredPlayerPosition, metadata_entries,
+    world_metadata_from_entries,
 };
 
 const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
@@ -39,6 +40,10 @@ ORDER BY y";
 const SQL_UPSERT_CHUNK: &str = "INSERT INTO chunks (dimension, x, z, y, blocks)
 VALUES (?1, ?2, ?3, ?4, ?5)
 ON CONFLICT(dimension, x, z, y) DO UPDATE SET blocks = excluded.blocks";
+const SQL_SELECT_STORED_COLUMNS: &str =
+    "SELECT DISTINCT x, z FROM chunks WHERE dimension = ?1 ORDER BY x, z";
+const SQL_DELETE_COLUMN_CHUNKS: &str =
+    "DELETE FROM chunks WHERE dimension = ?1 AND x = ?2 AND z = ?3";
 
 const SQL_CREATE_COLUMN_HEIGHTMAPS: &str = "CREATE TABLE IF NOT EXISTS column_heightmaps (
     dimension INTEGER NOT NULL,
@@ -53,6 +58,40 @@ const SQL_UPSERT_COLUMN_HEIGHTMAP: &str =
     "INSERT INTO column_heightmaps (dimension, x, z, heightmap)
     VALUES (?1, ?2, ?3, ?4)
     ON CONFLICT(dimension, x, z) DO UPDATE SET heightmap = excluded.heightmap";
+const SQL_DELETE_COLUMN_HEIGHTMAP: &str =
+    "DELETE FROM column_heightmaps WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+
+const SQL_CREATE_COLUMN_LIGHT: &str = "CREATE TABLE IF NOT EXISTS column_light (
+    dimension INTEGER NOT NULL,
+    x INTEGER NOT NULL,
+    z INTEGER NOT NULL,
+    light BLOB NOT NULL,
+    PRIMARY KEY (dimension, x, z)
+) WITHOUT ROWID";
+const SQL_SELECT_COLUMN_LIGHT: &str =
+    "SELECT light FROM column_light WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+const SQL_UPSERT_COLUMN_LIGHT: &str = "INSERT INTO column_light (dimension, x, z, light)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(dimension, x, z) DO UPDATE SET light = excluded.light";
+const SQL_DELETE_COLUMN_LIGHT: &str =
+    "DELETE FROM column_light WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+
+const SQL_CREATE_COLUMN_SURFACES: &str = "CREATE TABLE IF NOT EXISTS column_surfaces (
+    dimension INTEGER NOT NULL,
+    x INTEGER NOT NULL,
+    z INTEGER NOT NULL,
+    surface BLOB NOT NULL,
+    PRIMARY KEY (dimension, x, z)
+) WITHOUT ROWID";
+const SQL_SELECT_COLUMN_SURFACE: &str =
+    "SELECT surface FROM column_surfaces WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+const SQL_UPSERT_COLUMN_SURFACE: &str = "INSERT INTO column_surfaces (dimension, x, z, surface)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(dimension, x, z) DO UPDATE SET surface = excluded.surface";
+const SQL_DELETE_COLUMN_SURFACE: &str =
+    "DELETE FROM column_surfaces WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+
+const SQL_SELECT_METADATA_ENTRIES: &str = "SELECT key, value FROM world_metadata ORDER BY key";
 
 const SQL_CREATE_PLAYERS: &str = "CREATE TABLE IF NOT EXISTS players (
     id INTEGER NOT NULL,
@@ -104,6 +143,23 @@ impl SqliteChunkStore {
         Ok(store)
     }
 
+    /// Reads the metadata an existing world was created with, without creating
+    /// or modifying the file.
+    pub fn read_metadata(path: impl AsRef<Path>) -> ChunkStoreResult<WorldMetadata> {
+        world_metadata_from_entries(&Self::read_metadata_entries(path)?)
+    }
+
+    /// Reads every raw `world_metadata` row of an existing world, ordered by key.
+    pub fn read_metadata_entries(
+        path: impl AsRef<Path>,
+    ) -> ChunkStoreResult<Vec<(String, String)>> {
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        configure_connection(&connection)?;
+        let mut statement = connection.prepare(SQL_SELECT_METADATA_ENTRIES)?;
+        let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
     fn open_connection(&self) -> ChunkStoreResult<Connection> {
         let connection = Connection::open(&self.path)?;
         configure_connection(&connection)?;
@@ -119,6 +175,8 @@ impl SqliteChunkStore {
         }
         connection.execute(SQL_CREATE_CHUNKS, [])?;
         connection.execute(SQL_CREATE_COLUMN_HEIGHTMAPS, [])?;
+        connection.execute(SQL_CREATE_COLUMN_LIGHT, [])?;
+        connection.execute(SQL_CREATE_COLUMN_SURFACES, [])?;
         connection.execute(SQL_CREATE_PLAYERS, [])?;
         connection.execute(SQL_CREATE_PLAYERS_POSITION_INDEX, [])?;
 
@@ -210,6 +268,117 @@ impl ChunkStore for SqliteChunkStore {
             ],
         )?;
         save_column_heightmap(&tx, address.column(), heightmap)?;
+        tx.execute(
+            SQL_DELETE_COLUMN_SURFACE,
+            params![
+                i64::from(address.dimension().get()),
+                position.x(),
+                position.z()
+            ],
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn load_column_light(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnLight>> {
+        let connection = self.open_connection()?;
+        let column = address.column();
+        let bytes = connection
+            .query_row(
+                SQL_SELECT_COLUMN_LIGHT,
+                params![i64::from(address.dimension().get()), column.x(), column.z()],
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()?;
+
+        bytes
+            .as_deref()
+            .map(StoredColumnLight::try_from_bytes)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_column_light(
+        &self,
+        address: ColumnAddress,
+        light: &StoredColumnLight,
+    ) -> ChunkStoreResult<()> {
+        let connection = self.open_connection()?;
+        let column = address.column();
+        connection.execute(
+            SQL_UPSERT_COLUMN_LIGHT,
+            params![
+                i64::from(address.dimension().get()),
+                column.x(),
+                column.z(),
+                light.to_bytes()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load_column_surface(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnSurface>> {
+        let connection = self.open_connection()?;
+        let column = address.column();
+        let bytes = connection
+            .query_row(
+                SQL_SELECT_COLUMN_SURFACE,
+                params![i64::from(address.dimension().get()), column.x(), column.z()],
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()?;
+
+        bytes
+            .as_deref()
+            .map(StoredColumnSurface::try_from_bytes)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_column_surface(
+        &self,
+        address: ColumnAddress,
+        surface: &StoredColumnSurface,
+    ) -> ChunkStoreResult<()> {
+        let connection = self.open_connection()?;
+        let column = address.column();
+        connection.execute(
+            SQL_UPSERT_COLUMN_SURFACE,
+            params![
+                i64::from(address.dimension().get()),
+                column.x(),
+                column.z(),
+                surface.to_bytes()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn list_columns(&self, dimension: DimensionId) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        let connection = self.open_connection()?;
+        let mut statement = connection.prepare(SQL_SELECT_STORED_COLUMNS)?;
+        let rows = statement.query_map(params![i64::from(dimension.get())], |row| {
+            Ok(ChunkColumn::new(row.get(0)?, row.get(1)?))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    fn delete_column(&self, address: ColumnAddress) -> ChunkStoreResult<()> {
+        let mut connection = self.open_connection()?;
+        let column = address.column();
+        let key = params![i64::from(address.dimension().get()), column.x(), column.z()];
+        let tx = connection.transaction()?;
+        tx.execute(SQL_DELETE_COLUMN_CHUNKS, key)?;
+        tx.execute(SQL_DELETE_COLUMN_HEIGHTMAP, key)?;
+        tx.execute(SQL_DELETE_COLUMN_LIGHT, key)?;
+        tx.execute(SQL_DELETE_COLUMN_SURFACE, key)?;
         tx.commit()?;
 
         Ok(())
@@ -268,6 +437,20 @@ impl ChunkStore for SqliteChunkStore {
         )?;
         Ok(())
     }
+
+    fn load_world_state(&self, key: &str) -> ChunkStoreResult<Option<String>> {
+        let connection = self.open_connection()?;
+        connection
+            .query_row(SQL_SELECT_METADATA_VALUE, params![key], |row| row.get(0))
+            .optional()
+            .map_err(Into::into)
+    }
+
+    fn save_world_state(&self, key: &str, value: &str) -> ChunkStoreResult<()> {
+        let connection = self.open_connection()?;
+        connection.execute(SQL_UPSERT_METADATA_VALUE, params![key, value])?;
+        Ok(())
+    }
 }
 
 fn configure_connection(connection: &Connection) -> ChunkStoreResult<()> {
@@ -0,0 +1,152 @@
+use crate::item::Item;
+use crate::world::chunk::{CHUNK_SIZE, ChunkCell, ChunkDecodeError};
+
+const STORED_COLUMN_SURFACE_VERSION: u8 = 1;
+const SURFACE_KIND_EMPTY: u16 = 0;
+const SURFACE_KIND_WATER: u16 = u16::MAX;
+
+/// Blocks along each edge of one surface summary cell.
+pub const SURFACE_CELL_BLOCKS: usize = 2;
+/// Summary cells along each edge of one column.
+pub const SURFACE_CELLS: usize = CHUNK_SIZE / SURFACE_CELL_BLOCKS;
+
+/// The highest rendered cell within one [`SURFACE_CELL_BLOCKS`]² footprint.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceCell {
+    top: u16,
+    cell: ChunkCell,
+}
+
+impl SurfaceCell {
+    pub const EMPTY: Self = Self {
+        top: 0,
+        cell: ChunkCell::EMPTY,
+    };
+
+    /// A surface whose highest rendered cell sits at absolute `y`.
+    pub fn new(y: i32, cell: ChunkCell) -> Self {
+        if cell == ChunkCell::EMPTY {
+            return Self::EMPTY;
+        }
+        let top = u16::try_from(y + 1).expect("surface height must fit a configured column");
+        Self {
+            top,
+            cell: normalized_surface_cell(cell),
+        }
+    }
+
+    /// Absolute Y of the top face, one above the highest rendered cell, or
+    /// zero when the footprint holds nothing to draw.
+    pub const fn top(self) -> u16 {
+        self.top
+    }
+
+    pub const fn cell(self) -> ChunkCell {
+        self.cell
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.top == 0
+    }
+
+    fn kind(self) -> u16 {
+        match self.cell {
+            ChunkCell::Empty => SURFACE_KIND_EMPTY,
+            ChunkCell::Block(item) => {
+                item.block_storage_id()
+                    .expect("surface cells only hold block-items")
+                    + 1
+            }
+            ChunkCell::Fluid(_) => SURFACE_KIND_WATER,
+        }
+    }
+
+    fn from_kind(top: u16, kind: u16) -> Result<Self, ChunkDecodeError> {
+        let cell = match kind {
+            SURFACE_KIND_EMPTY => ChunkCell::EMPTY,
+            SURFACE_KIND_WATER => ChunkCell::water_source(),
+            id => Item::from_block_storage_id(id - 1)
+                .map(ChunkCell::Block)
+                .ok_or(ChunkDecodeError::InvalidHeader)?,
+        };
+        if (top == 0) != (cell == ChunkCell::EMPTY) {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+        Ok(Self { top, cell })
+    }
+}
+
+/// Fluids differ only by level, which a far-field summary cannot show.
+fn normalized_surface_cell(cell: ChunkCell) -> ChunkCell {
+    match cell {
+        ChunkCell::Fluid(_) => ChunkCell::water_source(),
+        cell => cell,
+    }
+}
+
+/// A downsampled top-surface summary of one column for far-field terrain.
+///
+/// Surfaces are a cache like stored light: they are derived from the column's
+/// blocks, or from the generator for columns that were never saved, and a
+/// store drops them whenever one of the column's chunks is saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredColumnSurface {
+    cells: [[SurfaceCell; SURFACE_CELLS]; SURFACE_CELLS],
+}
+
+impl StoredColumnSurface {
+    pub fn from_fn(mut cell: impl FnMut(usize, usize) -> SurfaceCell) -> Self {
+        Self {
+            cells: std::array::from_fn(|x| std::array::from_fn(|z| cell(x, z))),
+        }
+    }
+
+    /// Cell at summary coordinates `x` and `z`, each in `0..SURFACE_CELLS`.
+    pub const fn cell(&self, x: usize, z: usize) -> SurfaceCell {
+        self.cells[x][z]
+    }
+
+    /// Encodes a version byte followed by each cell's little-endian top and
+    /// kind, in X-major order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::encoded_len());
+        bytes.push(STORED_COLUMN_SURFACE_VERSION);
+        for cell in self.cells.as_flattened() {
+            bytes.extend_from_slice(&cell.top.to_le_bytes());
+            bytes.extend_from_slice(&cell.kind().to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, ChunkDecodeError> {
+        let [version, body @ ..] = bytes else {
+            return Err(ChunkDecodeError::Truncated);
+        };
+        if *version != STORED_COLUMN_SURFACE_VERSION {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+        if bytes.len() < Self::encoded_len() {
+            return Err(ChunkDecodeError::Truncated);
+        }
+        if bytes.len() > Self::encoded_len() {
+            return Err(ChunkDecodeError::InvalidHeader);
+        }
+
+        let mut cells = [[SurfaceCell::EMPTY; SURFACE_CELLS]; SURFACE_CELLS];
+        for (cell, raw) in cells
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(body.chunks_exact(4))
+        {
+            *cell = SurfaceCell::from_kind(
+                u16::from_le_bytes([raw[0], raw[1]]),
+                u16::from_le_bytes([raw[2], raw[3]]),
+            )?;
+        }
+        Ok(Self { cells })
+    }
+
+    const fn encoded_len() -> usize {
+        1 + SURFACE_CELLS * SURFACE_CELLS * 4
+    }
+}
@@ -7,7 +7,9 @@ use std::{
 use super::*;
 use crate::item::Item;
 use crate::player::PlayerId;
-use crate::world::chunk::{ChunkColumn, ChunkHeightmap, ChunkPos};
+use crate::world::chunk::{
+    ChunkCell, ChunkColumn, ChunkHeightmap, ChunkLight, ChunkPos, LocalBlockPos,
+};
 use crate::world::definition::{ChunkAddress, ColumnAddress, DimensionId};
 use crate::world::generation::WorldHeight;
 use bevy::math::{DVec2, DVec3};
@@ -19,6 +21,15 @@ struct TestSqliteStore {
     path: PathBuf,
 }
 
+/// Field order matters: the store checkpoints on drop before its directory is
+/// removed.
+struct TestRegionStore {
+    store: RegionChunkStore,
+    directory: TestRegionDirectory,
+}
+
+struct TestRegionDirectory(PathBuf);
+
 #[cfg(feature = "turso-store")]
 struct TestTursoStore {
     store: TursoChunkStore,
@@ -39,6 +50,20 @@ impl Drop for TestSqliteStore {
     }
 }
 
+impl Deref for TestRegionStore {
+    type Target = RegionChunkStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
+
+impl Drop for TestRegionDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 #[cfg(feature = "turso-store")]
 impl Deref for TestTursoStore {
     type Target = TursoChunkStore;
@@ -77,6 +102,15 @@ fn test_sqlite_store(metadata: &WorldMetadata) -> TestSqliteStore {
     TestSqliteStore { store, path }
 }
 
+fn test_region_store(metadata: &WorldMetadata) -> TestRegionStore {
+    let path = test_store_path("region-test").with_extension("region");
+    let _ = std::fs::remove_dir_all(&path);
+    let directory = TestRegionDirectory(path);
+    let store = RegionChunkStore::open(&directory.0, metadata).unwrap();
+
+    TestRegionStore { store, directory }
+}
+
 #[cfg(feature = "turso-store")]
 fn test_turso_store(metadata: &WorldMetadata) -> TestTursoStore {
     let path = test_store_path("turso-test");
@@ -106,6 +140,120 @@ fn column_address(column: ChunkColumn) -> ColumnAddress {
     ColumnAddress::new(TEST_DIMENSION, column)
 }
 
+fn stored_column_light(height_chunks: usize, seed: u64) -> StoredColumnLight {
+    let stamp = LightNeighborhoodStamp::new(std::array::from_fn(|index| {
+        ColumnContentRevision::from_raw(seed + index as u64)
+    }));
+    let heightmap = ChunkHeightmap {
+        heights: [[seed as u8; crate::world::chunk::CHUNK_SIZE]; crate::world::chunk::CHUNK_SIZE],
+    };
+    let chunks = (0..height_chunks)
+        .map(|y| {
+            let mut light = ChunkLight::default();
+            light.set_sky_light(LocalBlockPos::new(1, y as u32, 2), 15);
+            light.set_block_light(LocalBlockPos::new(3, 4, 5), seed as u8);
+            light
+        })
+        .collect();
+    let padded = (0..height_chunks)
+        .map(|y| std::sync::Arc::from(vec![seed as u32 ^ y as u32; 3]))
+        .collect();
+    StoredColumnLight::new(stamp, heightmap, chunks, padded)
+}
+
+fn assert_column_light_store_contract(store: &impl ChunkStore) {
+    let address = column_address(ChunkColumn::new(-3, 8));
+    let other_dimension = ColumnAddress::new(DimensionId::GRASS_FLOOR, address.column());
+    assert_eq!(store.load_column_light(address).unwrap(), None);
+
+    let first = stored_column_light(4, 7);
+    store.save_column_light(address, &first).unwrap();
+    assert_eq!(store.load_column_light(address).unwrap(), Some(first));
+    assert_eq!(store.load_column_light(other_dimension).unwrap(), None);
+
+    let replacement = stored_column_light(4, 9);
+    store.save_column_light(address, &replacement).unwrap();
+    assert_eq!(store.load_column_light(address).unwrap(), Some(replacement));
+}
+
+fn stored_column_surface(seed: i32) -> StoredColumnSurface {
+    StoredColumnSurface::from_fn(|x, z| match (x + z) % 3 {
+        0 => SurfaceCell::EMPTY,
+        1 => SurfaceCell::new(seed + x as i32, Item::Stone.into()),
+        _ => SurfaceCell::new(seed, ChunkCell::water_flow(3)),
+    })
+}
+
+fn assert_column_surface_store_contract(store: &impl ChunkStore) {
+    let address = column_address(ChunkColumn::new(5, -2));
+    let other_dimension = ColumnAddress::new(DimensionId::GRASS_FLOOR, address.column());
+    assert_eq!(store.load_column_surface(address).unwrap(), None);
+
+    let surface = stored_column_surface(20);
+    store.save_column_surface(address, &surface).unwrap();
+    store
+        .save_column_surface(other_dimension, &stored_column_surface(30))
+        .unwrap();
+    assert_eq!(store.load_column_surface(address).unwrap(), Some(surface));
+    assert!(
+        store.list_columns(TEST_DIMENSION).unwrap().is_empty(),
+        "a cached surface alone does not make a column stored"
+    );
+
+    store
+        .save_chunk(
+            address.chunk(0),
+            &chunk_with_block(Item::Stone),
+            &default_heightmap(),
+        )
+        .unwrap();
+    assert_eq!(store.load_column_surface(address).unwrap(), None);
+    assert_eq!(
+        store.load_column_surface(other_dimension).unwrap(),
+        Some(stored_column_surface(30))
+    );
+
+    store.delete_column(other_dimension).unwrap();
+    assert_eq!(store.load_column_surface(other_dimension).unwrap(), None);
+}
+
+fn assert_column_listing_contract(store: &impl ChunkStore) {
+    let kept = column_address(ChunkColumn::new(-4, 2));
+    let deleted = column_address(ChunkColumn::new(3, -1));
+    let other_dimension = ColumnAddress::new(DimensionId::GRASS_FLOOR, ChunkColumn::new(9, 9));
+    assert!(store.list_columns(TEST_DIMENSION).unwrap().is_empty());
+
+    for (address, y) in [(deleted, 1), (kept, 0), (deleted, 0), (other_dimension, 0)] {
+        store
+            .save_chunk(
+                address.chunk(y),
+                &chunk_with_block(Item::Stone),
+                &default_heightmap(),
+            )
+            .unwrap();
+    }
+    store
+        .save_column_light(deleted, &stored_column_light(2, 1))
+        .unwrap();
+    assert_eq!(
+        store.list_columns(TEST_DIMENSION).unwrap(),
+        vec![kept.column(), deleted.column()]
+    );
+    assert_eq!(
+        store.list_columns(DimensionId::GRASS_FLOOR).unwrap(),
+        vec![other_dimension.column()]
+    );
+
+    store.delete_column(deleted).unwrap();
+    assert_eq!(
+        store.list_columns(TEST_DIMENSION).unwrap(),
+        vec![kept.column()]
+    );
+    assert_eq!(store.load_chunk(deleted.chunk(0)).unwrap(), None);
+    assert_eq!(store.load_column_light(deleted).unwrap(), None);
+    assert!(store.load_chunk(kept.chunk(0)).unwrap().is_some());
+}
+
 fn stored_player(id: PlayerId, dimension: DimensionId, translation: Vec3) -> StoredPlayer {
     StoredPlayer::new(
         id,
@@ -238,22 +386,480 @@ impl ChunkStore for MisdirectedColumnStore {
     }
 }
 
-#[test]
-fn sqlite_store_roundtrips_full_chunks() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_sqlite_store(&metadata);
+/// A backend exercised by the shared store conformance suite.
+trait StoreHarness: Sized {
+    type Store: ChunkStore;
+
+    fn create(metadata: &WorldMetadata) -> Self;
+
+    fn store(&self) -> &Self::Store;
+
+    /// Opens the same world again, or `None` for stores without durable state.
+    fn reopen(&self, metadata: &WorldMetadata) -> Option<ChunkStoreResult<Self::Store>>;
+
+    /// The error the backend reports when another writer holds the database.
+    fn busy_error() -> Option<ChunkStoreError>;
+
+    /// Replaces one stored chunk blob with bytes that cannot decode.
+    fn corrupt_chunk(&self, address: ChunkAddress);
+}
+
+impl StoreHarness for InMemoryChunkStore {
+    type Store = Self;
+
+    fn create(metadata: &WorldMetadata) -> Self {
+        Self::new(metadata.clone())
+    }
+
+    fn store(&self) -> &Self {
+        self
+    }
+
+    fn reopen(&self, _metadata: &WorldMetadata) -> Option<ChunkStoreResult<Self>> {
+        None
+    }
+
+    fn busy_error() -> Option<ChunkStoreError> {
+        None
+    }
+
+    fn corrupt_chunk(&self, address: ChunkAddress) {
+        self.overwrite_chunk_bytes_for_test(address, &[0xff]);
+    }
+}
+
+impl StoreHarness for TestSqliteStore {
+    type Store = SqliteChunkStore;
+
+    fn create(metadata: &WorldMetadata) -> Self {
+        test_sqlite_store(metadata)
+    }
+
+    fn store(&self) -> &SqliteChunkStore {
+        &self.store
+    }
+
+    fn reopen(&self, metadata: &WorldMetadata) -> Option<ChunkStoreResult<SqliteChunkStore>> {
+        Some(SqliteChunkStore::open(&self.path, metadata))
+    }
+
+    fn busy_error() -> Option<ChunkStoreError> {
+        Some(
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some("database is locked".to_owned()),
+            )
+            .into(),
+        )
+    }
+
+    fn corrupt_chunk(&self, address: ChunkAddress) {
+        let position = address.position();
+        rusqlite::Connection::open(&self.path)
+            .unwrap()
+            .execute(
+                "UPDATE chunks SET blocks = x'ff'
+                WHERE dimension = ?1 AND x = ?2 AND z = ?3 AND y = ?4",
+                rusqlite::params![
+                    i64::from(address.dimension().get()),
+                    position.x(),
+                    position.z(),
+                    position.y()
+                ],
+            )
+            .unwrap();
+    }
+}
+
+impl StoreHarness for TestRegionStore {
+    type Store = RegionChunkStore;
+
+    fn create(metadata: &WorldMetadata) -> Self {
+        test_region_store(metadata)
+    }
+
+    fn store(&self) -> &RegionChunkStore {
+        &self.store
+    }
+
+    fn reopen(&self, metadata: &WorldMetadata) -> Option<ChunkStoreResult<RegionChunkStore>> {
+        Some(RegionChunkStore::open(&self.directory.0, metadata))
+    }
+
+    fn busy_error() -> Option<ChunkStoreError> {
+        None
+    }
+
+    fn corrupt_chunk(&self, address: ChunkAddress) {
+        self.store.overwrite_chunk_bytes_for_test(address, &[0xff]);
+    }
+}
+
+#[cfg(feature = "turso-store")]
+impl StoreHarness for TestTursoStore {
+    type Store = TursoChunkStore;
+
+    fn create(metadata: &WorldMetadata) -> Self {
+        test_turso_store(metadata)
+    }
+
+    fn store(&self) -> &TursoChunkStore {
+        &self.store
+    }
+
+    fn reopen(&self, metadata: &WorldMetadata) -> Option<ChunkStoreResult<TursoChunkStore>> {
+        Some(TursoChunkStore::open(&self.path, metadata))
+    }
+
+    fn busy_error() -> Option<ChunkStoreError> {
+        Some(::turso::Error::Busy("database is locked".to_owned()).into())
+    }
+
+    fn corrupt_chunk(&self, address: ChunkAddress) {
+        self.overwrite_chunk_bytes_for_test(address, &[0xff]);
+    }
+}
+
+/// Generates the conformance suite for one [`StoreHarness`].
+macro_rules! store_conformance_tests {
+    ($module:ident, $harness:ty) => {
+        mod $module {
+            use super::*;
+
+            #[test]
+            fn roundtrips_full_chunks() {
+                conformance_roundtrips_full_chunks::<$harness>();
+            }
+
+            #[test]
+            fn loads_columns_by_xz() {
+                conformance_loads_columns_by_xz::<$harness>();
+            }
+
+            #[test]
+            fn isolates_dimensions_at_equal_coordinates() {
+                let metadata = WorldMetadata::with_seed(42);
+                let harness = <$harness>::create(&metadata);
+
+                assert_addressed_store_contract(harness.store(), metadata.height());
+            }
+
+            #[test]
+            fn shares_one_heightmap_per_column() {
+                conformance_shares_one_heightmap_per_column::<$harness>();
+            }
+
+            #[test]
+            fn roundtrips_and_upserts_independent_player_rows() {
+                let harness = <$harness>::create(&WorldMetadata::with_seed(42));
+
+                assert_player_store_contract(harness.store());
+                assert_eq!(
+                    harness
+                        .store()
+                        .load_chunk(chunk_address(ChunkPos::ZERO))
+                        .unwrap(),
+                    None
+                );
+            }
+
+            #[test]
+            fn roundtrips_column_light() {
+                let harness = <$harness>::create(&WorldMetadata::with_seed(42));
+
+                assert_column_light_store_contract(harness.store());
+            }
+
+            #[test]
+            fn caches_column_surfaces_until_a_chunk_saves() {
+                let harness = <$harness>::create(&WorldMetadata::with_seed(42));
+
+                assert_column_surface_store_contract(harness.store());
+            }
+
+            #[test]
+            fn lists_and_deletes_columns() {
+                let harness = <$harness>::create(&WorldMetadata::with_seed(42));
+
+                assert_column_listing_contract(harness.store());
+            }
+
+            #[test]
+            fn rejects_world_metadata_mismatch() {
+                conformance_rejects_world_metadata_mismatch::<$harness>();
+            }
+
+            #[test]
+            fn persists_mutable_world_state() {
+                conformance_persists_mutable_world_state::<$harness>();
+            }
+
+            #[test]
+            fn classifies_errors_by_transience() {
+                conformance_classifies_errors_by_transience::<$harness>();
+            }
+
+            #[test]
+            fn completes_concurrent_saves() {
+                conformance_completes_concurrent_saves::<$harness>();
+            }
+        }
+    };
+}
+
+store_conformance_tests!(in_memory_conformance, InMemoryChunkStore);
+store_conformance_tests!(sqlite_conformance, TestSqliteStore);
+store_conformance_tests!(region_conformance, TestRegionStore);
+#[cfg(feature = "turso-store")]
+store_conformance_tests!(turso_conformance, TestTursoStore);
+
+fn conformance_roundtrips_full_chunks<H: StoreHarness>() {
+    let harness = H::create(&WorldMetadata::with_seed(42));
     let address = chunk_address(ChunkPos::new(-2, 1, 3));
     let mut chunk = chunk_with_block(Item::Grass);
     chunk.set_cell_xyz(15, 15, 15, Item::OakLeaves.into());
 
-    store
+    harness
+        .store()
         .save_chunk(address, &chunk, &default_heightmap())
         .unwrap();
 
-    let (loaded, _h) = store.load_chunk(address).unwrap().unwrap();
+    let (loaded, _h) = harness.store().load_chunk(address).unwrap().unwrap();
     assert_eq!(loaded, chunk);
 }
 
+fn conformance_loads_columns_by_xz<H: StoreHarness>() {
+    let metadata = WorldMetadata::with_seed(42);
+    let harness = H::create(&metadata);
+    let store = harness.store();
+    let column = ChunkColumn::new(-2, 3);
+    let address = column_address(column);
+    let lower = chunk_with_block(Item::Grass);
+    let upper = chunk_with_block(Item::Stone);
+    let other_column = chunk_with_block(Item::Dirt);
+
+    store
+        .save_chunk(address.chunk(3), &upper, &default_heightmap())
+        .unwrap();
+    store
+        .save_chunk(address.chunk(0), &lower, &default_heightmap())
+        .unwrap();
+    store
+        .save_chunk(
+            chunk_address(ChunkPos::new(column.x() + 1, 0, column.z())),
+            &other_column,
+            &default_heightmap(),
+        )
+        .unwrap();
+
+    let column_data = store
+        .load_stored_column(address, metadata.height())
+        .unwrap();
+    assert_eq!(column_data.address(), address);
+    assert_eq!(column_data.position(), column);
+    assert_eq!(column_data.chunks().len(), 2);
+    assert_eq!(column_data.chunks()[0].address, address.chunk(0));
+    assert_eq!(column_data.chunks()[0].chunk, lower);
+    assert_eq!(column_data.chunks()[1].address, address.chunk(3));
+    assert_eq!(column_data.chunks()[1].chunk, upper);
+}
+
+fn conformance_shares_one_heightmap_per_column<H: StoreHarness>() {
+    let metadata = WorldMetadata::with_seed(42);
+    let harness = H::create(&metadata);
+    let store = harness.store();
+    let address = column_address(ChunkColumn::new(5, -6));
+    let first = ChunkHeightmap {
+        heights: [[3; crate::world::chunk::CHUNK_SIZE]; crate::world::chunk::CHUNK_SIZE],
+    };
+    let mut latest = first;
+    latest.heights[4][7] = 40;
+
+    store
+        .save_chunk(address.chunk(0), &chunk_with_block(Item::Stone), &first)
+        .unwrap();
+    store
+        .save_chunk(address.chunk(2), &chunk_with_block(Item::Dirt), &latest)
+        .unwrap();
+
+    assert_eq!(
+        store
+            .load_chunk(address.chunk(0))
+            .unwrap()
+            .map(|(_, heightmap)| heightmap),
+        Some(latest)
+    );
+    assert_eq!(
+        store
+            .load_stored_column(address, metadata.height())
+            .unwrap()
+            .heightmap(),
+        &latest
+    );
+    assert_eq!(
+        store
+            .load_stored_column(column_address(ChunkColumn::new(6, -6)), metadata.height())
+            .unwrap()
+            .heightmap(),
+        &default_heightmap()
+    );
+}
+
+fn conformance_rejects_world_metadata_mismatch<H: StoreHarness>() {
+    let metadata = WorldMetadata::with_seed(42);
+    let harness = H::create(&metadata);
+    let address = chunk_address(ChunkPos::new(1, 0, 1));
+    harness
+        .store()
+        .save_chunk(
+            address,
+            &chunk_with_block(Item::Stone),
+            &default_heightmap(),
+        )
+        .unwrap();
+    let Some(reopened) = harness.reopen(&metadata) else {
+        return;
+    };
+    assert!(reopened.unwrap().load_chunk(address).unwrap().is_some());
+
+    let mut reseeded = metadata.clone();
+    reseeded.seed = 43;
+    assert!(matches!(
+        harness.reopen(&reseeded),
+        Some(Err(ChunkStoreError::WorldMetadataMismatch {
+            key,
+            expected,
+            found,
+        })) if key == "seed" && expected == "43" && found == "42"
+    ));
+    let taller = metadata
+        .clone()
+        .with_height_chunks(metadata.height_chunks() + 1)
+        .unwrap();
+    assert!(matches!(
+        harness.reopen(&taller),
+        Some(Err(ChunkStoreError::WorldMetadataMismatch { .. }))
+    ));
+}
+
+fn conformance_persists_mutable_world_state<H: StoreHarness>() {
+    let metadata = WorldMetadata::with_seed(42);
+    let harness = H::create(&metadata);
+    let store = harness.store();
+
+    assert_eq!(store.load_world_state("weather.kind").unwrap(), None);
+    store.save_world_state("weather.kind", "rain").unwrap();
+    store.save_world_state("weather.kind", "thunder").unwrap();
+    assert_eq!(
+        store.load_world_state("weather.kind").unwrap().as_deref(),
+        Some("thunder")
+    );
+
+    let Some(reopened) = harness.reopen(&metadata) else {
+        return;
+    };
+    assert_eq!(
+        reopened
+            .unwrap()
+            .load_world_state("weather.kind")
+            .unwrap()
+            .as_deref(),
+        Some("thunder")
+    );
+}
+
+fn conformance_classifies_errors_by_transience<H: StoreHarness>() {
+    if let Some(busy) = H::busy_error() {
+        assert!(busy.is_transient(), "{busy}");
+    }
+
+    let metadata = WorldMetadata::with_seed(42);
+    let harness = H::create(&metadata);
+    let address = chunk_address(ChunkPos::new(0, 1, 0));
+    harness
+        .store()
+        .save_chunk(
+            address,
+            &chunk_with_block(Item::Stone),
+            &default_heightmap(),
+        )
+        .unwrap();
+    harness.corrupt_chunk(address);
+
+    let error = harness.store().load_chunk(address).unwrap_err();
+    assert!(matches!(error, ChunkStoreError::Decode(_)), "{error}");
+    assert!(!error.is_transient());
+    let error = harness
+        .store()
+        .load_stored_column(address.column(), metadata.height())
+        .unwrap_err();
+    assert!(!error.is_transient());
+}
+
+fn conformance_completes_concurrent_saves<H: StoreHarness>() {
+    const WRITERS: i32 = 8;
+    let metadata = WorldMetadata::with_seed(42);
+    let height = metadata.height();
+    let harness = H::create(&metadata);
+    let store = harness.store();
+
+    std::thread::scope(|scope| {
+        for writer in 0..WRITERS {
+            scope.spawn(move || {
+                let address = column_address(ChunkColumn::new(writer, -writer));
+                for y in 0..height.chunks_i32() {
+                    store
+                        .save_chunk(
+                            address.chunk(y),
+                            &chunk_with_block(Item::Stone),
+                            &default_heightmap(),
+                        )
+                        .unwrap();
+                }
+                store
+                    .save_column_light(
+                        address,
+                        &stored_column_light(height.chunks(), writer as u64),
+                    )
+                    .unwrap();
+                store
+                    .save_player(&stored_player(
+                        PlayerId::new(i64::from(writer) + 10),
+                        TEST_DIMENSION,
+                        Vec3::new(writer as f32, 1.5, -2.25),
+                    ))
+                    .unwrap();
+            });
+        }
+    });
+
+    assert_eq!(
+        store.list_columns(TEST_DIMENSION).unwrap().len(),
+        WRITERS as usize
+    );
+    for writer in 0..WRITERS {
+        let address = column_address(ChunkColumn::new(writer, -writer));
+        assert_eq!(
+            store
+                .load_stored_column(address, height)
+                .unwrap()
+                .chunks()
+                .len(),
+            height.chunks()
+        );
+        assert_eq!(
+            store.load_column_light(address).unwrap(),
+            Some(stored_column_light(height.chunks(), writer as u64))
+        );
+        assert!(
+            store
+                .load_player(PlayerId::new(i64::from(writer) + 10))
+                .unwrap()
+                .is_some()
+        );
+    }
+}
+
 #[test]
 fn stored_player_positions_preserve_fractional_and_negative_coordinates() {
     for (translation, expected_chunk) in [
@@ -302,18 +908,6 @@ fn stored_player_positions_preserve_fractional_and_negative_coordinates() {
     }
 }
 
-#[test]
-fn sqlite_store_roundtrips_and_upserts_independent_player_rows() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_sqlite_store(&metadata);
-
-    assert_player_store_contract(&*store);
-    assert_eq!(
-        store.load_chunk(chunk_address(ChunkPos::ZERO)).unwrap(),
-        None
-    );
-}
-
 #[test]
 fn sqlite_player_position_index_uses_dimension_x_z_y_chunk_order() {
     let metadata = WorldMetadata::with_seed(42);
@@ -358,88 +952,289 @@ fn sqlite_open_adds_player_schema_to_an_existing_world() {
 }
 
 #[test]
-fn sqlite_store_isolates_dimensions_at_equal_coordinates() {
+fn region_store_replays_journaled_commits_after_a_crash() {
     let metadata = WorldMetadata::with_seed(42);
-    let store = test_sqlite_store(&metadata);
+    let harness = test_region_store(&metadata);
+    let address = column_address(ChunkColumn::new(33, -1));
+    let chunk = chunk_with_block(Item::Stone);
+    let light = stored_column_light(metadata.height_chunks(), 3);
+    let player = stored_player(PlayerId::LOCAL, TEST_DIMENSION, Vec3::new(-4.5, 70.0, 9.25));
+    harness
+        .save_chunk(address.chunk(1), &chunk, &default_heightmap())
+        .unwrap();
+    harness.save_column_light(address, &light).unwrap();
+    harness.save_player(&player).unwrap();
+    let deleted = column_address(ChunkColumn::new(0, 0));
+    harness
+        .save_chunk(deleted.chunk(0), &chunk, &default_heightmap())
+        .unwrap();
+    harness.delete_column(deleted).unwrap();
+
+    let TestRegionStore { store, directory } = harness;
+    store.abandon_for_test();
+    assert!(
+        std::fs::metadata(directory.0.join("journal"))
+            .unwrap()
+            .len()
+            > 0
+    );
+    let harness = TestRegionStore {
+        store: RegionChunkStore::open(&directory.0, &metadata).unwrap(),
+        directory,
+    };
 
-    assert_addressed_store_contract(&*store, metadata.height());
+    assert_eq!(
+        harness
+            .load_chunk(address.chunk(1))
+            .unwrap()
+            .map(|(chunk, _)| chunk),
+        Some(chunk)
+    );
+    assert_eq!(harness.load_column_light(address).unwrap(), Some(light));
+    assert_eq!(harness.load_player(PlayerId::LOCAL).unwrap(), Some(player));
+    assert_eq!(
+        harness.list_columns(TEST_DIMENSION).unwrap(),
+        [address.column()]
+    );
+    assert_eq!(
+        std::fs::metadata(harness.directory.0.join("journal"))
+            .unwrap()
+            .len(),
+        0
+    );
 }
 
 #[test]
-fn sqlite_store_loads_columns_by_xz() {
+fn region_store_checkpoints_into_region_files_on_drop() {
     let metadata = WorldMetadata::with_seed(42);
-    let store = test_sqlite_store(&metadata);
-    let column = ChunkColumn::new(-2, 3);
-    let address = column_address(column);
-    let lower = chunk_with_block(Item::Grass);
-    let upper = chunk_with_block(Item::Stone);
-    let other_column = chunk_with_block(Item::Dirt);
+    let harness = test_region_store(&metadata);
+    let columns = [
+        ChunkColumn::new(-1, -1),
+        ChunkColumn::new(0, 31),
+        ChunkColumn::new(32, 0),
+    ];
+    for (index, column) in columns.into_iter().enumerate() {
+        harness
+            .save_chunk(
+                column_address(column).chunk(index as i32),
+                &chunk_with_block(Item::Dirt),
+                &default_heightmap(),
+            )
+            .unwrap();
+    }
 
-    store
-        .save_chunk(address.chunk(3), &upper, &default_heightmap())
-        .unwrap();
-    store
-        .save_chunk(address.chunk(0), &lower, &default_heightmap())
-        .unwrap();
-    store
-        .save_chunk(
-            chunk_address(ChunkPos::new(column.x() + 1, 0, column.z())),
-            &other_column,
-            &default_heightmap(),
-        )
-        .unwrap();
+    let TestRegionStore { store, directory } = harness;
+    drop(store);
+    assert_eq!(
+        std::fs::metadata(directory.0.join("journal"))
+            .unwrap()
+            .len(),
+        0
+    );
+    let harness = TestRegionStore {
+        store: RegionChunkStore::open(&directory.0, &metadata).unwrap(),
+        directory,
+    };
 
-    let column_data = store
-        .load_stored_column(address, metadata.height())
-        .unwrap();
-    assert_eq!(column_data.address(), address);
-    assert_eq!(column_data.position(), column);
-    assert_eq!(column_data.chunks().len(), 2);
-    assert_eq!(column_data.chunks()[0].address, address.chunk(0));
-    assert_eq!(column_data.chunks()[0].chunk, lower);
-    assert_eq!(column_data.chunks()[1].address, address.chunk(3));
-    assert_eq!(column_data.chunks()[1].chunk, upper);
+    let mut expected = columns.to_vec();
+    expected.sort_unstable_by_key(|column| (column.x(), column.z()));
+    assert_eq!(harness.list_columns(TEST_DIMENSION).unwrap(), expected);
+    for (index, column) in columns.into_iter().enumerate() {
+        let stored = harness
+            .load_stored_column(column_address(column), metadata.height())
+            .unwrap();
+        assert_eq!(stored.chunks().len(), 1);
+        assert_eq!(stored.chunks()[0].position().y(), index as i32);
+    }
+    assert_eq!(
+        RegionChunkStore::read_metadata(&harness.directory.0).unwrap(),
+        metadata
+    );
 }
 
 #[test]
-fn in_memory_store_loads_columns_by_xz() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = InMemoryChunkStore::new(metadata.clone());
-    let column = ChunkColumn::new(2, -1);
-    let address = column_address(column);
-    let lower = chunk_with_block(Item::OakLog);
-    let upper = chunk_with_block(Item::OakLeaves);
+fn stored_column_light_bytes_roundtrip_and_reject_damage() {
+    let light = stored_column_light(3, 11);
+    let bytes = light.to_bytes();
+
+    assert_eq!(StoredColumnLight::try_from_bytes(&bytes).unwrap(), light);
+    assert!(matches!(
+        StoredColumnLight::try_from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ChunkDecodeError::Truncated)
+    ));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        StoredColumnLight::try_from_bytes(&trailing),
+        Err(ChunkDecodeError::InvalidHeader)
+    ));
+    let mut other_version = bytes;
+    for version in [1, u8::MAX] {
+        other_version[0] = version;
+        assert_eq!(
+            StoredColumnLight::try_from_bytes(&other_version),
+            Err(ChunkDecodeError::UnsupportedVersion(version))
+        );
+    }
+}
 
+#[test]
+fn stored_column_surface_bytes_roundtrip_and_reject_damage() {
+    let surface = stored_column_surface(7);
+    let bytes = surface.to_bytes();
+
+    let decoded = StoredColumnSurface::try_from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, surface);
+    assert_eq!(decoded.cell(1, 1).cell(), ChunkCell::water_source());
+    assert_eq!(decoded.cell(1, 0).top(), 9);
+    assert!(matches!(
+        StoredColumnSurface::try_from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ChunkDecodeError::Truncated)
+    ));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        StoredColumnSurface::try_from_bytes(&trailing),
+        Err(ChunkDecodeError::InvalidHeader)
+    ));
+    let mut future_version = bytes.clone();
+    future_version[0] = u8::MAX;
+    assert!(matches!(
+        StoredColumnSurface::try_from_bytes(&future_version),
+        Err(ChunkDecodeError::InvalidHeader)
+    ));
+    let mut unknown_block = bytes.clone();
+    unknown_block[3..5].copy_from_slice(&0xfffeu16.to_le_bytes());
+    assert!(matches!(
+        StoredColumnSurface::try_from_bytes(&unknown_block),
+        Err(ChunkDecodeError::InvalidHeader)
+    ));
+    let mut topless_block = bytes;
+    topless_block[5..7].copy_from_slice(&0u16.to_le_bytes());
+    assert!(matches!(
+        StoredColumnSurface::try_from_bytes(&topless_block),
+        Err(ChunkDecodeError::InvalidHeader)
+    ));
+}
+
+#[test]
+fn repository_rejects_column_light_for_another_height() {
+    let metadata = WorldMetadata::with_seed(42).with_height_chunks(2).unwrap();
+    let store = InMemoryChunkStore::new(metadata);
+    let address = column_address(ChunkColumn::new(1, 1));
     store
-        .save_chunk(address.chunk(2), &upper, &default_heightmap())
+        .save_column_light(address, &stored_column_light(3, 5))
         .unwrap();
-    store
-        .save_chunk(address.chunk(0), &lower, &default_heightmap())
+    let repository = ChunkRepository::new(store);
+
+    assert_eq!(repository.load_column_light(address).unwrap(), None);
+    assert!(matches!(
+        repository.save_column_light(address, &stored_column_light(3, 5)),
+        Err(ChunkStoreError::InvalidStoredColumn(
+            StoredColumnError::LightHeightMismatch { returned: 3, .. }
+        ))
+    ));
+    let matching = stored_column_light(2, 5);
+    repository.save_column_light(address, &matching).unwrap();
+    assert_eq!(
+        repository.load_column_light(address).unwrap(),
+        Some(matching)
+    );
+}
+
+#[test]
+fn sqlite_metadata_is_readable_without_knowing_the_world() {
+    let metadata = WorldMetadata::with_seed(0x5eed)
+        .with_height_chunks(3)
         .unwrap();
+    let store = test_sqlite_store(&metadata);
 
-    let column_data = store
-        .load_stored_column(address, metadata.height())
+    assert_eq!(
+        SqliteChunkStore::read_metadata(&store.path).unwrap(),
+        metadata
+    );
+    let entries = SqliteChunkStore::read_metadata_entries(&store.path).unwrap();
+    let mut expected = metadata_entries(&metadata);
+    expected.sort();
+    assert_eq!(entries, expected);
+
+    let missing = test_store_path("missing-world");
+    assert!(SqliteChunkStore::read_metadata(&missing).is_err());
+    assert!(!missing.exists());
+}
+
+#[test]
+fn world_store_paths_select_their_backend() {
+    let metadata = WorldMetadata::with_seed(0x5eed)
+        .with_height_chunks(3)
         .unwrap();
-    assert_eq!(column_data.chunks().len(), 2);
-    assert_eq!(column_data.chunks()[0].address, address.chunk(0));
-    assert_eq!(column_data.chunks()[0].chunk, lower);
-    assert_eq!(column_data.chunks()[1].address, address.chunk(2));
-    assert_eq!(column_data.chunks()[1].chunk, upper);
+    let sqlite = test_sqlite_store(&metadata);
+    let region = test_region_store(&metadata);
+
+    assert_eq!(
+        WorldStoreKind::from_path(&sqlite.path),
+        WorldStoreKind::Sqlite
+    );
+    assert_eq!(
+        WorldStoreKind::from_path(&region.directory.0),
+        WorldStoreKind::Region
+    );
+    assert_eq!(
+        WorldStoreKind::from_path(Path::new("not-created-yet.region")),
+        WorldStoreKind::Region
+    );
+    assert_eq!(
+        WorldStoreKind::from_extension(std::ffi::OsStr::new("txt")),
+        None
+    );
+    assert_eq!(
+        WorldStoreKind::Region.read_metadata(&region.directory.0),
+        Ok(metadata.clone())
+    );
+    assert_eq!(
+        open_world_store(&sqlite.path).unwrap().metadata(),
+        &metadata
+    );
 }
 
 #[test]
-fn in_memory_store_roundtrips_independent_player_rows() {
-    let store = InMemoryChunkStore::new(WorldMetadata::with_seed(42));
+fn repository_refuses_to_rewrite_world_identity() {
+    let repository = ChunkRepository::new(InMemoryChunkStore::new(WorldMetadata::with_seed(7)));
 
-    assert_player_store_contract(&store);
+    assert!(matches!(
+        repository.save_world_state("seed", "8"),
+        Err(ChunkStoreError::ImmutableWorldMetadata { key }) if key == "seed"
+    ));
+    repository.save_world_state("weather.kind", "rain").unwrap();
+    assert_eq!(
+        repository
+            .load_world_state("weather.kind")
+            .unwrap()
+            .as_deref(),
+        Some("rain")
+    );
 }
 
 #[test]
-fn in_memory_store_isolates_dimensions_at_equal_coordinates() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = InMemoryChunkStore::new(metadata.clone());
+fn world_metadata_entries_reject_missing_and_invalid_values() {
+    let mut entries = metadata_entries(&WorldMetadata::with_seed(1));
+    entries.retain(|(key, _)| key != "seed");
+    assert_eq!(
+        world_metadata_from_entries(&entries),
+        Err(ChunkStoreError::InvalidWorldMetadata {
+            key: "seed".to_owned(),
+            found: None,
+        })
+    );
 
-    assert_addressed_store_contract(&store, metadata.height());
+    entries.push(("seed".to_owned(), "not-a-seed".to_owned()));
+    assert_eq!(
+        world_metadata_from_entries(&entries),
+        Err(ChunkStoreError::InvalidWorldMetadata {
+            key: "seed".to_owned(),
+            found: Some("not-a-seed".to_owned()),
+        })
+    );
 }
 
 #[test]
@@ -480,36 +1275,6 @@ fn noop_store_discards_players() {
     assert_eq!(store.load_player(PlayerId::LOCAL).unwrap(), None);
 }
 
-#[cfg(feature = "turso-store")]
-#[test]
-fn turso_store_roundtrips_full_chunks() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_turso_store(&metadata);
-    let address = chunk_address(ChunkPos::new(-2, 1, 3));
-    let mut chunk = chunk_with_block(Item::Grass);
-    chunk.set_cell_xyz(15, 15, 15, Item::OakLeaves.into());
-
-    store
-        .save_chunk(address, &chunk, &default_heightmap())
-        .unwrap();
-
-    let (loaded, _h) = store.load_chunk(address).unwrap().unwrap();
-    assert_eq!(loaded, chunk);
-}
-
-#[cfg(feature = "turso-store")]
-#[test]
-fn turso_store_roundtrips_and_upserts_independent_player_rows() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_turso_store(&metadata);
-
-    assert_player_store_contract(&*store);
-    assert_eq!(
-        store.load_chunk(chunk_address(ChunkPos::ZERO)).unwrap(),
-        None
-    );
-}
-
 #[cfg(feature = "turso-store")]
 #[test]
 fn turso_player_position_index_uses_dimension_x_z_y_chunk_order() {
@@ -547,15 +1312,6 @@ fn turso_open_adds_player_schema_to_an_existing_world() {
     assert_player_store_contract(&reopened);
 }
 
-#[cfg(feature = "turso-store")]
-#[test]
-fn turso_store_isolates_dimensions_at_equal_coordinates() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_turso_store(&metadata);
-
-    assert_addressed_store_contract(&*store, metadata.height());
-}
-
 #[cfg(feature = "turso-store")]
 #[test]
 fn turso_store_rejects_dimension_generator_metadata_mismatch() {
@@ -577,50 +1333,16 @@ fn turso_store_rejects_dimension_generator_metadata_mismatch() {
 
 #[cfg(feature = "turso-store")]
 #[test]
-fn turso_store_loads_columns_by_xz() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_turso_store(&metadata);
-    let column = ChunkColumn::new(-2, 3);
-    let address = column_address(column);
-    let lower = chunk_with_block(Item::Grass);
-    let upper = chunk_with_block(Item::Stone);
-
-    store
-        .save_chunk(address.chunk(3), &upper, &default_heightmap())
-        .unwrap();
-    store
-        .save_chunk(address.chunk(0), &lower, &default_heightmap())
-        .unwrap();
-
-    let column_data = store
-        .load_stored_column(address, metadata.height())
-        .unwrap();
-    assert_eq!(column_data.chunks().len(), 2);
-    assert_eq!(column_data.chunks()[0].address, address.chunk(0));
-    assert_eq!(column_data.chunks()[0].chunk, lower);
-    assert_eq!(column_data.chunks()[1].address, address.chunk(3));
-    assert_eq!(column_data.chunks()[1].chunk, upper);
-}
-
-#[test]
-fn sqlite_store_rejects_world_metadata_mismatch() {
-    let metadata = WorldMetadata::with_seed(42);
-    let store = test_sqlite_store(&metadata);
-    let incompatible = metadata
-        .clone()
-        .with_height_chunks(metadata.height_chunks() + 1)
+fn turso_metadata_is_readable_without_knowing_the_world() {
+    let metadata = WorldMetadata::with_seed(0x5eed)
+        .with_height_chunks(3)
         .unwrap();
+    let store = test_turso_store(&metadata);
 
-    assert!(
-        store
-            .save_chunk(
-                chunk_address(ChunkPos::ZERO),
-                &Chunk::default(),
-                &default_heightmap(),
-            )
-            .is_ok()
+    assert_eq!(
+        TursoChunkStore::read_metadata(&store.path).unwrap(),
+        metadata
     );
-    assert!(SqliteChunkStore::open(&store.path, &incompatible).is_err());
 }
 
 #[test]
@@ -824,6 +1546,14 @@ fn repository_rejects_unknown_dimensions() {
         repository.load_stored_column(ColumnAddress::new(unknown, ChunkColumn::new(0, 0))),
         Err(ChunkStoreError::UnknownDimension { dimension }) if dimension == unknown
     ));
+    assert!(matches!(
+        repository.list_columns(unknown),
+        Err(ChunkStoreError::UnknownDimension { dimension }) if dimension == unknown
+    ));
+    assert!(matches!(
+        repository.delete_column(ColumnAddress::new(unknown, ChunkColumn::new(0, 0))),
+        Err(ChunkStoreError::UnknownDimension { dimension }) if dimension == unknown
+    ));
 }
 
 #[test]
@@ -914,6 +1644,7 @@ fn development_world_paths_include_complete_format_identity() {
         assert_ne!(path, development_world_path(&different));
     }
     assert!(path.ends_with("seed-0000000000000001-g1-c2-h5.sqlite3"));
+    assert!(development_region_path(&base).ends_with("seed-0000000000000001-g1-c2-h5.region"));
     #[cfg(feature = "turso-store")]
     assert!(development_turso_path(&base).ends_with("seed-0000000000000001-g1-c2-h5.turso"));
 }
@@ -3,17 +3,20 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use tokio::sync::{mpsc, oneshot};
+
 use crate::player::PlayerId;
 use crate::world::{
-    chunk::{Chunk, ChunkHeightmap, ChunkPos},
+    chunk::{Chunk, ChunkColumn, ChunkHeightmap, ChunkPos},
     definition::{ChunkAddress, ColumnAddress, DimensionId},
     generation::{WorldHeight, WorldMetadata},
 };
 
 use super::{
     ChunkStore, ChunkStoreError, ChunkStoreResult, SQL_CREATE_WORLD_METADATA,
-    SQL_INSERT_METADATA_VALUE, SQL_SELECT_METADATA_VALUE, StoredChunk, StoredColumn, StoredPlayer,
-    StoredPlayerPosition, metadata_entries,
+    SQL_INSERT_METADATA_VALUE, SQL_SELECT_METADATA_VALUE, SQL_UPSERT_METADATA_VALUE, StoredChunk,
+    StoredColumn, StoredColumnLight, StoredColumnSurface, StoredPlayer, StoredPlayerPosition,
+    metadata_entries, world_metadata_from_entries,
 };
 
 const SQL_CREATE_CHUNKS: &str = "CREATE TABLE IF NOT EXISTS chunks (
@@ -35,6 +38,10 @@ WHERE dimension = ?1 AND x = ?2 AND z = ?3 AND y = ?4";
 const SQL_INSERT_CHUNK: &str = "INSERT INTO chunks (
     dimension, x, z, y, blocks
 ) VALUES (?1, ?2, ?3, ?4, ?5)";
+const SQL_SELECT_STORED_COLUMNS: &str =
+    "SELECT DISTINCT x, z FROM chunks WHERE dimension = ?1 ORDER BY x, z";
+const SQL_DELETE_COLUMN_CHUNKS: &str =
+    "DELETE FROM chunks WHERE dimension = ?1 AND x = ?2 AND z = ?3";
 
 const SQL_CREATE_COLUMN_HEIGHTMAPS: &str = "CREATE TABLE IF NOT EXISTS column_heightmaps (
     dimension INTEGER NOT NULL,
@@ -49,6 +56,40 @@ const SQL_UPSERT_COLUMN_HEIGHTMAP: &str =
     "INSERT INTO column_heightmaps (dimension, x, z, heightmap)
     VALUES (?1, ?2, ?3, ?4)
     ON CONFLICT(dimension, x, z) DO UPDATE SET heightmap = excluded.heightmap";
+const SQL_DELETE_COLUMN_HEIGHTMAP: &str =
+    "DELETE FROM column_heightmaps WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+
+const SQL_CREATE_COLUMN_LIGHT: &str = "CREATE TABLE IF NOT EXISTS column_light (
+    dimension INTEGER NOT NULL,
+    x INTEGER NOT NULL,
+    z INTEGER NOT NULL,
+    light BLOB NOT NULL,
+    PRIMARY KEY (dimension, x, z)
+)";
+const SQL_SELECT_COLUMN_LIGHT: &str =
+    "SELECT light FROM column_light WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+const SQL_UPSERT_COLUMN_LIGHT: &str = "INSERT INTO column_light (dimension, x, z, light)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(dimension, x, z) DO UPDATE SET light = excluded.light";
+const SQL_DELETE_COLUMN_LIGHT: &str =
+    "DELETE FROM column_light WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+
+const SQL_CREATE_COLUMN_SURFACES: &str = "CREATE TABLE IF NOT EXISTS column_surfaces (
+    dimension INTEGER NOT NULL,
+    x INTEGER NOT NULL,
+    z INTEGER NOT NULL,
+    surface BLOB NOT NULL,
+    PRIMARY KEY (dimension, x, z)
+)";
+const SQL_SELECT_COLUMN_SURFACE: &str =
+    "SELECT surface FROM column_surfaces WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+const SQL_UPSERT_COLUMN_SURFACE: &str = "INSERT INTO column_surfaces (dimension, x, z, surface)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(dimension, x, z) DO UPDATE SET surface = excluded.surface";
+const SQL_DELETE_COLUMN_SURFACE: &str =
+    "DELETE FROM column_surfaces WHERE dimension = ?1 AND x = ?2 AND z = ?3";
+
+const SQL_SELECT_METADATA_ENTRIES: &str = "SELECT key, value FROM world_metadata ORDER BY key";
 
 const SQL_CREATE_PLAYERS: &str = "CREATE TABLE IF NOT EXISTS players (
     id INTEGER NOT NULL,
@@ -79,12 +120,44 @@ ON CONFLICT(id) DO UPDATE SET
     local_z = excluded.local_z,
     local_y = excluded.local_y";
 
+/// Writes already queued when the writer starts a transaction are committed
+/// together, up to this many.
+const TURSO_MAX_WRITES_PER_TRANSACTION: usize = 64;
+
 pub struct TursoChunkStore {
     database: turso::Database,
+    writes: mpsc::UnboundedSender<TursoWriteRequest>,
     runtime: tokio::runtime::Runtime,
     metadata: WorldMetadata,
 }
 
+enum TursoWrite {
+    Chunk {
+        address: ChunkAddress,
+        blocks: Vec<u8>,
+        heightmap: Vec<u8>,
+    },
+    ColumnLight {
+        address: ColumnAddress,
+        light: Vec<u8>,
+    },
+    ColumnSurface {
+        address: ColumnAddress,
+        surface: Vec<u8>,
+    },
+    DeleteColumn(ColumnAddress),
+    Player(StoredPlayer),
+    WorldState {
+        key: String,
+        value: String,
+    },
+}
+
+struct TursoWriteRequest {
+    write: TursoWrite,
+    committed: oneshot::Sender<ChunkStoreResult<()>>,
+}
+
 impl TursoChunkStore {
     pub fn open(path: impl AsRef<Path>, metadata: &WorldMetadata) -> ChunkStoreResult<Self> {
         let path = path.as_ref();
@@ -105,29 +178,69 @@ impl TursoChunkStore {
                 .build()
                 .await
         })?;
-        let store = Self {
+        runtime.block_on(initialize(&database, metadata))?;
+        let (writes, requests) = mpsc::unbounded_channel();
+        runtime.spawn(run_writer(database.connect()?, requests));
+        Ok(Self {
             database,
+            writes,
             runtime,
             metadata: metadata.clone(),
-        };
-        store.runtime.block_on(store.initialize(metadata))?;
-        Ok(store)
+        })
+    }
+
+    /// Reads the metadata an existing world was created with.
+    pub fn read_metadata(path: impl AsRef<Path>) -> ChunkStoreResult<WorldMetadata> {
+        world_metadata_from_entries(&Self::read_metadata_entries(path)?)
     }
 
-    async fn initialize(&self, metadata: &WorldMetadata) -> ChunkStoreResult<()> {
-        let connection = self.database.connect()?;
-        connection.execute(SQL_CREATE_WORLD_METADATA, ()).await?;
-        for (key, value) in metadata_entries(metadata) {
-            ensure_metadata_value(&connection, key, value).await?;
+    /// Reads every raw `world_metadata` row of an existing world, ordered by key.
+    pub fn read_metadata_entries(
+        path: impl AsRef<Path>,
+    ) -> ChunkStoreResult<Vec<(String, String)>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(ChunkStoreError::Io {
+                kind: ErrorKind::NotFound,
+                message: format!("world does not exist: {}", path.display()),
+            });
         }
-        connection.execute(SQL_CREATE_CHUNKS, ()).await?;
-        connection.execute(SQL_CREATE_COLUMN_HEIGHTMAPS, ()).await?;
-        connection.execute(SQL_CREATE_PLAYERS, ()).await?;
-        connection
-            .execute(SQL_CREATE_PLAYERS_POSITION_INDEX, ())
-            .await?;
+        let path = path_to_string(path)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|error| ChunkStoreError::Runtime {
+                message: error.to_string(),
+            })?;
+        runtime.block_on(async {
+            let database = turso::Builder::new_local(&path)
+                .experimental_multiprocess_wal(true)
+                .build()
+                .await?;
+            let connection = database.connect()?;
+            let mut rows = connection.query(SQL_SELECT_METADATA_ENTRIES, ()).await?;
+            let mut entries = Vec::new();
+            while let Some(row) = rows.next().await? {
+                entries.push((row.get::<String>(0)?, row.get::<String>(1)?));
+            }
+            Ok(entries)
+        })
+    }
 
-        Ok(())
+    /// Queues one write for the writer task and waits until the transaction
+    /// containing it commits.
+    ///
+    /// Callers on different threads queue concurrently, so in-flight saves are
+    /// pipelined into shared transactions instead of contending for the
+    /// database write lock.
+    fn write(&self, write: TursoWrite) -> ChunkStoreResult<()> {
+        let (committed, result) = oneshot::channel();
+        self.writes
+            .send(TursoWriteRequest { write, committed })
+            .map_err(|_| writer_stopped())?;
+        self.runtime
+            .block_on(result)
+            .map_err(|_| writer_stopped())?
     }
 
     #[cfg(test)]
@@ -144,6 +257,27 @@ impl TursoChunkStore {
         });
     }
 
+    #[cfg(test)]
+    pub(super) fn overwrite_chunk_bytes_for_test(&self, address: ChunkAddress, bytes: &[u8]) {
+        self.runtime.block_on(async {
+            let connection = self.database.connect().expect("test database must connect");
+            let position = address.position();
+            connection
+                .execute(
+                    SQL_UPDATE_CHUNK,
+                    (
+                        i64::from(address.dimension().get()),
+                        position.x(),
+                        position.z(),
+                        position.y(),
+                        bytes.to_vec(),
+                    ),
+                )
+                .await
+                .expect("test chunk update must succeed");
+        });
+    }
+
     #[cfg(test)]
     pub(super) fn drop_player_schema_for_test(&self) -> ChunkStoreResult<()> {
         self.runtime.block_on(async {
@@ -243,41 +377,95 @@ impl ChunkStore for TursoChunkStore {
         chunk: &Chunk,
         heightmap: &ChunkHeightmap,
     ) -> ChunkStoreResult<()> {
-        let blocks = chunk.to_storage_bytes();
-        let heightmap_bytes = heightmap.to_bytes();
+        self.write(TursoWrite::Chunk {
+            address,
+            blocks: chunk.to_storage_bytes(),
+            heightmap: heightmap.to_bytes(),
+        })
+    }
 
+    fn load_column_light(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnLight>> {
         self.runtime.block_on(async {
-            let mut connection = self.database.connect()?;
-            let transaction = connection.transaction().await?;
-            let position = address.position();
-            let dimension = i64::from(address.dimension().get());
-            let changed = transaction
-                .execute(
-                    SQL_UPDATE_CHUNK,
-                    (
-                        dimension,
-                        position.x(),
-                        position.z(),
-                        position.y(),
-                        blocks.clone(),
-                    ),
+            let connection = self.database.connect()?;
+            let column = address.column();
+            let mut rows = connection
+                .query(
+                    SQL_SELECT_COLUMN_LIGHT,
+                    (i64::from(address.dimension().get()), column.x(), column.z()),
                 )
                 .await?;
-            if changed == 0 {
-                transaction
-                    .execute(
-                        SQL_INSERT_CHUNK,
-                        (dimension, position.x(), position.z(), position.y(), blocks),
-                    )
-                    .await?;
-            }
-            save_column_heightmap(&transaction, address.column(), &heightmap_bytes).await?;
-            transaction.commit().await?;
+            let Some(row) = rows.next().await? else {
+                return Ok(None);
+            };
+            let bytes = row.get::<Vec<u8>>(0)?;
+            Ok(Some(StoredColumnLight::try_from_bytes(&bytes)?))
+        })
+    }
 
-            Ok(())
+    fn save_column_light(
+        &self,
+        address: ColumnAddress,
+        light: &StoredColumnLight,
+    ) -> ChunkStoreResult<()> {
+        self.write(TursoWrite::ColumnLight {
+            address,
+            light: light.to_bytes(),
+        })
+    }
+
+    fn load_column_surface(
+        &self,
+        address: ColumnAddress,
+    ) -> ChunkStoreResult<Option<StoredColumnSurface>> {
+        self.runtime.block_on(async {
+            let connection = self.database.connect()?;
+            let column = address.column();
+            let mut rows = connection
+                .query(
+                    SQL_SELECT_COLUMN_SURFACE,
+                    (i64::from(address.dimension().get()), column.x(), column.z()),
+                )
+                .await?;
+            let Some(row) = rows.next().await? else {
+                return Ok(None);
+            };
+            let bytes = row.get::<Vec<u8>>(0)?;
+            Ok(Some(StoredColumnSurface::try_from_bytes(&bytes)?))
+        })
+    }
+
+    fn save_column_surface(
+        &self,
+        address: ColumnAddress,
+        surface: &StoredColumnSurface,
+    ) -> ChunkStoreResult<()> {
+        self.write(TursoWrite::ColumnSurface {
+            address,
+            surface: surface.to_bytes(),
+        })
+    }
+
+    fn list_columns(&self, dimension: DimensionId) -> ChunkStoreResult<Vec<ChunkColumn>> {
+        self.runtime.block_on(async {
+            let connection = self.database.connect()?;
+            let mut rows = connection
+                .query(SQL_SELECT_STORED_COLUMNS, (i64::from(dimension.get()),))
+                .await?;
+            let mut columns = Vec::new();
+            while let Some(row) = rows.next().await? {
+                columns.push(ChunkColumn::new(row.get::<i32>(0)?, row.get::<i32>(1)?));
+            }
+            Ok(columns)
         })
     }
 
+    fn delete_column(&self, address: ColumnAddress) -> ChunkStoreResult<()> {
+        self.write(TursoWrite::DeleteColumn(address))
+    }
+
     fn load_player(&self, id: PlayerId) -> ChunkStoreResult<Option<StoredPlayer>> {
         self.runtime.block_on(async {
             let connection = self.database.connect()?;
@@ -301,8 +489,172 @@ impl ChunkStore for TursoChunkStore {
     }
 
     fn save_player(&self, player: &StoredPlayer) -> ChunkStoreResult<()> {
+        self.write(TursoWrite::Player(player.clone()))
+    }
+
+    fn load_world_state(&self, key: &str) -> ChunkStoreResult<Option<String>> {
         self.runtime.block_on(async {
             let connection = self.database.connect()?;
+            let mut rows = connection.query(SQL_SELECT_METADATA_VALUE, (key,)).await?;
+            let Some(row) = rows.next().await? else {
+                return Ok(None);
+            };
+            Ok(Some(row.get::<String>(0)?))
+        })
+    }
+
+    fn save_world_state(&self, key: &str, value: &str) -> ChunkStoreResult<()> {
+        self.write(TursoWrite::WorldState {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        })
+    }
+}
+
+async fn initialize(database: &turso::Database, metadata: &WorldMetadata) -> ChunkStoreResult<()> {
+    let connection = database.connect()?;
+    connection.execute(SQL_CREATE_WORLD_METADATA, ()).await?;
+    for (key, value) in metadata_entries(metadata) {
+        ensure_metadata_value(&connection, key, value).await?;
+    }
+    connection.execute(SQL_CREATE_CHUNKS, ()).await?;
+    connection.execute(SQL_CREATE_COLUMN_HEIGHTMAPS, ()).await?;
+    connection.execute(SQL_CREATE_COLUMN_LIGHT, ()).await?;
+    connection.execute(SQL_CREATE_COLUMN_SURFACES, ()).await?;
+    connection.execute(SQL_CREATE_PLAYERS, ()).await?;
+    connection
+        .execute(SQL_CREATE_PLAYERS_POSITION_INDEX, ())
+        .await?;
+
+    Ok(())
+}
+
+/// Commits queued writes on one connection until the store is dropped.
+///
+/// Everything queued while the previous transaction committed is grouped into
+/// the next one. If a grouped transaction fails, its writes are retried one
+/// at a time so an error is only reported to the write that caused it.
+async fn run_writer(
+    mut connection: turso::Connection,
+    mut requests: mpsc::UnboundedReceiver<TursoWriteRequest>,
+) {
+    let mut batch = Vec::new();
+    while let Some(request) = requests.recv().await {
+        batch.push(request);
+        while batch.len() < TURSO_MAX_WRITES_PER_TRANSACTION
+            && let Ok(request) = requests.try_recv()
+        {
+            batch.push(request);
+        }
+
+        let result =
+            commit_writes(&mut connection, batch.iter().map(|request| &request.write)).await;
+        if result.is_err() && batch.len() > 1 {
+            for request in batch.drain(..) {
+                let result = commit_writes(&mut connection, [&request.write]).await;
+                let _ = request.committed.send(result);
+            }
+        } else {
+            for request in batch.drain(..) {
+                let _ = request.committed.send(result.clone());
+            }
+        }
+    }
+}
+
+async fn commit_writes(
+    connection: &mut turso::Connection,
+    writes: impl IntoIterator<Item = &TursoWrite>,
+) -> ChunkStoreResult<()> {
+    let transaction = connection.transaction().await?;
+    for write in writes {
+        apply_write(&transaction, write).await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+async fn apply_write(connection: &turso::Connection, write: &TursoWrite) -> ChunkStoreResult<()> {
+    match write {
+        TursoWrite::Chunk {
+            address,
+            blocks,
+            heightmap,
+        } => {
+            let position = address.position();
+            let dimension = i64::from(address.dimension().get());
+            let changed = connection
+                .execute(
+                    SQL_UPDATE_CHUNK,
+                    (
+                        dimension,
+                        position.x(),
+                        position.z(),
+                        position.y(),
+                        blocks.clone(),
+                    ),
+                )
+                .await?;
+            if changed == 0 {
+                connection
+                    .execute(
+                        SQL_INSERT_CHUNK,
+                        (
+                            dimension,
+                            position.x(),
+                            position.z(),
+                            position.y(),
+                            blocks.clone(),
+                        ),
+                    )
+                    .await?;
+            }
+            save_column_heightmap(connection, address.column(), heightmap).await?;
+            let column = address.column();
+            connection
+                .execute(
+                    SQL_DELETE_COLUMN_SURFACE,
+                    (dimension, column.x(), column.z()),
+                )
+                .await?;
+        }
+        TursoWrite::ColumnLight { address, light } => {
+            let column = address.column();
+            connection
+                .execute(
+                    SQL_UPSERT_COLUMN_LIGHT,
+                    (
+                        i64::from(address.dimension().get()),
+                        column.x(),
+                        column.z(),
+                        light.clone(),
+                    ),
+                )
+                .await?;
+        }
+        TursoWrite::ColumnSurface { address, surface } => {
+            let column = address.column();
+            connection
+                .execute(
+                    SQL_UPSERT_COLUMN_SURFACE,
+                    (
+                        i64::from(address.dimension().get()),
+                        column.x(),
+                        column.z(),
+                        surface.clone(),
+                    ),
+                )
+                .await?;
+        }
+        TursoWrite::DeleteColumn(address) => {
+            let column = address.column();
+            let key = (i64::from(address.dimension().get()), column.x(), column.z());
+            connection.execute(SQL_DELETE_COLUMN_CHUNKS, key).await?;
+            connection.execute(SQL_DELETE_COLUMN_HEIGHTMAP, key).await?;
+            connection.execute(SQL_DELETE_COLUMN_LIGHT, key).await?;
+            connection.execute(SQL_DELETE_COLUMN_SURFACE, key).await?;
+        }
+        TursoWrite::Player(player) => {
             let position = player.position();
             let chunk = position.chunk();
             let local = position.local();
@@ -321,8 +673,19 @@ impl ChunkStore for TursoChunkStore {
                     ),
                 )
                 .await?;
-            Ok(())
-        })
+        }
+        TursoWrite::WorldState { key, value } => {
+            connection
+                .execute(SQL_UPSERT_METADATA_VALUE, (key.as_str(), value.as_str()))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+fn writer_stopped() -> ChunkStoreError {
+    ChunkStoreError::Runtime {
+        message: "turso writer task stopped".to_owned(),
     }
 }
 
@@ -121,8 +121,8 @@ fn vertex_pulling_resource_metadata_matches_render_layout() {
             ShaderResource {
                 group: 1,
                 binding: 1,
-                name: "chunk_origin",
-                kind: ResourceKind::Uniform,
+                name: "draws",
+                kind: ResourceKind::ReadOnlyStorage,
             },
             ShaderResource {
                 group: 1,